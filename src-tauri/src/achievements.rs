@@ -0,0 +1,270 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::focus;
+use crate::monitor;
+use crate::notifications;
+use crate::session_index;
+use crate::settings;
+use crate::storage::{self, SessionQuery, UnlockedAchievement};
+use crate::time::{self, DayClock};
+use crate::tray;
+
+use chrono::Timelike;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL_SECS: u64 = 30 * 60;
+/// First check shortly after launch, once the caches have warmed up
+const FIRST_CHECK_SECS: u64 = 60;
+const FLOURISH_GLYPH: &str = "🏆";
+const FLOURISH_SECS: u64 = 8;
+/// Sessions this recent are checked for night-owl hours
+const NIGHT_OWL_LOOKBACK_SECS: i64 = 7 * 86400;
+
+/// While set and in the future, the tray title shows the unlock instead of utilization
+static FLOURISH_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+struct Badge {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+}
+
+const BADGES: &[Badge] = &[
+    Badge {
+        id: "tokens-100k-day",
+        title: "Six Figures",
+        description: "100k input and output tokens in one day",
+    },
+    Badge {
+        id: "streak-7",
+        title: "On a Roll",
+        description: "Claude Code activity seven days in a row",
+    },
+    Badge {
+        id: "commits-50-week",
+        title: "Shipping Week",
+        description: "50 commits in a week (from devlogs)",
+    },
+    Badge {
+        id: "night-owl",
+        title: "Night Owl",
+        description: "A session between midnight and 4 a.m.",
+    },
+];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Achievement {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// RFC 3339; None while locked
+    pub unlocked_at: Option<String>,
+    pub detail: Option<String>,
+}
+
+fn tokens_100k_day() -> Option<String> {
+    let cache = claude::read_stats_cache().ok();
+    let mut days: Vec<(String, u64)> = cache
+        .iter()
+        .flat_map(|c| &c.daily_model_tokens)
+        .map(|d| (d.date.clone(), d.tokens_by_model.values().sum()))
+        .collect();
+    if let Some(s) = claude::get_cached_realtime_stats() {
+        let t = &s.today_tokens;
+        days.push((DayClock::load().today_key(), t.input + t.output));
+    }
+    days.into_iter()
+        .find(|(_, tokens)| *tokens >= 100_000)
+        .map(|(date, tokens)| format!("{}k tokens on {}", tokens / 1000, date))
+}
+
+fn streak_7() -> Option<String> {
+    let mut dates: BTreeSet<chrono::NaiveDate> = claude::read_stats_cache()
+        .map(|c| c.daily_activity)
+        .unwrap_or_default()
+        .iter()
+        .filter(|d| d.message_count > 0)
+        .filter_map(|d| time::parse_date(&d.date))
+        .collect();
+    if claude::get_cached_realtime_stats().is_some_and(|s| s.today_messages > 0) {
+        dates.insert(DayClock::load().today());
+    }
+    let mut run = 0;
+    let mut prev: Option<chrono::NaiveDate> = None;
+    for day in dates {
+        run = match prev {
+            Some(p) if p.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        if run == 7 {
+            return Some(format!("7 days ending {}", time::format_date(day)));
+        }
+        prev = Some(day);
+    }
+    None
+}
+
+fn commits_50_week() -> Option<String> {
+    if let Some(log) = storage::list_devlogs("weekly", 520)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|l| l.stats.total_commits >= 50)
+    {
+        return Some(format!(
+            "{} commits in the week of {}",
+            log.stats.total_commits, log.date
+        ));
+    }
+    // The week in progress has no weekly devlog yet
+    let since = time::format_date(DayClock::load().today() - chrono::Duration::days(6));
+    let commits: u32 = storage::list_devlogs("daily", 7)
+        .unwrap_or_default()
+        .iter()
+        .filter(|l| l.date >= since)
+        .map(|l| l.stats.total_commits)
+        .sum();
+    (commits >= 50).then(|| format!("{} commits in the 7 days from {}", commits, since))
+}
+
+fn night_owl() -> Option<String> {
+    let now = chrono::Utc::now().timestamp();
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(now - NIGHT_OWL_LOOKBACK_SECS),
+        to: None,
+        device: None,
+        sort: String::new(),
+        offset: 0,
+        limit: 1000,
+    })
+    .ok()?;
+    sessions.iter().find_map(|s| {
+        [s.first_message_at, s.last_message_at]
+            .into_iter()
+            .flatten()
+            .filter_map(|t| chrono::DateTime::from_timestamp(t, 0))
+            .map(|t| t.with_timezone(&chrono::Local))
+            .find(|t| t.hour() < 4)
+            .map(|t| format!("{} at {}", s.project, t.format("%Y-%m-%d %H:%M")))
+    })
+}
+
+fn evaluate(id: &str) -> Option<String> {
+    match id {
+        "tokens-100k-day" => tokens_100k_day(),
+        "streak-7" => streak_7(),
+        "commits-50-week" => commits_50_week(),
+        "night-owl" => night_owl(),
+        _ => None,
+    }
+}
+
+/// Evaluate every locked badge and record the ones now earned
+fn check() -> Result<Vec<&'static Badge>, SprtError> {
+    session_index::refresh_in_background()?;
+    let unlocked: Vec<String> = storage::unlocked_achievements()?
+        .into_iter()
+        .map(|a| a.id)
+        .collect();
+    let mut new = vec![];
+    for badge in BADGES
+        .iter()
+        .filter(|b| !unlocked.iter().any(|u| u == b.id))
+    {
+        let Some(detail) = evaluate(badge.id) else {
+            continue;
+        };
+        let unlock = UnlockedAchievement {
+            id: badge.id.to_string(),
+            unlocked_at: chrono::Utc::now().to_rfc3339(),
+            detail: Some(detail),
+        };
+        if storage::unlock_achievement(&unlock)? {
+            log::info!("Achievement unlocked: {}", badge.id);
+            new.push(badge);
+        }
+    }
+    Ok(new)
+}
+
+pub fn flourish_active() -> bool {
+    FLOURISH_UNTIL
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some_and(|until| Instant::now() < until)
+}
+
+/// One notification per check, however many unlocked at once (the first run can backfill several)
+fn announce(app: &AppHandle, badges: &[&Badge]) {
+    let _ = app.emit("achievement-unlocked", ());
+    if !settings::load().achievements.notify {
+        return;
+    }
+    let (title, body) = match badges {
+        [only] => (
+            format!("Achievement unlocked: {}", only.title),
+            only.description.to_string(),
+        ),
+        _ => (
+            format!("{} achievements unlocked", badges.len()),
+            badges
+                .iter()
+                .map(|b| b.title)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    };
+    if !notifications::notify(app, &title, &body) {
+        return;
+    }
+    // A running focus timer keeps the title
+    if !focus::is_active() {
+        *FLOURISH_UNTIL.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some(Instant::now() + Duration::from_secs(FLOURISH_SECS));
+        tray::set_tray_text(app, &format!("{} {}", FLOURISH_GLYPH, badges[0].title));
+    }
+}
+
+/// Background badge evaluation, all local; emits `achievement-unlocked` on each unlock
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(FIRST_CHECK_SECS));
+        loop {
+            if !monitor::is_paused() {
+                match check() {
+                    Ok(new) if !new.is_empty() => announce(&app, &new),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Achievement check failed: {e}"),
+                }
+            }
+            std::thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECS));
+        }
+    });
+}
+
+/// Every badge, unlocked ones with when and why
+#[tauri::command]
+pub fn get_achievements() -> Result<Vec<Achievement>, SprtError> {
+    let unlocked: HashMap<String, UnlockedAchievement> = storage::unlocked_achievements()?
+        .into_iter()
+        .map(|a| (a.id.clone(), a))
+        .collect();
+    Ok(BADGES
+        .iter()
+        .map(|b| {
+            let u = unlocked.get(b.id);
+            Achievement {
+                id: b.id.to_string(),
+                title: b.title.to_string(),
+                description: b.description.to_string(),
+                unlocked_at: u.map(|u| u.unlocked_at.clone()),
+                detail: u.and_then(|u| u.detail.clone()),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,166 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::git::{self, GitActivity};
+use crate::storage::{CommitMessageStats, SessionSummary};
+
+use serde::Serialize;
+use std::path::Path;
+
+/// Commits landing this long after a session's last message still count toward it
+const COMMIT_GRACE_MINUTES: i64 = 30;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCorrelation {
+    pub session_id: String,
+    pub project: String,
+    pub tokens: u64,
+    pub commits: u32,
+    /// True when at least one commit followed from the session
+    pub productive: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EfficiencyStats {
+    pub date: String,
+    pub total_tokens: u64,
+    pub total_commits: u32,
+    /// Commits made during or shortly after a session
+    pub correlated_commits: u32,
+    pub commits_per_1k_tokens: f64,
+    pub productive_sessions: u32,
+    pub exploratory_sessions: u32,
+    pub sessions: Vec<SessionCorrelation>,
+}
+
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// `type`, optional `(scope)`, optional `!`, then `: ` and a subject
+fn is_conventional(subject: &str) -> bool {
+    let Some((head, rest)) = subject.split_once(": ") else {
+        return false;
+    };
+    if rest.trim().is_empty() {
+        return false;
+    }
+    let head = head.strip_suffix('!').unwrap_or(head);
+    let kind = match head.split_once('(') {
+        Some((kind, scope)) if scope.ends_with(')') && scope.len() > 1 => kind,
+        Some(_) => return false,
+        None => head,
+    };
+    CONVENTIONAL_TYPES.contains(&kind)
+}
+
+fn is_wip(subject: &str) -> bool {
+    let lower = subject.trim().to_lowercase();
+    lower == "wip" || lower.starts_with("wip ") || lower.starts_with("wip:") || lower.contains("[wip]")
+}
+
+fn is_fixup(subject: &str) -> bool {
+    ["fixup!", "squash!", "amend!"].iter().any(|p| subject.starts_with(p))
+}
+
+/// Length, Conventional Commits compliance and WIP/fixup counts over commit subjects
+pub fn commit_message_stats<'a>(messages: impl IntoIterator<Item = &'a str>) -> CommitMessageStats {
+    let mut stats = CommitMessageStats::default();
+    let mut total_len = 0;
+    for message in messages {
+        let subject = message.lines().next().unwrap_or("").trim();
+        stats.commits += 1;
+        total_len += subject.chars().count();
+        stats.conventional += is_conventional(subject) as u32;
+        stats.wip += is_wip(subject) as u32;
+        stats.fixup += is_fixup(subject) as u32;
+    }
+    if stats.commits > 0 {
+        stats.avg_length = total_len as f64 / stats.commits as f64;
+    }
+    stats
+}
+
+fn parse_ts(s: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    chrono::DateTime::parse_from_rfc3339(s).ok()
+}
+
+/// A session belongs to a repo when it ran in the repo or one of its subdirectories
+fn session_in_repo(session: &SessionSummary, repo: &GitActivity) -> bool {
+    !session.project_path.is_empty() && Path::new(&session.project_path).starts_with(&repo.repo_path)
+}
+
+/// Match each session to commits in the same repo made between its first message and
+/// COMMIT_GRACE_MINUTES after its last. A commit inside overlapping sessions counts for each.
+pub fn correlate(date: &str, git_data: &[GitActivity], session_data: &[SessionSummary]) -> EfficiencyStats {
+    let grace = chrono::Duration::minutes(COMMIT_GRACE_MINUTES);
+    let mut correlated = std::collections::HashSet::new();
+
+    let sessions: Vec<SessionCorrelation> = session_data
+        .iter()
+        .map(|s| {
+            let window = s
+                .first_message
+                .as_deref()
+                .and_then(parse_ts)
+                .zip(s.last_message.as_deref().and_then(parse_ts));
+
+            let mut commits = 0;
+            if let Some((start, end)) = window {
+                for repo in git_data.iter().filter(|g| session_in_repo(s, g)) {
+                    for c in &repo.commits {
+                        let in_window = parse_ts(&c.timestamp)
+                            .map(|t| t >= start && t <= end + grace)
+                            .unwrap_or(false);
+                        if in_window {
+                            commits += 1;
+                            correlated.insert(c.hash.clone());
+                        }
+                    }
+                }
+            }
+
+            SessionCorrelation {
+                session_id: s.session_id.clone(),
+                project: s.project.clone(),
+                tokens: s.input_tokens + s.output_tokens + s.cache_read,
+                commits,
+                productive: commits > 0,
+            }
+        })
+        .collect();
+
+    let total_tokens: u64 = sessions.iter().map(|s| s.tokens).sum();
+    let total_commits: u32 = git_data.iter().map(|g| g.commits.len() as u32).sum();
+    let productive_sessions = sessions.iter().filter(|s| s.productive).count() as u32;
+
+    EfficiencyStats {
+        date: date.to_string(),
+        total_tokens,
+        total_commits,
+        correlated_commits: correlated.len() as u32,
+        commits_per_1k_tokens: if total_tokens > 0 {
+            total_commits as f64 / (total_tokens as f64 / 1000.0)
+        } else {
+            0.0
+        },
+        productive_sessions,
+        exploratory_sessions: sessions.len() as u32 - productive_sessions,
+        sessions,
+    }
+}
+
+#[tauri::command]
+pub async fn get_efficiency_stats(date: String) -> Result<EfficiencyStats, SprtError> {
+    if crate::time::parse_date(&date).is_none() {
+        return Err(SprtError::InvalidInput(format!("Invalid date: {}", date)));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let git_data = git::collect_git_activity(&date);
+        let session_data = claude::get_session_summaries(&date);
+        correlate(&date, &git_data, &session_data)
+    })
+    .await
+    .map_err(|e| SprtError::Internal(format!("Task join error: {}", e)))
+}
@@ -0,0 +1,100 @@
+use crate::claude;
+use crate::devlog;
+use crate::error::SprtError;
+use crate::secrets;
+use crate::server::{Request, Response};
+use crate::settings::ServerSettings;
+
+use base64::Engine;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateRequest {
+    date: String,
+    #[serde(default = "default_log_type")]
+    log_type: String,
+    model: Option<String>,
+    max_tokens: Option<u32>,
+}
+
+fn default_log_type() -> String {
+    "daily".to_string()
+}
+
+/// Constant-time comparison so the token can't be guessed byte by byte from timing
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// `Authorization: Bearer <token>`, or `Basic base64(<token>)` as WakaTime clients send it
+pub(crate) fn authorized(req: &Request, config: &ServerSettings) -> bool {
+    let Some(expected) = secrets::api_token(config) else {
+        return false;
+    };
+    let expected = expected.as_str();
+    let Some(header) = req.headers.get("authorization") else {
+        return false;
+    };
+    if let Some(given) = header.strip_prefix("Bearer ") {
+        return token_matches(given.trim(), expected);
+    }
+    if let Some(given) = header.strip_prefix("Basic ") {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(expected);
+        return token_matches(given.trim(), &encoded);
+    }
+    false
+}
+
+fn result_response<T: serde::Serialize>(result: Result<T, SprtError>) -> Response {
+    let e = match result {
+        Ok(v) => return Response::json(200, &v),
+        Err(e) => e,
+    };
+    let status = match e {
+        SprtError::InvalidInput(_) => 400,
+        SprtError::NoData(_) => 404,
+        SprtError::InProgress(_) => 409,
+        SprtError::Network(_) | SprtError::Api(_) => 502,
+        SprtError::Paused(_) | SprtError::NotAuthenticated(_) | SprtError::TokenExpired(_) => 503,
+        _ => 500,
+    };
+    // The { code, message } shape the app's commands return, plus "error" like other failures
+    let mut body = serde_json::to_value(&e).unwrap_or_default();
+    body["error"] = serde_json::json!(e.message());
+    Response::json(status, &body)
+}
+
+/// Scripting API mirroring the Tauri commands. Every route requires
+/// `Authorization: Bearer <server.apiToken>`.
+pub fn handle(req: &Request, config: &ServerSettings) -> Response {
+    if !authorized(req, config) {
+        return Response::error(401, "Missing or invalid API token");
+    }
+
+    match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/stats") => result_response(claude::realtime_stats()),
+        ("GET", "/rate-limits") => {
+            let force = req.query.get("force").map(|v| v == "true" || v == "1");
+            result_response(tauri::async_runtime::block_on(claude::get_rate_limits(force)))
+        }
+        ("POST", "/devlogs/generate") => {
+            let body: GenerateRequest = match serde_json::from_slice(&req.body) {
+                Ok(b) => b,
+                Err(e) => return Response::error(400, &format!("Invalid body: {}", e)),
+            };
+            result_response(tauri::async_runtime::block_on(devlog::generate_devlog(
+                body.date,
+                body.log_type,
+                body.model,
+                body.max_tokens,
+            )))
+        }
+        _ => Response::error(404, "Not found"),
+    }
+}
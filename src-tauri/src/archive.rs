@@ -0,0 +1,181 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::session_index;
+use crate::storage;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Anything newer could still be an open session
+const MIN_AGE_DAYS: u32 = 2;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedSession {
+    pub path: String,
+    pub archived_path: String,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveReport {
+    pub dry_run: bool,
+    pub compressed: bool,
+    pub archived: Vec<ArchivedSession>,
+    pub failed: Vec<ArchiveFailure>,
+    /// Freed under ~/.claude/projects minus what the archive copies take
+    pub bytes_saved: u64,
+}
+
+fn archive_dir() -> Option<PathBuf> {
+    storage::sprt_dir().map(|d| d.join("archive"))
+}
+
+/// Copy (gzipped or as-is) into the archive and check the copy; the original is left alone
+fn copy_to_archive(src: &Path, dest: &Path, compress: bool) -> Result<u64, String> {
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create archive directory: {}", e))?;
+    }
+    let input = fs::File::open(src).map_err(|e| format!("Cannot open: {}", e))?;
+    let output =
+        fs::File::create(dest).map_err(|e| format!("Cannot create archive file: {}", e))?;
+    let copied = if compress {
+        let mut encoder = GzEncoder::new(BufWriter::new(output), Compression::default());
+        let n = std::io::copy(&mut BufReader::new(input), &mut encoder);
+        n.and_then(|n| encoder.finish()?.flush().map(|_| n))
+    } else {
+        let mut writer = BufWriter::new(output);
+        std::io::copy(&mut BufReader::new(input), &mut writer)
+            .and_then(|n| writer.flush().map(|_| n))
+    };
+    let copied = copied.map_err(|e| {
+        let _ = fs::remove_file(dest);
+        format!("Cannot write archive file: {}", e)
+    })?;
+
+    let original = fs::metadata(src).map(|m| m.len()).unwrap_or(0);
+    if copied != original {
+        let _ = fs::remove_file(dest);
+        return Err("File changed while archiving; left in place".to_string());
+    }
+    Ok(fs::metadata(dest).map(|m| m.len()).unwrap_or(0))
+}
+
+pub fn archive(
+    older_than_days: u32,
+    compress: bool,
+    dry_run: bool,
+) -> Result<ArchiveReport, SprtError> {
+    if older_than_days < MIN_AGE_DAYS {
+        return Err(SprtError::InvalidInput(format!(
+            "Sessions must be at least {} days old to archive",
+            MIN_AGE_DAYS
+        )));
+    }
+    let projects_dir = claude::claude_dir()
+        .ok_or_else(|| SprtError::ClaudeNotFound("Cannot find home directory".to_string()))?
+        .join("projects");
+    let archive_root = archive_dir()
+        .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?;
+
+    // Record every session's stats before any file goes away
+    session_index::refresh()?;
+
+    let cutoff = std::time::SystemTime::now()
+        - std::time::Duration::from_secs(older_than_days as u64 * 86400);
+    let pattern = projects_dir.join("*/*.jsonl").to_string_lossy().to_string();
+    let candidates: Vec<(PathBuf, u64)> = glob::glob(&pattern)
+        .map_err(|e| format!("Glob error: {}", e))?
+        .filter_map(|p| p.ok())
+        .filter_map(|p| {
+            let meta = fs::metadata(&p).ok()?;
+            (meta.modified().ok()? < cutoff).then_some((p, meta.len()))
+        })
+        .collect();
+
+    let mut report = ArchiveReport {
+        dry_run,
+        compressed: compress,
+        archived: vec![],
+        failed: vec![],
+        bytes_saved: 0,
+    };
+    for (path, bytes_before) in candidates {
+        let relative = path.strip_prefix(&projects_dir).unwrap_or(&path);
+        let mut dest = archive_root.join(relative);
+        if compress {
+            dest.set_extension("jsonl.gz");
+        }
+        let path_str = path.to_string_lossy().to_string();
+        let dest_str = dest.to_string_lossy().to_string();
+
+        let bytes_after = if dry_run {
+            0
+        } else {
+            let bytes_after = match copy_to_archive(&path, &dest, compress) {
+                Ok(n) => n,
+                Err(error) => {
+                    report.failed.push(ArchiveFailure {
+                        path: path_str,
+                        error,
+                    });
+                    continue;
+                }
+            };
+            // The index row must point at the copy before the original goes,
+            // or the next refresh would drop it
+            if let Err(e) = storage::mark_session_archived(&path_str, &dest_str) {
+                let _ = fs::remove_file(&dest);
+                return Err(e);
+            }
+            if let Err(e) = fs::remove_file(&path) {
+                report.failed.push(ArchiveFailure {
+                    path: path_str,
+                    error: format!("Archived, but cannot remove original: {}", e),
+                });
+                continue;
+            }
+            bytes_after
+        };
+        report.bytes_saved += bytes_before.saturating_sub(bytes_after);
+        report.archived.push(ArchivedSession {
+            path: path_str,
+            archived_path: dest_str,
+            bytes_before,
+            bytes_after,
+        });
+    }
+    Ok(report)
+}
+
+/// Move session logs untouched for `older_than_days` into ~/.claude/sprt/archive, gzipped
+/// unless `compress` is false. Their stats stay in the session index. With `dry_run`, only list.
+#[tauri::command]
+pub async fn archive_sessions(
+    older_than_days: u32,
+    compress: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<ArchiveReport, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        archive(
+            older_than_days,
+            compress.unwrap_or(true),
+            dry_run.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
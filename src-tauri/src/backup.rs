@@ -0,0 +1,162 @@
+use crate::error::SprtError;
+use crate::settings::{self, Settings};
+use crate::storage::{self, sprt_dir};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupManifest {
+    format_version: u32,
+    app_version: String,
+    created_at: String,
+}
+
+fn default_backup_path() -> Option<PathBuf> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    sprt_dir().map(|d| d.join("backups").join(format!("sprt-backup-{}.tar.gz", stamp)))
+}
+
+fn append_bytes<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    bytes: &[u8],
+) -> Result<(), SprtError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, bytes)
+        .map_err(|e| SprtError::Storage(format!("Archive error: {}", e)))
+}
+
+/// Bundle the database (devlogs and everything else stored in SQLite) and settings into a
+/// single .tar.gz at `dest`.
+pub fn export_archive(dest: &Path) -> Result<(), SprtError> {
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir).map_err(|e| SprtError::Storage(format!("Cannot create directory: {}", e)))?;
+    }
+
+    // VACUUM INTO gives a consistent snapshot even while the app holds the database open
+    let snapshot = dest.with_extension("db.tmp");
+    let _ = fs::remove_file(&snapshot);
+    storage::snapshot_database(&snapshot)?;
+    let db_bytes = fs::read(&snapshot).map_err(|e| SprtError::Storage(format!("Read error: {}", e)));
+    let _ = fs::remove_file(&snapshot);
+    let db_bytes = db_bytes?;
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| SprtError::Internal(format!("Serialize error: {}", e)))?;
+    let settings_json = serde_json::to_vec_pretty(&settings::load())
+        .map_err(|e| SprtError::Internal(format!("Serialize error: {}", e)))?;
+
+    let file = fs::File::create(dest).map_err(|e| SprtError::Storage(format!("Write error: {}", e)))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append_bytes(&mut builder, "manifest.json", &manifest_json)?;
+    append_bytes(&mut builder, "sprt.db", &db_bytes)?;
+    append_bytes(&mut builder, "settings.json", &settings_json)?;
+    builder
+        .into_inner()
+        .and_then(|gz| gz.finish())
+        .map_err(|e| SprtError::Storage(format!("Archive error: {}", e)))?;
+    Ok(())
+}
+
+/// Restore a backup made by export_archive, replacing the current database and settings
+pub fn import_archive(src: &Path) -> Result<(), SprtError> {
+    let file = fs::File::open(src).map_err(|e| SprtError::InvalidInput(format!("Cannot open backup: {}", e)))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut manifest: Option<BackupManifest> = None;
+    let mut db_bytes: Option<Vec<u8>> = None;
+    let mut settings_data: Option<Settings> = None;
+
+    // Only read the entries we know by name — nothing is unpacked to disk as-is
+    for entry in archive.entries().map_err(|e| SprtError::Parse(format!("Invalid backup: {}", e)))? {
+        let mut entry = entry.map_err(|e| SprtError::Parse(format!("Invalid backup: {}", e)))?;
+        let name = entry
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut bytes = vec![];
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| SprtError::Parse(format!("Invalid backup: {}", e)))?;
+        match name.as_str() {
+            "manifest.json" => {
+                manifest = Some(
+                    serde_json::from_slice(&bytes)
+                        .map_err(|e| SprtError::Parse(format!("Invalid backup manifest: {}", e)))?,
+                )
+            }
+            "sprt.db" => db_bytes = Some(bytes),
+            "settings.json" => {
+                settings_data = Some(
+                    serde_json::from_slice(&bytes)
+                        .map_err(|e| SprtError::Parse(format!("Invalid settings in backup: {}", e)))?,
+                )
+            }
+            _ => {}
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| {
+        SprtError::InvalidInput("Not an SPRT backup (missing manifest.json)".to_string())
+    })?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(SprtError::InvalidInput(format!(
+            "Backup was made by a newer SPRT ({}). Update SPRT to restore it.",
+            manifest.app_version
+        )));
+    }
+
+    if let Some(bytes) = db_bytes {
+        let staged = sprt_dir()
+            .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?
+            .join("sprt.db.restore");
+        fs::write(&staged, bytes).map_err(|e| SprtError::Storage(format!("Write error: {}", e)))?;
+        let result = storage::restore_database(&staged);
+        let _ = fs::remove_file(&staged);
+        result?;
+    }
+    if let Some(s) = settings_data {
+        settings::save(&s)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn export_all_data(path: Option<String>) -> Result<String, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || -> Result<String, SprtError> {
+        let dest = match path {
+            Some(p) => PathBuf::from(p),
+            None => default_backup_path().ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?,
+        };
+        export_archive(&dest)?;
+        Ok(dest.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn import_all_data(path: String) -> Result<(), SprtError> {
+    tauri::async_runtime::spawn_blocking(move || import_archive(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,195 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::notifications;
+use crate::scheduler;
+use crate::session_index::DateRange;
+use crate::settings;
+use crate::storage;
+use crate::time::{self, DayClock};
+use crate::time_report;
+
+use chrono::Timelike;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const CHECK_INTERVAL_SECS: u64 = 5 * 60;
+/// Days before yesterday that make up the baseline
+const BASELINE_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BriefingDay {
+    pub date: String,
+    pub commits: u32,
+    pub tokens: u64,
+    /// Session time minus idle gaps
+    pub active_hours: f64,
+    /// From the day's devlog, when one was generated
+    pub sprint_score: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BriefingAverage {
+    pub commits: f64,
+    pub tokens: f64,
+    pub active_hours: f64,
+    /// Over days with a devlog only
+    pub sprint_score: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Briefing {
+    pub yesterday: BriefingDay,
+    /// The 7 days before yesterday, idle days included
+    pub average: BriefingAverage,
+    /// Notification text
+    pub title: String,
+    pub body: String,
+}
+
+fn compact(n: f64) -> String {
+    if n >= 1_000_000.0 {
+        format!("{:.1}M", n / 1_000_000.0)
+    } else if n >= 1000.0 {
+        format!("{:.0}k", n / 1000.0)
+    } else {
+        format!("{:.0}", n)
+    }
+}
+
+/// "+25%" / "−10%" against the average, or None when there's no baseline
+fn change(value: f64, avg: f64) -> Option<String> {
+    if avg <= 0.0 {
+        return None;
+    }
+    let pct = ((value - avg) / avg * 100.0).round();
+    Some(if pct >= 0.0 {
+        format!("+{:.0}%", pct)
+    } else {
+        format!("−{:.0}%", -pct)
+    })
+}
+
+fn render(y: &BriefingDay, avg: &BriefingAverage) -> String {
+    let mut facts = vec![
+        format!("{} commits", y.commits),
+        format!("{} tokens", compact(y.tokens as f64)),
+        format!("{:.1}h active", y.active_hours),
+    ];
+    if let Some(score) = y.sprint_score {
+        facts.push(format!("score {}", score));
+    }
+    let mut changes: Vec<String> = [
+        ("commits", change(y.commits as f64, avg.commits)),
+        ("tokens", change(y.tokens as f64, avg.tokens)),
+        ("hours", change(y.active_hours, avg.active_hours)),
+    ]
+    .into_iter()
+    .filter_map(|(name, c)| c.map(|c| format!("{} {}", name, c)))
+    .collect();
+    if let Some((score, avg_score)) = y.sprint_score.zip(avg.sprint_score) {
+        changes.push(format!("score {:+.0}", score as f64 - avg_score));
+    }
+    let mut body = facts.join(" · ");
+    if !changes.is_empty() {
+        body.push_str(&format!("\nvs 7-day avg: {}", changes.join(", ")));
+    }
+    body
+}
+
+/// Yesterday against the 7 days before it, from session logs, repos and stored devlogs
+pub fn briefing() -> Result<Briefing, SprtError> {
+    let clock = DayClock::load();
+    let yesterday = clock.today() - chrono::Duration::days(1);
+    let first = yesterday - chrono::Duration::days(BASELINE_DAYS);
+    let report = time_report::time_report(
+        DateRange {
+            from: Some(time::format_date(first)),
+            to: Some(time::format_date(yesterday)),
+        },
+        None,
+        None,
+        None,
+    )?;
+
+    let mut days: HashMap<String, BriefingDay> = HashMap::new();
+    for d in report.days {
+        let day = days.entry(d.date.clone()).or_default();
+        day.commits = d.commits;
+        day.active_hours = d.active_hours;
+    }
+    let start = clock
+        .day_start(first)
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .ok_or_else(|| SprtError::Internal("Cannot determine day start".to_string()))?;
+    claude::scan_usage(start, |e| {
+        days.entry(clock.date_key(&e.timestamp)).or_default().tokens += e.total_tokens();
+    })?;
+    for (date, score) in storage::daily_scores_since(&time::format_date(first))? {
+        days.entry(date).or_default().sprint_score = Some(score);
+    }
+
+    let key = time::format_date(yesterday);
+    let yesterday = BriefingDay {
+        date: key.clone(),
+        ..days.remove(&key).unwrap_or_default()
+    };
+    let baseline: Vec<&BriefingDay> = (1..=BASELINE_DAYS)
+        .filter_map(|back| {
+            let date = time::format_date(first + chrono::Duration::days(back - 1));
+            days.get(&date)
+        })
+        .collect();
+    let n = BASELINE_DAYS as f64;
+    let scores: Vec<u32> = baseline.iter().filter_map(|d| d.sprint_score).collect();
+    let average = BriefingAverage {
+        commits: baseline.iter().map(|d| d.commits as f64).sum::<f64>() / n,
+        tokens: baseline.iter().map(|d| d.tokens as f64).sum::<f64>() / n,
+        active_hours: baseline.iter().map(|d| d.active_hours).sum::<f64>() / n,
+        sprint_score: (!scores.is_empty())
+            .then(|| scores.iter().map(|s| *s as f64).sum::<f64>() / scores.len() as f64),
+    };
+
+    Ok(Briefing {
+        title: format!("Yesterday ({})", yesterday.date),
+        body: render(&yesterday, &average),
+        yesterday,
+        average,
+    })
+}
+
+/// Send the briefing once per day, at or after the configured hour. Days with no commits
+/// and no tokens are skipped.
+fn check_and_send() -> Result<(), SprtError> {
+    let mut s = settings::load();
+    let cfg = &s.morning_briefing;
+    if !cfg.enabled || chrono::Local::now().hour() < cfg.hour {
+        return Ok(());
+    }
+    let today = DayClock::load().today_key();
+    if cfg.last_sent.as_deref() == Some(today.as_str()) {
+        return Ok(());
+    }
+    let b = briefing()?;
+    let active = b.yesterday.commits > 0 || b.yesterday.tokens > 0;
+    if active && !notifications::notify_app(&b.title, &b.body) {
+        return Ok(());
+    }
+    s.morning_briefing.last_sent = Some(today);
+    settings::save(&s)
+}
+
+/// Background check for the morning briefing; off unless `morningBriefing.enabled`
+pub fn start() {
+    scheduler::every("Morning briefing", CHECK_INTERVAL_SECS, check_and_send);
+}
+
+/// Yesterday's commits, tokens, active hours and score against the 7-day average
+#[tauri::command]
+pub async fn get_morning_briefing() -> Result<Briefing, SprtError> {
+    tauri::async_runtime::spawn_blocking(briefing)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,170 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::notifications;
+use crate::scheduler;
+use crate::settings::{self, BudgetSettings};
+use crate::time::{self, DayClock};
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+
+/// How often the background check looks at pacing
+const CHECK_INTERVAL_SECS: u64 = 5 * 60;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    /// "tokens" or "utilization" (percent of the 7-day limit)
+    pub mode: String,
+    pub weekly_budget: f64,
+    pub used: f64,
+    pub remaining: f64,
+    /// Budget week: aligned to the 7-day rate-limit window when known, else Monday–Sunday
+    pub week_start: String,
+    pub week_end: String,
+    /// 1-based day of the budget week that today is
+    pub day_of_week: u32,
+    /// Even split: weekly_budget / 7
+    pub daily_allowance: f64,
+    /// What's left before today, spread over today and the remaining days
+    pub today_allowance: f64,
+    /// Known in tokens mode only; utilization isn't broken down by day
+    pub today_used: Option<f64>,
+    /// Even-split budget through the end of today
+    pub allowed_to_date: f64,
+    pub on_pace: bool,
+    /// Today alone went over its share
+    pub over_today: bool,
+}
+
+/// Budget week containing now
+fn budget_week(clock: &DayClock) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let reset = claude::get_cached_rate_limits()
+        .and_then(|i| i.seven_day)
+        .and_then(|c| c.reset)
+        .and_then(|r| DateTime::from_timestamp(r as i64, 0));
+    if let Some(end) = reset.filter(|r| *r > Utc::now()) {
+        return Some((end - Duration::days(7), end));
+    }
+    let today = clock.today();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let start = clock.day_start(monday)?.with_timezone(&Utc);
+    let end = clock.day_start(monday + Duration::days(7))?.with_timezone(&Utc);
+    Some((start, end))
+}
+
+pub fn budget_status() -> Result<Option<BudgetStatus>, SprtError> {
+    let cfg: BudgetSettings = settings::load().budget;
+    if cfg.weekly_tokens.is_none() && cfg.weekly_utilization_pct.is_none() {
+        return Ok(None);
+    }
+
+    let clock = DayClock::load();
+    let (week_start, week_end) = budget_week(&clock)
+        .ok_or_else(|| SprtError::Internal("Cannot determine budget week".to_string()))?;
+    let now = Utc::now();
+    let day_of_week = ((now - week_start).num_days() + 1).clamp(1, 7) as u32;
+    let days_left = (8 - day_of_week) as f64;
+
+    let (mode, weekly_budget, used, used_before_today, today_used) = match cfg.weekly_tokens {
+        Some(tokens) => {
+            let stats = claude::compute_realtime_stats()?;
+            let first_day = time::format_date(clock.date_of(&week_start));
+            let today = clock.today_key();
+            let in_week: u64 = stats
+                .daily_tokens
+                .iter()
+                .filter(|(d, _)| **d >= first_day)
+                .map(|(_, t)| *t)
+                .sum();
+            let today_used = stats.daily_tokens.get(&today).copied().unwrap_or(0) as f64;
+            let used = in_week as f64;
+            ("tokens", tokens as f64, used, used - today_used, Some(today_used))
+        }
+        None => {
+            let budget = cfg.weekly_utilization_pct.unwrap_or(100.0);
+            let used = claude::get_cached_rate_limits()
+                .and_then(|i| i.seven_day)
+                .map(|c| c.utilization * 100.0)
+                .unwrap_or(0.0);
+            ("utilization", budget, used, used, None)
+        }
+    };
+
+    let daily_allowance = weekly_budget / 7.0;
+    let today_allowance = ((weekly_budget - used_before_today) / days_left).max(0.0);
+    let allowed_to_date = daily_allowance * day_of_week as f64;
+    let over_today = match today_used {
+        Some(t) => t > today_allowance,
+        None => used > allowed_to_date,
+    };
+
+    Ok(Some(BudgetStatus {
+        mode: mode.to_string(),
+        weekly_budget,
+        used,
+        remaining: (weekly_budget - used).max(0.0),
+        week_start: week_start.to_rfc3339(),
+        week_end: week_end.to_rfc3339(),
+        day_of_week,
+        daily_allowance,
+        today_allowance,
+        today_used,
+        allowed_to_date,
+        on_pace: used <= allowed_to_date,
+        over_today,
+    }))
+}
+
+/// Date key of the last over-budget warning, so each day warns at most once
+static LAST_WARNED: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::new(None));
+
+fn check_and_warn() {
+    if !settings::load().budget.notify {
+        return;
+    }
+    let status = match budget_status() {
+        Ok(Some(s)) if s.over_today => s,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Budget check failed: {e}");
+            return;
+        }
+    };
+
+    let today = DayClock::load().today_key();
+    let mut last = LAST_WARNED.lock().unwrap_or_else(|e| e.into_inner());
+    if last.as_deref() == Some(today.as_str()) {
+        return;
+    }
+    let body = match status.today_used {
+        Some(used) => format!(
+            "Today used {:.0} tokens; today's share is {:.0}.",
+            used, status.today_allowance
+        ),
+        None => format!(
+            "{:.0}% of the weekly limit used; the plan allows {:.0}% by today.",
+            status.used, status.allowed_to_date
+        ),
+    };
+    if notifications::notify_app("Over today's budget", &body) {
+        *last = Some(today);
+    }
+}
+
+/// Background pacing check; only notifies when `budget.notify` is on
+pub fn start() {
+    scheduler::every("Budget check", CHECK_INTERVAL_SECS, || {
+        check_and_warn();
+        Ok(())
+    });
+}
+
+/// None when no budget is configured
+#[tauri::command]
+pub async fn get_budget_status() -> Result<Option<BudgetStatus>, SprtError> {
+    tauri::async_runtime::spawn_blocking(budget_status)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
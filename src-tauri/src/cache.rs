@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bound on staleness when the watcher misses a change (or nothing changed but
+/// time-based cutoffs like "last 48h" and the day rollover moved)
+const TTL: Duration = Duration::from_secs(60);
+
+pub const REALTIME_STATS: &str = "realtime_stats";
+pub const PROJECT_USAGE: &str = "project_usage";
+pub const ACTIVE_SESSIONS: &str = "active_sessions";
+
+/// Results derived from the session JSONL files under projects/
+const PROJECT_SCANS: &[&str] = &[REALTIME_STATS, PROJECT_USAGE, ACTIVE_SESSIONS];
+
+struct Entry {
+    at: Instant,
+    /// How long the computation took
+    took: Duration,
+    /// Generation the value was computed in; older than the key's current one means stale
+    generation: u64,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<&'static str, Entry>,
+    /// Bumped by invalidate, so a scan that started before a change doesn't store its result
+    generations: HashMap<&'static str, u64>,
+    /// One computation per key at a time; concurrent callers wait and reuse the result
+    computing: HashMap<&'static str, Arc<Mutex<()>>>,
+}
+
+static CACHE: LazyLock<Mutex<Cache>> = LazyLock::new(|| Mutex::new(Cache::default()));
+
+fn lookup<T: Clone + 'static>(cache: &Cache, key: &str) -> Option<T> {
+    let entry = cache.entries.get(key)?;
+    let current = cache.generations.get(key).copied().unwrap_or(0);
+    if entry.generation != current || entry.at.elapsed() >= TTL {
+        return None;
+    }
+    entry.value.downcast_ref::<T>().cloned()
+}
+
+/// Return the cached value for `key`, or run `compute` and cache it on success.
+/// Errors are not cached.
+pub fn cached<T, E>(key: &'static str, compute: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let gate = {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(v) = lookup(&cache, key) {
+            return Ok(v);
+        }
+        cache.computing.entry(key).or_default().clone()
+    };
+
+    let _computing = gate.lock().unwrap_or_else(|e| e.into_inner());
+    // Another caller may have filled it while we waited
+    let generation = {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(v) = lookup(&cache, key) {
+            return Ok(v);
+        }
+        cache.generations.get(key).copied().unwrap_or(0)
+    };
+
+    let started = Instant::now();
+    let value = compute()?;
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.generations.get(key).copied().unwrap_or(0) == generation {
+        cache.entries.insert(
+            key,
+            Entry {
+                at: Instant::now(),
+                took: started.elapsed(),
+                generation,
+                value: Arc::new(value.clone()),
+            },
+        );
+    }
+    Ok(value)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntryInfo {
+    pub key: String,
+    pub age_secs: u64,
+    /// How long the last computation took
+    pub compute_ms: u64,
+    /// Invalidated or past the TTL; recomputed on next use
+    pub stale: bool,
+}
+
+/// What's cached right now, for health reporting
+pub fn snapshot() -> Vec<CacheEntryInfo> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries: Vec<CacheEntryInfo> = cache
+        .entries
+        .iter()
+        .map(|(key, e)| CacheEntryInfo {
+            key: key.to_string(),
+            age_secs: e.at.elapsed().as_secs(),
+            compute_ms: e.took.as_millis() as u64,
+            stale: e.generation != cache.generations.get(key).copied().unwrap_or(0)
+                || e.at.elapsed() >= TTL,
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    entries
+}
+
+pub fn invalidate(keys: &[&'static str]) {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    for key in keys {
+        *cache.generations.entry(key).or_insert(0) += 1;
+        cache.entries.remove(key);
+    }
+}
+
+/// Drop every entry, e.g. after sleep: `Instant` doesn't advance while a Mac sleeps, so
+/// the TTL alone would keep serving results from before it
+pub fn invalidate_all() {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let keys: Vec<&'static str> = cache.entries.keys().copied().collect();
+    for key in keys {
+        *cache.generations.entry(key).or_insert(0) += 1;
+        cache.entries.remove(key);
+    }
+}
+
+/// Called by the file watcher for every changed path
+pub fn on_file_changed(path: &Path) {
+    if path.extension().is_some_and(|e| e == "jsonl") {
+        invalidate(PROJECT_SCANS);
+    }
+}
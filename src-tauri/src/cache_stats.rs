@@ -0,0 +1,113 @@
+use crate::claude::{self, UsageEntry};
+use crate::error::SprtError;
+use crate::pricing;
+use crate::time::DayClock;
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheBucket {
+    pub input_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// Share of prompt tokens served from cache: read / (input + read + creation)
+    pub hit_rate: f64,
+    /// cache_read / input; None when there was no uncached input
+    pub read_to_input_ratio: Option<f64>,
+    /// Versus the same prompts uncached, at API list prices; negative when writes
+    /// cost more than reads saved
+    pub savings_usd: f64,
+}
+
+impl CacheBucket {
+    fn add(&mut self, e: &UsageEntry) {
+        self.input_tokens += e.input;
+        self.cache_read_tokens += e.cache_read;
+        self.cache_creation_tokens += e.cache_creation;
+        self.savings_usd += pricing::cache_savings_usd(e.model, e.cache_read, e.cache_creation);
+    }
+
+    fn finish(&mut self) {
+        let prompt = self.input_tokens + self.cache_read_tokens + self.cache_creation_tokens;
+        self.hit_rate = if prompt > 0 {
+            self.cache_read_tokens as f64 / prompt as f64
+        } else {
+            0.0
+        };
+        self.read_to_input_ratio = (self.input_tokens > 0)
+            .then(|| self.cache_read_tokens as f64 / self.input_tokens as f64);
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyedCacheBucket {
+    pub key: String,
+    #[serde(flatten)]
+    pub bucket: CacheBucket,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub days: u32,
+    pub total: CacheBucket,
+    pub by_day: BTreeMap<String, CacheBucket>,
+    /// Biggest savings first
+    pub by_project: Vec<KeyedCacheBucket>,
+    pub by_model: Vec<KeyedCacheBucket>,
+}
+
+fn ranked(map: HashMap<String, CacheBucket>) -> Vec<KeyedCacheBucket> {
+    let mut out: Vec<KeyedCacheBucket> = map
+        .into_iter()
+        .map(|(key, mut bucket)| {
+            bucket.finish();
+            KeyedCacheBucket { key, bucket }
+        })
+        .collect();
+    out.sort_by(|a, b| b.bucket.savings_usd.total_cmp(&a.bucket.savings_usd));
+    out
+}
+
+pub fn cache_stats(days: u32) -> Result<CacheStats, SprtError> {
+    let days = days.max(1);
+    let clock = DayClock::load();
+    let since = clock
+        .day_start(clock.today() - chrono::Duration::days(days as i64 - 1))
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|| chrono::Utc::now() - chrono::Duration::days(days as i64));
+
+    let mut total = CacheBucket::default();
+    let mut by_day: BTreeMap<String, CacheBucket> = BTreeMap::new();
+    let mut by_project: HashMap<String, CacheBucket> = HashMap::new();
+    let mut by_model: HashMap<String, CacheBucket> = HashMap::new();
+
+    claude::scan_usage(since, |e| {
+        total.add(e);
+        by_day.entry(clock.date_key(&e.timestamp)).or_default().add(e);
+        by_project.entry(e.project.to_string()).or_default().add(e);
+        by_model.entry(e.model.to_string()).or_default().add(e);
+    })?;
+
+    total.finish();
+    by_day.values_mut().for_each(CacheBucket::finish);
+    Ok(CacheStats {
+        days,
+        total,
+        by_day,
+        by_project: ranked(by_project),
+        by_model: ranked(by_model),
+    })
+}
+
+/// Prompt-caching effectiveness over the last `days` days (default 30)
+#[tauri::command]
+pub async fn get_cache_stats(days: Option<u32>) -> Result<CacheStats, SprtError> {
+    let days = days.unwrap_or(30);
+    tauri::async_runtime::spawn_blocking(move || cache_stats(days))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
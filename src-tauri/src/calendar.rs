@@ -0,0 +1,209 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::git;
+use crate::session_index::DateRange;
+use crate::storage;
+use crate::time::{self, DayClock};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const MAX_DAYS: i64 = 366;
+/// Blocks shorter than this (a quick question between meetings) are left off the calendar
+const MIN_BLOCK_MINUTES: i64 = 5;
+
+/// Continuous work on one project: messages no further apart than the idle gap
+struct WorkBlock {
+    project: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    messages: u64,
+    tokens: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarExport {
+    pub path: String,
+    pub from: String,
+    pub to: String,
+    pub events: usize,
+}
+
+/// Split each project's messages into blocks wherever the gap exceeds the idle gap
+fn work_blocks(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<WorkBlock>, SprtError> {
+    let mut by_project: HashMap<String, Vec<(DateTime<Utc>, u64)>> = HashMap::new();
+    claude::scan_usage(start, |e| {
+        if e.timestamp < end {
+            by_project
+                .entry(e.project.to_string())
+                .or_default()
+                .push((e.timestamp, e.total_tokens()));
+        }
+    })?;
+
+    let idle_gap = claude::idle_gap();
+    let mut blocks = vec![];
+    for (project, mut messages) in by_project {
+        messages.sort_by_key(|(t, _)| *t);
+        let name = {
+            let path = git::decode_project_path(&project);
+            Path::new(&path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or(path)
+        };
+        let mut current: Option<WorkBlock> = None;
+        for (t, tokens) in messages {
+            if let Some(block) = current.as_mut().filter(|b| t - b.end <= idle_gap) {
+                block.end = t;
+                block.messages += 1;
+                block.tokens += tokens;
+                continue;
+            }
+            blocks.extend(current.replace(WorkBlock {
+                project: name.clone(),
+                start: t,
+                end: t,
+                messages: 1,
+                tokens,
+            }));
+        }
+        blocks.extend(current);
+    }
+    blocks.retain(|b| (b.end - b.start).num_minutes() >= MIN_BLOCK_MINUTES);
+    blocks.sort_by_key(|b| b.start);
+    Ok(blocks)
+}
+
+fn ics_time(t: &DateTime<Utc>) -> String {
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn ics_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold to 75-octet lines as RFC 5545 requires, never splitting a UTF-8 character
+fn fold(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn render(blocks: &[WorkBlock]) -> String {
+    let now = ics_time(&Utc::now());
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//SPRT//Work blocks//EN",
+        "CALSCALE:GREGORIAN",
+        "X-WR-CALNAME:SPRT coding time",
+    ] {
+        fold(line, &mut out);
+    }
+    for b in blocks {
+        let uid: String = b
+            .project
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        for line in [
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}-{}@sprt", uid, b.start.timestamp()),
+            format!("DTSTAMP:{}", now),
+            format!("DTSTART:{}", ics_time(&b.start)),
+            format!("DTEND:{}", ics_time(&b.end)),
+            format!("SUMMARY:{}", ics_escape(&b.project)),
+            format!(
+                "DESCRIPTION:{}",
+                ics_escape(&format!("{} messages, {} tokens", b.messages, b.tokens))
+            ),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ] {
+            fold(&line, &mut out);
+        }
+    }
+    fold("END:VCALENDAR", &mut out);
+    out
+}
+
+pub fn export(range: DateRange, path: Option<String>) -> Result<CalendarExport, SprtError> {
+    let clock = DayClock::load();
+    let parse = |d: Option<&str>, default| -> Result<chrono::NaiveDate, SprtError> {
+        match d {
+            Some(d) => time::parse_date(d)
+                .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", d))),
+            None => Ok(default),
+        }
+    };
+    let to = parse(range.to.as_deref(), clock.today())?;
+    let from = parse(range.from.as_deref(), to - chrono::Duration::days(6))?;
+    if from > to {
+        return Err(SprtError::InvalidInput(
+            "Range start is after its end".to_string(),
+        ));
+    }
+    if (to - from).num_days() >= MAX_DAYS {
+        return Err(SprtError::InvalidInput(format!(
+            "Calendar exports cover at most {} days",
+            MAX_DAYS
+        )));
+    }
+    let start = clock.day_start(from).map(|t| t.with_timezone(&Utc));
+    let end = to
+        .succ_opt()
+        .and_then(|d| clock.day_start(d))
+        .map(|t| t.with_timezone(&Utc));
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(SprtError::InvalidInput("Invalid date range".to_string()));
+    };
+
+    let blocks = work_blocks(start, end)?;
+    let (from, to) = (time::format_date(from), time::format_date(to));
+    let dest = match path {
+        Some(p) => PathBuf::from(p),
+        None => storage::sprt_dir()
+            .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?
+            .join("export")
+            .join(format!("sprt-{}-{}.ics", from, to)),
+    };
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| SprtError::Storage(format!("Cannot create directory: {}", e)))?;
+    }
+    storage::write_atomic(&dest, &render(&blocks))?;
+
+    Ok(CalendarExport {
+        path: dest.to_string_lossy().to_string(),
+        from,
+        to,
+        events: blocks.len(),
+    })
+}
+
+/// Write coding time in a date range (default the last 7 days) as an .ics file: one event per
+/// work block, split at idle gaps and titled with the project. Returns where it was written.
+#[tauri::command]
+pub async fn export_calendar(
+    range: Option<DateRange>,
+    path: Option<String>,
+) -> Result<CalendarExport, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || export(range.unwrap_or_default(), path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
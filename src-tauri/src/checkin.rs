@@ -0,0 +1,60 @@
+use crate::error::SprtError;
+use crate::storage::{self, Checkin};
+use crate::time::{self, DayClock};
+
+fn validate_level(name: &str, value: u8) -> Result<u8, SprtError> {
+    if (1..=5).contains(&value) {
+        Ok(value)
+    } else {
+        Err(SprtError::InvalidInput(format!(
+            "{} must be between 1 and 5",
+            name
+        )))
+    }
+}
+
+pub fn set_checkin(
+    date: &str,
+    mood: u8,
+    energy: u8,
+    note: Option<String>,
+) -> Result<Checkin, SprtError> {
+    if time::parse_date(date).is_none() {
+        return Err(SprtError::InvalidInput(format!("Invalid date: {}", date)));
+    }
+    let checkin = Checkin {
+        date: date.to_string(),
+        mood: validate_level("Mood", mood)?,
+        energy: validate_level("Energy", energy)?,
+        note: note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    storage::save_checkin(&checkin)?;
+
+    // A devlog already written for the day carries the check-in too
+    if let Some(mut log) = storage::get_devlog(date, "daily")? {
+        log.checkin = Some(checkin.clone());
+        storage::save_devlog(&log)?;
+    }
+    Ok(checkin)
+}
+
+/// Record how the day went; mood and energy are 1–5. Replaces any earlier check-in for the date.
+#[tauri::command]
+pub fn set_daily_checkin(
+    date: String,
+    mood: u8,
+    energy: u8,
+    note: Option<String>,
+) -> Result<Checkin, SprtError> {
+    set_checkin(&date, mood, energy, note)
+}
+
+/// Check-ins from the last `days` days (default 30), oldest first
+#[tauri::command]
+pub fn get_checkin_history(days: Option<u32>) -> Result<Vec<Checkin>, SprtError> {
+    let clock = DayClock::load();
+    let days = days.unwrap_or(30).max(1);
+    let since = clock.today() - chrono::Duration::days(days as i64 - 1);
+    storage::checkins_since(&time::format_date(since))
+}
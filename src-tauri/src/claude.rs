@@ -1,5 +1,10 @@
+use crate::diagnostics::{self, DiagnosticKind};
+use crate::error::SprtError;
+use crate::providers::ProviderStats;
+use crate::time::DayClock;
+
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
@@ -71,6 +76,80 @@ pub struct SessionInfo {
     pub project: String,
     pub message_count: u64,
     pub last_active: String,
+    pub title: Option<String>,
+    /// Data root label, "local" for this machine's
+    pub source: String,
+}
+
+/// Session titles longer than this are cut with an ellipsis
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Picks a session title from JSONL lines: the first "summary" record (Claude Code writes
+/// these when compacting or resuming) or, failing that, the first real user prompt.
+#[derive(Default)]
+pub(crate) struct SessionTitle {
+    summary: Option<String>,
+    first_prompt: Option<String>,
+}
+
+impl SessionTitle {
+    pub(crate) fn observe(&mut self, line: &str) {
+        if self.summary.is_none() && line.contains("\"type\":\"summary\"") {
+            let entry: serde_json::Value = match serde_json::from_str(line) {
+                Ok(v) => v,
+                Err(_) => return,
+            };
+            self.summary = entry
+                .get("summary")
+                .and_then(|v| v.as_str())
+                .and_then(clean_title);
+        } else if self.first_prompt.is_none() && line.contains("\"type\":\"user\"") {
+            let entry: serde_json::Value = match serde_json::from_str(line) {
+                Ok(v) => v,
+                Err(_) => return,
+            };
+            // Subagent prompts are written by the Task tool, not the user
+            if entry.get("isMeta").and_then(|v| v.as_bool()) == Some(true) || is_sidechain(&entry) {
+                return;
+            }
+            // content is a plain string for typed prompts, or blocks (tool results, images)
+            let content = entry.get("message").and_then(|m| m.get("content"));
+            let text = match content {
+                Some(serde_json::Value::String(s)) => Some(s.as_str()),
+                Some(serde_json::Value::Array(blocks)) => blocks
+                    .iter()
+                    .find(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                    .and_then(|b| b.get("text"))
+                    .and_then(|t| t.as_str()),
+                _ => None,
+            };
+            self.first_prompt = text.and_then(clean_title);
+        }
+    }
+
+    pub(crate) fn into_title(self) -> Option<String> {
+        self.summary.or(self.first_prompt)
+    }
+}
+
+/// First line, trimmed and shortened. Skips slash-command and system wrappers like
+/// "<command-name>" that aren't something the user wrote.
+fn clean_title(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    if line.starts_with('<') {
+        return None;
+    }
+    if line.chars().count() > MAX_TITLE_CHARS {
+        let cut: String = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+        return Some(format!("{}…", cut.trim_end()));
+    }
+    Some(line.to_string())
+}
+
+/// Entries written by Task-tool subagents are marked isSidechain and chain back to the
+/// main conversation through parentUuid; their tokens count against the same budget.
+pub(crate) fn is_sidechain(entry: &serde_json::Value) -> bool {
+    entry.get("isSidechain").and_then(|v| v.as_bool()) == Some(true)
 }
 
 // ── Project Usage ──
@@ -80,157 +159,404 @@ pub struct SessionInfo {
 pub struct ProjectUsage {
     pub project: String,
     pub session_count: u64,
+    /// Assistant messages, subagents included
     pub total_messages: u64,
+    /// Input + output + cache tokens
+    pub total_tokens: u64,
+    /// Newest session file modification, RFC 3339
+    pub last_active: Option<String>,
 }
 
-fn claude_dir() -> Option<PathBuf> {
-    dirs::home_dir().map(|h| h.join(".claude"))
-}
+const DEFAULT_PROJECT_LIMIT: u32 = 10;
+const DEFAULT_SESSION_LIMIT: u32 = 20;
 
-#[tauri::command]
-pub async fn get_stats_cache() -> Result<StatsCache, String> {
-    tauri::async_runtime::spawn_blocking(|| -> Result<StatsCache, String> {
-        let path = claude_dir()
-            .ok_or("Cannot find home directory")?
-            .join("stats-cache.json");
-
-        let content = match fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(_) => return Ok(StatsCache::default()),
-        };
+/// Skip `offset` items and keep `limit` (0 = all)
+fn page<T>(items: Vec<T>, offset: u32, limit: u32) -> Vec<T> {
+    let items = items.into_iter().skip(offset as usize);
+    if limit == 0 {
+        items.collect()
+    } else {
+        items.take(limit as usize).collect()
+    }
+}
 
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Cannot parse stats-cache.json: {}", e))
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+/// Claude Code's config directory: $CLAUDE_CONFIG_DIR when set, else ~/.claude
+pub(crate) fn claude_dir() -> Option<PathBuf> {
+    crate::paths::get().claude_dir.clone()
 }
 
-#[tauri::command]
-pub async fn get_active_sessions() -> Result<Vec<SessionInfo>, String> {
-    tauri::async_runtime::spawn_blocking(|| -> Result<Vec<SessionInfo>, String> {
-        let claude_dir = claude_dir().ok_or("Cannot find home directory")?;
-        let projects_dir = claude_dir.join("projects");
+/// Source label of this machine's own Claude data directory
+pub const LOCAL_SOURCE: &str = "local";
 
-        if !projects_dir.exists() {
-            return Ok(vec![]);
+/// A Claude data directory: this machine's, or one from `extraClaudeDirs` (a work VM's
+/// mount, an rsynced copy from another machine)
+#[derive(Debug, Clone)]
+pub struct DataRoot {
+    pub label: String,
+    pub dir: PathBuf,
+}
+
+/// Extra roots as of the settings file's mtime. Scans run every minute; this way they
+/// don't re-read settings, and a missing extra directory is reported once per change.
+type RootsCache = Option<(Option<std::time::SystemTime>, Vec<DataRoot>)>;
+static ROOTS: LazyLock<Mutex<RootsCache>> = LazyLock::new(|| Mutex::new(None));
+
+fn extra_roots() -> Vec<DataRoot> {
+    let local = claude_dir();
+    let mut roots: Vec<DataRoot> = vec![];
+    for extra in crate::settings::load().extra_claude_dirs {
+        let dir = crate::git::expand_home(&extra.path);
+        if local.as_ref() == Some(&dir) || roots.iter().any(|r| r.dir == dir) {
+            continue;
         }
+        if !dir.is_dir() {
+            log::warn!("Extra Claude directory not found: {}", dir.display());
+        }
+        let label = extra.label.filter(|l| !l.is_empty()).unwrap_or_else(|| {
+            dir.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| extra.path.clone())
+        });
+        roots.push(DataRoot { label, dir });
+    }
+    roots
+}
 
-        let mut sessions: Vec<SessionInfo> = Vec::new();
+/// This machine's data directory first, then the configured extra ones that exist (a mount
+/// that comes up later is picked up without a settings change)
+pub fn data_roots() -> Vec<DataRoot> {
+    let stamp = crate::settings::modified();
+    let mut cached = ROOTS.lock().unwrap_or_else(|e| e.into_inner());
+    let extra = match cached.as_ref() {
+        Some((at, roots)) if *at == stamp => roots,
+        _ => &cached.insert((stamp, extra_roots())).1,
+    };
+    let local = claude_dir().map(|dir| DataRoot {
+        label: LOCAL_SOURCE.to_string(),
+        dir,
+    });
+    local
+        .into_iter()
+        .chain(extra.iter().filter(|r| r.dir.is_dir()).cloned())
+        .collect()
+}
 
-        let pattern = projects_dir
-            .join("*/*.jsonl")
-            .to_string_lossy()
-            .to_string();
+/// Session logs across data roots as (source label, path). A session found in more than one
+/// root, as with a copy of this machine's data, is read once: from its newest copy.
+pub(crate) fn session_logs() -> Result<Vec<(String, PathBuf)>, SprtError> {
+    let mut newest: HashMap<PathBuf, (std::time::SystemTime, String, PathBuf)> = HashMap::new();
+    for root in data_roots() {
+        let projects_dir = root.dir.join("projects");
+        if !projects_dir.exists() {
+            continue;
+        }
+        let pattern = projects_dir.join("*/*.jsonl").to_string_lossy().to_string();
+        let paths = glob::glob(&pattern).map_err(|e| format!("Glob error: {}", e))?;
+        for path in paths.filter_map(|p| p.ok()) {
+            let Ok(relative) = path.strip_prefix(&projects_dir).map(PathBuf::from) else {
+                continue;
+            };
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::UNIX_EPOCH);
+            if newest.get(&relative).is_none_or(|(t, _, _)| modified > *t) {
+                newest.insert(relative, (modified, root.label.clone(), path));
+            }
+        }
+    }
+    let mut logs: Vec<(String, PathBuf)> = newest
+        .into_values()
+        .map(|(_, label, path)| (label, path))
+        .collect();
+    logs.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(logs)
+}
 
-        let paths: Vec<PathBuf> = glob::glob(&pattern)
-            .map_err(|e| format!("Glob error: {}", e))?
-            .filter_map(|p| p.ok())
-            .collect();
+/// Read ~/.claude/stats-cache.json; a missing file reads as empty stats
+pub fn read_stats_cache() -> Result<StatsCache, SprtError> {
+    let path = require_claude_dir()?
+        .join("stats-cache.json");
 
-        for path in paths {
-            if let Ok(modified_time) = fs::metadata(&path).and_then(|m| m.modified()) {
-                let elapsed = modified_time.elapsed().unwrap_or_default();
-                if elapsed.as_secs() > 172800 {
-                    continue;
-                }
+    let content = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) => {
+            let msg = format!("Cannot read stats-cache.json: {}", e);
+            diagnostics::critical("stats-cache", &path, diagnostics::io_kind(&e), msg);
+            return Ok(StatsCache::default());
+        }
+    };
 
-                let modified_str = {
-                    let dt: chrono::DateTime<chrono::Utc> = modified_time.into();
-                    dt.to_rfc3339()
-                };
-
-                let project = path
-                    .parent()
-                    .and_then(|p| p.file_name())
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-
-                let session_id = path
-                    .file_stem()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-
-                // BufReader streaming — no full file load into memory
-                let message_count = match fs::File::open(&path) {
-                    Ok(f) => BufReader::new(f).lines().count() as u64,
-                    Err(_) => 0,
-                };
-
-                sessions.push(SessionInfo {
-                    session_id,
-                    project,
-                    message_count,
-                    last_active: modified_str,
-                });
-            }
+    match serde_json::from_str(&content) {
+        Ok(cache) => {
+            diagnostics::clear_file(&path);
+            Ok(cache)
         }
+        Err(e) => {
+            let msg = format!("Cannot parse stats-cache.json: {}", e);
+            diagnostics::critical("stats-cache", &path, DiagnosticKind::InvalidJson, msg.clone());
+            Err(SprtError::Parse(msg))
+        }
+    }
+}
 
-        sessions.sort_by(|a, b| b.last_active.cmp(&a.last_active));
-        sessions.truncate(20);
+fn require_claude_dir() -> Result<PathBuf, SprtError> {
+    claude_dir().ok_or_else(|| SprtError::ClaudeNotFound("Cannot find home directory".to_string()))
+}
 
-        Ok(sessions)
+#[tauri::command]
+pub async fn get_stats_cache() -> Result<StatsCache, SprtError> {
+    tauri::async_runtime::spawn_blocking(|| -> Result<StatsCache, SprtError> {
+        let cache = read_stats_cache()?;
+        if let Err(e) = crate::stats_history::record_snapshot(&cache) {
+            log::warn!("{e}");
+        }
+        Ok(cache)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
+/// Sessions active in the last 48 hours. limit defaults to 20 (0 = all);
+/// sort: "recent" (default) or "messages".
 #[tauri::command]
-pub async fn get_project_usage() -> Result<Vec<ProjectUsage>, String> {
-    tauri::async_runtime::spawn_blocking(|| -> Result<Vec<ProjectUsage>, String> {
-        let claude_dir = claude_dir().ok_or("Cannot find home directory")?;
-        let projects_dir = claude_dir.join("projects");
+pub async fn get_active_sessions(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    sort: Option<String>,
+) -> Result<Vec<SessionInfo>, SprtError> {
+    let mut sessions = tauri::async_runtime::spawn_blocking(|| {
+        crate::cache::cached(crate::cache::ACTIVE_SESSIONS, scan_active_sessions)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
 
-        if !projects_dir.exists() {
-            return Ok(vec![]);
-        }
+    if sort.as_deref() == Some("messages") {
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.message_count));
+    }
+    Ok(page(
+        sessions,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_SESSION_LIMIT),
+    ))
+}
 
-        let pattern = projects_dir
-            .join("*/*.jsonl")
-            .to_string_lossy()
-            .to_string();
+fn scan_active_sessions() -> Result<Vec<SessionInfo>, SprtError> {
+    require_claude_dir()?;
+    let mut sessions: Vec<SessionInfo> = Vec::new();
 
-        let mut project_map: HashMap<String, (u64, u64)> = HashMap::new();
+    for (source, path) in session_logs()? {
+        if let Ok(modified_time) = fs::metadata(&path).and_then(|m| m.modified()) {
+            let elapsed = modified_time.elapsed().unwrap_or_default();
+            if elapsed.as_secs() > 172800 {
+                continue;
+            }
 
-        let paths: Vec<PathBuf> = glob::glob(&pattern)
-            .map_err(|e| format!("Glob error: {}", e))?
-            .filter_map(|p| p.ok())
-            .collect();
+            let modified_str = {
+                let dt: chrono::DateTime<chrono::Utc> = modified_time.into();
+                dt.to_rfc3339()
+            };
 
-        for path in paths {
             let project = path
                 .parent()
                 .and_then(|p| p.file_name())
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_default();
 
+            let session_id = path
+                .file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
             // BufReader streaming — no full file load into memory
-            let msgs = match fs::File::open(&path) {
-                Ok(f) => BufReader::new(f).lines().count() as u64,
+            let mut title = SessionTitle::default();
+            let message_count = match fs::File::open(&path) {
+                Ok(f) => BufReader::new(f)
+                    .lines()
+                    .map_while(Result::ok)
+                    .inspect(|line| title.observe(line))
+                    .count() as u64,
                 Err(_) => 0,
             };
 
-            let entry = project_map.entry(project).or_insert((0, 0));
-            entry.0 += 1;
-            entry.1 += msgs;
+            sessions.push(SessionInfo {
+                session_id,
+                project,
+                message_count,
+                last_active: modified_str,
+                title: title.into_title(),
+                source,
+            });
         }
+    }
 
-        let mut usages: Vec<ProjectUsage> = project_map
-            .into_iter()
-            .map(|(project, (session_count, total_messages))| ProjectUsage {
-                project,
-                session_count,
-                total_messages,
-            })
-            .collect();
+    sessions.sort_by(|a, b| b.last_active.cmp(&a.last_active));
 
-        usages.sort_by(|a, b| b.total_messages.cmp(&a.total_messages));
-        usages.truncate(10);
+    Ok(sessions)
+}
 
-        Ok(usages)
+/// limit defaults to 10 (0 = all);
+/// sort: "messages" (default), "sessions", "tokens" or "recent".
+#[tauri::command]
+pub async fn get_project_usage(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    sort: Option<String>,
+) -> Result<Vec<ProjectUsage>, SprtError> {
+    let mut usages = tauri::async_runtime::spawn_blocking(|| {
+        crate::cache::cached(crate::cache::PROJECT_USAGE, scan_project_usage)
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    match sort.as_deref() {
+        Some("sessions") => usages.sort_by_key(|u| std::cmp::Reverse(u.session_count)),
+        Some("tokens") => usages.sort_by_key(|u| std::cmp::Reverse(u.total_tokens)),
+        Some("recent") => usages.sort_by(|a, b| b.last_active.cmp(&a.last_active)),
+        _ => {}
+    }
+    Ok(page(
+        usages,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_PROJECT_LIMIT),
+    ))
+}
+
+/// From the session index, which only rereads logs whose size or mtime changed
+fn scan_project_usage() -> Result<Vec<ProjectUsage>, SprtError> {
+    require_claude_dir()?;
+    crate::session_index::refresh()?;
+    let mut usages: Vec<ProjectUsage> = crate::storage::project_totals()?
+        .into_iter()
+        .map(|t| ProjectUsage {
+            project: t.project,
+            session_count: t.sessions,
+            total_messages: t.messages,
+            total_tokens: t.tokens,
+            last_active: chrono::DateTime::from_timestamp(t.modified_at, 0).map(|t| t.to_rfc3339()),
+        })
+        .collect();
+    usages.sort_by_key(|u| std::cmp::Reverse(u.total_messages));
+
+    Ok(usages)
+}
+
+/// Input + output + cache tokens of an assistant line, 0 for anything else
+pub(crate) fn line_tokens(line: &str) -> u64 {
+    let entry: serde_json::Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(_) => return 0,
+    };
+    let Some(usage) = entry.get("message").and_then(|m| m.get("usage")) else {
+        return 0;
+    };
+    [
+        "input_tokens",
+        "output_tokens",
+        "cache_read_input_tokens",
+        "cache_creation_input_tokens",
+    ]
+    .iter()
+    .filter_map(|k| usage.get(*k).and_then(|v| v.as_u64()))
+    .sum()
+}
+
+/// One assistant response's token usage, as read by scan_usage
+pub(crate) struct UsageEntry<'a> {
+    /// Encoded projects dir name
+    pub project: &'a str,
+    /// Session log file stem
+    pub session_id: &'a str,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub model: &'a str,
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_creation: u64,
+    pub sidechain: bool,
+}
+
+impl UsageEntry<'_> {
+    pub fn total_tokens(&self) -> u64 {
+        self.input + self.output + self.cache_read + self.cache_creation
+    }
+
+    pub fn cost_usd(&self) -> f64 {
+        crate::pricing::cost_usd(self.model, self.input, self.output, self.cache_read, self.cache_creation)
+    }
+}
+
+/// Call `f` for every assistant response at or after `since`. Files last modified
+/// before `since` are skipped without being opened.
+pub(crate) fn scan_usage(
+    since: chrono::DateTime<chrono::Utc>,
+    mut f: impl FnMut(&UsageEntry),
+) -> Result<(), SprtError> {
+    require_claude_dir()?;
+    for (_, path) in &session_logs()? {
+        let modified = match fs::metadata(path).and_then(|m| m.modified()) {
+            Ok(t) => chrono::DateTime::<chrono::Utc>::from(t),
+            Err(_) => continue,
+        };
+        if modified < since {
+            continue;
+        }
+        let Ok(file) = fs::File::open(path) else {
+            continue;
+        };
+        let project = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let session_id = path
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if !line.contains("\"type\":\"assistant\"") {
+                continue;
+            }
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let Some(timestamp) = entry
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<chrono::DateTime<chrono::Utc>>().ok())
+            else {
+                continue;
+            };
+            if timestamp < since {
+                continue;
+            }
+            let Some(message) = entry.get("message") else {
+                continue;
+            };
+            let usage = message.get("usage");
+            let tokens = |key: &str| {
+                usage
+                    .and_then(|u| u.get(key))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0)
+            };
+            f(&UsageEntry {
+                project: &project,
+                session_id: &session_id,
+                timestamp,
+                model: message
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown"),
+                input: tokens("input_tokens"),
+                output: tokens("output_tokens"),
+                cache_read: tokens("cache_read_input_tokens"),
+                cache_creation: tokens("cache_creation_input_tokens"),
+                sidechain: is_sidechain(&entry),
+            });
+        }
+    }
+    Ok(())
 }
 
 // ── Realtime Stats from JSONL parsing ──
@@ -248,179 +574,307 @@ pub struct TokenUsage {
 #[serde(rename_all = "camelCase")]
 pub struct RealtimeStats {
     pub last_activity: Option<String>,
+    /// Working directory of the most recent assistant message
+    /// (the encoded projects dir name if the entry has no cwd)
+    pub last_project: Option<String>,
     pub today_messages: u64,
     pub today_tokens: TokenUsage,
     pub week_messages: u64,
     pub week_tokens: TokenUsage,
+    /// Estimated at API list prices (see pricing.rs)
+    pub today_cost_usd: f64,
+    pub week_cost_usd: f64,
+    /// Portion of today_tokens / week_tokens spent by subagents
+    pub today_subagent_tokens: TokenUsage,
+    pub week_subagent_tokens: TokenUsage,
     pub active_sessions: u64,
     pub plan_type: String,
     pub rate_limit_tier: String,
     pub today_model_tokens: HashMap<String, u64>,
     pub week_model_tokens: HashMap<String, u64>,
     pub daily_messages: HashMap<String, u64>,
+    /// Tokens (input + output + cache) per day, same keys as daily_messages
+    pub daily_tokens: HashMap<String, u64>,
+    /// Week tokens per data root label ("local" plus any extra Claude directories)
+    pub week_source_tokens: HashMap<String, u64>,
+    /// Today and the week per AI CLI: "claude" (the totals above) plus "codex" and "gemini"
+    /// when their logs exist
+    pub providers: HashMap<String, ProviderStats>,
 }
 
 #[tauri::command]
-pub async fn get_realtime_stats() -> Result<RealtimeStats, String> {
-    tauri::async_runtime::spawn_blocking(|| -> Result<RealtimeStats, String> {
-        let claude_dir = claude_dir().ok_or("Cannot find home directory")?;
-        let projects_dir = claude_dir.join("projects");
+pub async fn get_realtime_stats() -> Result<RealtimeStats, SprtError> {
+    tauri::async_runtime::spawn_blocking(realtime_stats)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
 
-        let creds_path = claude_dir.join(".credentials.json");
-        let (plan_type, rate_limit_tier) = read_credentials(&creds_path);
+pub fn compute_realtime_stats() -> Result<RealtimeStats, SprtError> {
+    let claude_dir = require_claude_dir()?;
+    let creds_path = claude_dir.join(".credentials.json");
+    let (plan_type, rate_limit_tier) = read_credentials(&creds_path);
+    let paths = session_logs()?;
+
+    if paths.is_empty() {
+        return Ok(RealtimeStats {
+            last_activity: None,
+            last_project: None,
+            today_messages: 0,
+            today_tokens: TokenUsage::default(),
+            week_messages: 0,
+            week_tokens: TokenUsage::default(),
+            today_cost_usd: 0.0,
+            week_cost_usd: 0.0,
+            today_subagent_tokens: TokenUsage::default(),
+            week_subagent_tokens: TokenUsage::default(),
+            active_sessions: 0,
+            plan_type,
+            rate_limit_tier,
+            today_model_tokens: HashMap::new(),
+            week_model_tokens: HashMap::new(),
+            daily_messages: HashMap::new(),
+            daily_tokens: HashMap::new(),
+            week_source_tokens: HashMap::new(),
+            providers: HashMap::new(),
+        });
+    }
 
-        if !projects_dir.exists() {
-            return Ok(RealtimeStats {
-                last_activity: None,
-                today_messages: 0,
-                today_tokens: TokenUsage::default(),
-                week_messages: 0,
-                week_tokens: TokenUsage::default(),
-                active_sessions: 0,
-                plan_type,
-                rate_limit_tier,
-                today_model_tokens: HashMap::new(),
-                week_model_tokens: HashMap::new(),
-                daily_messages: HashMap::new(),
-            });
+    let now = chrono::Utc::now();
+    let clock = DayClock::load();
+    let today_str = clock.today_key();
+    // Today plus the six days before it, on the same day boundaries as today
+    let week_ago = clock.week_start().unwrap_or(now - chrono::Duration::days(7));
+    let five_hours_ago = now - chrono::Duration::hours(5);
+
+    let mut last_activity: Option<chrono::DateTime<chrono::Utc>> = None;
+    let mut today_messages: u64 = 0;
+    let mut today_tokens = TokenUsage::default();
+    let mut week_messages: u64 = 0;
+    let mut week_tokens = TokenUsage::default();
+    let mut today_cost_usd = 0.0;
+    let mut week_cost_usd = 0.0;
+    let mut today_subagent_tokens = TokenUsage::default();
+    let mut week_subagent_tokens = TokenUsage::default();
+    let mut active_sessions: u64 = 0;
+    let mut today_model_tokens: HashMap<String, u64> = HashMap::new();
+    let mut week_model_tokens: HashMap<String, u64> = HashMap::new();
+    let mut daily_messages: HashMap<String, u64> = HashMap::new();
+    let mut daily_tokens: HashMap<String, u64> = HashMap::new();
+    let mut week_source_tokens: HashMap<String, u64> = HashMap::new();
+    let mut week_sessions: u64 = 0;
+    let mut last_project: Option<String> = None;
+
+    for (source, path) in &paths {
+        let modified = match fs::metadata(path).and_then(|m| m.modified()) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        let elapsed_secs = modified.elapsed().unwrap_or_default().as_secs();
+        if elapsed_secs > 8 * 86400 {
+            continue;
         }
 
-        let pattern = projects_dir
-            .join("*/*.jsonl")
-            .to_string_lossy()
-            .to_string();
-
-        let paths: Vec<PathBuf> = glob::glob(&pattern)
-            .map_err(|e| format!("Glob error: {}", e))?
-            .filter_map(|p| p.ok())
-            .collect();
-
-        let now = chrono::Utc::now();
-        let local_now = chrono::Local::now();
-        let today_str = local_now.format("%Y-%m-%d").to_string();
-        let week_ago = now - chrono::Duration::days(7);
-        let five_hours_ago = now - chrono::Duration::hours(5);
-
-        let mut last_activity: Option<chrono::DateTime<chrono::Utc>> = None;
-        let mut today_messages: u64 = 0;
-        let mut today_tokens = TokenUsage::default();
-        let mut week_messages: u64 = 0;
-        let mut week_tokens = TokenUsage::default();
-        let mut active_sessions: u64 = 0;
-        let mut today_model_tokens: HashMap<String, u64> = HashMap::new();
-        let mut week_model_tokens: HashMap<String, u64> = HashMap::new();
-        let mut daily_messages: HashMap<String, u64> = HashMap::new();
-
-        for path in &paths {
-            let modified = match fs::metadata(path).and_then(|m| m.modified()) {
-                Ok(t) => t,
+        let modified_dt: chrono::DateTime<chrono::Utc> = modified.into();
+        if modified_dt > five_hours_ago {
+            active_sessions += 1;
+        }
+
+        let file = match fs::File::open(path) {
+            Ok(f) => f,
+            Err(e) => {
+                let msg = format!("Cannot open session log: {}", e);
+                diagnostics::warn("session-log", path, None, diagnostics::io_kind(&e), msg);
+                continue;
+            }
+        };
+        diagnostics::clear_file(path);
+        let reader = BufReader::new(file);
+        let mut in_week = false;
+
+        for (idx, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(l) => l,
                 Err(_) => continue,
             };
-            let elapsed_secs = modified.elapsed().unwrap_or_default().as_secs();
-            if elapsed_secs > 8 * 86400 {
+            if line.is_empty() || !line.contains("\"type\":\"assistant\"") {
                 continue;
             }
 
-            let modified_dt: chrono::DateTime<chrono::Utc> = modified.into();
-            if modified_dt > five_hours_ago {
-                active_sessions += 1;
+            let entry: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(e) => {
+                    // A truncated last line is just Claude Code mid-write
+                    if !e.is_eof() {
+                        let msg = format!("Skipped malformed line: {}", e);
+                        diagnostics::warn("session-log", path, Some(idx as u64 + 1), DiagnosticKind::InvalidJson, msg);
+                    }
+                    continue;
+                }
+            };
+
+            if entry.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+                continue;
             }
 
-            let file = match fs::File::open(path) {
-                Ok(f) => f,
-                Err(_) => continue,
+            let timestamp_str = match entry.get("timestamp").and_then(|v| v.as_str()) {
+                Some(s) => s,
+                None => continue,
             };
-            let reader = BufReader::new(file);
-
-            for line in reader.lines() {
-                let line = match line {
-                    Ok(l) => l,
-                    Err(_) => continue,
-                };
-                if line.is_empty() || !line.contains("\"type\":\"assistant\"") {
-                    continue;
-                }
 
-                let entry: serde_json::Value = match serde_json::from_str(&line) {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
+            let ts = match timestamp_str.parse::<chrono::DateTime<chrono::Utc>>() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
 
-                if entry.get("type").and_then(|v| v.as_str()) != Some("assistant") {
-                    continue;
-                }
+            if last_activity.is_none_or(|la| ts > la) {
+                last_activity = Some(ts);
+                last_project = entry
+                    .get("cwd")
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+                    .or_else(|| {
+                        path.parent()
+                            .and_then(|p| p.file_name())
+                            .map(|n| n.to_string_lossy().to_string())
+                    });
+            }
 
-                let timestamp_str = match entry.get("timestamp").and_then(|v| v.as_str()) {
-                    Some(s) => s,
-                    None => continue,
-                };
+            if ts < week_ago {
+                continue;
+            }
 
-                let ts = match timestamp_str.parse::<chrono::DateTime<chrono::Utc>>() {
-                    Ok(t) => t,
-                    Err(_) => continue,
-                };
+            let day_key = clock.date_key(&ts);
+            let is_today = day_key == today_str;
 
-                if last_activity.map_or(true, |la| ts > la) {
-                    last_activity = Some(ts);
+            if let Some(usage) = entry.get("message").and_then(|m| m.get("usage")) {
+                let input = usage.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                let output = usage.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                let cache_read = usage.get("cache_read_input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                let cache_creation = usage.get("cache_creation_input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+
+                let model = entry.get("message")
+                    .and_then(|m| m.get("model"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+                let total_tokens = input + output + cache_read + cache_creation;
+                let sidechain = is_sidechain(&entry);
+                let cost = crate::pricing::cost_usd(model, input, output, cache_read, cache_creation);
+                week_cost_usd += cost;
+                if is_today {
+                    today_cost_usd += cost;
                 }
 
-                if ts < week_ago {
-                    continue;
+                *daily_tokens.entry(day_key.clone()).or_insert(0) += total_tokens;
+                *daily_messages.entry(day_key).or_insert(0) += 1;
+
+                week_messages += 1;
+                in_week = true;
+                week_tokens.input += input;
+                week_tokens.output += output;
+                week_tokens.cache_read += cache_read;
+                week_tokens.cache_creation += cache_creation;
+                *week_model_tokens.entry(model.to_string()).or_insert(0) += total_tokens;
+                *week_source_tokens.entry(source.clone()).or_insert(0) += total_tokens;
+                if sidechain {
+                    week_subagent_tokens.input += input;
+                    week_subagent_tokens.output += output;
+                    week_subagent_tokens.cache_read += cache_read;
+                    week_subagent_tokens.cache_creation += cache_creation;
                 }
 
-                let local_ts = ts.with_timezone(&chrono::Local);
-                let is_today = local_ts.format("%Y-%m-%d").to_string() == today_str;
-
-                if let Some(usage) = entry.get("message").and_then(|m| m.get("usage")) {
-                    let input = usage.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                    let output = usage.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                    let cache_read = usage.get("cache_read_input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                    let cache_creation = usage.get("cache_creation_input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-
-                    let model = entry.get("message")
-                        .and_then(|m| m.get("model"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-                    let total_tokens = input + output + cache_read + cache_creation;
-
-                    let day_key = local_ts.format("%Y-%m-%d").to_string();
-                    *daily_messages.entry(day_key).or_insert(0) += 1;
-
-                    week_messages += 1;
-                    week_tokens.input += input;
-                    week_tokens.output += output;
-                    week_tokens.cache_read += cache_read;
-                    week_tokens.cache_creation += cache_creation;
-                    *week_model_tokens.entry(model.to_string()).or_insert(0) += total_tokens;
-
-                    if is_today {
-                        today_messages += 1;
-                        today_tokens.input += input;
-                        today_tokens.output += output;
-                        today_tokens.cache_read += cache_read;
-                        today_tokens.cache_creation += cache_creation;
-                        *today_model_tokens.entry(model.to_string()).or_insert(0) += total_tokens;
+                if is_today {
+                    today_messages += 1;
+                    today_tokens.input += input;
+                    today_tokens.output += output;
+                    today_tokens.cache_read += cache_read;
+                    today_tokens.cache_creation += cache_creation;
+                    *today_model_tokens.entry(model.to_string()).or_insert(0) += total_tokens;
+                    if sidechain {
+                        today_subagent_tokens.input += input;
+                        today_subagent_tokens.output += output;
+                        today_subagent_tokens.cache_read += cache_read;
+                        today_subagent_tokens.cache_creation += cache_creation;
                     }
                 }
             }
         }
+        if in_week {
+            week_sessions += 1;
+        }
+    }
 
-        Ok(RealtimeStats {
-            last_activity: last_activity.map(|t| t.to_rfc3339()),
-            today_messages,
-            today_tokens,
-            week_messages,
-            week_tokens,
-            active_sessions,
-            plan_type,
-            rate_limit_tier,
-            today_model_tokens,
-            week_model_tokens,
-            daily_messages,
-        })
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    let mut providers: HashMap<String, ProviderStats> = HashMap::new();
+    if week_messages > 0 {
+        providers.insert(
+            "claude".to_string(),
+            ProviderStats {
+                today_messages,
+                today_tokens: today_tokens.input + today_tokens.output + today_tokens.cache_read + today_tokens.cache_creation,
+                week_messages,
+                week_tokens: week_tokens.input + week_tokens.output + week_tokens.cache_read + week_tokens.cache_creation,
+                week_sessions,
+                week_model_tokens: week_model_tokens.clone(),
+                last_activity: last_activity.map(|t| t.to_rfc3339()),
+                last_project: last_project.clone(),
+            },
+        );
+    }
+    let mut provider_sessions: HashSet<(&'static str, String)> = HashSet::new();
+    crate::providers::scan(week_ago, |e| {
+        let p = providers.entry(e.provider.to_string()).or_default();
+        let tokens = e.total_tokens();
+        p.week_messages += 1;
+        p.week_tokens += tokens;
+        *p.week_model_tokens.entry(e.model.to_string()).or_insert(0) += tokens;
+        if provider_sessions.insert((e.provider, e.session_id.to_string())) {
+            p.week_sessions += 1;
+        }
+        if clock.date_key(&e.timestamp) == today_str {
+            p.today_messages += 1;
+            p.today_tokens += tokens;
+        }
+        let at = e.timestamp.to_rfc3339();
+        if p.last_activity.as_ref().is_none_or(|l| at > *l) {
+            p.last_activity = Some(at);
+            p.last_project = e.cwd.map(String::from);
+        }
+    });
+
+    let stats = RealtimeStats {
+        last_activity: last_activity.map(|t| t.to_rfc3339()),
+        last_project,
+        today_messages,
+        today_tokens,
+        week_messages,
+        week_tokens,
+        today_cost_usd,
+        week_cost_usd,
+        today_subagent_tokens,
+        week_subagent_tokens,
+        active_sessions,
+        plan_type,
+        rate_limit_tier,
+        today_model_tokens,
+        week_model_tokens,
+        daily_messages,
+        daily_tokens,
+        week_source_tokens,
+        providers,
+    };
+    *REALTIME_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(stats.clone());
+    Ok(stats)
+}
+
+/// compute_realtime_stats behind the shared TTL cache
+pub fn realtime_stats() -> Result<RealtimeStats, SprtError> {
+    crate::cache::cached(crate::cache::REALTIME_STATS, compute_realtime_stats)
+}
+
+/// Last result of compute_realtime_stats, for callers that must not scan files
+static REALTIME_CACHE: LazyLock<Mutex<Option<RealtimeStats>>> = LazyLock::new(|| Mutex::new(None));
+
+pub fn get_cached_realtime_stats() -> Option<RealtimeStats> {
+    REALTIME_CACHE.lock().unwrap_or_else(|e| e.into_inner()).clone()
 }
 
 // ── Plan Usage from Anthropic unified rate limit headers ──
@@ -441,32 +895,158 @@ pub struct RateLimitInfo {
     pub five_hour: Option<UsageClaim>,
     pub seven_day: Option<UsageClaim>,
     pub seven_day_sonnet: Option<UsageClaim>,
+    /// Separate weekly Opus cap (Max plans)
+    pub seven_day_opus: Option<UsageClaim>,
     pub overage_status: Option<String>,              // "allowed", "rejected", etc.
     pub overage_disabled_reason: Option<String>,
     pub overage_reset: Option<u64>,
     pub fallback_percentage: Option<f64>,
     pub checked_at: String,
+    /// Every window the response reported, keyed by its header name ("5h", "7d_opus", ...),
+    /// including ones added after this version
+    pub claims: BTreeMap<String, UsageClaim>,
+    /// Every anthropic-ratelimit-unified-* header, raw, without the prefix
+    pub extra: BTreeMap<String, String>,
+    /// Key in `claims` of the model-specific weekly window that matches the model family
+    /// used most this week, e.g. "7d_opus"; None when no such window applies
+    pub model_weekly_claim: Option<String>,
+}
+
+/// A weekly window limited to one model family
+pub struct ModelWeeklyClaim<'a> {
+    /// Key in RateLimitInfo.claims, e.g. "7d_opus"
+    pub key: &'a str,
+    /// Lowercase, as it appears in model names: "opus"
+    pub family: String,
+    /// "7-day Opus"
+    pub label: String,
+    pub claim: &'a UsageClaim,
+}
+
+impl RateLimitInfo {
+    /// Weekly windows limited to one model family ("7d_opus", "7d_sonnet", ...)
+    pub fn model_weekly_claims(&self) -> Vec<ModelWeeklyClaim<'_>> {
+        self.claims
+            .iter()
+            .filter_map(|(key, claim)| {
+                let family = key
+                    .strip_prefix("7d")
+                    .or_else(|| key.strip_prefix("seven_day"))?
+                    .trim_start_matches(['_', '-'])
+                    .to_lowercase();
+                let mut chars = family.chars();
+                let label = chars.next()?.to_uppercase().chain(chars).collect::<String>();
+                Some(ModelWeeklyClaim {
+                    key,
+                    label: format!("7-day {}", label.replace(['_', '-'], " ")),
+                    family,
+                    claim,
+                })
+            })
+            .collect()
+    }
+
+    /// Every window's utilization, known or not
+    pub fn utilizations(&self) -> impl Iterator<Item = f64> + '_ {
+        [&self.five_hour, &self.seven_day, &self.seven_day_sonnet, &self.seven_day_opus]
+            .into_iter()
+            .flatten()
+            .chain(self.claims.values())
+            .map(|c| c.utilization)
+    }
+}
+
+const UNIFIED_HEADER_PREFIX: &str = "anthropic-ratelimit-unified-";
+/// The probe is a 1-token request; anything slower is treated as a failure
+const RATE_LIMIT_TIMEOUT_SECS: u64 = 15;
+/// Header names each known window has gone by
+const FIVE_HOUR_CLAIMS: &[&str] = &["5h", "five_hour", "five-hour"];
+const SEVEN_DAY_CLAIMS: &[&str] = &["7d", "seven_day", "seven-day"];
+const SEVEN_DAY_SONNET_CLAIMS: &[&str] = &["7d_sonnet", "7d-sonnet", "seven_day_sonnet"];
+const SEVEN_DAY_OPUS_CLAIMS: &[&str] = &["7d_opus", "7d-opus", "seven_day_opus"];
+
+/// Build RateLimitInfo from the unified headers (names without the prefix). Any
+/// "<claim>-utilization" header is a window, so ones this code doesn't know yet still
+/// land in `claims`.
+fn parse_rate_limit_headers(headers: BTreeMap<String, String>) -> RateLimitInfo {
+    let get_str = |name: &str| headers.get(name).cloned();
+    let get_f64 = |name: &str| -> Option<f64> { get_str(name).and_then(|s| s.parse().ok()) };
+    let get_u64 = |name: &str| -> Option<u64> { get_str(name).and_then(|s| s.parse().ok()) };
+
+    let claims: BTreeMap<String, UsageClaim> = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let claim = name.strip_suffix("-utilization")?;
+            Some((
+                claim.to_string(),
+                UsageClaim {
+                    utilization: value.parse().ok()?,
+                    reset: get_u64(&format!("{}-reset", claim)),
+                    status: get_str(&format!("{}-status", claim))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                },
+            ))
+        })
+        .collect();
+    let known = |aliases: &[&str]| aliases.iter().find_map(|a| claims.get(*a).cloned());
+
+    RateLimitInfo {
+        status: get_str("status").unwrap_or_else(|| "unknown".to_string()),
+        representative_claim: get_str("representative-claim"),
+        five_hour: known(FIVE_HOUR_CLAIMS),
+        seven_day: known(SEVEN_DAY_CLAIMS),
+        seven_day_sonnet: known(SEVEN_DAY_SONNET_CLAIMS),
+        seven_day_opus: known(SEVEN_DAY_OPUS_CLAIMS),
+        overage_status: get_str("overage-status"),
+        overage_disabled_reason: get_str("overage-disabled-reason"),
+        overage_reset: get_u64("overage-reset"),
+        fallback_percentage: get_f64("fallback-percentage"),
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        claims,
+        extra: headers,
+        model_weekly_claim: None,
+    }
+}
+
+/// The model-specific weekly window for the family with the most tokens this week
+fn model_weekly_claim(info: &RateLimitInfo) -> Option<String> {
+    let weekly = info.model_weekly_claims();
+    if weekly.is_empty() {
+        return None;
+    }
+    let stats = get_cached_realtime_stats()?;
+    let mut by_family: HashMap<&str, u64> = HashMap::new();
+    for (model, tokens) in &stats.week_model_tokens {
+        let model = model.to_lowercase();
+        if let Some(w) = weekly.iter().find(|w| model.contains(&w.family)) {
+            *by_family.entry(w.key).or_insert(0) += tokens;
+        }
+    }
+    by_family
+        .into_iter()
+        .max_by_key(|(_, tokens)| *tokens)
+        .map(|(key, _)| key.to_string())
 }
 
 static RATE_LIMIT_CACHE: LazyLock<Mutex<Option<(Instant, RateLimitInfo)>>> =
     LazyLock::new(|| Mutex::new(None));
 
 /// Read access token from ~/.claude/.credentials.json, auto-refreshing if expired.
-pub async fn get_access_token() -> Result<String, String> {
-    let creds_path = claude_dir()
-        .ok_or("Cannot find home directory")?
+pub async fn get_access_token() -> Result<String, SprtError> {
+    let creds_path = require_claude_dir()?
         .join(".credentials.json");
+    let not_authenticated = |msg: String| SprtError::NotAuthenticated(msg);
     let content = fs::read_to_string(&creds_path)
-        .map_err(|e| format!("Cannot read credentials: {}", e))?;
+        .map_err(|e| not_authenticated(format!("Cannot read credentials: {}", e)))?;
     let creds: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Cannot parse credentials: {}", e))?;
+        .map_err(|e| not_authenticated(format!("Cannot parse credentials: {}", e)))?;
 
     let oauth = creds.get("claudeAiOauth")
-        .ok_or("No claudeAiOauth in credentials file")?;
+        .ok_or_else(|| not_authenticated("No claudeAiOauth in credentials file".to_string()))?;
 
     let access_token = oauth.get("accessToken")
         .and_then(|v| v.as_str())
-        .ok_or("No access token found in credentials file")?;
+        .ok_or_else(|| not_authenticated("No access token found in credentials file".to_string()))?;
 
     // Check if token is still valid (with 5-minute buffer)
     let now_ms = std::time::SystemTime::now()
@@ -484,7 +1064,9 @@ pub async fn get_access_token() -> Result<String, String> {
             return refresh_access_token(refresh_token, &creds_path, &creds).await;
         }
 
-        return Err("Session token expired. Re-run claude to refresh.".to_string());
+        return Err(SprtError::TokenExpired(
+            "Session token expired. Re-run claude to refresh.".to_string(),
+        ));
     }
 
     // No expiresAt field — use token as-is
@@ -498,30 +1080,28 @@ async fn refresh_access_token(
     refresh_token: &str,
     creds_path: &std::path::Path,
     original_creds: &serde_json::Value,
-) -> Result<String, String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
-
-    let resp = client
-        .post(OAUTH_TOKEN_URL)
-        .header("content-type", "application/json")
-        .json(&serde_json::json!({
-            "grant_type": "refresh_token",
-            "refresh_token": refresh_token,
-            "client_id": OAUTH_CLIENT_ID,
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Token refresh failed: {}", e))?;
+) -> Result<String, SprtError> {
+    let resp = crate::http::send_once("Token refresh", |client| {
+        client
+            .post(OAUTH_TOKEN_URL)
+            .header("content-type", "application/json")
+            .json(&serde_json::json!({
+                "grant_type": "refresh_token",
+                "refresh_token": refresh_token,
+                "client_id": OAUTH_CLIENT_ID,
+            }))
+    })
+    .await?;
 
     if !resp.status().is_success() {
-        return Err(format!("Token refresh returned {}", resp.status()));
+        return Err(SprtError::TokenExpired(format!(
+            "Token refresh returned {}. Re-run claude to log in again.",
+            resp.status()
+        )));
     }
 
     let body: serde_json::Value = resp.json().await
-        .map_err(|e| format!("Cannot parse refresh response: {}", e))?;
+        .map_err(|e| SprtError::Parse(format!("Cannot parse refresh response: {}", e)))?;
 
     let new_access = body.get("access_token")
         .and_then(|v| v.as_str())
@@ -548,93 +1128,106 @@ async fn refresh_access_token(
     }
 
     if let Ok(json_str) = serde_json::to_string_pretty(&updated) {
-        let _ = fs::write(creds_path, json_str);
+        if let Err(e) = fs::write(creds_path, json_str) {
+            log::warn!("Cannot save refreshed credentials: {e}");
+        }
     }
 
     Ok(new_access.to_string())
 }
 
+/// One rate-limit probe: a 1-token request whose response headers carry the limits
+async fn probe_rate_limits() -> Result<RateLimitInfo, SprtError> {
+    if crate::demo::active() {
+        return Ok(crate::demo::rate_limits());
+    }
+    let token = get_access_token().await?;
+
+    let resp = crate::http::send_once("API call", |client| {
+        client
+            .post("https://api.anthropic.com/v1/messages")
+            // The tray and wake refresh wait on this; don't hold them for the full HTTP timeout
+            .timeout(std::time::Duration::from_secs(RATE_LIMIT_TIMEOUT_SECS))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("anthropic-version", "2023-06-01")
+            .header("anthropic-beta", "oauth-2025-04-20")
+            .header("content-type", "application/json")
+            .json(&serde_json::json!({
+                "model": "claude-sonnet-4-20250514",
+                "max_tokens": 1,
+                "messages": [{"role": "user", "content": "."}]
+            }))
+    })
+    .await?;
+
+    // Only the unified rate-limit headers matter; names are lowercase in HeaderMap
+    let unified: BTreeMap<String, String> = resp
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            let key = name.as_str().strip_prefix(UNIFIED_HEADER_PREFIX)?;
+            Some((key.to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect();
+    Ok(parse_rate_limit_headers(unified))
+}
+
 #[tauri::command]
-pub async fn get_rate_limits(force: Option<bool>) -> Result<RateLimitInfo, String> {
+pub async fn get_rate_limits(force: Option<bool>) -> Result<RateLimitInfo, SprtError> {
     let force = force.unwrap_or(false);
 
-    // Check cache (valid for 60 seconds) — recover from poisoned mutex
+    // Paused: serve whatever is cached, never probe the API
+    if crate::monitor::is_paused() {
+        return get_cached_rate_limits()
+            .ok_or_else(|| SprtError::Paused("Monitoring is paused".to_string()));
+    }
+
+    // Check cache (1–10 min depending on utilization, longer during quiet hours) — recover
+    // from poisoned mutex
     if !force {
         let cache = RATE_LIMIT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
         if let Some((instant, ref info)) = *cache {
-            if instant.elapsed().as_secs() < 60 {
+            if instant.elapsed().as_secs() < crate::probe::interval_secs(Some(info)) {
                 return Ok(info.clone());
             }
         }
     }
 
-    let token = get_access_token().await?;
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
-    let resp = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("anthropic-version", "2023-06-01")
-        .header("anthropic-beta", "oauth-2025-04-20")
-        .header("content-type", "application/json")
-        .json(&serde_json::json!({
-            "model": "claude-sonnet-4-20250514",
-            "max_tokens": 1,
-            "messages": [{"role": "user", "content": "."}]
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("API call failed: {}", e))?;
-
-    let headers = resp.headers().clone();
-
-    let get_str = |name: &str| -> Option<String> {
-        headers
-            .get(name)
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string())
-    };
-    let get_f64 = |name: &str| -> Option<f64> {
-        get_str(name).and_then(|s| s.parse().ok())
-    };
-    let get_u64 = |name: &str| -> Option<u64> {
-        get_str(name).and_then(|s| s.parse().ok())
-    };
-
-    let parse_claim = |prefix: &str| -> Option<UsageClaim> {
-        let utilization = get_f64(&format!("anthropic-ratelimit-unified-{}-utilization", prefix))?;
-        Some(UsageClaim {
-            utilization,
-            reset: get_u64(&format!("anthropic-ratelimit-unified-{}-reset", prefix)),
-            status: get_str(&format!("anthropic-ratelimit-unified-{}-status", prefix))
-                .unwrap_or_else(|| "unknown".to_string()),
-        })
-    };
+    // Daily cap reached: keep serving the last result, however old
+    if !crate::probe::try_acquire(force) {
+        return get_cached_rate_limits().ok_or_else(|| {
+            SprtError::Api("Daily rate-limit probe cap reached".to_string())
+        });
+    }
 
-    let info = RateLimitInfo {
-        status: get_str("anthropic-ratelimit-unified-status")
-            .unwrap_or_else(|| "unknown".to_string()),
-        representative_claim: get_str("anthropic-ratelimit-unified-representative-claim"),
-        five_hour: parse_claim("5h"),
-        seven_day: parse_claim("7d"),
-        seven_day_sonnet: parse_claim("7d_sonnet"),
-        overage_status: get_str("anthropic-ratelimit-unified-overage-status"),
-        overage_disabled_reason: get_str("anthropic-ratelimit-unified-overage-disabled-reason"),
-        overage_reset: get_u64("anthropic-ratelimit-unified-overage-reset"),
-        fallback_percentage: get_f64("anthropic-ratelimit-unified-fallback-percentage"),
-        checked_at: chrono::Utc::now().to_rfc3339(),
-    };
+    let started = Instant::now();
+    let result = probe_rate_limits().await;
+    crate::health::record(crate::health::RATE_LIMITS, started, &result);
+    let mut info = result?;
+    info.model_weekly_claim = model_weekly_claim(&info);
 
     // Update cache — recover from poisoned mutex
     let mut cache = RATE_LIMIT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
     *cache = Some((Instant::now(), info.clone()));
+    drop(cache);
+
+    crate::overage::observe(&info);
+    crate::timeline::observe(&info);
 
     Ok(info)
 }
 
+/// Last fetched rate limits from the in-memory cache, however old (non-async)
+pub fn get_cached_rate_limits() -> Option<RateLimitInfo> {
+    let cache = RATE_LIMIT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.as_ref().map(|(_, info)| info.clone())
+}
+
+/// Forget the last rate limits, e.g. after switching accounts; the next get_rate_limits probes
+pub fn clear_rate_limits() {
+    *RATE_LIMIT_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
 /// Read 5h utilization from the in-memory rate limit cache (non-async, for tray thread)
 pub fn get_cached_utilization() -> Option<f64> {
     let cache = RATE_LIMIT_CACHE.lock().unwrap_or_else(|e| e.into_inner());
@@ -647,29 +1240,41 @@ pub fn get_cached_utilization() -> Option<f64> {
 use crate::storage::SessionSummary;
 use crate::git::decode_project_path;
 
-pub fn get_session_summaries(date: &str) -> Vec<SessionSummary> {
-    let claude_dir = match claude_dir() {
-        Some(d) => d,
-        None => return vec![],
-    };
-    let projects_dir = claude_dir.join("projects");
-    if !projects_dir.exists() {
-        return vec![];
-    }
+/// Gaps between messages longer than this count as idle unless settings say otherwise
+const DEFAULT_IDLE_GAP_MINUTES: u32 = 15;
 
-    let pattern = projects_dir
-        .join("*/*.jsonl")
-        .to_string_lossy()
-        .to_string();
+/// Longest gap between messages that still counts as continuous work
+pub(crate) fn idle_gap() -> chrono::Duration {
+    chrono::Duration::minutes(
+        crate::settings::load()
+            .idle_gap_minutes
+            .unwrap_or(DEFAULT_IDLE_GAP_MINUTES) as i64,
+    )
+}
+
+/// Time between consecutive messages, skipping gaps longer than `idle_gap` (breaks, meetings)
+fn focused_minutes(times: &mut [chrono::DateTime<chrono::Utc>], idle_gap: chrono::Duration) -> u64 {
+    times.sort();
+    let secs: i64 = times
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|gap| *gap <= idle_gap)
+        .map(|gap| gap.num_seconds())
+        .sum();
+    (secs / 60) as u64
+}
 
-    let paths: Vec<PathBuf> = match glob::glob(&pattern) {
-        Ok(p) => p.filter_map(|p| p.ok()).collect(),
+pub fn get_session_summaries(date: &str) -> Vec<SessionSummary> {
+    let paths = match session_logs() {
+        Ok(p) => p,
         Err(_) => return vec![],
     };
+    let clock = DayClock::load();
+    let idle_gap = idle_gap();
 
     let mut summaries = vec![];
 
-    for path in &paths {
+    for (source, path) in &paths {
         // Only process files modified in the last 7 days
         if let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) {
             let elapsed = modified.elapsed().unwrap_or_default().as_secs();
@@ -693,30 +1298,47 @@ pub fn get_session_summaries(date: &str) -> Vec<SessionSummary> {
 
         let file = match fs::File::open(path) {
             Ok(f) => f,
-            Err(_) => continue,
+            Err(e) => {
+                let msg = format!("Cannot open session log: {}", e);
+                diagnostics::warn("session-log", path, None, diagnostics::io_kind(&e), msg);
+                continue;
+            }
         };
+        diagnostics::clear_file(path);
         let reader = BufReader::new(file);
 
         let mut msg_count: u64 = 0;
         let mut input_tokens: u64 = 0;
         let mut output_tokens: u64 = 0;
         let mut cache_read: u64 = 0;
+        let mut subagent_messages: u64 = 0;
+        let mut subagent_tokens: u64 = 0;
         let mut first_ts: Option<String> = None;
         let mut last_ts: Option<String> = None;
         let mut has_date_match = false;
+        let mut message_times: Vec<chrono::DateTime<chrono::Utc>> = vec![];
+        let mut title = SessionTitle::default();
 
-        for line in reader.lines() {
+        for (idx, line) in reader.lines().enumerate() {
             let line = match line {
                 Ok(l) => l,
                 Err(_) => continue,
             };
+            title.observe(&line);
             if line.is_empty() || !line.contains("\"type\":\"assistant\"") {
                 continue;
             }
 
             let entry: serde_json::Value = match serde_json::from_str(&line) {
                 Ok(v) => v,
-                Err(_) => continue,
+                Err(e) => {
+                    // A truncated last line is just Claude Code mid-write
+                    if !e.is_eof() {
+                        let msg = format!("Skipped malformed line: {}", e);
+                        diagnostics::warn("session-log", path, Some(idx as u64 + 1), DiagnosticKind::InvalidJson, msg);
+                    }
+                    continue;
+                }
             };
 
             if entry.get("type").and_then(|v| v.as_str()) != Some("assistant") {
@@ -728,28 +1350,39 @@ pub fn get_session_summaries(date: &str) -> Vec<SessionSummary> {
                 None => continue,
             };
 
-            // Convert UTC timestamp to local timezone before comparing date
+            // Compare on local day boundaries (with rollover), not the UTC date
             let ts_utc = match timestamp_str.parse::<chrono::DateTime<chrono::Utc>>() {
                 Ok(t) => t,
                 Err(_) => continue,
             };
-            let local_date = ts_utc.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string();
-            if local_date != date {
+            if clock.date_key(&ts_utc) != date {
                 continue;
             }
 
             has_date_match = true;
             msg_count += 1;
+            message_times.push(ts_utc);
 
             if first_ts.is_none() {
                 first_ts = Some(timestamp_str.to_string());
             }
             last_ts = Some(timestamp_str.to_string());
 
+            let sidechain = is_sidechain(&entry);
+            if sidechain {
+                subagent_messages += 1;
+            }
+
             if let Some(usage) = entry.get("message").and_then(|m| m.get("usage")) {
-                input_tokens += usage.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                output_tokens += usage.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                cache_read += usage.get("cache_read_input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                let input = usage.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                let output = usage.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                let read = usage.get("cache_read_input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+                input_tokens += input;
+                output_tokens += output;
+                cache_read += read;
+                if sidechain {
+                    subagent_tokens += input + output + read;
+                }
             }
         }
 
@@ -776,8 +1409,13 @@ pub fn get_session_summaries(date: &str) -> Vec<SessionSummary> {
                 output_tokens,
                 cache_read,
                 duration_minutes,
+                focused_minutes: focused_minutes(&mut message_times, idle_gap),
                 first_message: first_ts,
                 last_message: last_ts,
+                title: title.into_title(),
+                subagent_messages,
+                subagent_tokens,
+                source: source.clone(),
             });
         }
     }
@@ -785,16 +1423,36 @@ pub fn get_session_summaries(date: &str) -> Vec<SessionSummary> {
     summaries
 }
 
+/// (subscription type, rate-limit tier) from the credentials file, "unknown" when unreadable.
+/// Both read "unknown" after logging out.
+pub(crate) fn plan() -> Result<(String, String), SprtError> {
+    Ok(read_credentials(&require_claude_dir()?.join(".credentials.json")))
+}
+
 fn read_credentials(path: &PathBuf) -> (String, String) {
     let content = match fs::read_to_string(path) {
         Ok(c) => c,
-        Err(_) => return ("unknown".to_string(), "unknown".to_string()),
+        Err(e) => {
+            let msg = format!("Cannot read credentials: {}", e);
+            diagnostics::critical("credentials", path, diagnostics::io_kind(&e), msg);
+            return ("unknown".to_string(), "unknown".to_string());
+        }
     };
     let json: serde_json::Value = match serde_json::from_str(&content) {
         Ok(v) => v,
-        Err(_) => return ("unknown".to_string(), "unknown".to_string()),
+        Err(e) => {
+            let msg = format!("Cannot parse credentials: {}", e);
+            diagnostics::critical("credentials", path, DiagnosticKind::InvalidJson, msg);
+            return ("unknown".to_string(), "unknown".to_string());
+        }
     };
     let oauth = json.get("claudeAiOauth");
+    if oauth.is_none() {
+        let msg = "No claudeAiOauth in credentials file — log in to Claude Code".to_string();
+        diagnostics::critical("credentials", path, DiagnosticKind::MissingField, msg);
+    } else {
+        diagnostics::clear_file(path);
+    }
     let plan = oauth
         .and_then(|o| o.get("subscriptionType"))
         .and_then(|v| v.as_str())
@@ -807,3 +1465,43 @@ fn read_credentials(path: &PathBuf) -> (String, String) {
         .to_string();
     (plan, tier)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_usage_reads_fixture_dir() {
+        crate::paths::init_for_tests();
+        let mut entries = vec![];
+        scan_usage(chrono::DateTime::UNIX_EPOCH, |e| {
+            entries.push((e.session_id.to_string(), e.model.to_string(), e.total_tokens(), e.sidechain));
+        })
+        .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("0b7d5c3e-1111-4a2b-9c3d-5e6f7a8b9c0d".to_string(), "claude-sonnet-4-20250514".to_string(), 1350, false),
+                ("0b7d5c3e-1111-4a2b-9c3d-5e6f7a8b9c0d".to_string(), "claude-sonnet-4-20250514".to_string(), 2, true),
+                ("0b7d5c3e-1111-4a2b-9c3d-5e6f7a8b9c0d".to_string(), "claude-opus-4-20250514".to_string(), 15, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_usage_skips_entries_before_since() {
+        crate::paths::init_for_tests();
+        let since = "2025-03-01T09:15:00Z".parse().unwrap();
+        let mut tokens = 0;
+        scan_usage(since, |e| tokens += e.total_tokens()).unwrap();
+        assert_eq!(tokens, 15);
+    }
+
+    #[test]
+    fn line_tokens_sums_usage() {
+        let line = r#"{"type":"assistant","message":{"usage":{"input_tokens":3,"output_tokens":4,"cache_read_input_tokens":5}}}"#;
+        assert_eq!(line_tokens(line), 12);
+        assert_eq!(line_tokens(r#"{"type":"user","message":{"content":"hi"}}"#), 0);
+        assert_eq!(line_tokens("not json"), 0);
+    }
+}
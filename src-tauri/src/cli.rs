@@ -0,0 +1,206 @@
+use crate::backup;
+use crate::claude;
+use crate::delivery;
+use crate::devlog;
+use crate::paths::{self, DataPaths};
+use crate::settings;
+use crate::storage::{self, DevLog};
+
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: sprt <command> [options]
+
+Commands:
+  stats [--json]                          Today's and this week's usage
+  limits [--force] [--json]               Plan rate-limit utilization
+  devlog generate [DATE] [--type TYPE] [--model MODEL]
+                                          Generate a devlog (default: today, daily)
+  devlog show [DATE] [--type TYPE] [--json]
+  export [PATH]                           Back up all SPRT data to a .tar.gz
+
+DATE is YYYY-MM-DD. TYPE is daily or weekly.";
+
+/// Parsed flags plus remaining positional arguments
+struct Args {
+    positional: Vec<String>,
+    json: bool,
+    force: bool,
+    log_type: String,
+    model: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args {
+        positional: vec![],
+        json: false,
+        force: false,
+        log_type: "daily".to_string(),
+        model: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => parsed.json = true,
+            "--force" => parsed.force = true,
+            "--type" => parsed.log_type = iter.next().ok_or("--type needs a value")?.clone(),
+            "--model" => parsed.model = Some(iter.next().ok_or("--model needs a value")?.clone()),
+            a if a.starts_with("--") => return Err(format!("Unknown option: {}", a)),
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+    Ok(parsed)
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    let s = serde_json::to_string_pretty(value).map_err(|e| format!("Serialize error: {}", e))?;
+    println!("{}", s);
+    Ok(())
+}
+
+fn today() -> String {
+    crate::time::DayClock::load().today_key()
+}
+
+fn print_devlog(log: &DevLog) {
+    println!("{} log — {} (score {}/100)", log.log_type, log.date, log.sprint_score);
+    println!();
+    println!("{}", log.summary);
+    if !log.highlights.is_empty() {
+        println!();
+        for h in &log.highlights {
+            println!("  • {}", h);
+        }
+    }
+    println!();
+    println!(
+        "{} commits · {} messages · +{} -{} · {:.1}h active",
+        log.stats.total_commits,
+        log.stats.total_messages,
+        log.stats.total_insertions,
+        log.stats.total_deletions,
+        log.stats.active_hours
+    );
+}
+
+fn cmd_stats(args: &Args) -> Result<(), String> {
+    let stats = claude::compute_realtime_stats()?;
+    if args.json {
+        return print_json(&stats);
+    }
+    let total = |t: &claude::TokenUsage| t.input + t.output + t.cache_read + t.cache_creation;
+    println!("Plan:            {} ({})", stats.plan_type, stats.rate_limit_tier);
+    println!("Today:           {} messages, {} tokens", stats.today_messages, total(&stats.today_tokens));
+    println!("Last 7 days:     {} messages, {} tokens", stats.week_messages, total(&stats.week_tokens));
+    println!("Active sessions: {}", stats.active_sessions);
+    if let Some(last) = &stats.last_activity {
+        println!("Last activity:   {}", last);
+    }
+    Ok(())
+}
+
+fn cmd_limits(args: &Args) -> Result<(), String> {
+    let info = tauri::async_runtime::block_on(claude::get_rate_limits(Some(args.force)))?;
+    if args.json {
+        return print_json(&info);
+    }
+    println!("Status: {}", info.status);
+    for (label, claim) in [
+        ("5-hour", &info.five_hour),
+        ("7-day", &info.seven_day),
+        ("7-day Sonnet", &info.seven_day_sonnet),
+        ("7-day Opus", &info.seven_day_opus),
+    ] {
+        if let Some(c) = claim {
+            let reset = c
+                .reset
+                .and_then(|r| chrono::DateTime::from_timestamp(r as i64, 0))
+                .map(|dt| dt.with_timezone(&chrono::Local).format(" (resets %m-%d %H:%M)").to_string())
+                .unwrap_or_default();
+            println!("{:<13} {:>5.1}%{}", label, c.utilization * 100.0, reset);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_devlog(args: &Args) -> Result<(), String> {
+    let sub = args.positional.get(1).map(|s| s.as_str());
+    let date = args.positional.get(2).cloned().unwrap_or_else(today);
+    match sub {
+        Some("generate") => {
+            let drafts = settings::load().devlog_drafts;
+            let choice = devlog::ModelOverride {
+                model: args.model.clone(),
+                max_tokens: None,
+            };
+            let log = tauri::async_runtime::block_on(async {
+                if drafts {
+                    return Ok::<DevLog, String>(devlog::draft(&date, &args.log_type, &choice).await?);
+                }
+                let log = devlog::generate_new(&date, &args.log_type, &choice).await?;
+                // Await delivery here — the process exits right after
+                delivery::deliver_devlog(log.clone()).await;
+                Ok::<DevLog, String>(log)
+            })?;
+            if drafts {
+                eprintln!("Draft only (devlogDrafts is on): not saved. Approve it in the app.");
+            }
+            if args.json {
+                return print_json(&log);
+            }
+            print_devlog(&log);
+            Ok(())
+        }
+        Some("show") => match storage::get_devlog(&date, &args.log_type)? {
+            Some(log) if args.json => print_json(&log),
+            Some(log) => {
+                print_devlog(&log);
+                Ok(())
+            }
+            None => Err(format!("No {} devlog for {}", args.log_type, date)),
+        },
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn cmd_export(args: &Args) -> Result<(), String> {
+    let dest = match args.positional.get(1) {
+        Some(p) => PathBuf::from(p),
+        None => PathBuf::from(format!(
+            "sprt-backup-{}.tar.gz",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+    backup::export_archive(&dest)?;
+    println!("Exported to {}", dest.display());
+    Ok(())
+}
+
+/// Entry point for the `sprt` binary. `args` excludes the program name. Returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    paths::init(DataPaths::from_env());
+    let parsed = match parse_args(args) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    let result = match parsed.positional.first().map(|s| s.as_str()) {
+        Some("stats") => cmd_stats(&parsed),
+        Some("limits") => cmd_limits(&parsed),
+        Some("devlog") => cmd_devlog(&parsed),
+        Some("export") => cmd_export(&parsed),
+        Some("help") | None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
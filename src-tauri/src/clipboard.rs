@@ -0,0 +1,156 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::notifications;
+use crate::standup;
+use crate::storage::{self, DevLog, ProjectWork};
+use crate::time::DayClock;
+
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Put `text` on the system clipboard
+pub fn copy(app: &AppHandle, text: &str) -> Result<(), SprtError> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| SprtError::Internal(format!("Failed to write to clipboard: {}", e)))
+}
+
+// ── Copy actions (commands and tray menu) ──
+
+pub fn devlog_markdown(log: &DevLog) -> String {
+    let mut md = format!("## {} devlog — {}\n\n{}\n", capitalize(&log.log_type), log.date, log.summary);
+    if !log.highlights.is_empty() {
+        md.push_str("\n### Highlights\n");
+        for h in &log.highlights {
+            md.push_str(&format!("- {}\n", h));
+        }
+    }
+    let projects: Vec<&ProjectWork> = log
+        .projects_worked
+        .iter()
+        .filter(|p| !p.key_changes.is_empty())
+        .collect();
+    if !projects.is_empty() {
+        md.push_str("\n### Projects\n");
+        for p in projects {
+            md.push_str(&format!("**{}** ({} commits)\n", p.name, p.commits));
+            for change in &p.key_changes {
+                md.push_str(&format!("- {}\n", change));
+            }
+        }
+    }
+    if !log.issues_touched.is_empty() {
+        md.push_str("\n### Issues\n");
+        for i in &log.issues_touched {
+            md.push_str(&format!("- [{}]({}) {}\n", i.key, i.url, i.title));
+        }
+    }
+    let s = &log.stats;
+    if log.log_type != "insights" {
+        md.push_str(&format!(
+            "\n_{} commits · {} messages · +{} −{} · {:.1}h active · score {}/100_\n",
+            s.total_commits, s.total_messages, s.total_insertions, s.total_deletions, s.active_hours, log.sprint_score
+        ));
+    }
+    if let Some(v) = &log.value {
+        md.push_str(&format!("_${:.2} at API prices", v.api_cost_usd));
+        if let Some(fee) = v.fee_usd {
+            md.push_str(&format!(" vs. ${:.2} of the {} plan", fee, v.plan_type));
+        }
+        md.push_str("_\n");
+    }
+    md
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn today_stats_text() -> Result<String, SprtError> {
+    let stats = claude::realtime_stats()?;
+    let t = &stats.today_tokens;
+    let mut text = format!(
+        "Claude Code today ({}): {} messages, {} tokens (~${:.2} at API prices), {} active sessions",
+        DayClock::load().today_key(),
+        stats.today_messages,
+        t.input + t.output + t.cache_read + t.cache_creation,
+        stats.today_cost_usd,
+        stats.active_sessions
+    );
+    if let Some(info) = claude::get_cached_rate_limits() {
+        let pct = |c: &Option<claude::UsageClaim>| {
+            c.as_ref()
+                .map(|c| format!("{:.0}%", c.utilization * 100.0))
+                .unwrap_or_else(|| "—".to_string())
+        };
+        text.push_str(&format!(
+            "\nLimits: 5-hour {}, 7-day {}",
+            pct(&info.five_hour),
+            pct(&info.seven_day)
+        ));
+    }
+    Ok(text)
+}
+
+fn devlog_for(date: Option<&str>, log_type: &str) -> Result<DevLog, SprtError> {
+    let date = date
+        .map(String::from)
+        .unwrap_or_else(|| DayClock::load().today_key());
+    storage::get_devlog(&date, log_type)?
+        .ok_or_else(|| SprtError::NoData(format!("No {} devlog for {}", log_type, date)))
+}
+
+/// Copy a stored devlog as Markdown (default: today's daily log). Returns the copied text.
+#[tauri::command]
+pub fn copy_devlog_markdown(
+    app: AppHandle,
+    date: Option<String>,
+    log_type: Option<String>,
+) -> Result<String, SprtError> {
+    let log = devlog_for(date.as_deref(), log_type.as_deref().unwrap_or("daily"))?;
+    let md = devlog_markdown(&log);
+    copy(&app, &md)?;
+    Ok(md)
+}
+
+#[tauri::command]
+pub async fn copy_today_stats(app: AppHandle) -> Result<String, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = today_stats_text()?;
+        copy(&app, &text)?;
+        Ok(text)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn copy_standup(app: AppHandle, date: Option<String>) -> Result<String, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = standup::standup(date.as_deref())?.text;
+        copy(&app, &text)?;
+        Ok(text)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Tray menu actions: build the text off the main thread, copy it, confirm with a notification
+pub fn copy_from_tray(app: &AppHandle, what: &'static str) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let (label, text) = match what {
+            "devlog" => ("Devlog", devlog_for(None, "daily").map(|l| devlog_markdown(&l))),
+            "stats" => ("Today's stats", today_stats_text()),
+            _ => ("Standup", standup::standup(None).map(|s| s.text)),
+        };
+        match text.and_then(|t| copy(&app, &t)) {
+            Ok(()) => notifications::notify_app(&format!("{} copied", label), "Ready to paste."),
+            Err(e) => notifications::notify_app(&format!("Cannot copy {}", label.to_lowercase()), &e.to_string()),
+        };
+    });
+}
@@ -0,0 +1,73 @@
+use crate::error::SprtError;
+use crate::git;
+use crate::notifications;
+use crate::scheduler;
+use crate::session_index;
+use crate::settings;
+use crate::storage::{self, SessionQuery};
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+const CHECK_INTERVAL_SECS: u64 = 5 * 60;
+/// A session with a message this recent is still in progress
+const ACTIVE_WITHIN_SECS: i64 = 15 * 60;
+
+/// Session files already nudged, so each long session gets one reminder
+static NUDGED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn check_and_nudge() -> Result<(), SprtError> {
+    let cfg = settings::load().commit_nudge;
+    if !cfg.enabled || cfg.after_hours <= 0.0 {
+        return Ok(());
+    }
+    let now = chrono::Utc::now().timestamp();
+    let min_secs = (cfg.after_hours * 3600.0) as i64;
+
+    session_index::refresh_in_background()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(now - ACTIVE_WITHIN_SECS),
+        to: None,
+        device: None,
+        sort: String::new(),
+        offset: 0,
+        limit: 100,
+    })?;
+
+    let mut nudged = NUDGED.lock().unwrap_or_else(|e| e.into_inner());
+    for s in sessions {
+        let Some(started) = s.first_message_at else {
+            continue;
+        };
+        if now - started < min_secs || nudged.contains(&s.path) {
+            continue;
+        }
+        if !git::is_repo(&s.project_path) {
+            continue;
+        }
+        // Any commit since the session started, on any branch, counts
+        if git::latest_commit_time(&s.project_path).is_some_and(|t| t >= started) {
+            continue;
+        }
+        let name = Path::new(&s.project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| s.project_path.clone());
+        let hours = (now - started) as f64 / 3600.0;
+        let body = format!(
+            "{:.1}h in {} without a commit. Consider committing your progress.",
+            hours, name
+        );
+        if notifications::notify_app("Time for a commit?", &body) {
+            nudged.insert(s.path);
+        }
+    }
+    Ok(())
+}
+
+/// Background check for long sessions with no commits; off when `commitNudge.enabled` is false
+pub fn start() {
+    scheduler::every("Commit nudge check", CHECK_INTERVAL_SECS, check_and_nudge);
+}
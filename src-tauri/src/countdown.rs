@@ -0,0 +1,99 @@
+use crate::claude::{self, UsageClaim};
+use crate::monitor;
+use crate::notifications;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Below this, the countdown is emitted every second; above it, once a minute
+const FINE_GRAINED_SECS: u64 = 3600;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimCountdown {
+    /// Unix timestamp of the reset
+    pub reset_at: u64,
+    pub remaining_secs: u64,
+    /// "4d 03h", "2h 05m", "12m 05s"
+    pub remaining: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitCountdown {
+    pub five_hour: Option<ClaimCountdown>,
+    pub seven_day: Option<ClaimCountdown>,
+}
+
+pub fn format_remaining(secs: u64) -> String {
+    let (d, h, m, s) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+    if d > 0 {
+        format!("{}d {:02}h", d, h)
+    } else if h > 0 {
+        format!("{}h {:02}m", h, m)
+    } else {
+        format!("{}m {:02}s", m, s)
+    }
+}
+
+fn countdown(claim: Option<&UsageClaim>, now: u64) -> Option<ClaimCountdown> {
+    let reset_at = claim?.reset?;
+    let remaining_secs = reset_at.saturating_sub(now);
+    Some(ClaimCountdown {
+        reset_at,
+        remaining_secs,
+        remaining: format_remaining(remaining_secs),
+    })
+}
+
+/// Tick once a second, emitting `limit-countdown` from the cached rate limits (never probes
+/// the API) and notifying when the 5-hour window resets.
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        // Reset timestamp already announced, so each window notifies once
+        let mut notified_reset: Option<u64> = None;
+        let mut last_emit: Option<u64> = None;
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            if monitor::is_paused() {
+                continue;
+            }
+            let Some(info) = claude::get_cached_rate_limits() else {
+                continue;
+            };
+            let now = chrono::Utc::now().timestamp().max(0) as u64;
+
+            let payload = LimitCountdown {
+                five_hour: countdown(info.five_hour.as_ref(), now),
+                seven_day: countdown(info.seven_day.as_ref(), now),
+            };
+
+            if let Some(five) = &payload.five_hour {
+                if five.remaining_secs == 0 && notified_reset != Some(five.reset_at) {
+                    notified_reset = Some(five.reset_at);
+                    notifications::notify(
+                        &app,
+                        "Budget refreshed",
+                        "Your 5-hour Claude usage window has reset.",
+                    );
+                }
+            }
+
+            let soonest = [&payload.five_hour, &payload.seven_day]
+                .into_iter()
+                .flatten()
+                .map(|c| c.remaining_secs)
+                .min();
+            let fine = soonest.is_some_and(|s| s < FINE_GRAINED_SECS);
+            let due = match last_emit {
+                Some(t) => fine || now >= t + 60,
+                None => true,
+            };
+            if due {
+                last_emit = Some(now);
+                let _ = app.emit("limit-countdown", &payload);
+            }
+        }
+    });
+}
@@ -0,0 +1,194 @@
+use crate::error::SprtError;
+use crate::secrets;
+use crate::settings;
+use crate::storage;
+
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Marks an encrypted value: "sprt-enc1:" + base64(salt | nonce | ciphertext)
+const PREFIX: &str = "sprt-enc1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const PBKDF2_ROUNDS: u32 = 210_000;
+
+/// Derived keys by salt. Key derivation is deliberately slow, so each salt is derived once;
+/// this process encrypts everything under the first salt it picks.
+type KeyCache = HashMap<[u8; SALT_LEN], Key>;
+static KEYS: LazyLock<Mutex<KeyCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static WRITE_SALT: LazyLock<[u8; SALT_LEN]> = LazyLock::new(|| {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+});
+
+fn passphrase() -> Result<String, SprtError> {
+    secrets::get(secrets::ENCRYPTION_PASSPHRASE)?.ok_or_else(|| {
+        SprtError::InvalidInput("No encryption passphrase in the keychain".to_string())
+    })
+}
+
+fn key_for(salt: &[u8; SALT_LEN]) -> Result<Key, SprtError> {
+    if let Some(key) = KEYS.lock().unwrap_or_else(|e| e.into_inner()).get(salt) {
+        return Ok(*key);
+    }
+    let mut key = Key::default();
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase()?.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    KEYS.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(*salt, key);
+    Ok(key)
+}
+
+/// Whether new devlogs and exports are written encrypted
+pub fn enabled() -> bool {
+    settings::load().encryption.enabled
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+pub fn encrypt(plaintext: &str) -> Result<String, SprtError> {
+    let salt = *WRITE_SALT;
+    let cipher = XChaCha20Poly1305::new(&key_for(&salt)?);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| SprtError::Internal("Encryption failed".to_string()))?;
+    let mut blob = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+    Ok(format!(
+        "{}{}",
+        PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(blob)
+    ))
+}
+
+/// Plaintext of `value`; values without the encryption prefix pass through unchanged,
+/// so logs written before encryption was turned on keep reading
+pub fn decrypt(value: &str) -> Result<String, SprtError> {
+    let Some(encoded) = value.strip_prefix(PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let blob = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| SprtError::Parse(format!("Corrupt encrypted value: {}", e)))?;
+    if blob.len() < SALT_LEN + NONCE_LEN {
+        return Err(SprtError::Parse(
+            "Corrupt encrypted value: too short".to_string(),
+        ));
+    }
+    let (salt, rest) = blob.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let salt: [u8; SALT_LEN] = salt.try_into().unwrap_or_default();
+    let plaintext = XChaCha20Poly1305::new(&key_for(&salt)?)
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            SprtError::InvalidInput("Cannot decrypt: wrong passphrase or corrupt data".to_string())
+        })?;
+    String::from_utf8(plaintext)
+        .map_err(|e| SprtError::Parse(format!("Decrypted value isn't text: {}", e)))
+}
+
+/// Path and content for an export file meant for `dest`: encrypted, with ".enc" appended,
+/// when encryption is on and the file lands inside the (possibly synced) SPRT directory.
+/// Exports elsewhere stay readable.
+pub fn protect_export(dest: PathBuf, content: &str) -> Result<(PathBuf, String), SprtError> {
+    let in_sprt_dir = storage::sprt_dir().is_some_and(|dir| dest.starts_with(dir));
+    if !in_sprt_dir || !enabled() {
+        return Ok((dest, content.to_string()));
+    }
+    let mut name = dest.clone().into_os_string();
+    name.push(".enc");
+    Ok((PathBuf::from(name), encrypt(content)?))
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    /// A passphrase is stored in the keychain
+    pub has_passphrase: bool,
+    pub encrypted_devlogs: u64,
+    pub plaintext_devlogs: u64,
+}
+
+fn status() -> Result<EncryptionStatus, SprtError> {
+    let (encrypted_devlogs, plaintext_devlogs) = storage::devlog_encryption_counts(PREFIX)?;
+    Ok(EncryptionStatus {
+        enabled: enabled(),
+        has_passphrase: passphrase().is_ok(),
+        encrypted_devlogs,
+        plaintext_devlogs,
+    })
+}
+
+#[tauri::command]
+pub async fn get_encryption_status() -> Result<EncryptionStatus, SprtError> {
+    tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Turn encryption of stored devlogs and in-directory exports on or off, then bring existing
+/// devlogs in line. A new passphrase replaces the keychain's; every synced machine needs the
+/// same one.
+#[tauri::command]
+pub async fn set_encryption(
+    enabled: bool,
+    passphrase: Option<String>,
+) -> Result<EncryptionStatus, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Some(p) = passphrase.filter(|p| !p.is_empty()) {
+            // Rows under the old passphrase must be readable before it's replaced
+            if self::passphrase().is_ok() && storage::devlog_encryption_counts(PREFIX)?.0 > 0 {
+                storage::migrate_devlog_encryption(false)?;
+            }
+            secrets::set(secrets::ENCRYPTION_PASSPHRASE, &p)?;
+            KEYS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+        if enabled {
+            // Fail before flipping the setting, not on the next devlog write
+            self::passphrase()?;
+        }
+        let mut s = settings::load();
+        s.encryption.enabled = enabled;
+        settings::save(&s)?;
+        storage::migrate_devlog_encryption(enabled)?;
+        status()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Encrypt stored devlogs that are still plaintext (or decrypt them all when encryption is
+/// off), e.g. after logs synced in from another machine. Returns how many were rewritten.
+#[tauri::command]
+pub async fn migrate_devlog_encryption() -> Result<u64, SprtError> {
+    tauri::async_runtime::spawn_blocking(|| storage::migrate_devlog_encryption(enabled()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Decrypt an encrypted export file to `dest` (default: alongside, without ".enc")
+#[tauri::command]
+pub fn decrypt_export(path: String, dest: Option<String>) -> Result<String, SprtError> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| SprtError::Storage(format!("Cannot read {}: {}", path, e)))?;
+    let dest = dest.unwrap_or_else(|| {
+        path.strip_suffix(".enc")
+            .map(String::from)
+            .unwrap_or_else(|| format!("{}.decrypted", path))
+    });
+    storage::write_atomic(Path::new(&dest), &decrypt(&content)?)?;
+    Ok(dest)
+}
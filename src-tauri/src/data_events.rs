@@ -0,0 +1,166 @@
+use crate::cache;
+use crate::claude;
+use crate::git;
+use crate::stats_history;
+use crate::velocity;
+
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use tauri::{AppHandle, Emitter};
+
+/// A session log was written; payload lists the affected projects
+pub const SESSIONS_CHANGED: &str = "sessions-changed";
+/// ~/.claude/stats-cache.json was rewritten
+pub const STATS_CACHE_CHANGED: &str = "stats-cache-changed";
+/// ~/.claude/.credentials.json changed: login, logout, token refresh or plan change
+pub const CREDENTIALS_CHANGED: &str = "credentials-changed";
+/// Plan or rate-limit tier differs from before (login, logout, plan switch); payload is the
+/// new Account. Token refreshes don't count.
+pub const ACCOUNT_CHANGED: &str = "account-changed";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsChanged {
+    /// Decoded project paths, sorted
+    pub projects: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub plan_type: String,
+    pub rate_limit_tier: String,
+}
+
+fn read_account() -> Option<Account> {
+    claude::plan()
+        .ok()
+        .map(|(plan_type, rate_limit_tier)| Account {
+            plan_type,
+            rate_limit_tier,
+        })
+}
+
+/// Watch ~/.claude and every data root's projects dir, sending events to `tx`.
+/// None when the platform watcher can't be created.
+pub fn watch(tx: Sender<notify::Result<notify::Event>>) -> Option<RecommendedWatcher> {
+    let mut watcher = match RecommendedWatcher::new(tx, Config::default()) {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Failed to create file watcher: {e}");
+            return None;
+        }
+    };
+    if let Some(cd) = claude::claude_dir() {
+        // Watch parent dir so we catch file creation (stats-cache.json may not exist yet)
+        if let Err(e) = watcher.watch(&cd, RecursiveMode::NonRecursive) {
+            log::warn!("Cannot watch {}: {e}", cd.display());
+        }
+    }
+    // Session logs in this machine's and any extra Claude directories. Network
+    // mounts may not report changes; those are picked up on the next scan.
+    for root in claude::data_roots() {
+        let pd = root.dir.join("projects");
+        if pd.exists() {
+            if let Err(e) = watcher.watch(&pd, RecursiveMode::Recursive) {
+                log::warn!("Cannot watch {}: {e}", pd.display());
+            }
+        }
+    }
+    Some(watcher)
+}
+
+/// Changes seen since the last emit; the watcher flushes them at most every 2 seconds
+pub struct Pending {
+    projects: BTreeSet<String>,
+    stats_cache: bool,
+    credentials: bool,
+    /// Set when the account differs from `account`
+    account_changed: bool,
+    /// Plan and tier as of the last credentials change (or watcher start)
+    account: Option<Account>,
+}
+
+/// Encoded project dir of a session log: the component right after "projects"
+/// (subagent logs sit deeper, under the session's own directory)
+fn project_dir(path: &Path) -> Option<String> {
+    let mut parts = path.components().map(|c| c.as_os_str().to_string_lossy());
+    parts.find(|p| p == "projects")?;
+    parts.next().map(|p| p.to_string())
+}
+
+impl Pending {
+    pub fn new() -> Self {
+        Pending {
+            projects: BTreeSet::new(),
+            stats_cache: false,
+            credentials: false,
+            account_changed: false,
+            account: read_account(),
+        }
+    }
+
+    fn on_credentials_changed(&mut self) {
+        self.credentials = true;
+        let account = read_account();
+        if account == self.account {
+            return;
+        }
+        log::info!("Claude account changed: {:?}", account);
+        self.account = account;
+        self.account_changed = true;
+        // Plan type is read during the realtime scan; limits belonged to the old account
+        cache::invalidate(&[cache::REALTIME_STATS]);
+        claude::clear_rate_limits();
+        std::thread::spawn(|| {
+            if let Err(e) = claude::realtime_stats() {
+                log::warn!("Cannot refresh stats after account change: {e}");
+            }
+        });
+    }
+
+    /// Record a changed path and invalidate the caches derived from it
+    pub fn on_file_changed(&mut self, path: &Path) {
+        match path.file_name().and_then(|n| n.to_str()) {
+            Some("stats-cache.json") => {
+                stats_history::on_file_changed(path);
+                self.stats_cache = true;
+            }
+            Some(".credentials.json") => self.on_credentials_changed(),
+            _ if path.extension().is_some_and(|e| e == "jsonl") => {
+                cache::on_file_changed(path);
+                velocity::on_file_changed(path);
+                if let Some(dir) = project_dir(path) {
+                    self.projects.insert(git::decode_project_path(&dir));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty() && !self.stats_cache && !self.credentials
+    }
+
+    /// Emit one event per changed domain and start over
+    pub fn flush(&mut self, app: &AppHandle) {
+        if !self.projects.is_empty() {
+            let payload = SessionsChanged {
+                projects: std::mem::take(&mut self.projects).into_iter().collect(),
+            };
+            let _ = app.emit(SESSIONS_CHANGED, payload);
+        }
+        if std::mem::take(&mut self.stats_cache) {
+            let _ = app.emit(STATS_CACHE_CHANGED, ());
+        }
+        if std::mem::take(&mut self.credentials) {
+            let _ = app.emit(CREDENTIALS_CHANGED, ());
+        }
+        if std::mem::take(&mut self.account_changed) {
+            let _ = app.emit(ACCOUNT_CHANGED, self.account.clone());
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::window_state;
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "sprt";
+
+/// Views a link can open; anything else is ignored
+const VIEWS: &[&str] = &[
+    "dashboard",
+    "devlog",
+    "project",
+    "session",
+    "sessions",
+    "timeline",
+];
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Navigation {
+    pub view: String,
+    /// Rest of the path: a date for devlog, a project name or session id, ...
+    pub target: Option<String>,
+}
+
+/// Last link opened, until the dashboard confirms it through take_pending_navigation. Covers a
+/// link that launched the app, which arrives before the dashboard listens for "navigate".
+static PENDING: Mutex<Option<Navigation>> = Mutex::new(None);
+
+/// "sprt://devlog/2025-03-01" → view "devlog", target "2025-03-01"
+pub fn parse(url: &str) -> Option<Navigation> {
+    let rest = url.strip_prefix(SCHEME)?.strip_prefix("://")?;
+    let path = rest
+        .split(['?', '#'])
+        .next()
+        .unwrap_or("")
+        .trim_matches('/');
+    let (view, target) = match path.split_once('/') {
+        Some((v, t)) => (v, Some(t.trim_matches('/'))),
+        None => (path, None),
+    };
+    let view = if view.is_empty() { "dashboard" } else { view };
+    if !VIEWS.contains(&view) {
+        return None;
+    }
+    Some(Navigation {
+        view: view.to_string(),
+        target: target.filter(|t| !t.is_empty()).map(String::from),
+    })
+}
+
+/// Bring up the dashboard and tell it where to go
+pub fn navigate(app: &AppHandle, nav: Navigation) {
+    window_state::show_dashboard(app);
+    // Stored before emitting, so the dashboard's confirmation can't race ahead of it
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(nav.clone());
+    let _ = app.emit("navigate", &nav);
+}
+
+pub fn open(app: &AppHandle, url: &str) {
+    match parse(url) {
+        Some(nav) => navigate(app, nav),
+        None => log::warn!("Ignoring unknown link: {url}"),
+    }
+}
+
+/// Route sprt:// links through the deep-link plugin: the link that launched the app, and
+/// links opened later (on Windows and Linux the single-instance plugin forwards them from
+/// the second process to this one).
+pub fn init(app: &AppHandle) {
+    // The bundle registers the scheme on install; this also covers dev builds and
+    // AppImages that were never integrated with the desktop
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("Cannot register the {SCHEME}:// scheme: {e}");
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, url.as_str());
+        }
+    });
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            open(app, url.as_str());
+        }
+    }
+}
+
+/// The last link opened, once. The dashboard calls this on load and again whenever it
+/// receives "navigate", so a delivered link is never replayed on the next load.
+#[tauri::command]
+pub fn take_pending_navigation() -> Option<Navigation> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
@@ -0,0 +1,183 @@
+use crate::error::SprtError;
+use crate::http;
+use crate::notion;
+use crate::secrets;
+use crate::settings::{self, WebhookConfig};
+use crate::storage::{DevLog, DevLogStats};
+
+/// Slack rejects section text longer than this
+const SLACK_SECTION_MAX_CHARS: usize = 3000;
+
+pub(crate) fn title_for(log: &DevLog) -> String {
+    match log.log_type.as_str() {
+        "weekly" => format!("SPRT weekly log — week of {}", log.date),
+        "monthly" => format!("SPRT monthly log — {}", log.date.get(..7).unwrap_or(&log.date)),
+        _ => format!("SPRT daily log — {}", log.date),
+    }
+}
+
+fn stats_line(stats: &DevLogStats, score: u32) -> String {
+    format!(
+        "Score {}/100 · {} commits · {} messages · +{} -{} · {:.1}h active",
+        score,
+        stats.total_commits,
+        stats.total_messages,
+        stats.total_insertions,
+        stats.total_deletions,
+        stats.active_hours
+    )
+}
+
+fn highlights_text(log: &DevLog) -> String {
+    log.highlights
+        .iter()
+        .map(|h| format!("• {}", h))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `text` as Slack section blocks of at most SLACK_SECTION_MAX_CHARS each, split between
+/// lines where possible
+fn slack_sections(text: &str) -> Vec<serde_json::Value> {
+    let mut chunks: Vec<String> = vec![];
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        let chars: Vec<char> = line.chars().collect();
+        for piece in chars.chunks(SLACK_SECTION_MAX_CHARS) {
+            if current.chars().count() + piece.len() > SLACK_SECTION_MAX_CHARS {
+                chunks.push(std::mem::take(&mut current));
+            }
+            current.extend(piece);
+        }
+    }
+    chunks.push(current);
+    chunks
+        .iter()
+        .map(|c| c.trim_end())
+        .filter(|c| !c.is_empty())
+        .map(|c| {
+            serde_json::json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": c }
+            })
+        })
+        .collect()
+}
+
+/// Build the request body for a destination kind ("json", "slack", "discord")
+fn build_payload(kind: &str, log: &DevLog) -> serde_json::Value {
+    let title = title_for(log);
+    let stats = stats_line(&log.stats, log.sprint_score);
+    let highlights = highlights_text(log);
+
+    match kind {
+        "slack" => {
+            let mut blocks = vec![serde_json::json!({
+                "type": "header",
+                "text": { "type": "plain_text", "text": title }
+            })];
+            blocks.extend(slack_sections(&log.summary));
+            blocks.extend(slack_sections(&highlights));
+            blocks.push(serde_json::json!({
+                "type": "context",
+                "elements": [{ "type": "mrkdwn", "text": stats }]
+            }));
+            serde_json::json!({ "text": title, "blocks": blocks })
+        }
+        "discord" => {
+            let mut description = log.summary.clone();
+            if !highlights.is_empty() {
+                description.push_str("\n\n");
+                description.push_str(&highlights);
+            }
+            // Discord rejects embed descriptions over 4096 chars
+            let description: String = description.chars().take(4096).collect();
+            serde_json::json!({
+                "embeds": [{
+                    "title": title,
+                    "description": description,
+                    "footer": { "text": stats }
+                }]
+            })
+        }
+        _ => serde_json::json!({
+            "event": "devlog.generated",
+            "devlog": log,
+        }),
+    }
+}
+
+async fn post_webhook(hook: &WebhookConfig, log: &DevLog) -> Result<(), SprtError> {
+    let url = secrets::webhook_url(hook)
+        .ok_or_else(|| SprtError::InvalidInput(format!("Webhook '{}' has no URL", hook.name)))?;
+    let payload = build_payload(&hook.kind, log);
+    let resp = http::send(&format!("Webhook '{}'", hook.name), |client| {
+        client
+            .post(&url)
+            .header("content-type", "application/json")
+            .json(&payload)
+    })
+    .await?;
+
+    if !resp.status().is_success() {
+        return Err(SprtError::Api(format!(
+            "Webhook '{}' returned {}",
+            hook.name,
+            resp.status()
+        )));
+    }
+    Ok(())
+}
+
+/// Send a freshly generated devlog to every enabled webhook and to Notion. Failures are
+/// logged, not returned — delivery never affects generation.
+pub async fn deliver_devlog(log: DevLog) {
+    for hook in settings::load().webhooks.iter().filter(|h| h.enabled) {
+        if let Err(e) = post_webhook(hook, &log).await {
+            log::warn!("{e}");
+        }
+    }
+    notion::export_devlog(&log).await;
+}
+
+pub(crate) fn sample_devlog() -> DevLog {
+    DevLog {
+        id: "test".to_string(),
+        date: crate::time::DayClock::load().today_key(),
+        log_type: "daily".to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        summary: "This is a test message from SPRT. Your webhook is configured correctly."
+            .to_string(),
+        highlights: vec!["Webhook delivery works".to_string()],
+        projects_worked: vec![],
+        stats: DevLogStats {
+            total_commits: 0,
+            total_messages: 0,
+            total_tokens: 0,
+            total_files_changed: 0,
+            total_insertions: 0,
+            total_deletions: 0,
+            active_hours: 0.0,
+            projects_count: 0,
+            commit_messages: Default::default(),
+            devices: vec![],
+            provider_tokens: Default::default(),
+            ai_assisted_hours: 0.0,
+            manual_hours: 0.0,
+        },
+        sprint_score: 0,
+        github_activity: vec![],
+        checkin: None,
+        value: None,
+        issues_touched: vec![],
+        prompt_truncation: None,
+        plan_completion: None,
+        candidate_todos: vec![],
+    }
+}
+
+/// Send a sample devlog to a webhook so the user can verify it before enabling it
+#[tauri::command]
+pub async fn test_webhook(webhook: WebhookConfig) -> Result<(), SprtError> {
+    post_webhook(&webhook, &sample_devlog()).await
+}
@@ -0,0 +1,445 @@
+use crate::claude::{RateLimitInfo, UsageClaim};
+use crate::error::SprtError;
+use crate::paths::{self, DataPaths};
+use crate::storage;
+
+use chrono::{Duration, Local, TimeZone, Timelike};
+use git2::{Repository, Signature, Time};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Days of history generated
+const DAYS: i64 = 14;
+const EMAIL: &str = "demo@sprt.dev";
+
+const PROJECTS: &[(&str, &[&str])] = &[
+    (
+        "storefront",
+        &[
+            "Add a cart badge to the header",
+            "Fix rounding in the checkout total",
+            "Make the product grid responsive",
+            "Add skeleton loaders to the catalog page",
+        ],
+    ),
+    (
+        "api-gateway",
+        &[
+            "Add per-client rate limiting",
+            "Return 404 instead of 500 for unknown routes",
+            "Cache JWKS keys for five minutes",
+            "Add request ids to the access log",
+        ],
+    ),
+    (
+        "ml-pipeline",
+        &[
+            "Parallelize feature extraction",
+            "Fix the off-by-one in the sliding window",
+            "Add a dry-run flag to the training job",
+        ],
+    ),
+    (
+        "dotfiles",
+        &[
+            "Switch the prompt to starship",
+            "Add git aliases for rebasing",
+        ],
+    ),
+];
+
+/// Model and its share weight
+const MODELS: &[(&str, u64)] = &[
+    ("claude-sonnet-4-20250514", 6),
+    ("claude-opus-4-20250514", 3),
+    ("claude-3-5-haiku-20241022", 1),
+];
+
+/// Canned reply for every AI feature; each reads only the fields it knows
+const AI_RESPONSE: &str = r#"{
+  "summary": "Steady progress across the storefront and the API gateway: checkout rounding is fixed, rate limiting landed behind a flag, and the catalog page loads noticeably faster.",
+  "highlights": [
+    "Fixed rounding errors in the checkout total",
+    "Added per-client rate limiting to the gateway",
+    "Parallelized feature extraction in the ML pipeline",
+    "Try batching small commits into reviewable chunks before lunch"
+  ],
+  "sprint_score": 78,
+  "project_notes": {
+    "storefront": ["Checkout total rounding", "Cart badge in the header"],
+    "api-gateway": ["Per-client rate limiting", "Request ids in the access log"]
+  },
+  "features": ["Per-client rate limiting", "Cart badge in the header"],
+  "fixes": ["Checkout total rounding", "404 for unknown routes"],
+  "chores": ["Access log request ids"]
+}"#;
+
+/// Demo mode: $SPRT_DEMO=1, or demoMode in the settings file of the user's real data
+/// directory (`real`, which honours SPRT_HOME and SPRT_DATA_DIR). Checked before the data
+/// paths are chosen, so the file is read directly.
+pub fn requested(real: &DataPaths) -> bool {
+    if let Some(v) = std::env::var_os("SPRT_DEMO") {
+        return v == "1" || v == "true";
+    }
+    flag(real)
+}
+
+fn real_settings_path(real: &DataPaths) -> Option<PathBuf> {
+    real.sprt_dir.as_ref().map(|d| d.join("settings.json"))
+}
+
+/// demoMode as saved in the real settings file
+pub fn flag(real: &DataPaths) -> bool {
+    real_settings_path(real)
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .and_then(|v| v.get("demoMode").and_then(|d| d.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Save demoMode to the real settings file. While the demo runs, the app's own settings file
+/// is the demo copy, so this is the only way the flag reaches the next launch.
+pub fn set_flag(real: &DataPaths, on: bool) -> Result<(), SprtError> {
+    let path = real_settings_path(real)
+        .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?;
+    let mut value = fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .filter(|v| v.is_object())
+        .unwrap_or_else(|| json!({}));
+    value["demoMode"] = json!(on);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| SprtError::Storage(format!("Cannot create directory: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(&value)
+        .map_err(|e| SprtError::Internal(format!("Serialize error: {}", e)))?;
+    storage::write_atomic(&path, &content)
+}
+
+pub fn active() -> bool {
+    paths::get().demo
+}
+
+/// Where the generated home directory lives
+pub fn root() -> PathBuf {
+    std::env::temp_dir().join("sprt-demo")
+}
+
+/// Small deterministic generator, so every launch tells the same story
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// lo..=hi
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next() % (hi - lo + 1)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next() as usize % items.len()]
+    }
+
+    fn model(&mut self) -> &'static str {
+        let total: u64 = MODELS.iter().map(|(_, w)| w).sum();
+        let mut roll = self.next() % total;
+        for (model, weight) in MODELS {
+            if roll < *weight {
+                return model;
+            }
+            roll -= weight;
+        }
+        MODELS[0].0
+    }
+}
+
+#[derive(Default)]
+struct DayStats {
+    messages: u64,
+    sessions: u64,
+    tool_calls: u64,
+    tokens_by_model: HashMap<String, u64>,
+}
+
+#[derive(Default)]
+struct Generated {
+    days: BTreeMap<String, DayStats>,
+    hours: HashMap<String, u64>,
+    models: HashMap<String, [u64; 4]>,
+    /// (project index, time, message)
+    commits: Vec<(usize, i64, String)>,
+}
+
+fn project_dir(root: &Path, name: &str) -> PathBuf {
+    root.join("code").join(name)
+}
+
+/// Claude Code's projects/ dir name for a path: separators become "-"
+fn encode(path: &Path) -> String {
+    path.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn write_session(
+    root: &Path,
+    project: usize,
+    start: chrono::DateTime<Local>,
+    messages: u64,
+    rng: &mut Rng,
+    out: &mut Generated,
+) -> Result<(), String> {
+    let (name, prompts) = PROJECTS[project];
+    let cwd = project_dir(root, name);
+    let dir = root.join(".claude/projects").join(encode(&cwd));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let stamp = |t: chrono::DateTime<Local>| {
+        t.with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    };
+
+    let prompt = rng.pick(prompts).to_string();
+    let mut lines = vec![json!({
+        "type": "user",
+        "timestamp": stamp(start),
+        "cwd": cwd,
+        "sessionId": session_id,
+        "message": {"role": "user", "content": prompt},
+    })
+    .to_string()];
+
+    let now = Local::now();
+    let mut t = start;
+    let day = out
+        .days
+        .entry(start.format("%Y-%m-%d").to_string())
+        .or_default();
+    day.sessions += 1;
+    for _ in 0..messages {
+        t += Duration::seconds(rng.range(20, 240) as i64);
+        if t > now {
+            break;
+        }
+        let model = rng.model();
+        let usage = [
+            rng.range(5, 400),
+            rng.range(50, 2500),
+            rng.range(10_000, 90_000),
+            rng.range(0, 6_000),
+        ];
+        lines.push(
+            json!({
+                "type": "assistant",
+                "timestamp": stamp(t),
+                "cwd": cwd,
+                "sessionId": session_id,
+                "message": {
+                    "model": model,
+                    "role": "assistant",
+                    "content": [{"type": "text", "text": "Done."}],
+                    "usage": {
+                        "input_tokens": usage[0],
+                        "output_tokens": usage[1],
+                        "cache_read_input_tokens": usage[2],
+                        "cache_creation_input_tokens": usage[3],
+                    },
+                },
+            })
+            .to_string(),
+        );
+        day.messages += 1;
+        day.tool_calls += rng.range(0, 2);
+        *day.tokens_by_model.entry(model.to_string()).or_insert(0) += usage.iter().sum::<u64>();
+        *out.hours.entry(t.hour().to_string()).or_insert(0) += 1;
+        let totals = out.models.entry(model.to_string()).or_default();
+        for (total, n) in totals.iter_mut().zip(usage) {
+            *total += n;
+        }
+    }
+
+    let path = dir.join(format!("{}.jsonl", session_id));
+    fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    // Scans look at modification times, so make the file as old as its last message
+    if let Ok(f) = fs::File::options().write(true).open(&path) {
+        let _ = f.set_modified(t.into());
+    }
+
+    let span = (t - start).num_seconds().max(1) as u64;
+    for _ in 0..rng.range(0, 3) {
+        let at = start.timestamp() + rng.range(0, span) as i64;
+        out.commits
+            .push((project, at, rng.pick(prompts).to_string()));
+    }
+    Ok(())
+}
+
+fn write_commits(root: &Path, commits: &mut [(usize, i64, String)]) -> Result<(), String> {
+    commits.sort_by_key(|(_, at, _)| *at);
+    for (i, (name, _)) in PROJECTS.iter().enumerate() {
+        let dir = project_dir(root, name);
+        let repo = Repository::init(&dir).map_err(|e| e.to_string())?;
+        let mut config = repo.config().map_err(|e| e.to_string())?;
+        config
+            .set_str("user.name", "Demo User")
+            .map_err(|e| e.to_string())?;
+        config
+            .set_str("user.email", EMAIL)
+            .map_err(|e| e.to_string())?;
+
+        let mut parent: Option<git2::Oid> = None;
+        for (n, (_, at, message)) in commits.iter().filter(|(p, _, _)| *p == i).enumerate() {
+            let file = dir.join("NOTES.md");
+            let mut notes = fs::read_to_string(&file).unwrap_or_default();
+            notes.push_str(&format!("- {}\n", message));
+            fs::write(&file, notes).map_err(|e| e.to_string())?;
+            let mut index = repo.index().map_err(|e| e.to_string())?;
+            index
+                .add_path(Path::new("NOTES.md"))
+                .map_err(|e| e.to_string())?;
+            index.write().map_err(|e| e.to_string())?;
+            let tree = repo
+                .find_tree(index.write_tree().map_err(|e| e.to_string())?)
+                .map_err(|e| e.to_string())?;
+            let sig = Signature::new("Demo User", EMAIL, &Time::new(*at, 0))
+                .map_err(|e| e.to_string())?;
+            let parents: Vec<git2::Commit> = parent
+                .and_then(|p| repo.find_commit(p).ok())
+                .into_iter()
+                .collect();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            let subject = if n == 0 { "Initial commit" } else { message };
+            parent = Some(
+                repo.commit(Some("HEAD"), &sig, &sig, subject, &tree, &parent_refs)
+                    .map_err(|e| e.to_string())?,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn write_stats_cache(root: &Path, generated: &Generated) -> Result<(), String> {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    // Claude Code computes the cache up to yesterday; today only shows up in live scans
+    let days: Vec<(&String, &DayStats)> =
+        generated.days.iter().filter(|(d, _)| **d < today).collect();
+    let stats = json!({
+        "version": 2,
+        "lastComputedDate": days.last().map(|(d, _)| d.to_string()),
+        "dailyActivity": days.iter().map(|(date, d)| json!({
+            "date": date,
+            "messageCount": d.messages,
+            "sessionCount": d.sessions,
+            "toolCallCount": d.tool_calls,
+        })).collect::<Vec<_>>(),
+        "dailyModelTokens": days.iter().map(|(date, d)| json!({
+            "date": date,
+            "tokensByModel": d.tokens_by_model,
+        })).collect::<Vec<_>>(),
+        "modelUsage": generated.models.iter().map(|(model, u)| (model.clone(), json!({
+            "inputTokens": u[0],
+            "outputTokens": u[1],
+            "cacheReadInputTokens": u[2],
+            "cacheCreationInputTokens": u[3],
+        }))).collect::<serde_json::Map<_, _>>(),
+        "totalSessions": days.iter().map(|(_, d)| d.sessions).sum::<u64>(),
+        "totalMessages": days.iter().map(|(_, d)| d.messages).sum::<u64>(),
+        "firstSessionDate": days.first().map(|(d, _)| d.to_string()),
+        "hourCounts": generated.hours,
+    });
+    let content = serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?;
+    fs::write(root.join(".claude/stats-cache.json"), content).map_err(|e| e.to_string())
+}
+
+/// Generate a fresh home directory under `root`: two weeks of sessions across a few
+/// projects (one still running), matching git repos, and a stats cache
+pub fn prepare(root: &Path) -> Result<(), String> {
+    let _ = fs::remove_dir_all(root);
+    fs::create_dir_all(root.join(".claude/sprt")).map_err(|e| e.to_string())?;
+
+    let mut rng = Rng(0x5eed_cafe_f00d);
+    let mut generated = Generated::default();
+    let today = Local::now().date_naive();
+    for days_ago in (1..DAYS).rev() {
+        let date = today - Duration::days(days_ago);
+        let weekend = date.format("%u").to_string().parse::<u32>().unwrap_or(1) >= 6;
+        for _ in 0..rng.range(if weekend { 0 } else { 1 }, if weekend { 1 } else { 3 }) {
+            let Some(start) = Local
+                .from_local_datetime(
+                    &date
+                        .and_hms_opt(rng.range(9, 19) as u32, rng.range(0, 59) as u32, 0)
+                        .unwrap_or_default(),
+                )
+                .earliest()
+            else {
+                continue;
+            };
+            let project = rng.range(0, PROJECTS.len() as u64 - 1) as usize;
+            let messages = rng.range(15, 90);
+            write_session(root, project, start, messages, &mut rng, &mut generated)?;
+        }
+    }
+    // One session still going, so live stats and active sessions have something to show
+    let start = Local::now() - Duration::minutes(45);
+    write_session(root, 0, start, 40, &mut rng, &mut generated)?;
+
+    write_commits(root, &mut generated.commits)?;
+    write_stats_cache(root, &generated)
+}
+
+/// Plausible limits that drift over the 5-hour window
+pub fn rate_limits() -> RateLimitInfo {
+    let now = chrono::Utc::now().timestamp();
+    let window = 5 * 3600;
+    let reset = (now / window + 1) * window;
+    let elapsed = 1.0 - (reset - now) as f64 / window as f64;
+    let claim = |utilization: f64, reset: i64| UsageClaim {
+        utilization,
+        reset: Some(reset as u64),
+        status: if utilization >= 0.9 {
+            "allowed_warning".to_string()
+        } else {
+            "allowed".to_string()
+        },
+    };
+    let five_hour = claim(0.15 + 0.6 * elapsed, reset);
+    let seven_day = claim(0.38, now + 3 * 86400);
+    let seven_day_sonnet = claim(0.22, now + 3 * 86400);
+    let claims: BTreeMap<String, UsageClaim> = [
+        ("5h", &five_hour),
+        ("7d", &seven_day),
+        ("7d_sonnet", &seven_day_sonnet),
+    ]
+    .into_iter()
+    .map(|(k, c)| (k.to_string(), c.clone()))
+    .collect();
+    RateLimitInfo {
+        status: "allowed".to_string(),
+        representative_claim: Some("five_hour".to_string()),
+        five_hour: Some(five_hour),
+        seven_day: Some(seven_day),
+        seven_day_sonnet: Some(seven_day_sonnet),
+        seven_day_opus: None,
+        overage_status: Some("allowed".to_string()),
+        overage_disabled_reason: None,
+        overage_reset: None,
+        fallback_percentage: None,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        claims,
+        extra: BTreeMap::new(),
+        model_weekly_claim: None,
+    }
+}
+
+pub fn ai_response() -> String {
+    AI_RESPONSE.to_string()
+}
@@ -0,0 +1,134 @@
+use crate::error::SprtError;
+use crate::session_index;
+use crate::settings;
+use crate::storage::{self, SessionQuery};
+use crate::time::DayClock;
+
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// A session log this process sees grow, whose newest message is at most this old, may be
+/// written here (see session_index::refresh). Anything older arrived by sync or is history.
+pub const LIVE_WITHIN_SECS: i64 = 5 * 60;
+
+/// Name this machine's sessions are recorded under: `deviceName` from settings, else the
+/// hostname without a trailing ".local"
+pub fn current() -> String {
+    if let Some(name) = settings::load()
+        .device_name
+        .filter(|n| !n.trim().is_empty())
+    {
+        return name.trim().to_string();
+    }
+    let host = gethostname::gethostname().to_string_lossy().to_string();
+    host.strip_suffix(".local").unwrap_or(&host).to_string()
+}
+
+/// Devices with sessions on a date, sorted; sessions of unknown origin are left out
+pub fn active_on(date: chrono::NaiveDate) -> Result<Vec<String>, SprtError> {
+    let Some((start, end)) = DayClock::load().day_range(date) else {
+        return Ok(vec![]);
+    };
+    session_index::refresh()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(start.timestamp()),
+        to: Some(end.timestamp()),
+        device: None,
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: u32::MAX as u64,
+    })?;
+    let devices: BTreeSet<String> = sessions.into_iter().filter_map(|s| s.device).collect();
+    Ok(devices.into_iter().collect())
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceStats {
+    /// "unknown" for sessions indexed before they could be attributed
+    pub device: String,
+    pub sessions: u64,
+    pub messages: u64,
+    pub tokens: u64,
+    /// First to last message of each session
+    pub session_hours: f64,
+    /// RFC 3339
+    pub last_active: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsByDevice {
+    pub days: u32,
+    /// This machine's name
+    pub current: String,
+    /// Busiest first (by tokens)
+    pub devices: Vec<DeviceStats>,
+}
+
+pub fn stats_by_device(days: u32) -> Result<StatsByDevice, SprtError> {
+    let days = days.max(1);
+    let clock = DayClock::load();
+    let first_day = clock.today() - chrono::Duration::days(days as i64 - 1);
+    let from = clock
+        .day_start(first_day)
+        .map(|t| t.timestamp())
+        .ok_or_else(|| SprtError::Internal("Cannot determine period start".to_string()))?;
+
+    session_index::refresh()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(from),
+        to: None,
+        device: None,
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: u32::MAX as u64,
+    })?;
+
+    let mut stats: HashMap<String, (DeviceStats, i64)> = HashMap::new();
+    for s in &sessions {
+        let device = s.device.clone().unwrap_or_else(|| "unknown".to_string());
+        let (d, last) = stats.entry(device.clone()).or_insert_with(|| {
+            (
+                DeviceStats {
+                    device,
+                    ..Default::default()
+                },
+                0,
+            )
+        });
+        d.sessions += 1;
+        d.messages += s.message_count;
+        d.tokens += s.total_tokens;
+        if let (Some(f), Some(l)) = (s.first_message_at, s.last_message_at) {
+            d.session_hours += (l - f).max(0) as f64 / 3600.0;
+            *last = (*last).max(l);
+        }
+    }
+    let mut devices: Vec<DeviceStats> = stats
+        .into_values()
+        .map(|(mut d, last)| {
+            d.last_active = chrono::DateTime::from_timestamp(last, 0)
+                .filter(|_| last > 0)
+                .map(|t| t.to_rfc3339());
+            d
+        })
+        .collect();
+    devices.sort_by_key(|d| std::cmp::Reverse(d.tokens));
+
+    Ok(StatsByDevice {
+        days,
+        current: current(),
+        devices,
+    })
+}
+
+/// Sessions, messages and tokens per machine over the last `days` days (default 30)
+#[tauri::command]
+pub async fn get_stats_by_device(days: Option<u32>) -> Result<StatsByDevice, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || stats_by_device(days.unwrap_or(30)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -1,474 +1,1497 @@
-use crate::claude;
-use crate::git;
-use crate::storage::{self, DevLog, DevLogStats, ProjectWork, SessionSummary};
-
-use std::collections::HashMap;
-
-const DEVLOG_SYSTEM_PROMPT: &str = r#"You are a development journal writer for SPRT (Sprint), a developer productivity tool.
-Given git commits, Claude Code session data, and code statistics, write a concise daily development log.
-
-Respond ONLY with valid JSON (no markdown fences, no extra text) in this exact format:
-{
-  "summary": "2-3 sentence overview of what was accomplished",
-  "highlights": ["key accomplishment 1", "key accomplishment 2", ...],
-  "sprint_score": 75,
-  "project_notes": {
-    "project-name": ["key change 1", "key change 2"]
-  }
-}
-
-Guidelines:
-- summary: Focus on outcomes, not process. Be specific about features/fixes.
-- highlights: 3-5 bullet points of the most important accomplishments.
-- sprint_score: 0-100 based on productivity. Consider commits, code volume, session duration.
-  - 90-100: Exceptional day (many commits, major features)
-  - 70-89: Productive day (steady progress)
-  - 50-69: Moderate day (some progress)
-  - 30-49: Light day (minor work)
-  - 0-29: Minimal activity
-- project_notes: Key changes per project (used for project cards).
-- Write in English. Keep it factual and concise."#;
-
-const WEEKLY_SYSTEM_PROMPT: &str = r#"You are a development journal writer for SPRT (Sprint).
-Given a collection of daily development logs, write a weekly summary report.
-
-Respond ONLY with valid JSON (no markdown fences, no extra text) in this exact format:
-{
-  "summary": "3-5 sentence weekly overview",
-  "highlights": ["weekly highlight 1", "weekly highlight 2", ...],
-  "sprint_score": 75,
-  "project_notes": {
-    "project-name": ["key change 1", "key change 2"]
-  }
-}
-
-Guidelines:
-- Summarize the week's progress at a high level.
-- Identify patterns (e.g., "focused heavily on X project").
-- sprint_score is the week's average productivity.
-- Write in English."#;
-
-#[tauri::command]
-pub async fn generate_devlog(date: String, log_type: String) -> Result<DevLog, String> {
-    // Check if already exists
-    if let Ok(Some(existing)) = storage::get_devlog(&date, &log_type) {
-        return Ok(existing);
-    }
-
-    match log_type.as_str() {
-        "daily" => generate_daily(&date).await,
-        "weekly" => generate_weekly(&date).await,
-        _ => Err(format!("Unknown log type: {}", log_type)),
-    }
-}
-
-#[tauri::command]
-pub fn get_devlog(date: String, log_type: String) -> Result<Option<DevLog>, String> {
-    storage::get_devlog(&date, &log_type)
-}
-
-#[tauri::command]
-pub fn list_devlogs(log_type: String, limit: Option<usize>) -> Result<Vec<DevLog>, String> {
-    storage::list_devlogs(&log_type, limit.unwrap_or(30))
-}
-
-#[tauri::command]
-pub fn get_git_activity(date: String) -> Result<Vec<git::GitActivity>, String> {
-    Ok(git::collect_git_activity(&date))
-}
-
-async fn generate_daily(date: &str) -> Result<DevLog, String> {
-    // 1. Collect data
-    let git_data = git::collect_git_activity(date);
-    let session_data = claude::get_session_summaries(date);
-
-    // If no data at all, return an empty-ish log
-    if git_data.is_empty() && session_data.is_empty() {
-        return Err("No activity found for this date. Nothing to generate.".to_string());
-    }
-
-    // 2. Build stats
-    let stats = build_stats(&git_data, &session_data);
-    let projects_worked = build_project_work(&git_data, &session_data);
-
-    // 3. Build prompt
-    let prompt = build_daily_prompt(date, &git_data, &session_data, &stats);
-
-    // 4. Call Claude API
-    let ai_response = call_claude_api(DEVLOG_SYSTEM_PROMPT, &prompt).await?;
-
-    // 5. Parse response
-    let parsed: serde_json::Value = serde_json::from_str(&ai_response)
-        .map_err(|e| format!("Failed to parse AI response: {}. Raw: {}", e, ai_response))?;
-
-    let summary = parsed
-        .get("summary")
-        .and_then(|v| v.as_str())
-        .unwrap_or("No summary generated.")
-        .to_string();
-
-    let highlights: Vec<String> = parsed
-        .get("highlights")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let sprint_score = parsed
-        .get("sprint_score")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(50) as u32;
-
-    // Merge project_notes from AI into projects_worked
-    let project_notes: HashMap<String, Vec<String>> = parsed
-        .get("project_notes")
-        .and_then(|v| serde_json::from_value(v.clone()).ok())
-        .unwrap_or_default();
-
-    let projects_worked = projects_worked
-        .into_iter()
-        .map(|mut pw| {
-            if let Some(notes) = project_notes.get(&pw.name) {
-                pw.key_changes = notes.clone();
-            }
-            pw
-        })
-        .collect();
-
-    let id = format!(
-        "{}-{}",
-        date,
-        chrono::Utc::now().timestamp_millis() % 10000
-    );
-
-    let devlog = DevLog {
-        id,
-        date: date.to_string(),
-        log_type: "daily".to_string(),
-        generated_at: chrono::Utc::now().to_rfc3339(),
-        summary,
-        highlights,
-        projects_worked,
-        stats,
-        sprint_score,
-    };
-
-    storage::save_devlog(&devlog)?;
-    Ok(devlog)
-}
-
-async fn generate_weekly(week_start: &str) -> Result<DevLog, String> {
-    // Get daily logs for the past 7 days
-    let start_date =
-        chrono::NaiveDate::parse_from_str(week_start, "%Y-%m-%d").map_err(|e| e.to_string())?;
-
-    let mut daily_logs = vec![];
-    let mut all_stats = DevLogStats {
-        total_commits: 0,
-        total_messages: 0,
-        total_tokens: 0,
-        total_files_changed: 0,
-        total_insertions: 0,
-        total_deletions: 0,
-        active_hours: 0.0,
-        projects_count: 0,
-    };
-
-    let mut all_projects: HashMap<String, ProjectWork> = HashMap::new();
-
-    for i in 0..7 {
-        let d = start_date + chrono::Duration::days(i);
-        let ds = d.format("%Y-%m-%d").to_string();
-        if let Ok(Some(log)) = storage::get_devlog(&ds, "daily") {
-            // Accumulate stats
-            all_stats.total_commits += log.stats.total_commits;
-            all_stats.total_messages += log.stats.total_messages;
-            all_stats.total_tokens += log.stats.total_tokens;
-            all_stats.total_files_changed += log.stats.total_files_changed;
-            all_stats.total_insertions += log.stats.total_insertions;
-            all_stats.total_deletions += log.stats.total_deletions;
-            all_stats.active_hours += log.stats.active_hours;
-
-            for pw in &log.projects_worked {
-                let entry = all_projects
-                    .entry(pw.name.clone())
-                    .or_insert_with(|| ProjectWork {
-                        name: pw.name.clone(),
-                        path: pw.path.clone(),
-                        commits: 0,
-                        messages: 0,
-                        tokens: 0,
-                        duration_minutes: 0,
-                        key_changes: vec![],
-                    });
-                entry.commits += pw.commits;
-                entry.messages += pw.messages;
-                entry.tokens += pw.tokens;
-                entry.duration_minutes += pw.duration_minutes;
-            }
-
-            daily_logs.push(log);
-        }
-    }
-
-    if daily_logs.is_empty() {
-        return Err("No daily logs found for this week. Generate daily logs first.".to_string());
-    }
-
-    all_stats.projects_count = all_projects.len() as u32;
-
-    // Build weekly prompt from daily summaries
-    let prompt = build_weekly_prompt(&daily_logs);
-    let ai_response = call_claude_api(WEEKLY_SYSTEM_PROMPT, &prompt).await?;
-
-    let parsed: serde_json::Value = serde_json::from_str(&ai_response)
-        .map_err(|e| format!("Failed to parse AI response: {}", e))?;
-
-    let summary = parsed
-        .get("summary")
-        .and_then(|v| v.as_str())
-        .unwrap_or("No summary generated.")
-        .to_string();
-    let highlights: Vec<String> = parsed
-        .get("highlights")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default();
-    let sprint_score = parsed
-        .get("sprint_score")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(50) as u32;
-
-    let id = format!(
-        "w-{}-{}",
-        week_start,
-        chrono::Utc::now().timestamp_millis() % 10000
-    );
-
-    let devlog = DevLog {
-        id,
-        date: week_start.to_string(),
-        log_type: "weekly".to_string(),
-        generated_at: chrono::Utc::now().to_rfc3339(),
-        summary,
-        highlights,
-        projects_worked: all_projects.into_values().collect(),
-        stats: all_stats,
-        sprint_score,
-    };
-
-    storage::save_devlog(&devlog)?;
-    Ok(devlog)
-}
-
-fn build_stats(
-    git_data: &[git::GitActivity],
-    session_data: &[SessionSummary],
-) -> DevLogStats {
-    let total_commits: u32 = git_data.iter().map(|g| g.commits.len() as u32).sum();
-    let total_messages: u64 = session_data.iter().map(|s| s.message_count).sum();
-    let total_tokens: u64 = session_data
-        .iter()
-        .map(|s| s.input_tokens + s.output_tokens + s.cache_read)
-        .sum();
-    let total_files: u32 = git_data.iter().map(|g| g.files_changed).sum();
-    let total_ins: u32 = git_data.iter().map(|g| g.insertions).sum();
-    let total_del: u32 = git_data.iter().map(|g| g.deletions).sum();
-    let total_duration_min: u64 = session_data.iter().map(|s| s.duration_minutes).sum();
-    let active_hours = total_duration_min as f64 / 60.0;
-    let projects_count = {
-        let mut names: Vec<&str> = git_data.iter().map(|g| g.repo_name.as_str()).collect();
-        names.sort();
-        names.dedup();
-        names.len() as u32
-    };
-
-    DevLogStats {
-        total_commits,
-        total_messages,
-        total_tokens,
-        total_files_changed: total_files,
-        total_insertions: total_ins,
-        total_deletions: total_del,
-        active_hours,
-        projects_count,
-    }
-}
-
-fn build_project_work(
-    git_data: &[git::GitActivity],
-    session_data: &[SessionSummary],
-) -> Vec<ProjectWork> {
-    let mut projects: HashMap<String, ProjectWork> = HashMap::new();
-
-    for g in git_data {
-        let entry = projects
-            .entry(g.repo_name.clone())
-            .or_insert_with(|| ProjectWork {
-                name: g.repo_name.clone(),
-                path: g.repo_path.clone(),
-                commits: 0,
-                messages: 0,
-                tokens: 0,
-                duration_minutes: 0,
-                key_changes: vec![],
-            });
-        entry.commits += g.commits.len() as u32;
-    }
-
-    // Match sessions to projects by path
-    for s in session_data {
-        // Find matching project by path
-        let repo_name = std::path::Path::new(&s.project_path)
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| s.project.clone());
-
-        let entry = projects
-            .entry(repo_name.clone())
-            .or_insert_with(|| ProjectWork {
-                name: repo_name,
-                path: s.project_path.clone(),
-                commits: 0,
-                messages: 0,
-                tokens: 0,
-                duration_minutes: 0,
-                key_changes: vec![],
-            });
-        entry.messages += s.message_count;
-        entry.tokens += s.input_tokens + s.output_tokens + s.cache_read;
-        entry.duration_minutes += s.duration_minutes;
-    }
-
-    projects.into_values().collect()
-}
-
-fn build_daily_prompt(
-    date: &str,
-    git_data: &[git::GitActivity],
-    session_data: &[SessionSummary],
-    stats: &DevLogStats,
-) -> String {
-    let mut prompt = format!("Generate a daily development log for {}.\n\n", date);
-
-    prompt.push_str(&format!(
-        "## Overview Stats\n- Commits: {}\n- Messages: {}\n- Tokens: {}\n- Files changed: {} (+{} -{})\n- Active hours: {:.1}\n- Projects: {}\n\n",
-        stats.total_commits, stats.total_messages, stats.total_tokens,
-        stats.total_files_changed, stats.total_insertions, stats.total_deletions,
-        stats.active_hours, stats.projects_count
-    ));
-
-    if !git_data.is_empty() {
-        prompt.push_str("## Git Activity\n");
-        for g in git_data {
-            prompt.push_str(&format!(
-                "### {} (branch: {})\n",
-                g.repo_name, g.branch
-            ));
-            for c in &g.commits {
-                prompt.push_str(&format!(
-                    "- [{}] {} (+{} -{})\n",
-                    c.hash.get(..7).unwrap_or(&c.hash),
-                    c.message,
-                    c.insertions,
-                    c.deletions
-                ));
-            }
-            prompt.push('\n');
-        }
-    }
-
-    if !session_data.is_empty() {
-        prompt.push_str("## Claude Code Sessions\n");
-        for s in session_data {
-            let repo_name = std::path::Path::new(&s.project_path)
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| s.project.clone());
-            prompt.push_str(&format!(
-                "- Project: {}, Messages: {}, Duration: {}min, Tokens: {}\n",
-                repo_name,
-                s.message_count,
-                s.duration_minutes,
-                s.input_tokens + s.output_tokens
-            ));
-        }
-    }
-
-    prompt
-}
-
-fn build_weekly_prompt(daily_logs: &[DevLog]) -> String {
-    let mut prompt = String::from("Generate a weekly summary from these daily logs:\n\n");
-
-    for log in daily_logs {
-        prompt.push_str(&format!(
-            "## {}\nScore: {}/100\nSummary: {}\nHighlights:\n",
-            log.date, log.sprint_score, log.summary
-        ));
-        for h in &log.highlights {
-            prompt.push_str(&format!("- {}\n", h));
-        }
-        prompt.push_str(&format!(
-            "Stats: {} commits, {} messages, {:.1}h active\n\n",
-            log.stats.total_commits, log.stats.total_messages, log.stats.active_hours
-        ));
-    }
-
-    prompt
-}
-
-async fn call_claude_api(system: &str, prompt: &str) -> Result<String, String> {
-    let token = claude::get_access_token().await?;
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
-    let resp = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("Authorization", format!("Bearer {}", token))
-        .header("anthropic-version", "2023-06-01")
-        .header("anthropic-beta", "oauth-2025-04-20")
-        .header("content-type", "application/json")
-        .json(&serde_json::json!({
-            "model": "claude-sonnet-4-20250514",
-            "max_tokens": 2048,
-            "system": system,
-            "messages": [{"role": "user", "content": prompt}]
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("API call failed: {}", e))?;
-
-    let status = resp.status();
-    let body: serde_json::Value = resp
-        .json()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-
-    if !status.is_success() {
-        let err_msg = body
-            .get("error")
-            .and_then(|e| e.get("message"))
-            .and_then(|m| m.as_str())
-            .unwrap_or("Unknown API error");
-        return Err(format!("API error ({}): {}", status, err_msg));
-    }
-
-    // Extract text from first content block
-    body.get("content")
-        .and_then(|c| c.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|block| block.get("text"))
-        .and_then(|t| t.as_str())
-        .map(|s| s.to_string())
-        .ok_or_else(|| "No text in API response".to_string())
-}
+use crate::analysis::{self, EfficiencyStats};
+use crate::claude;
+use crate::delivery;
+use crate::devices;
+use crate::editor;
+use crate::error::SprtError;
+use crate::git;
+use crate::github::{self, GithubItem};
+use crate::http;
+use crate::issues::{self, IssueRef};
+use crate::plan;
+use crate::privacy;
+use crate::providers;
+use crate::settings;
+use crate::tags;
+use crate::time;
+use crate::todos;
+use crate::value;
+use crate::storage::{
+    self, CandidateTodo, Checkin, DevLog, DevLogStats, FocusBlock, Plan, ProjectWork, PromptTruncation,
+    SessionSummary,
+};
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Mutex};
+
+const DEVLOG_SYSTEM_PROMPT: &str = r#"You are a development journal writer for SPRT (Sprint), a developer productivity tool.
+Given git commits, GitHub PR/issue activity, Claude Code session data, and code statistics, write a concise daily development log.
+
+Respond ONLY with valid JSON (no markdown fences, no extra text) in this exact format:
+{
+  "summary": "2-3 sentence overview of what was accomplished",
+  "highlights": ["key accomplishment 1", "key accomplishment 2", ...],
+  "sprint_score": 75,
+  "project_notes": {
+    "project-name": ["key change 1", "key change 2"]
+  }
+}
+
+Guidelines:
+- summary: Focus on outcomes, not process. Be specific about features/fixes.
+- highlights: 3-5 bullet points of the most important accomplishments.
+- sprint_score: 0-100 based on productivity. Consider commits, code volume, session duration.
+  - 90-100: Exceptional day (many commits, major features)
+  - 70-89: Productive day (steady progress)
+  - 50-69: Moderate day (some progress)
+  - 30-49: Light day (minor work)
+  - 0-29: Minimal activity
+- project_notes: Key changes per project (used for project cards).
+- If a check-in is given, let it inform the tone (e.g. acknowledge a short day due to illness)
+  without judging the person; don't quote mood or energy numbers.
+- If a plan for the day is given, add "plan_status": [{"done": true, "note": "short reason"}, ...]
+  with one entry per plan item, in order, judged from the commits and sessions. Mention notable
+  gaps between plan and outcome in the summary without scolding.
+- When issues are listed, refer to work by issue title (and key) rather than commit wording.
+- When project summaries are given instead of raw commits, build on them; each was written
+  from that project's full commit and session data.
+- Commit message stats are context, not accomplishments. Mention them in the summary only if
+  hygiene was notably poor (many WIP or fixup commits) or notably good.
+- Write in English. Keep it factual and concise."#;
+
+const WEEKLY_SYSTEM_PROMPT: &str = r#"You are a development journal writer for SPRT (Sprint).
+Given a collection of daily development logs, write a weekly summary report.
+
+Respond ONLY with valid JSON (no markdown fences, no extra text) in this exact format:
+{
+  "summary": "3-5 sentence weekly overview",
+  "highlights": ["weekly highlight 1", "weekly highlight 2", ...],
+  "sprint_score": 75,
+  "project_notes": {
+    "project-name": ["key change 1", "key change 2"]
+  }
+}
+
+Guidelines:
+- Summarize the week's progress at a high level.
+- Identify patterns (e.g., "focused heavily on X project").
+- sprint_score is the week's average productivity.
+- Write in English."#;
+
+const PROJECT_SUMMARY_SYSTEM_PROMPT: &str = r#"You summarize one project's development activity for a single day.
+Given its git commits and Claude Code sessions, write 2-4 plain sentences on what was built,
+fixed or explored. Name concrete features and fixes. No preamble, no markdown, no scores.
+Write in English."#;
+
+/// Writes the devlogs themselves, unless devlogModel.model says otherwise
+const DEFAULT_DEVLOG_MODEL: &str = "claude-sonnet-4-20250514";
+const DEFAULT_MAX_TOKENS: u32 = 2048;
+/// Per-project summaries in two-stage generation, unless devlogModel.summaryModel is set
+const DEFAULT_SUMMARY_MODEL: &str = "claude-3-5-haiku-20241022";
+const SUMMARY_MAX_TOKENS: u32 = 400;
+/// Project summaries requested at once
+const SUMMARY_CONCURRENCY: usize = 4;
+
+/// Daily prompts are trimmed until they fit this many (estimated) tokens
+const PROMPT_TOKEN_BUDGET: usize = 12_000;
+const CHARS_PER_TOKEN: usize = 4;
+/// Longer commit subjects are cut, with an ellipsis
+const MAX_COMMIT_MESSAGE_CHARS: usize = 200;
+
+/// How much raw activity goes into the daily prompt
+#[derive(Debug, Clone, Copy)]
+struct PromptLimits {
+    commits_per_repo: usize,
+    sessions: usize,
+    github_items: usize,
+}
+
+const DEFAULT_PROMPT_LIMITS: PromptLimits = PromptLimits {
+    commits_per_repo: 50,
+    sessions: 30,
+    github_items: 30,
+};
+
+/// Budgeting never trims below these; a prompt still over budget is sent as is
+const MIN_PROMPT_LIMITS: PromptLimits = PromptLimits {
+    commits_per_repo: 5,
+    sessions: 0,
+    github_items: 5,
+};
+
+impl PromptLimits {
+    /// The next smaller step. Session lines go first, then GitHub items, then commits.
+    fn tighten(self) -> Option<PromptLimits> {
+        let min = MIN_PROMPT_LIMITS;
+        let mut next = self;
+        if self.sessions > min.sessions {
+            next.sessions /= 2;
+        } else if self.github_items > min.github_items {
+            next.github_items = (self.github_items / 2).max(min.github_items);
+        } else if self.commits_per_repo > min.commits_per_repo {
+            next.commits_per_repo = (self.commits_per_repo / 2).max(min.commits_per_repo);
+        } else {
+            return None;
+        }
+        Some(next)
+    }
+}
+
+/// Model and max_tokens for one generation; unset fields fall back to settings.devlog_model,
+/// then to DEFAULT_DEVLOG_MODEL and DEFAULT_MAX_TOKENS
+#[derive(Debug, Clone, Default)]
+pub struct ModelOverride {
+    pub model: Option<String>,
+    pub max_tokens: Option<u32>,
+}
+
+impl ModelOverride {
+    fn resolve(&self) -> (String, u32) {
+        let cfg = settings::load().devlog_model;
+        // A blank override or setting means "not set", so it can't hide the next one down
+        let nonblank = |m: &String| !m.trim().is_empty();
+        let model = self
+            .model
+            .clone()
+            .filter(nonblank)
+            .or(cfg.model.filter(nonblank))
+            .unwrap_or_else(|| DEFAULT_DEVLOG_MODEL.to_string());
+        (model, self.max_tokens.or(cfg.max_tokens).unwrap_or(DEFAULT_MAX_TOKENS))
+    }
+}
+
+fn summary_model() -> String {
+    settings::load()
+        .devlog_model
+        .summary_model
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SUMMARY_MODEL.to_string())
+}
+
+/// `model` and `max_tokens` override settings.devlogModel for this call
+#[tauri::command]
+pub async fn generate_devlog(
+    date: String,
+    log_type: String,
+    model: Option<String>,
+    max_tokens: Option<u32>,
+) -> Result<DevLog, SprtError> {
+    // Check if already exists
+    if let Ok(Some(existing)) = storage::get_devlog(&date, &log_type) {
+        return Ok(existing);
+    }
+
+    let choice = ModelOverride { model, max_tokens };
+    // Drafts by default: nothing is stored or delivered until approve_devlog
+    if settings::load().devlog_drafts {
+        return draft(&date, &log_type, &choice).await;
+    }
+
+    let devlog = generate_new(&date, &log_type, &choice).await?;
+
+    // Deliver in the background so slow webhooks don't hold up the UI
+    tauri::async_runtime::spawn(delivery::deliver_devlog(devlog.clone()));
+    Ok(devlog)
+}
+
+/// Generate a devlog for review without storing or delivering it
+#[tauri::command]
+pub async fn generate_devlog_draft(
+    date: String,
+    log_type: String,
+    model: Option<String>,
+    max_tokens: Option<u32>,
+) -> Result<DevLog, SprtError> {
+    draft(&date, &log_type, &ModelOverride { model, max_tokens }).await
+}
+
+/// Store a reviewed (possibly edited) draft, replacing any devlog for its date, and deliver it
+#[tauri::command]
+pub async fn approve_devlog(devlog: DevLog) -> Result<DevLog, SprtError> {
+    if !matches!(devlog.log_type.as_str(), "daily" | "weekly") {
+        return Err(SprtError::InvalidInput(format!(
+            "Unknown log type: {}",
+            devlog.log_type
+        )));
+    }
+    if crate::time::parse_date(&devlog.date).is_none() {
+        return Err(SprtError::InvalidInput(format!("Invalid date: {}", devlog.date)));
+    }
+    storage::save_devlog(&devlog)?;
+    tauri::async_runtime::spawn(delivery::deliver_devlog(devlog.clone()));
+    Ok(devlog)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Generation {
+    pub date: String,
+    pub log_type: String,
+    /// RFC 3339
+    pub started_at: String,
+}
+
+/// Devlogs being generated right now, keyed by (date, log type)
+static GENERATING: LazyLock<Mutex<HashMap<(String, String), String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Holds a (date, log type) slot in GENERATING until dropped
+struct GenerationGuard(String, String);
+
+impl GenerationGuard {
+    fn acquire(date: &str, log_type: &str) -> Result<Self, SprtError> {
+        let mut running = GENERATING.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (date.to_string(), log_type.to_string());
+        if let Some(started_at) = running.get(&key) {
+            return Err(SprtError::InProgress(format!(
+                "The {} devlog for {} is already being generated (started {})",
+                log_type, date, started_at
+            )));
+        }
+        running.insert(key, chrono::Utc::now().to_rfc3339());
+        Ok(GenerationGuard(date.to_string(), log_type.to_string()))
+    }
+}
+
+impl Drop for GenerationGuard {
+    fn drop(&mut self) {
+        let mut running = GENERATING.lock().unwrap_or_else(|e| e.into_inner());
+        running.remove(&(std::mem::take(&mut self.0), std::mem::take(&mut self.1)));
+    }
+}
+
+/// Generate and store a devlog, replacing any existing one. Does not deliver webhooks.
+pub async fn generate_new(
+    date: &str,
+    log_type: &str,
+    choice: &ModelOverride,
+) -> Result<DevLog, SprtError> {
+    let devlog = draft(date, log_type, choice).await?;
+    storage::save_devlog(&devlog)?;
+    Ok(devlog)
+}
+
+/// Generate a devlog without storing it. Every generation path (stored, drafts, CLI) goes
+/// through here, so this is where the (date, type) slot is held: fails with InProgress
+/// while the same devlog is already being generated.
+pub async fn draft(date: &str, log_type: &str, choice: &ModelOverride) -> Result<DevLog, SprtError> {
+    let _guard = GenerationGuard::acquire(date, log_type)?;
+    match log_type {
+        "daily" => generate_daily(date, choice).await,
+        "weekly" => generate_weekly(date, choice).await,
+        _ => Err(SprtError::InvalidInput(format!("Unknown log type: {}", log_type))),
+    }
+}
+
+/// Devlogs being generated right now, oldest first
+#[tauri::command]
+pub fn get_devlog_generations() -> Vec<Generation> {
+    let running = GENERATING.lock().unwrap_or_else(|e| e.into_inner());
+    let mut generations: Vec<Generation> = running
+        .iter()
+        .map(|((date, log_type), started_at)| Generation {
+            date: date.clone(),
+            log_type: log_type.clone(),
+            started_at: started_at.clone(),
+        })
+        .collect();
+    generations.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    generations
+}
+
+#[tauri::command]
+pub fn get_devlog(date: String, log_type: String) -> Result<Option<DevLog>, SprtError> {
+    storage::get_devlog(&date, &log_type)
+}
+
+/// Newest first. With `tag`, only devlogs tagged with it directly or through a project.
+#[tauri::command]
+pub fn list_devlogs(
+    log_type: String,
+    limit: Option<usize>,
+    tag: Option<String>,
+) -> Result<Vec<DevLog>, SprtError> {
+    let limit = limit.unwrap_or(30);
+    let Some(tag) = tag.filter(|t| !t.is_empty()) else {
+        return storage::list_devlogs(&log_type, limit);
+    };
+    let index = tags::TagIndex::load()?;
+    Ok(storage::list_devlogs(&log_type, i64::MAX as usize)?
+        .into_iter()
+        .filter(|log| index.devlog_has(log, &tag))
+        .take(limit)
+        .collect())
+}
+
+/// Export devlogs (all, or those carrying `tag`) as JSON files, one per log, for portability.
+/// Returns the export directory.
+#[tauri::command]
+pub fn export_devlogs_json(path: Option<String>, tag: Option<String>) -> Result<String, SprtError> {
+    let dir = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => storage::default_export_dir()
+            .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?,
+    };
+    match tag.filter(|t| !t.is_empty()) {
+        Some(tag) => {
+            let index = tags::TagIndex::load()?;
+            storage::export_devlogs_json(&dir, Some(&|log: &DevLog| index.devlog_has(log, &tag)))?
+        }
+        None => storage::export_devlogs_json(&dir, None)?,
+    };
+    Ok(dir.to_string_lossy().to_string())
+}
+
+/// List legacy JSON devlog files that fail to parse (instead of silently hiding them)
+#[tauri::command]
+pub fn check_devlog_storage() -> Result<Vec<storage::CorruptDevLog>, SprtError> {
+    storage::find_corrupt_devlogs()
+}
+
+#[tauri::command]
+pub fn repair_devlog_storage() -> Result<Vec<storage::CorruptDevLog>, SprtError> {
+    storage::repair_devlogs()
+}
+
+#[tauri::command]
+pub fn get_git_activity(date: String) -> Result<Vec<git::GitActivity>, SprtError> {
+    Ok(git::collect_git_activity(&date))
+}
+
+/// What a daily devlog is built from: the prompt plus the data stored alongside the AI's answer
+struct DailyInput {
+    prompt: String,
+    stats: DevLogStats,
+    projects_worked: Vec<ProjectWork>,
+    github_data: Vec<GithubItem>,
+    checkin: Option<Checkin>,
+    issues_touched: Vec<IssueRef>,
+    /// Set the evening before with plan::set_plan
+    plan: Option<Plan>,
+    candidate_todos: Vec<CandidateTodo>,
+    /// None when everything fit
+    truncation: Option<PromptTruncation>,
+    /// Stage-one prompts, one per project, when the day gets two-stage generation
+    project_prompts: Vec<ProjectPrompt>,
+}
+
+/// How daily_input runs stage one on days that get two-stage generation
+/// (settings.two_stage_devlogs)
+#[derive(Clone, Copy, PartialEq)]
+enum StageOne {
+    /// Summarize each project with summary_model() and compose from the summaries
+    Summarize,
+    /// Previews never call the API: stand-ins mark where each summary would go
+    Placeholders,
+}
+
+async fn daily_input(date: &str, stage_one: StageOne) -> Result<DailyInput, SprtError> {
+    // 1. Collect data
+    let git_data = git::collect_git_activity(date);
+    let session_data = claude::get_session_summaries(date);
+    // GitHub is optional — a failed fetch shouldn't block the devlog
+    let github_data = github::collect_github_activity(date).await.unwrap_or_else(|e| {
+        log::warn!("GitHub activity unavailable: {e}");
+        vec![]
+    });
+
+    // If no data at all, return an empty-ish log
+    if git_data.is_empty() && session_data.is_empty() && github_data.is_empty() {
+        return Err(SprtError::NoData(
+            "No activity found for this date. Nothing to generate.".to_string(),
+        ));
+    }
+
+    // 2. Build stats
+    let mut stats = build_stats(&git_data, &session_data);
+    if let Some(day) = time::parse_date(date) {
+        stats.devices = devices::active_on(day).unwrap_or_else(|e| {
+            log::warn!("Cannot list devices for {date}: {e}");
+            vec![]
+        });
+        stats.provider_tokens = provider_tokens(day);
+        // Editor time outside Claude sessions makes active hours total coding time
+        match editor::activity(day) {
+            Ok(Some(a)) => {
+                stats.manual_hours = a.manual_minutes as f64 / 60.0;
+                stats.active_hours += stats.manual_hours;
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Editor activity unavailable for {date}: {e}"),
+        }
+    }
+    let projects_worked = build_project_work(&git_data, &session_data);
+
+    // 3. Build prompt
+    let efficiency = analysis::correlate(date, &git_data, &session_data);
+    let focus_blocks = storage::focus_blocks_on(date).unwrap_or_default();
+    let checkin = storage::get_checkin(date).unwrap_or_default();
+    let plan = storage::get_plan(date).unwrap_or_default();
+    let candidate_todos = todos::extract(date).unwrap_or_else(|e| {
+        log::warn!("Cannot extract TODOs for {date}: {e}");
+        vec![]
+    });
+    let issues_touched = issues::collect_issues(&git_data).await;
+    let projects = if two_stage_wanted(projects_worked.len()) {
+        project_inputs(date, &git_data, &session_data)
+    } else {
+        vec![]
+    };
+    let summaries = match stage_one {
+        _ if projects.is_empty() => None,
+        StageOne::Summarize => summarize_projects(date, &projects).await,
+        StageOne::Placeholders => Some(
+            projects
+                .iter()
+                .map(|p| ProjectSummary {
+                    name: p.name.clone(),
+                    commits: p.commits,
+                    sessions: p.sessions,
+                    text: format!("[{}'s summary of {}, written in stage one]", summary_model(), p.name),
+                })
+                .collect(),
+        ),
+    };
+
+    // Rebuild with tighter limits until the prompt fits the budget
+    let mut limits = DEFAULT_PROMPT_LIMITS;
+    let (prompt, truncation) = loop {
+        let mut prompt;
+        let mut truncation;
+        if let Some(summaries) = &summaries {
+            // Raw commits and sessions went into the summaries
+            let (_, _, github_kept, t) = trim_daily_data(&[], &[], &github_data, limits);
+            truncation = t;
+            prompt = build_daily_prompt(
+                date,
+                &[],
+                &[],
+                &github_kept,
+                &efficiency,
+                &focus_blocks,
+                &stats,
+            );
+            prompt.push_str(&project_summaries_prompt(summaries));
+            if !session_data.is_empty() {
+                prompt.push_str(&efficiency_prompt(&efficiency));
+            }
+        } else {
+            let (git_kept, sessions_kept, github_kept, t) =
+                trim_daily_data(&git_data, &session_data, &github_data, limits);
+            truncation = t;
+            prompt = build_daily_prompt(
+                date,
+                &git_kept,
+                &sessions_kept,
+                &github_kept,
+                &efficiency,
+                &focus_blocks,
+                &stats,
+            );
+        }
+        if let Some(c) = &checkin {
+            prompt.push_str(&checkin_prompt(c));
+        }
+        if let Some(p) = &plan {
+            prompt.push_str(&plan::prompt_section(p));
+        }
+        if !issues_touched.is_empty() {
+            prompt.push_str(&issues_prompt(&issues_touched));
+        }
+        prompt.push_str(&truncation_prompt(&truncation));
+
+        let tokens = prompt.len().div_ceil(CHARS_PER_TOKEN);
+        let next = limits.tighten();
+        if tokens <= PROMPT_TOKEN_BUDGET || next.is_none() {
+            truncation.estimated_tokens = tokens as u32;
+            break (prompt, truncation);
+        }
+        limits = next.unwrap_or(limits);
+    };
+    let truncated = truncation.commits_omitted > 0
+        || truncation.commit_messages_shortened > 0
+        || truncation.sessions_omitted > 0
+        || truncation.github_items_omitted > 0;
+    if truncated {
+        log::info!(
+            "Daily prompt for {date} trimmed to ~{} tokens",
+            truncation.estimated_tokens
+        );
+    }
+
+    Ok(DailyInput {
+        prompt,
+        stats,
+        projects_worked,
+        github_data,
+        checkin,
+        issues_touched,
+        plan,
+        candidate_todos,
+        truncation: truncated.then_some(truncation),
+        project_prompts: projects
+            .into_iter()
+            .map(|p| ProjectPrompt {
+                project: p.name,
+                prompt: p.prompt,
+            })
+            .collect(),
+    })
+}
+
+/// Cut a message to `max` characters, ending in an ellipsis
+fn shorten(message: &str, max: usize) -> Option<String> {
+    if message.chars().count() <= max {
+        return None;
+    }
+    let mut short: String = message.chars().take(max - 1).collect();
+    short.push('…');
+    Some(short)
+}
+
+/// The slice of the day's activity that goes into the prompt under `limits`.
+/// Commits keep the newest per repo, sessions the longest focused ones.
+fn trim_daily_data(
+    git_data: &[git::GitActivity],
+    session_data: &[SessionSummary],
+    github_data: &[GithubItem],
+    limits: PromptLimits,
+) -> (
+    Vec<git::GitActivity>,
+    Vec<SessionSummary>,
+    Vec<GithubItem>,
+    PromptTruncation,
+) {
+    let mut truncation = PromptTruncation::default();
+
+    let git_kept = git_data
+        .iter()
+        .map(|g| {
+            let mut g = g.clone();
+            if g.commits.len() > limits.commits_per_repo {
+                truncation.commits_omitted += (g.commits.len() - limits.commits_per_repo) as u32;
+                g.commits.truncate(limits.commits_per_repo);
+            }
+            for c in &mut g.commits {
+                if let Some(short) = shorten(&c.message, MAX_COMMIT_MESSAGE_CHARS) {
+                    c.message = short;
+                    truncation.commit_messages_shortened += 1;
+                }
+            }
+            g
+        })
+        .collect();
+
+    let mut sessions_kept = session_data.to_vec();
+    if sessions_kept.len() > limits.sessions {
+        sessions_kept.sort_by_key(|s| std::cmp::Reverse(s.focused_minutes));
+        truncation.sessions_omitted = (sessions_kept.len() - limits.sessions) as u32;
+        sessions_kept.truncate(limits.sessions);
+    }
+
+    let github_kept = github_data.iter().take(limits.github_items).cloned().collect();
+    truncation.github_items_omitted = github_data.len().saturating_sub(limits.github_items) as u32;
+
+    (git_kept, sessions_kept, github_kept, truncation)
+}
+
+fn two_stage_wanted(projects: usize) -> bool {
+    if crate::demo::active() {
+        return false;
+    }
+    let s = settings::load().two_stage_devlogs;
+    match s.mode.as_str() {
+        "always" => true,
+        "off" => false,
+        _ => projects >= s.min_projects.max(1) as usize,
+    }
+}
+
+/// Repo name a session is filed under
+fn session_project(s: &SessionSummary) -> String {
+    std::path::Path::new(&s.project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| s.project.clone())
+}
+
+struct ProjectSummary {
+    name: String,
+    commits: usize,
+    sessions: usize,
+    text: String,
+}
+
+/// One project's stage-one request
+struct ProjectInput {
+    name: String,
+    commits: usize,
+    sessions: usize,
+    prompt: String,
+}
+
+/// A stage-one prompt, as shown by preview_prompt
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectPrompt {
+    pub project: String,
+    pub prompt: String,
+}
+
+/// The day's activity grouped by project, with the prompt stage one sends for each
+fn project_inputs(
+    date: &str,
+    git_data: &[git::GitActivity],
+    session_data: &[SessionSummary],
+) -> Vec<ProjectInput> {
+    let mut by_name: BTreeMap<String, (Vec<git::GitActivity>, Vec<SessionSummary>)> =
+        BTreeMap::new();
+    for g in git_data {
+        by_name.entry(g.repo_name.clone()).or_default().0.push(g.clone());
+    }
+    for s in session_data {
+        by_name.entry(session_project(s)).or_default().1.push(s.clone());
+    }
+    by_name
+        .into_iter()
+        .map(|(name, (git, sessions))| {
+            let (git_kept, sessions_kept, _, _) =
+                trim_daily_data(&git, &sessions, &[], DEFAULT_PROMPT_LIMITS);
+            ProjectInput {
+                prompt: build_project_prompt(date, &name, &git_kept, &sessions_kept),
+                commits: git.iter().map(|g| g.commits.len()).sum(),
+                sessions: sessions.len(),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Stage one of two-stage generation: a short summary per project from summary_model().
+/// None if any call fails, so the caller falls back to the single-stage prompt.
+async fn summarize_projects(date: &str, projects: &[ProjectInput]) -> Option<Vec<ProjectSummary>> {
+    let model = summary_model();
+    if let Err(e) = crate::models::validate(&model).await {
+        log::warn!("Summary model unusable, using one-stage prompt: {e}");
+        return None;
+    }
+    // One token for every call: refresh tokens are single-use, so concurrent refreshes near
+    // expiry would race and all but one fail
+    let ctx = match ApiContext::load().await {
+        Ok(ctx) => Arc::new(ctx),
+        Err(e) => {
+            log::warn!("Cannot summarize projects, using one-stage prompt: {e}");
+            return None;
+        }
+    };
+    let mut summaries = vec![];
+    for chunk in projects.chunks(SUMMARY_CONCURRENCY) {
+        let handles: Vec<_> = chunk
+            .iter()
+            .map(|p| {
+                let prompt = p.prompt.clone();
+                let model = model.clone();
+                let ctx = Arc::clone(&ctx);
+                tauri::async_runtime::spawn(async move {
+                    ctx.send(&model, SUMMARY_MAX_TOKENS, PROJECT_SUMMARY_SYSTEM_PROMPT, &prompt)
+                        .await
+                })
+            })
+            .collect();
+        for (p, handle) in chunk.iter().zip(handles) {
+            let result = handle
+                .await
+                .map_err(|e| SprtError::Internal(format!("Task join error: {}", e)))
+                .and_then(|r| r);
+            let text = match result {
+                Ok(text) => text,
+                Err(e) => {
+                    log::warn!("Project summary for {} failed, using one-stage prompt: {e}", p.name);
+                    return None;
+                }
+            };
+            summaries.push(ProjectSummary {
+                name: p.name.clone(),
+                commits: p.commits,
+                sessions: p.sessions,
+                text: text.trim().to_string(),
+            });
+        }
+    }
+    log::info!("Summarized {} projects for {date}", summaries.len());
+    Some(summaries)
+}
+
+fn build_project_prompt(
+    date: &str,
+    name: &str,
+    git_data: &[git::GitActivity],
+    sessions: &[SessionSummary],
+) -> String {
+    let mut prompt = format!("Activity on {} for {}.\n\n", name, date);
+    for g in git_data {
+        prompt.push_str(&format!("## Commits (branch: {})\n", g.branch));
+        for c in &g.commits {
+            prompt.push_str(&format!(
+                "- [{}] {} (+{} -{})\n",
+                c.hash.get(..7).unwrap_or(&c.hash),
+                c.message,
+                c.insertions,
+                c.deletions
+            ));
+        }
+        prompt.push('\n');
+    }
+    if !sessions.is_empty() {
+        prompt.push_str("## Claude Code Sessions\n");
+        for s in sessions {
+            prompt.push_str(&format!(
+                "- Messages: {}, Duration: {}min ({}min focused)\n",
+                s.message_count, s.duration_minutes, s.focused_minutes
+            ));
+            if let Some(title) = &s.title {
+                prompt.push_str(&format!("  Topic: {}\n", title));
+            }
+        }
+    }
+    prompt
+}
+
+fn project_summaries_prompt(summaries: &[ProjectSummary]) -> String {
+    let mut prompt = "\n## Project Summaries\n".to_string();
+    for s in summaries {
+        prompt.push_str(&format!(
+            "### {} ({} commits, {} sessions)\n{}\n\n",
+            s.name, s.commits, s.sessions, s.text
+        ));
+    }
+    prompt
+}
+
+fn truncation_prompt(t: &PromptTruncation) -> String {
+    let mut left_out = vec![];
+    if t.commits_omitted > 0 {
+        left_out.push(format!("{} older commits", t.commits_omitted));
+    }
+    if t.sessions_omitted > 0 {
+        left_out.push(format!("{} shorter Claude sessions", t.sessions_omitted));
+    }
+    if t.github_items_omitted > 0 {
+        left_out.push(format!("{} GitHub items", t.github_items_omitted));
+    }
+    if left_out.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\nNote: {} were left out above for length; the overview stats include them.\n",
+        left_out.join(", ")
+    )
+}
+
+async fn generate_daily(date: &str, choice: &ModelOverride) -> Result<DevLog, SprtError> {
+    let DailyInput {
+        prompt,
+        stats,
+        projects_worked,
+        github_data,
+        checkin,
+        issues_touched,
+        plan,
+        candidate_todos,
+        truncation,
+        ..
+    } = daily_input(date, StageOne::Summarize).await?;
+
+    // 4. Call Claude API
+    let ai_response = call_claude_api_with(choice, DEVLOG_SYSTEM_PROMPT, &prompt).await?;
+
+    // 5. Parse response
+    let parsed: serde_json::Value = serde_json::from_str(&ai_response)
+        .map_err(|e| SprtError::Parse(format!("Failed to parse AI response: {}. Raw: {}", e, ai_response)))?;
+
+    let summary = parsed
+        .get("summary")
+        .and_then(|v| v.as_str())
+        .unwrap_or("No summary generated.")
+        .to_string();
+
+    let highlights: Vec<String> = parsed
+        .get("highlights")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let sprint_score = parsed
+        .get("sprint_score")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(50) as u32;
+
+    // Merge project_notes from AI into projects_worked
+    let project_notes: HashMap<String, Vec<String>> = parsed
+        .get("project_notes")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+
+    let projects_worked = projects_worked
+        .into_iter()
+        .map(|mut pw| {
+            if let Some(notes) = project_notes.get(&pw.name) {
+                pw.key_changes = notes.clone();
+            }
+            pw
+        })
+        .collect();
+
+    let id = format!(
+        "{}-{}",
+        date,
+        chrono::Utc::now().timestamp_millis() % 10000
+    );
+
+    let devlog = DevLog {
+        id,
+        date: date.to_string(),
+        log_type: "daily".to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        summary,
+        highlights,
+        projects_worked,
+        stats,
+        sprint_score,
+        github_activity: github_data,
+        checkin,
+        value: None,
+        issues_touched,
+        prompt_truncation: truncation,
+        plan_completion: plan.map(|p| plan::completion(&p, parsed.get("plan_status"))),
+        candidate_todos,
+    };
+
+    Ok(devlog)
+}
+
+/// What a weekly devlog is built from: the prompt plus the week's merged daily data
+struct WeeklyInput {
+    prompt: String,
+    start_date: chrono::NaiveDate,
+    stats: DevLogStats,
+    projects: HashMap<String, ProjectWork>,
+    github: Vec<GithubItem>,
+    issues: Vec<IssueRef>,
+}
+
+fn weekly_input(week_start: &str) -> Result<WeeklyInput, SprtError> {
+    // Get daily logs for the past 7 days
+    let start_date =
+        chrono::NaiveDate::parse_from_str(week_start, "%Y-%m-%d")
+            .map_err(|e| SprtError::InvalidInput(format!("Invalid date {}: {}", week_start, e)))?;
+
+    let mut daily_logs = vec![];
+    let mut all_stats = DevLogStats {
+        total_commits: 0,
+        total_messages: 0,
+        total_tokens: 0,
+        total_files_changed: 0,
+        total_insertions: 0,
+        total_deletions: 0,
+        active_hours: 0.0,
+        projects_count: 0,
+        commit_messages: Default::default(),
+        devices: vec![],
+        provider_tokens: HashMap::new(),
+        ai_assisted_hours: 0.0,
+        manual_hours: 0.0,
+    };
+
+    let mut all_projects: HashMap<String, ProjectWork> = HashMap::new();
+    let mut all_github: Vec<GithubItem> = vec![];
+    let mut all_issues: Vec<IssueRef> = vec![];
+
+    for i in 0..7 {
+        let d = start_date + chrono::Duration::days(i);
+        let ds = d.format("%Y-%m-%d").to_string();
+        if let Ok(Some(log)) = storage::get_devlog(&ds, "daily") {
+            // Accumulate stats
+            all_stats.total_commits += log.stats.total_commits;
+            all_stats.total_messages += log.stats.total_messages;
+            all_stats.total_tokens += log.stats.total_tokens;
+            all_stats.total_files_changed += log.stats.total_files_changed;
+            all_stats.total_insertions += log.stats.total_insertions;
+            all_stats.total_deletions += log.stats.total_deletions;
+            all_stats.active_hours += log.stats.active_hours;
+            all_stats.commit_messages.merge(&log.stats.commit_messages);
+            for (provider, tokens) in &log.stats.provider_tokens {
+                *all_stats.provider_tokens.entry(provider.clone()).or_insert(0) += tokens;
+            }
+            // Logs from before the split have no ai_assisted_hours; derive it
+            all_stats.ai_assisted_hours += log.stats.active_hours - log.stats.manual_hours;
+            all_stats.manual_hours += log.stats.manual_hours;
+            for d in &log.stats.devices {
+                if !all_stats.devices.contains(d) {
+                    all_stats.devices.push(d.clone());
+                }
+            }
+
+            for pw in &log.projects_worked {
+                let entry = all_projects
+                    .entry(pw.name.clone())
+                    .or_insert_with(|| ProjectWork {
+                        name: pw.name.clone(),
+                        path: pw.path.clone(),
+                        commits: 0,
+                        messages: 0,
+                        tokens: 0,
+                        duration_minutes: 0,
+                        key_changes: vec![],
+                    });
+                entry.commits += pw.commits;
+                entry.messages += pw.messages;
+                entry.tokens += pw.tokens;
+                entry.duration_minutes += pw.duration_minutes;
+            }
+            all_github.extend(log.github_activity.iter().cloned());
+            issues::merge(&mut all_issues, &log.issues_touched);
+
+            daily_logs.push(log);
+        }
+    }
+
+    if daily_logs.is_empty() {
+        return Err(SprtError::NoData(
+            "No daily logs found for this week. Generate daily logs first.".to_string(),
+        ));
+    }
+
+    all_stats.projects_count = all_projects.len() as u32;
+
+    Ok(WeeklyInput {
+        // Built from the daily summaries
+        prompt: build_weekly_prompt(&daily_logs),
+        start_date,
+        stats: all_stats,
+        projects: all_projects,
+        github: all_github,
+        issues: all_issues,
+    })
+}
+
+async fn generate_weekly(week_start: &str, choice: &ModelOverride) -> Result<DevLog, SprtError> {
+    let WeeklyInput {
+        prompt,
+        start_date,
+        stats: all_stats,
+        projects: all_projects,
+        github: all_github,
+        issues: all_issues,
+    } = weekly_input(week_start)?;
+
+    let ai_response = call_claude_api_with(choice, WEEKLY_SYSTEM_PROMPT, &prompt).await?;
+
+    let parsed: serde_json::Value = serde_json::from_str(&ai_response)
+        .map_err(|e| SprtError::Parse(format!("Failed to parse AI response: {}", e)))?;
+
+    let summary = parsed
+        .get("summary")
+        .and_then(|v| v.as_str())
+        .unwrap_or("No summary generated.")
+        .to_string();
+    let highlights: Vec<String> = parsed
+        .get("highlights")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let sprint_score = parsed
+        .get("sprint_score")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(50) as u32;
+
+    let id = format!(
+        "w-{}-{}",
+        week_start,
+        chrono::Utc::now().timestamp_millis() % 10000
+    );
+
+    let devlog = DevLog {
+        id,
+        date: week_start.to_string(),
+        log_type: "weekly".to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        summary,
+        highlights,
+        projects_worked: all_projects.into_values().collect(),
+        stats: all_stats,
+        sprint_score,
+        github_activity: all_github,
+        checkin: None,
+        value: value::report(start_date, start_date + chrono::Duration::days(6))
+            .inspect_err(|e| log::warn!("Value report unavailable: {e}"))
+            .ok(),
+        issues_touched: all_issues,
+        prompt_truncation: None,
+        plan_completion: None,
+        candidate_todos: vec![],
+    };
+
+    Ok(devlog)
+}
+
+fn build_stats(
+    git_data: &[git::GitActivity],
+    session_data: &[SessionSummary],
+) -> DevLogStats {
+    let total_commits: u32 = git_data.iter().map(|g| g.commits.len() as u32).sum();
+    let total_messages: u64 = session_data.iter().map(|s| s.message_count).sum();
+    let total_tokens: u64 = session_data
+        .iter()
+        .map(|s| s.input_tokens + s.output_tokens + s.cache_read)
+        .sum();
+    let total_files: u32 = git_data.iter().map(|g| g.files_changed).sum();
+    let total_ins: u32 = git_data.iter().map(|g| g.insertions).sum();
+    let total_del: u32 = git_data.iter().map(|g| g.deletions).sum();
+    // Focused time, so breaks inside a long-running session don't count as work
+    let total_focused_min: u64 = session_data.iter().map(|s| s.focused_minutes).sum();
+    let active_hours = total_focused_min as f64 / 60.0;
+    let projects_count = {
+        let mut names: Vec<&str> = git_data.iter().map(|g| g.repo_name.as_str()).collect();
+        names.sort();
+        names.dedup();
+        names.len() as u32
+    };
+    let commit_messages = analysis::commit_message_stats(
+        git_data.iter().flat_map(|g| g.commits.iter().map(|c| c.message.as_str())),
+    );
+
+    DevLogStats {
+        total_commits,
+        total_messages,
+        total_tokens,
+        total_files_changed: total_files,
+        total_insertions: total_ins,
+        total_deletions: total_del,
+        active_hours,
+        projects_count,
+        commit_messages,
+        devices: vec![],
+        provider_tokens: HashMap::new(),
+        ai_assisted_hours: active_hours,
+        manual_hours: 0.0,
+    }
+}
+
+/// Tokens per other AI CLI on a day
+fn provider_tokens(day: chrono::NaiveDate) -> HashMap<String, u64> {
+    let mut tokens: HashMap<String, u64> = HashMap::new();
+    let Some((start, end)) = time::DayClock::load().day_range(day) else {
+        return tokens;
+    };
+    let (start, end) = (start.with_timezone(&chrono::Utc), end.with_timezone(&chrono::Utc));
+    providers::scan(start, |e| {
+        if e.timestamp < end {
+            *tokens.entry(e.provider.to_string()).or_insert(0) += e.total_tokens();
+        }
+    });
+    tokens
+}
+
+fn build_project_work(
+    git_data: &[git::GitActivity],
+    session_data: &[SessionSummary],
+) -> Vec<ProjectWork> {
+    let mut projects: HashMap<String, ProjectWork> = HashMap::new();
+
+    for g in git_data {
+        let entry = projects
+            .entry(g.repo_name.clone())
+            .or_insert_with(|| ProjectWork {
+                name: g.repo_name.clone(),
+                path: g.repo_path.clone(),
+                commits: 0,
+                messages: 0,
+                tokens: 0,
+                duration_minutes: 0,
+                key_changes: vec![],
+            });
+        entry.commits += g.commits.len() as u32;
+    }
+
+    // Match sessions to projects by path
+    for s in session_data {
+        let repo_name = session_project(s);
+
+        let entry = projects
+            .entry(repo_name.clone())
+            .or_insert_with(|| ProjectWork {
+                name: repo_name,
+                path: s.project_path.clone(),
+                commits: 0,
+                messages: 0,
+                tokens: 0,
+                duration_minutes: 0,
+                key_changes: vec![],
+            });
+        entry.messages += s.message_count;
+        entry.tokens += s.input_tokens + s.output_tokens + s.cache_read;
+        entry.duration_minutes += s.duration_minutes;
+    }
+
+    projects.into_values().collect()
+}
+
+fn efficiency_prompt(efficiency: &EfficiencyStats) -> String {
+    format!(
+        "## Session Efficiency\n- Sessions that led to commits: {}\n- Exploratory sessions (no commits): {}\n- Commits per 1k tokens: {:.2}\n",
+        efficiency.productive_sessions,
+        efficiency.exploratory_sessions,
+        efficiency.commits_per_1k_tokens
+    )
+}
+
+fn build_daily_prompt(
+    date: &str,
+    git_data: &[git::GitActivity],
+    session_data: &[SessionSummary],
+    github_data: &[GithubItem],
+    efficiency: &EfficiencyStats,
+    focus_blocks: &[FocusBlock],
+    stats: &DevLogStats,
+) -> String {
+    let mut prompt = format!("Generate a daily development log for {}.\n\n", date);
+
+    prompt.push_str(&format!(
+        "## Overview Stats\n- Commits: {}\n- Messages: {}\n- Tokens: {}\n- Files changed: {} (+{} -{})\n- Active hours: {:.1}\n- Projects: {}\n\n",
+        stats.total_commits, stats.total_messages, stats.total_tokens,
+        stats.total_files_changed, stats.total_insertions, stats.total_deletions,
+        stats.active_hours, stats.projects_count
+    ));
+    if !stats.provider_tokens.is_empty() {
+        let mut others: Vec<String> = stats
+            .provider_tokens
+            .iter()
+            .map(|(p, t)| format!("{}: {} tokens", p, t))
+            .collect();
+        others.sort();
+        prompt.push_str(&format!("Other AI CLIs used: {}\n\n", others.join(", ")));
+    }
+    if stats.manual_hours > 0.0 {
+        prompt.push_str(&format!(
+            "Active hours split: {:.1} AI-assisted (Claude sessions), {:.1} manual (editor only)\n\n",
+            stats.ai_assisted_hours, stats.manual_hours
+        ));
+    }
+
+    if !git_data.is_empty() {
+        prompt.push_str("## Git Activity\n");
+        for g in git_data {
+            prompt.push_str(&format!(
+                "### {} (branch: {})\n",
+                g.repo_name, g.branch
+            ));
+            if g.branches.len() > 1 {
+                let per_branch: Vec<String> = g
+                    .branches
+                    .iter()
+                    .map(|b| format!("{} commits on {}", b.commits, b.name))
+                    .collect();
+                prompt.push_str(&format!("Branches: {}\n", per_branch.join(", ")));
+            }
+            for c in &g.commits {
+                prompt.push_str(&format!(
+                    "- [{}] {} (+{} -{})\n",
+                    c.hash.get(..7).unwrap_or(&c.hash),
+                    c.message,
+                    c.insertions,
+                    c.deletions
+                ));
+            }
+            prompt.push('\n');
+        }
+    }
+
+    if !github_data.is_empty() {
+        prompt.push_str("## GitHub Activity\n");
+        for item in github_data {
+            let label = match item.kind.as_str() {
+                "pr_opened" => "Opened PR",
+                "pr_merged" => "Merged PR",
+                "pr_reviewed" => "Reviewed PR",
+                "issue_closed" => "Closed issue",
+                _ => "Activity",
+            };
+            prompt.push_str(&format!(
+                "- {} {}#{}: {}\n",
+                label, item.repo, item.number, item.title
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    if !session_data.is_empty() {
+        prompt.push_str("## Claude Code Sessions\n");
+        for s in session_data {
+            let repo_name = std::path::Path::new(&s.project_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| s.project.clone());
+            prompt.push_str(&format!(
+                "- Project: {}, Messages: {}, Duration: {}min ({}min focused), Tokens: {}\n",
+                repo_name,
+                s.message_count,
+                s.duration_minutes,
+                s.focused_minutes,
+                s.input_tokens + s.output_tokens
+            ));
+            if let Some(title) = &s.title {
+                prompt.push_str(&format!("  Topic: {}\n", title));
+            }
+        }
+        prompt.push('\n');
+        prompt.push_str(&efficiency_prompt(efficiency));
+    }
+
+    let cm = &stats.commit_messages;
+    if cm.commits > 0 {
+        prompt.push_str(&format!(
+            "\n## Commit Messages\n- Average subject length: {:.0} chars\n- Conventional commits: {}/{}\n- WIP commits: {}, unsquashed fixup/squash commits: {}\n",
+            cm.avg_length, cm.conventional, cm.commits, cm.wip, cm.fixup
+        ));
+    }
+
+    let completed: Vec<&FocusBlock> = focus_blocks.iter().filter(|b| b.completed).collect();
+    if !completed.is_empty() {
+        let minutes: u32 = completed.iter().map(|b| b.planned_minutes).sum();
+        prompt.push_str(&format!(
+            "\n## Focus Timer\n- Focus blocks completed: {} ({} min)\n",
+            completed.len(),
+            minutes
+        ));
+        for label in completed.iter().filter_map(|b| b.label.as_deref()) {
+            prompt.push_str(&format!("- {}\n", label));
+        }
+    }
+
+    prompt
+}
+
+fn checkin_prompt(c: &Checkin) -> String {
+    let mut section = format!(
+        "\n## Check-in\n- Mood: {}/5\n- Energy: {}/5\n",
+        c.mood, c.energy
+    );
+    if let Some(note) = c.note.as_deref().filter(|n| !n.is_empty()) {
+        section.push_str(&format!("- Note: {}\n", note));
+    }
+    section
+}
+
+fn issues_prompt(issues: &[IssueRef]) -> String {
+    let mut section = String::from("\n## Issues Touched\n");
+    for i in issues {
+        let state = i.state.as_deref().map(|s| format!(" [{}]", s)).unwrap_or_default();
+        section.push_str(&format!(
+            "- {}: {}{} ({} commits)\n",
+            i.key, i.title, state, i.commits
+        ));
+    }
+    section
+}
+
+fn build_weekly_prompt(daily_logs: &[DevLog]) -> String {
+    let mut prompt = String::from("Generate a weekly summary from these daily logs:\n\n");
+
+    for log in daily_logs {
+        prompt.push_str(&format!(
+            "## {}\nScore: {}/100\nSummary: {}\nHighlights:\n",
+            log.date, log.sprint_score, log.summary
+        ));
+        for h in &log.highlights {
+            prompt.push_str(&format!("- {}\n", h));
+        }
+        prompt.push_str(&format!(
+            "Stats: {} commits, {} messages, {:.1}h active\n\n",
+            log.stats.total_commits, log.stats.total_messages, log.stats.active_hours
+        ));
+    }
+
+    prompt
+}
+
+/// The configured devlog model (settings.devlog_model); also used by insights and release notes
+pub(crate) async fn call_claude_api(system: &str, prompt: &str) -> Result<String, SprtError> {
+    call_claude_api_with(&ModelOverride::default(), system, prompt).await
+}
+
+/// Validates the chosen model against the account's model list before calling it
+pub(crate) async fn call_claude_api_with(
+    choice: &ModelOverride,
+    system: &str,
+    prompt: &str,
+) -> Result<String, SprtError> {
+    let (model, max_tokens) = choice.resolve();
+    if max_tokens == 0 {
+        return Err(SprtError::InvalidInput("max_tokens must be at least 1".to_string()));
+    }
+    crate::models::validate(&model).await?;
+    call_claude_model(&model, max_tokens, system, prompt).await
+}
+
+pub(crate) async fn call_claude_model(
+    model: &str,
+    max_tokens: u32,
+    system: &str,
+    prompt: &str,
+) -> Result<String, SprtError> {
+    if crate::demo::active() {
+        return Ok(crate::demo::ai_response());
+    }
+    ApiContext::load().await?.send(model, max_tokens, system, prompt).await
+}
+
+/// The access token and prompt scrubber behind message calls, loaded once and shared by
+/// calls made together (two-stage project summaries)
+struct ApiContext {
+    token: String,
+    scrubber: privacy::Scrubber,
+}
+
+impl ApiContext {
+    async fn load() -> Result<Self, SprtError> {
+        Ok(ApiContext {
+            token: claude::get_access_token().await?,
+            scrubber: privacy::Scrubber::load(),
+        })
+    }
+
+    async fn send(
+        &self,
+        model: &str,
+        max_tokens: u32,
+        system: &str,
+        prompt: &str,
+    ) -> Result<String, SprtError> {
+        // Nothing leaves the machine unscrubbed; aliases in the answer map back to real names
+        let prompt = self.scrubber.scrub(prompt).text;
+
+        let body = serde_json::json!({
+            "model": model,
+            "max_tokens": max_tokens,
+            "system": system,
+            "messages": [{"role": "user", "content": prompt}]
+        });
+        let resp = http::send("API call", |client| {
+            client
+                .post("https://api.anthropic.com/v1/messages")
+                .header("Authorization", format!("Bearer {}", self.token))
+                .header("anthropic-version", "2023-06-01")
+                .header("anthropic-beta", "oauth-2025-04-20")
+                .header("content-type", "application/json")
+                .json(&body)
+        })
+        .await?;
+
+        let status = resp.status();
+        let retry_hint = http::retry_hint(resp.headers());
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| SprtError::Parse(format!("Failed to read response: {}", e)))?;
+
+        if !status.is_success() {
+            let err_msg = body
+                .get("error")
+                .and_then(|e| e.get("message"))
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown API error");
+            return Err(SprtError::Api(format!(
+                "API error ({}): {}{}",
+                status, err_msg, retry_hint
+            )));
+        }
+
+        // Extract text from first content block
+        body.get("content")
+            .and_then(|c| c.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(|block| block.get("text"))
+            .and_then(|t| t.as_str())
+            .map(|s| self.scrubber.restore(s))
+            .ok_or_else(|| SprtError::Api("No text in API response".to_string()))
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreview {
+    pub system: String,
+    /// Exactly what would be sent, after scrubbing
+    pub prompt: String,
+    pub redactions: u32,
+    pub pseudonyms: Vec<privacy::Pseudonym>,
+    /// Redaction patterns from settings that failed to compile and were skipped
+    pub invalid_patterns: Vec<String>,
+    /// What budgeting left out (daily prompts only)
+    pub truncation: Option<PromptTruncation>,
+    /// Two-stage days: what stage one sends per project (after scrubbing). `prompt` is then
+    /// the composed prompt, with a stand-in where each project's summary goes.
+    pub project_prompts: Vec<ProjectPrompt>,
+    /// System prompt sent with each of `project_prompts`
+    pub project_system: Option<String>,
+}
+
+/// The prompt a devlog generation would send to the API, scrubbed per settings.privacy,
+/// without sending it. Days that get two-stage generation include the per-project prompts.
+#[tauri::command]
+pub async fn preview_prompt(date: String, log_type: String) -> Result<PromptPreview, SprtError> {
+    let (system, prompt, truncation, project_prompts) = match log_type.as_str() {
+        "daily" => {
+            let input = daily_input(&date, StageOne::Placeholders).await?;
+            (DEVLOG_SYSTEM_PROMPT, input.prompt, input.truncation, input.project_prompts)
+        }
+        "weekly" => (WEEKLY_SYSTEM_PROMPT, weekly_input(&date)?.prompt, None, vec![]),
+        _ => {
+            return Err(SprtError::InvalidInput(format!(
+                "Unknown log type: {}",
+                log_type
+            )))
+        }
+    };
+    let scrubber = privacy::Scrubber::load();
+    let scrubbed = scrubber.scrub(&prompt);
+    let mut redactions = scrubbed.redactions;
+    let mut pseudonyms = scrubbed.pseudonyms;
+    let project_prompts: Vec<ProjectPrompt> = project_prompts
+        .into_iter()
+        .map(|p| {
+            let s = scrubber.scrub(&p.prompt);
+            redactions += s.redactions;
+            for alias in s.pseudonyms {
+                if !pseudonyms.iter().any(|known| known.name == alias.name) {
+                    pseudonyms.push(alias);
+                }
+            }
+            ProjectPrompt {
+                project: scrubber.scrub(&p.project).text,
+                prompt: s.text,
+            }
+        })
+        .collect();
+    Ok(PromptPreview {
+        system: system.to_string(),
+        prompt: scrubbed.text,
+        redactions,
+        pseudonyms,
+        invalid_patterns: scrubber.invalid_patterns,
+        truncation,
+        project_system: (!project_prompts.is_empty())
+            .then(|| PROJECT_SUMMARY_SYSTEM_PROMPT.to_string()),
+        project_prompts,
+    })
+}
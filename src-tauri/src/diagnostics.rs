@@ -0,0 +1,113 @@
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+/// Oldest warnings are dropped past this many
+const MAX_DIAGNOSTICS: usize = 200;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    Missing,
+    Unreadable,
+    InvalidJson,
+    MissingField,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// "credentials", "stats-cache", or "session-log"
+    pub source: String,
+    pub file: String,
+    /// 1-based line for JSONL files
+    pub line: Option<u64>,
+    pub kind: DiagnosticKind,
+    pub message: String,
+    /// The UI can't show real numbers without this source
+    pub critical: bool,
+    pub at: String,
+}
+
+static DIAGNOSTICS: LazyLock<Mutex<Vec<Diagnostic>>> = LazyLock::new(|| Mutex::new(vec![]));
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Give the module a handle for emitting "diagnostics-critical"; parsing also runs outside the
+/// app (CLI, MCP), where critical problems are only recorded
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+fn record(source: &str, file: &Path, line: Option<u64>, kind: DiagnosticKind, message: String, critical: bool) {
+    let diag = Diagnostic {
+        source: source.to_string(),
+        file: file.to_string_lossy().to_string(),
+        line,
+        kind,
+        message,
+        critical,
+        at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let mut all = DIAGNOSTICS.lock().unwrap_or_else(|e| e.into_inner());
+    // The same problem is seen on every poll; keep one entry and only announce it once
+    let existing = all
+        .iter()
+        .position(|d| d.file == diag.file && d.line == diag.line && d.kind == diag.kind);
+    let is_new = existing.is_none();
+    if let Some(i) = existing {
+        all.remove(i);
+    }
+    all.push(diag.clone());
+    if all.len() > MAX_DIAGNOSTICS {
+        let excess = all.len() - MAX_DIAGNOSTICS;
+        all.drain(..excess);
+    }
+    drop(all);
+
+    if critical && is_new {
+        if let Some(app) = APP.get() {
+            let _ = app.emit("diagnostics-critical", &diag);
+        }
+    }
+}
+
+/// Missing vs. present-but-unreadable, from a failed read
+pub fn io_kind(e: &std::io::Error) -> DiagnosticKind {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        DiagnosticKind::Missing
+    } else {
+        DiagnosticKind::Unreadable
+    }
+}
+
+/// Non-fatal problem, e.g. a malformed JSONL line that was skipped
+pub fn warn(source: &str, file: &Path, line: Option<u64>, kind: DiagnosticKind, message: String) {
+    record(source, file, line, kind, message, false);
+}
+
+/// A source the dashboard depends on couldn't be read
+pub fn critical(source: &str, file: &Path, kind: DiagnosticKind, message: String) {
+    record(source, file, None, kind, message, true);
+}
+
+/// Forget earlier problems with `file` once it has been read again
+pub fn clear_file(file: &Path) {
+    let file = file.to_string_lossy();
+    DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|d| d.file != file);
+}
+
+#[tauri::command]
+pub fn get_diagnostics() -> Vec<Diagnostic> {
+    DIAGNOSTICS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[tauri::command]
+pub fn clear_diagnostics() {
+    DIAGNOSTICS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
@@ -0,0 +1,166 @@
+use crate::error::SprtError;
+use crate::paths::{self, DataPaths};
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Entries in each largest/oldest list
+const TOP_FILES: usize = 20;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFile {
+    pub path: String,
+    /// Encoded projects dir name
+    pub project: String,
+    pub session_id: String,
+    pub bytes: u64,
+    /// RFC 3339
+    pub modified_at: String,
+    pub age_days: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDiskUsage {
+    pub project: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupSuggestion {
+    pub older_than_days: u32,
+    /// Oldest first
+    pub files: Vec<SessionFile>,
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    pub projects_dir: String,
+    /// Everything under projects/, not only session logs
+    pub total_bytes: u64,
+    pub session_files: u64,
+    /// Biggest first
+    pub projects: Vec<ProjectDiskUsage>,
+    pub largest_files: Vec<SessionFile>,
+    pub oldest_files: Vec<SessionFile>,
+    pub cleanup: Option<CleanupSuggestion>,
+}
+
+/// Walk `dir` recursively, adding every file's size to `total` and collecting .jsonl files
+fn walk(dir: &Path, project: &str, total: &mut u64, files: &mut Vec<SessionFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let now = chrono::Utc::now();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            walk(&path, project, total, files);
+            continue;
+        }
+        *total += meta.len();
+        if path.extension().is_none_or(|e| e != "jsonl") {
+            continue;
+        }
+        let modified: chrono::DateTime<chrono::Utc> = match meta.modified() {
+            Ok(t) => t.into(),
+            Err(_) => continue,
+        };
+        files.push(SessionFile {
+            path: path.to_string_lossy().to_string(),
+            project: project.to_string(),
+            session_id: path
+                .file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            bytes: meta.len(),
+            modified_at: modified.to_rfc3339(),
+            age_days: (now - modified).num_days().max(0) as u64,
+        });
+    }
+}
+
+pub fn disk_usage(paths: &DataPaths, older_than_days: Option<u32>) -> Result<DiskUsage, SprtError> {
+    let projects_dir = paths
+        .claude_dir
+        .clone()
+        .ok_or_else(|| SprtError::ClaudeNotFound("Cannot find home directory".to_string()))?
+        .join("projects");
+
+    let mut total_bytes = 0;
+    let mut files: Vec<SessionFile> = vec![];
+    let mut projects: HashMap<String, ProjectDiskUsage> = HashMap::new();
+    if let Ok(entries) = fs::read_dir(&projects_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let mut bytes = 0;
+            let mut project_files = vec![];
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                walk(&entry.path(), &name, &mut bytes, &mut project_files);
+            } else {
+                bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+            total_bytes += bytes;
+            projects.insert(
+                name.clone(),
+                ProjectDiskUsage {
+                    project: name,
+                    bytes,
+                    files: project_files.len() as u64,
+                },
+            );
+            files.extend(project_files);
+        }
+    }
+
+    let mut projects: Vec<ProjectDiskUsage> = projects.into_values().collect();
+    projects.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+
+    let cleanup = older_than_days.map(|days| {
+        let mut old: Vec<SessionFile> = files
+            .iter()
+            .filter(|f| f.age_days >= days as u64)
+            .cloned()
+            .collect();
+        old.sort_by(|a, b| a.modified_at.cmp(&b.modified_at));
+        CleanupSuggestion {
+            older_than_days: days,
+            reclaimable_bytes: old.iter().map(|f| f.bytes).sum(),
+            files: old,
+        }
+    });
+
+    files.sort_by_key(|f| std::cmp::Reverse(f.bytes));
+    let largest_files = files.iter().take(TOP_FILES).cloned().collect();
+    files.sort_by(|a, b| a.modified_at.cmp(&b.modified_at));
+    let oldest_files = files.iter().take(TOP_FILES).cloned().collect();
+
+    Ok(DiskUsage {
+        projects_dir: projects_dir.to_string_lossy().to_string(),
+        total_bytes,
+        session_files: files.len() as u64,
+        projects,
+        largest_files,
+        oldest_files,
+        cleanup,
+    })
+}
+
+/// Size of ~/.claude/projects. With `older_than_days`, also lists session files untouched
+/// for that long as cleanup candidates; nothing is deleted.
+#[tauri::command]
+pub async fn get_disk_usage(older_than_days: Option<u32>) -> Result<DiskUsage, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || disk_usage(paths::get(), older_than_days))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
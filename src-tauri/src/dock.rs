@@ -0,0 +1,105 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::settings;
+use crate::storage;
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Badge currently shown, so the tray thread only touches the Dock when it changes
+static SHOWN: Mutex<Option<String>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+pub fn activation_policy(visible: bool) -> tauri::ActivationPolicy {
+    if visible {
+        tauri::ActivationPolicy::Regular
+    } else {
+        tauri::ActivationPolicy::Accessory
+    }
+}
+
+fn badge_text(mode: Option<&str>) -> Option<String> {
+    match mode? {
+        "utilization" => {
+            claude::get_cached_utilization().map(|pct| format!("{}%", (pct * 100.0).round() as u32))
+        }
+        "devlogs" => {
+            let seen = settings::load().dock.devlogs_seen_at.unwrap_or_default();
+            match storage::count_devlogs_since(&seen) {
+                Ok(0) | Err(_) => None,
+                Ok(n) => Some(n.to_string()),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn show_badge<R: Runtime>(app: &AppHandle<R>, text: Option<String>) {
+    let mut shown = SHOWN.lock().unwrap_or_else(|e| e.into_inner());
+    if *shown == text {
+        return;
+    }
+    let Some(w) = app.get_webview_window("main") else {
+        return;
+    };
+    // Only macOS takes a text label; elsewhere the badge is a number, if supported at all
+    #[cfg(target_os = "macos")]
+    let _ = w.set_badge_label(text.clone());
+    #[cfg(not(target_os = "macos"))]
+    let _ = w.set_badge_count(
+        text.as_deref()
+            .and_then(|t| t.trim_end_matches('%').parse().ok()),
+    );
+    *shown = text;
+}
+
+/// Called from the tray thread: keeps the badge current while the Dock icon is visible
+pub fn tick<R: Runtime>(app: &AppHandle<R>) {
+    let dock = settings::load().dock;
+    let text = if dock.visible {
+        badge_text(dock.badge.as_deref())
+    } else {
+        None
+    };
+    show_badge(app, text);
+}
+
+/// Opening the dashboard counts as reading the devlogs
+pub fn mark_devlogs_seen() {
+    let mut s = settings::load();
+    if s.dock.badge.as_deref() != Some("devlogs") {
+        return;
+    }
+    s.dock.devlogs_seen_at = Some(chrono::Utc::now().to_rfc3339());
+    if let Err(e) = settings::save(&s) {
+        log::warn!("Failed to save dock state: {e}");
+    }
+}
+
+/// Show or hide the Dock icon (macOS). Saved to settings, so it sticks across launches.
+#[tauri::command]
+pub fn set_dock_visibility(app: AppHandle, visible: bool) -> Result<(), SprtError> {
+    let mut s = settings::load();
+    s.dock.visible = visible;
+    settings::save(&s)?;
+    #[cfg(target_os = "macos")]
+    app.set_activation_policy(activation_policy(visible))
+        .map_err(|e| SprtError::Internal(format!("Cannot change Dock visibility: {}", e)))?;
+    tick(&app);
+    Ok(())
+}
+
+/// Badge mode: "utilization", "devlogs" or None to clear it
+#[tauri::command]
+pub fn set_badge(app: AppHandle, badge: Option<String>) -> Result<(), SprtError> {
+    if let Some(b) = badge.as_deref() {
+        if !matches!(b, "utilization" | "devlogs") {
+            return Err(SprtError::InvalidInput(format!("Unknown badge: {}", b)));
+        }
+    }
+    let mut s = settings::load();
+    s.dock.badge = badge;
+    settings::save(&s)?;
+    tick(&app);
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use crate::api;
+use crate::claude;
+use crate::error::SprtError;
+use crate::server::{Request, Response};
+use crate::settings::ServerSettings;
+use crate::storage::{self, EditorHeartbeat};
+use crate::time::{self, DayClock};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Paths WakaTime clients post to, relative to their api_url, plus a plain one
+const HEARTBEAT_PATHS: &[&str] = &[
+    "/heartbeats",
+    "/api/v1/users/current/heartbeats",
+    "/api/v1/users/current/heartbeats.bulk",
+];
+
+pub fn is_heartbeat_path(path: &str) -> bool {
+    HEARTBEAT_PATHS.contains(&path)
+}
+
+/// The subset of a WakaTime heartbeat SPRT keeps
+#[derive(Deserialize)]
+struct WakaHeartbeat {
+    entity: String,
+    /// Unix seconds, fractional
+    time: f64,
+    project: Option<String>,
+    #[serde(default)]
+    is_write: bool,
+    /// "file", "app", "domain"; only file activity counts as coding
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    user_agent: Option<String>,
+}
+
+/// Single heartbeats and .bulk arrays share the handler
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HeartbeatBody {
+    One(WakaHeartbeat),
+    Many(Vec<WakaHeartbeat>),
+}
+
+/// Editor name from a WakaTime user agent, e.g.
+/// "wakatime/v1.90.0 (darwin-23.0.0-arm64) go1.21.0 vscode/1.85.0 vscode-wakatime/24.4.0"
+pub fn editor_name(user_agent: &str) -> &'static str {
+    let ua = user_agent.to_lowercase();
+    // Cursor is a VS Code fork and its agent often mentions vscode as well
+    if ua.contains("cursor") {
+        "cursor"
+    } else if ua.contains("vscode") {
+        "vscode"
+    } else if ua.contains("jetbrains") || ua.contains("intellij") {
+        "jetbrains"
+    } else if ua.contains("vim") {
+        "vim"
+    } else if ua.contains("emacs") {
+        "emacs"
+    } else if ua.contains("zed") {
+        "zed"
+    } else {
+        "editor"
+    }
+}
+
+/// Record heartbeats posted by an editor plugin. Authenticated like the API.
+pub fn handle(req: &Request, config: &ServerSettings) -> Response {
+    if !api::authorized(req, config) {
+        return Response::error(401, "Missing or invalid API token");
+    }
+    let beats = match serde_json::from_slice(&req.body) {
+        Ok(HeartbeatBody::One(b)) => vec![b],
+        Ok(HeartbeatBody::Many(b)) => b,
+        Err(e) => return Response::error(400, &format!("Invalid body: {}", e)),
+    };
+    let header_agent = req
+        .headers
+        .get("user-agent")
+        .map(String::as_str)
+        .unwrap_or("");
+    let count = beats.len();
+    let rows: Vec<EditorHeartbeat> = beats
+        .into_iter()
+        .filter(|b| b.kind.as_deref().is_none_or(|k| k == "file"))
+        .map(|b| EditorHeartbeat {
+            at: b.time as i64,
+            editor: editor_name(b.user_agent.as_deref().unwrap_or(header_agent)).to_string(),
+            entity: b.entity,
+            project: b.project.filter(|p| !p.is_empty()),
+            is_write: b.is_write,
+        })
+        .collect();
+    if let Err(e) = storage::insert_editor_heartbeats(&rows) {
+        return Response::error(500, e.message());
+    }
+    // The shape wakatime-cli expects back from .bulk: one [body, status] pair per heartbeat
+    let responses: Vec<serde_json::Value> = (0..count)
+        .map(|_| serde_json::json!([{ "data": {} }, 201]))
+        .collect();
+    Response::json(201, &serde_json::json!({ "responses": responses }))
+}
+
+/// Merge sorted-or-not timestamps into [start, end] spans, bridging gaps up to `idle_gap`
+fn spans(mut times: Vec<i64>, idle_gap: i64) -> Vec<(i64, i64)> {
+    times.sort_unstable();
+    let mut out: Vec<(i64, i64)> = vec![];
+    for t in times {
+        match out.last_mut() {
+            Some(last) if t - last.1 <= idle_gap => last.1 = t,
+            _ => out.push((t, t)),
+        }
+    }
+    out
+}
+
+fn total_secs(spans: &[(i64, i64)]) -> i64 {
+    spans.iter().map(|(s, e)| e - s).sum()
+}
+
+/// Seconds covered by both span lists; each must be sorted and non-overlapping
+fn overlap_secs(a: &[(i64, i64)], b: &[(i64, i64)]) -> i64 {
+    let (mut i, mut j, mut secs) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        secs += (end - start).max(0);
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    secs
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorActivity {
+    pub date: String,
+    pub heartbeats: u64,
+    /// Editor time minus idle gaps
+    pub editor_minutes: u64,
+    /// Time with Claude responding, editor open or not
+    pub ai_assisted_minutes: u64,
+    /// Editor time outside Claude activity
+    pub manual_minutes: u64,
+    /// Editor and Claude time together, overlaps counted once
+    pub total_minutes: u64,
+    pub minutes_by_editor: HashMap<String, u64>,
+    pub minutes_by_project: HashMap<String, u64>,
+}
+
+/// Editor activity on a day set against Claude's. None when no heartbeats were recorded.
+pub fn activity(day: chrono::NaiveDate) -> Result<Option<EditorActivity>, SprtError> {
+    let (start, end) = DayClock::load()
+        .day_range(day)
+        .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", day)))?;
+    let (start, end) = (
+        start.with_timezone(&chrono::Utc),
+        end.with_timezone(&chrono::Utc),
+    );
+    let beats = storage::editor_heartbeats_between(start.timestamp(), end.timestamp())?;
+    if beats.is_empty() {
+        return Ok(None);
+    }
+    let idle_gap = claude::idle_gap().num_seconds();
+
+    let mut claude_times = vec![];
+    claude::scan_usage(start, |e| {
+        if e.timestamp < end {
+            claude_times.push(e.timestamp.timestamp());
+        }
+    })?;
+    let claude_spans = spans(claude_times, idle_gap);
+    let editor_spans = spans(beats.iter().map(|b| b.at).collect(), idle_gap);
+
+    let mut by_editor: HashMap<String, Vec<i64>> = HashMap::new();
+    let mut by_project: HashMap<String, Vec<i64>> = HashMap::new();
+    for b in &beats {
+        by_editor.entry(b.editor.clone()).or_default().push(b.at);
+        if let Some(p) = &b.project {
+            by_project.entry(p.clone()).or_default().push(b.at);
+        }
+    }
+    let minutes = |groups: HashMap<String, Vec<i64>>| -> HashMap<String, u64> {
+        groups
+            .into_iter()
+            .map(|(k, times)| (k, (total_secs(&spans(times, idle_gap)) / 60) as u64))
+            .collect()
+    };
+
+    let editor_secs = total_secs(&editor_spans);
+    let claude_secs = total_secs(&claude_spans);
+    let manual_secs = editor_secs - overlap_secs(&editor_spans, &claude_spans);
+    Ok(Some(EditorActivity {
+        date: time::format_date(day),
+        heartbeats: beats.len() as u64,
+        editor_minutes: (editor_secs / 60) as u64,
+        ai_assisted_minutes: (claude_secs / 60) as u64,
+        manual_minutes: (manual_secs / 60) as u64,
+        total_minutes: ((claude_secs + manual_secs) / 60) as u64,
+        minutes_by_editor: minutes(by_editor),
+        minutes_by_project: minutes(by_project),
+    }))
+}
+
+/// Editor time on a day (default today), split into AI-assisted and manual work.
+/// None when no editor has sent heartbeats that day.
+#[tauri::command]
+pub async fn get_editor_activity(
+    date: Option<String>,
+) -> Result<Option<EditorActivity>, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let day = match date {
+            Some(d) => time::parse_date(&d)
+                .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", d)))?,
+            None => DayClock::load().today(),
+        };
+        activity(day)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
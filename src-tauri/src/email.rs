@@ -0,0 +1,240 @@
+use crate::delivery;
+use crate::devlog;
+use crate::error::SprtError;
+use crate::scheduler;
+use crate::secrets;
+use crate::session_export::escape;
+use crate::settings::{self, EmailRecipient, EmailSettings};
+use crate::storage::{self, DevLog};
+use crate::time::{self, DayClock};
+
+use chrono::{Datelike, Timelike};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+const CHECK_INTERVAL_SECS: u64 = 15 * 60;
+
+const STYLE: &str = "font:15px/1.5 -apple-system,system-ui,sans-serif;color:#222;max-width:640px";
+
+fn render_html(log: &DevLog, summary_only: bool) -> String {
+    let mut html = format!(
+        "<div style=\"{}\"><h2 style=\"margin:0 0 .5em\">{}</h2><p>{}</p>",
+        STYLE,
+        escape(&delivery::title_for(log)),
+        escape(&log.summary)
+    );
+    if !log.highlights.is_empty() {
+        html.push_str("<h3>Highlights</h3><ul>");
+        for h in &log.highlights {
+            html.push_str(&format!("<li>{}</li>", escape(h)));
+        }
+        html.push_str("</ul>");
+    }
+    if !summary_only {
+        let projects = log
+            .projects_worked
+            .iter()
+            .filter(|p| !p.key_changes.is_empty());
+        for p in projects {
+            html.push_str(&format!(
+                "<h4 style=\"margin-bottom:.2em\">{} <span style=\"color:#666;font-weight:normal\">({} commits)</span></h4><ul>",
+                escape(&p.name),
+                p.commits
+            ));
+            for change in &p.key_changes {
+                html.push_str(&format!("<li>{}</li>", escape(change)));
+            }
+            html.push_str("</ul>");
+        }
+        let s = &log.stats;
+        html.push_str(&format!(
+            "<p style=\"color:#666;font-size:.9em;border-top:1px solid #ddd;padding-top:.6em\">\
+             Score {}/100 · {} commits · {} messages · +{} −{} · {:.1}h active</p>",
+            log.sprint_score,
+            s.total_commits,
+            s.total_messages,
+            s.total_insertions,
+            s.total_deletions,
+            s.active_hours
+        ));
+    }
+    html.push_str("</div>");
+    html
+}
+
+fn render_text(log: &DevLog, summary_only: bool) -> String {
+    let mut text = format!("{}\n\n{}\n", delivery::title_for(log), log.summary);
+    for h in &log.highlights {
+        text.push_str(&format!("- {}\n", h));
+    }
+    if !summary_only {
+        let s = &log.stats;
+        text.push_str(&format!(
+            "\nScore {}/100 · {} commits · {} messages · {:.1}h active\n",
+            log.sprint_score, s.total_commits, s.total_messages, s.active_hours
+        ));
+    }
+    text
+}
+
+fn mailbox(address: &str, name: Option<&str>) -> Result<Mailbox, SprtError> {
+    let address = address.trim().parse().map_err(|e| {
+        SprtError::InvalidInput(format!("Invalid email address {}: {}", address, e))
+    })?;
+    Ok(Mailbox::new(name.map(String::from), address))
+}
+
+fn transport(config: &EmailSettings) -> Result<SmtpTransport, SprtError> {
+    let host = config
+        .smtp_host
+        .as_deref()
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| SprtError::InvalidInput("Set an SMTP host first".to_string()))?;
+    let smtp_err = |e: lettre::transport::smtp::Error| {
+        SprtError::InvalidInput(format!("SMTP setup failed: {}", e))
+    };
+    let mut builder = match config.security.as_str() {
+        "tls" => SmtpTransport::relay(host).map_err(smtp_err)?,
+        "none" => SmtpTransport::builder_dangerous(host),
+        _ => SmtpTransport::starttls_relay(host).map_err(smtp_err)?,
+    };
+    if let Some(port) = config.smtp_port {
+        builder = builder.port(port);
+    }
+    let password = secrets::resolve(secrets::SMTP_PASSWORD, config.password.clone());
+    if let (Some(user), Some(password)) = (&config.username, password) {
+        builder = builder.credentials(Credentials::new(user.clone(), password));
+    }
+    Ok(builder.build())
+}
+
+fn send_to(
+    mailer: &SmtpTransport,
+    config: &EmailSettings,
+    recipient: &EmailRecipient,
+    log: &DevLog,
+) -> Result<(), SprtError> {
+    let from = config
+        .from
+        .as_deref()
+        .or(config.username.as_deref())
+        .ok_or_else(|| SprtError::InvalidInput("Set a sender address first".to_string()))?;
+    let message = Message::builder()
+        .from(mailbox(from, Some("SPRT"))?)
+        .to(mailbox(&recipient.address, recipient.name.as_deref())?)
+        .subject(delivery::title_for(log))
+        .multipart(MultiPart::alternative_plain_html(
+            render_text(log, recipient.summary_only),
+            render_html(log, recipient.summary_only),
+        ))
+        .map_err(|e| SprtError::Internal(format!("Cannot build email: {}", e)))?;
+    mailer
+        .send(&message)
+        .map(|_| ())
+        .map_err(|e| SprtError::Network(format!("Cannot send to {}: {}", recipient.address, e)))
+}
+
+/// Send `log` to every enabled recipient. Returns how many got it; fails only when none did.
+fn send_all(config: &EmailSettings, log: &DevLog) -> Result<u32, SprtError> {
+    let recipients: Vec<&EmailRecipient> = config.recipients.iter().filter(|r| r.enabled).collect();
+    if recipients.is_empty() {
+        return Err(SprtError::InvalidInput(
+            "No email recipients enabled".to_string(),
+        ));
+    }
+    let mailer = transport(config)?;
+    let mut sent = 0;
+    let mut last_err = None;
+    for r in recipients {
+        match send_to(&mailer, config, r, log) {
+            Ok(()) => sent += 1,
+            Err(e) => {
+                log::warn!("{e}");
+                last_err = Some(e);
+            }
+        }
+    }
+    match last_err {
+        Some(e) if sent == 0 => Err(e),
+        _ => Ok(sent),
+    }
+}
+
+/// The weekly devlog for the seven days before this Monday, generated if missing. With
+/// `drafts` only a stored (approved) log qualifies: generating one here would skip review.
+fn last_week_log(monday: chrono::NaiveDate, drafts: bool) -> Result<DevLog, SprtError> {
+    let week_start = time::format_date(monday - chrono::Duration::days(7));
+    if let Some(log) = storage::get_devlog(&week_start, "weekly")? {
+        return Ok(log);
+    }
+    if drafts {
+        return Err(SprtError::NoData(format!(
+            "The weekly devlog for {} hasn't been approved yet",
+            week_start
+        )));
+    }
+    tauri::async_runtime::block_on(devlog::generate_new(
+        &week_start,
+        "weekly",
+        &devlog::ModelOverride::default(),
+    ))
+}
+
+/// Send last week's devlog on Mondays, at or after the configured hour, once per week.
+/// Weeks without daily logs are skipped. With devlogDrafts on, only an approved log is sent.
+fn check_and_send() -> Result<(), SprtError> {
+    let mut s = settings::load();
+    let cfg = &s.email;
+    let today = DayClock::load().today();
+    if !cfg.enabled
+        || today.weekday() != chrono::Weekday::Mon
+        || chrono::Local::now().hour() < cfg.hour
+    {
+        return Ok(());
+    }
+    let today_key = time::format_date(today);
+    if cfg.last_sent.as_deref() == Some(today_key.as_str()) {
+        return Ok(());
+    }
+    match last_week_log(today, s.devlog_drafts) {
+        Ok(log) => {
+            send_all(cfg, &log)?;
+        }
+        // Not sent yet: checked again until the week's log is approved or Monday ends
+        Err(SprtError::NoData(_)) if s.devlog_drafts => return Ok(()),
+        Err(SprtError::NoData(_)) => {}
+        Err(e) => return Err(e),
+    }
+    s.email.last_sent = Some(today_key);
+    settings::save(&s)
+}
+
+/// Background check for the Monday email; off unless `email.enabled`
+pub fn start() {
+    scheduler::every("Weekly email", CHECK_INTERVAL_SECS, check_and_send);
+}
+
+/// Send the latest weekly devlog (or a sample when there is none) to `address`, or to every
+/// enabled recipient, so SMTP settings can be verified. Returns how many were sent.
+#[tauri::command]
+pub async fn send_test_email(address: Option<String>) -> Result<u32, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut config = settings::load().email;
+        if let Some(address) = address {
+            config.recipients = vec![EmailRecipient {
+                address,
+                name: None,
+                enabled: true,
+                summary_only: false,
+            }];
+        }
+        let log = match storage::list_devlogs("weekly", 1)?.into_iter().next() {
+            Some(log) => log,
+            None => delivery::sample_devlog(),
+        };
+        send_all(&config, &log)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
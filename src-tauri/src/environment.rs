@@ -0,0 +1,245 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::paths::{self, DataPaths};
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but something will be missing (e.g. no stats yet)
+    Warning,
+    /// Blocks a feature until the user fixes it
+    Error,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentCheck {
+    /// Stable key for the UI: "claude_dir", "credentials", "stats_cache", "git", "projects"
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about it, when not Ok
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentReport {
+    /// No check is an Error
+    pub ready: bool,
+    pub checks: Vec<EnvironmentCheck>,
+}
+
+fn check(
+    id: &str,
+    label: &str,
+    status: CheckStatus,
+    detail: String,
+    hint: Option<&str>,
+) -> EnvironmentCheck {
+    EnvironmentCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        detail,
+        hint: hint.map(|h| h.to_string()),
+    }
+}
+
+fn check_credentials(path: &Path) -> EnvironmentCheck {
+    const LABEL: &str = "Claude login";
+    const LOGIN_HINT: &str = "Run `claude` and log in with your Claude account.";
+
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            return check(
+                "credentials",
+                LABEL,
+                CheckStatus::Error,
+                format!("Cannot read credentials: {}", e),
+                Some(LOGIN_HINT),
+            )
+        }
+    };
+    let json: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            return check(
+                "credentials",
+                LABEL,
+                CheckStatus::Error,
+                format!("Cannot parse credentials: {}", e),
+                Some(LOGIN_HINT),
+            )
+        }
+    };
+    let oauth = match json.get("claudeAiOauth") {
+        Some(o) if o.get("accessToken").and_then(|v| v.as_str()).is_some() => o,
+        _ => {
+            return check(
+                "credentials",
+                LABEL,
+                CheckStatus::Error,
+                "No Claude account session in credentials".to_string(),
+                Some(LOGIN_HINT),
+            )
+        }
+    };
+
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let expired = oauth
+        .get("expiresAt")
+        .and_then(|v| v.as_u64())
+        .is_some_and(|exp| exp <= now_ms);
+    let has_refresh = oauth.get("refreshToken").and_then(|v| v.as_str()).is_some();
+    if expired && !has_refresh {
+        return check(
+            "credentials",
+            LABEL,
+            CheckStatus::Error,
+            "Session expired".to_string(),
+            Some(LOGIN_HINT),
+        );
+    }
+
+    let plan = oauth
+        .get("subscriptionType")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown plan");
+    // An expired token with a refresh token is renewed on the next rate-limit check
+    check(
+        "credentials",
+        LABEL,
+        CheckStatus::Ok,
+        format!("Logged in ({})", plan),
+        None,
+    )
+}
+
+fn check_stats_cache(path: &Path) -> EnvironmentCheck {
+    const LABEL: &str = "Usage statistics";
+
+    match fs::read_to_string(path) {
+        Ok(content) => match serde_json::from_str::<claude::StatsCache>(&content) {
+            Ok(cache) => check(
+                "stats_cache",
+                LABEL,
+                CheckStatus::Ok,
+                format!("{} sessions, {} messages", cache.total_sessions, cache.total_messages),
+                None,
+            ),
+            Err(e) => check(
+                "stats_cache",
+                LABEL,
+                CheckStatus::Warning,
+                format!("Cannot parse stats-cache.json: {}", e),
+                Some("Claude Code rewrites this file; it should fix itself after your next session."),
+            ),
+        },
+        Err(_) => check(
+            "stats_cache",
+            LABEL,
+            CheckStatus::Warning,
+            "stats-cache.json not found".to_string(),
+            Some("Claude Code creates it after a few sessions. Historical charts stay empty until then."),
+        ),
+    }
+}
+
+/// Repos are read through libgit2, so a missing git binary only matters for Claude Code itself
+fn check_git() -> EnvironmentCheck {
+    const LABEL: &str = "Git";
+
+    match std::process::Command::new("git").arg("--version").output() {
+        Ok(out) if out.status.success() => check(
+            "git",
+            LABEL,
+            CheckStatus::Ok,
+            String::from_utf8_lossy(&out.stdout).trim().to_string(),
+            None,
+        ),
+        _ => check(
+            "git",
+            LABEL,
+            CheckStatus::Warning,
+            "git not found on PATH".to_string(),
+            Some("Install git so Claude Code can work with your repositories."),
+        ),
+    }
+}
+
+fn check_projects(projects_dir: &Path) -> EnvironmentCheck {
+    const LABEL: &str = "Session history";
+
+    let pattern = projects_dir.join("*/*.jsonl").to_string_lossy().to_string();
+    let count = glob::glob(&pattern)
+        .map(|paths| paths.filter_map(|p| p.ok()).count())
+        .unwrap_or(0);
+    if count == 0 {
+        return check(
+            "projects",
+            LABEL,
+            CheckStatus::Warning,
+            "No Claude Code sessions found".to_string(),
+            Some("Use Claude Code in a project; sessions show up here as soon as they start."),
+        );
+    }
+    check(
+        "projects",
+        LABEL,
+        CheckStatus::Ok,
+        format!("{} session logs", count),
+        None,
+    )
+}
+
+pub fn run_checks(paths: &DataPaths) -> EnvironmentReport {
+    let mut checks = vec![];
+
+    match paths.claude_dir.clone().filter(|d| d.is_dir()) {
+        Some(dir) => {
+            checks.push(check(
+                "claude_dir",
+                "Claude Code",
+                CheckStatus::Ok,
+                dir.to_string_lossy().to_string(),
+                None,
+            ));
+            checks.push(check_credentials(&dir.join(".credentials.json")));
+            checks.push(check_stats_cache(&dir.join("stats-cache.json")));
+            checks.push(check_git());
+            checks.push(check_projects(&dir.join("projects")));
+        }
+        None => {
+            // Nothing else can be checked without the directory
+            checks.push(check(
+                "claude_dir",
+                "Claude Code",
+                CheckStatus::Error,
+                "~/.claude not found".to_string(),
+                Some("Install Claude Code and run `claude` once, or set CLAUDE_CONFIG_DIR."),
+            ));
+            checks.push(check_git());
+        }
+    }
+
+    EnvironmentReport {
+        ready: checks.iter().all(|c| c.status != CheckStatus::Error),
+        checks,
+    }
+}
+
+/// First-launch checklist explaining why dashboards might be empty
+#[tauri::command]
+pub async fn check_environment() -> Result<EnvironmentReport, SprtError> {
+    tauri::async_runtime::spawn_blocking(|| run_checks(paths::get()))
+        .await
+        .map_err(|e| SprtError::Internal(format!("Task join error: {}", e)))
+}
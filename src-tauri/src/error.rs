@@ -0,0 +1,77 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Command error the frontend can branch on. Serialized as `{ "code": "...", "message": "..." }`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum SprtError {
+    /// No home directory or ~/.claude — Claude Code isn't installed or hasn't run yet
+    ClaudeNotFound(String),
+    /// Credentials missing, unreadable, or without an OAuth session
+    NotAuthenticated(String),
+    /// OAuth session expired and couldn't be refreshed
+    TokenExpired(String),
+    /// No response from a server (offline, DNS, timeout)
+    Network(String),
+    /// The Anthropic API answered with an error status
+    Api(String),
+    /// A file or response couldn't be parsed
+    Parse(String),
+    /// Database or devlog file storage failed
+    Storage(String),
+    /// Monitoring is paused and nothing is cached
+    Paused(String),
+    /// Nothing to work with, e.g. no activity on the requested date
+    NoData(String),
+    /// The same work is already running, e.g. a devlog being generated for that date
+    InProgress(String),
+    InvalidInput(String),
+    Internal(String),
+}
+
+impl SprtError {
+    pub fn message(&self) -> &str {
+        match self {
+            SprtError::ClaudeNotFound(m)
+            | SprtError::NotAuthenticated(m)
+            | SprtError::TokenExpired(m)
+            | SprtError::Network(m)
+            | SprtError::Api(m)
+            | SprtError::Parse(m)
+            | SprtError::Storage(m)
+            | SprtError::Paused(m)
+            | SprtError::NoData(m)
+            | SprtError::InProgress(m)
+            | SprtError::InvalidInput(m)
+            | SprtError::Internal(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for SprtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for SprtError {}
+
+/// Helpers that still return String errors flow into commands as Internal
+impl From<String> for SprtError {
+    fn from(message: String) -> Self {
+        SprtError::Internal(message)
+    }
+}
+
+impl From<&str> for SprtError {
+    fn from(message: &str) -> Self {
+        SprtError::Internal(message.to_string())
+    }
+}
+
+/// Lets String-returning callers (CLI, API, MCP) keep using `?`
+impl From<SprtError> for String {
+    fn from(e: SprtError) -> Self {
+        e.to_string()
+    }
+}
@@ -0,0 +1,181 @@
+use crate::error::SprtError;
+use crate::monitor;
+use crate::notifications;
+use crate::storage::{self, FocusBlock};
+use crate::time::DayClock;
+use crate::tray;
+
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+
+const DEFAULT_FOCUS_MINUTES: u32 = 25;
+
+/// Shown before the countdown in the tray title
+const FOCUS_GLYPH: &str = "🍅";
+
+#[derive(Debug, Clone)]
+struct FocusTimer {
+    /// Distinguishes timers so a stopped timer's thread exits instead of ending a newer one
+    id: u64,
+    started_at: chrono::DateTime<chrono::Utc>,
+    planned_minutes: u32,
+    label: Option<String>,
+}
+
+impl FocusTimer {
+    fn ends_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.started_at + chrono::Duration::minutes(self.planned_minutes as i64)
+    }
+}
+
+static TIMER: LazyLock<Mutex<Option<FocusTimer>>> = LazyLock::new(|| Mutex::new(None));
+static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusStatus {
+    pub active: bool,
+    pub started_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub remaining_secs: u64,
+    pub planned_minutes: u32,
+    pub label: Option<String>,
+    /// Completed blocks today, including any just finished
+    pub completed_today: usize,
+}
+
+pub fn is_active() -> bool {
+    TIMER.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+fn status() -> FocusStatus {
+    let timer = TIMER.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let completed_today = storage::focus_blocks_on(&DayClock::load().today_key())
+        .map(|blocks| blocks.iter().filter(|b| b.completed).count())
+        .unwrap_or(0);
+    match timer {
+        Some(t) => FocusStatus {
+            active: true,
+            started_at: Some(t.started_at.to_rfc3339()),
+            ends_at: Some(t.ends_at().to_rfc3339()),
+            remaining_secs: (t.ends_at() - chrono::Utc::now()).num_seconds().max(0) as u64,
+            planned_minutes: t.planned_minutes,
+            label: t.label,
+            completed_today,
+        },
+        None => FocusStatus {
+            active: false,
+            started_at: None,
+            ends_at: None,
+            remaining_secs: 0,
+            planned_minutes: 0,
+            label: None,
+            completed_today,
+        },
+    }
+}
+
+/// Store the block, give the tray title back, and tell the UI
+fn finish(app: &AppHandle, timer: &FocusTimer, completed: bool) -> Result<FocusBlock, SprtError> {
+    let ended_at = if completed {
+        timer.ends_at()
+    } else {
+        chrono::Utc::now()
+    };
+    let block = FocusBlock {
+        date: DayClock::load().date_key(&timer.started_at),
+        started_at: timer.started_at.to_rfc3339(),
+        ended_at: ended_at.to_rfc3339(),
+        planned_minutes: timer.planned_minutes,
+        completed,
+        label: timer.label.clone(),
+    };
+    storage::save_focus_block(&block)?;
+
+    if !monitor::is_paused() {
+        tray::set_tray_text(app, &tray::utilization_text());
+    }
+    let _ = app.emit("focus-changed", status());
+    Ok(block)
+}
+
+fn tray_countdown(remaining_secs: i64) -> String {
+    let secs = remaining_secs.max(0);
+    format!("{} {:02}:{:02}", FOCUS_GLYPH, secs / 60, secs % 60)
+}
+
+/// Update the tray every second until the block ends or another timer replaces this one
+fn run_timer(app: AppHandle, id: u64) {
+    std::thread::spawn(move || loop {
+        let timer = match TIMER.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+            Some(t) if t.id == id => t,
+            _ => return,
+        };
+        let remaining = (timer.ends_at() - chrono::Utc::now()).num_seconds();
+        if remaining <= 0 {
+            {
+                let mut current = TIMER.lock().unwrap_or_else(|e| e.into_inner());
+                if current.as_ref().map(|t| t.id) != Some(id) {
+                    return;
+                }
+                *current = None;
+            }
+            if let Err(e) = finish(&app, &timer, true) {
+                log::warn!("Cannot save focus block: {e}");
+            }
+            let body = match &timer.label {
+                Some(l) => format!("{} minutes on \"{}\" done. Take a break.", timer.planned_minutes, l),
+                None => format!("{} minutes done. Take a break.", timer.planned_minutes),
+            };
+            notifications::notify(&app, "Focus block complete", &body);
+            return;
+        }
+        if !monitor::is_paused() {
+            tray::set_tray_text(&app, &tray_countdown(remaining));
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    });
+}
+
+/// Start a focus block, replacing any running one (which is logged as stopped early)
+#[tauri::command]
+pub fn start_focus(app: AppHandle, minutes: Option<u32>, label: Option<String>) -> Result<FocusStatus, SprtError> {
+    let planned_minutes = minutes.unwrap_or(DEFAULT_FOCUS_MINUTES);
+    if planned_minutes == 0 || planned_minutes > 24 * 60 {
+        return Err(SprtError::InvalidInput(format!("Invalid focus length: {} minutes", planned_minutes)));
+    }
+    let timer = FocusTimer {
+        id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        started_at: chrono::Utc::now(),
+        planned_minutes,
+        label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+    };
+    let id = timer.id;
+    let previous = TIMER.lock().unwrap_or_else(|e| e.into_inner()).replace(timer);
+    // The new block is already running; failing to log the old one mustn't strand it
+    run_timer(app.clone(), id);
+    if let Some(prev) = previous {
+        if let Err(e) = finish(&app, &prev, false) {
+            log::warn!("Cannot save focus block: {e}");
+        }
+    }
+    let status = status();
+    let _ = app.emit("focus-changed", &status);
+    Ok(status)
+}
+
+/// Stop the running block early. Returns the logged block, or None if nothing was running.
+#[tauri::command]
+pub fn stop_focus(app: AppHandle) -> Result<Option<FocusBlock>, SprtError> {
+    let timer = TIMER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match timer {
+        Some(t) => finish(&app, &t, false).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub fn get_focus_status() -> FocusStatus {
+    status()
+}
@@ -1,273 +1,752 @@
-use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::Duration;
-
-/// Run a git command with a 10-second timeout to prevent hangs (e.g. on network drives)
-fn run_git_command(args: &[&str], dir: &str) -> Option<std::process::Output> {
-    let mut child = Command::new("git")
-        .args(args)
-        .current_dir(dir)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .ok()?;
-
-    let timeout = Duration::from_secs(10);
-    let start = std::time::Instant::now();
-    loop {
-        match child.try_wait() {
-            Ok(Some(_status)) => {
-                // Process finished — collect output
-                return child.wait_with_output().ok();
-            }
-            Ok(None) => {
-                if start.elapsed() > timeout {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    return None;
-                }
-                std::thread::sleep(Duration::from_millis(50));
-            }
-            Err(_) => return None,
-        }
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct GitActivity {
-    pub repo_path: String,
-    pub repo_name: String,
-    pub branch: String,
-    pub commits: Vec<GitCommit>,
-    pub files_changed: u32,
-    pub insertions: u32,
-    pub deletions: u32,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct GitCommit {
-    pub hash: String,
-    pub message: String,
-    pub author: String,
-    pub timestamp: String,
-    pub files_changed: u32,
-    pub insertions: u32,
-    pub deletions: u32,
-}
-
-/// Decode a Claude projects directory name to a filesystem path.
-/// e.g. "-Users-sooyoungbae-butter" → "/Users/sooyoungbae/butter"
-pub fn decode_project_path(dir_name: &str) -> String {
-    if dir_name.is_empty() {
-        return String::new();
-    }
-    // The directory name is the absolute path with "/" replaced by "-"
-    // e.g., "-Users-sooyoungbae-butter" represents "/Users/sooyoungbae/butter"
-    // We try to reconstruct by greedily matching existing directories.
-    let parts: Vec<&str> = dir_name.split('-').collect();
-    // Skip first empty segment (leading dash)
-    let segments: Vec<&str> = if parts.first() == Some(&"") {
-        parts[1..].to_vec()
-    } else {
-        parts.clone()
-    };
-
-    // Greedy path reconstruction: try longest matching segments
-    let mut path = PathBuf::from("/");
-    let mut i = 0;
-    while i < segments.len() {
-        // Try joining multiple segments (for names containing dashes)
-        let mut best_len = 0;
-        for j in (i + 1..=segments.len()).rev() {
-            let candidate = segments[i..j].join("-");
-            let test_path = path.join(&candidate);
-            if test_path.exists() {
-                path = test_path;
-                best_len = j - i;
-                break;
-            }
-        }
-        if best_len == 0 {
-            // No match — just use single segment
-            path = path.join(segments[i]);
-            i += 1;
-        } else {
-            i += best_len;
-        }
-    }
-    path.to_string_lossy().to_string()
-}
-
-/// Discover project paths from ~/.claude/projects/
-pub fn discover_project_paths() -> Vec<(String, String)> {
-    let claude_dir = match dirs::home_dir() {
-        Some(h) => h.join(".claude").join("projects"),
-        None => return vec![],
-    };
-    if !claude_dir.exists() {
-        return vec![];
-    }
-
-    let mut results = vec![];
-    if let Ok(entries) = std::fs::read_dir(&claude_dir) {
-        for entry in entries.flatten() {
-            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-                let dir_name = entry.file_name().to_string_lossy().to_string();
-                let decoded = decode_project_path(&dir_name);
-                if Path::new(&decoded).join(".git").exists() {
-                    results.push((dir_name, decoded));
-                }
-            }
-        }
-    }
-    results
-}
-
-/// Get current branch for a git repo
-fn get_branch(repo_path: &str) -> String {
-    run_git_command(&["rev-parse", "--abbrev-ref", "HEAD"], repo_path)
-        .and_then(|o| {
-            if o.status.success() {
-                Some(String::from_utf8_lossy(&o.stdout).trim().to_string())
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "unknown".to_string())
-}
-
-/// Get the repo name from path (last component)
-fn repo_name_from_path(path: &str) -> String {
-    Path::new(path)
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| path.to_string())
-}
-
-/// Collect git activity for a specific date across all known projects.
-pub fn collect_git_activity(date: &str) -> Vec<GitActivity> {
-    let projects = discover_project_paths();
-    let mut activities = vec![];
-
-    for (_dir_name, repo_path) in &projects {
-        if let Some(activity) = collect_repo_activity(repo_path, date) {
-            if !activity.commits.is_empty() {
-                activities.push(activity);
-            }
-        }
-    }
-
-    activities
-}
-
-
-fn collect_repo_activity(repo_path: &str, date: &str) -> Option<GitActivity> {
-    let since = format!("{}T00:00:00", date);
-    let until = format!("{}T23:59:59", date);
-    collect_repo_activity_range(repo_path, &since, &until)
-}
-
-fn collect_repo_activity_range(repo_path: &str, since: &str, until: &str) -> Option<GitActivity> {
-    let since_arg = format!("--since={}", since);
-    let until_arg = format!("--until={}", until);
-    let output = run_git_command(
-        &["log", &since_arg, &until_arg, "--format=%H|%s|%an|%aI", "--shortstat"],
-        repo_path,
-    )?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let raw = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = raw.lines().collect();
-
-    let mut commits = vec![];
-    let mut total_files: u32 = 0;
-    let mut total_ins: u32 = 0;
-    let mut total_del: u32 = 0;
-    let mut i = 0;
-
-    while i < lines.len() {
-        let line = lines[i].trim();
-        if line.is_empty() {
-            i += 1;
-            continue;
-        }
-
-        // Try to parse as commit line (hash|message|author|timestamp)
-        let parts: Vec<&str> = line.splitn(4, '|').collect();
-        if parts.len() == 4 && parts[0].len() == 40 {
-            let mut fc: u32 = 0;
-            let mut ins: u32 = 0;
-            let mut del: u32 = 0;
-
-            // Next non-empty line might be shortstat
-            if i + 1 < lines.len() {
-                let stat_line = lines[i + 1].trim();
-                if stat_line.contains("changed") {
-                    let (f, a, d) = parse_shortstat(stat_line);
-                    fc = f;
-                    ins = a;
-                    del = d;
-                    i += 1;
-                }
-            }
-
-            total_files += fc;
-            total_ins += ins;
-            total_del += del;
-
-            commits.push(GitCommit {
-                hash: parts[0].to_string(),
-                message: parts[1].to_string(),
-                author: parts[2].to_string(),
-                timestamp: parts[3].to_string(),
-                files_changed: fc,
-                insertions: ins,
-                deletions: del,
-            });
-        }
-
-        i += 1;
-    }
-
-    Some(GitActivity {
-        repo_path: repo_path.to_string(),
-        repo_name: repo_name_from_path(repo_path),
-        branch: get_branch(repo_path),
-        commits,
-        files_changed: total_files,
-        insertions: total_ins,
-        deletions: total_del,
-    })
-}
-
-/// Parse git shortstat line like "3 files changed, 120 insertions(+), 45 deletions(-)"
-fn parse_shortstat(line: &str) -> (u32, u32, u32) {
-    let mut files: u32 = 0;
-    let mut ins: u32 = 0;
-    let mut del: u32 = 0;
-
-    for part in line.split(',') {
-        let part = part.trim();
-        let num: u32 = part
-            .split_whitespace()
-            .next()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0);
-
-        if part.contains("file") {
-            files = num;
-        } else if part.contains("insertion") {
-            ins = num;
-        } else if part.contains("deletion") {
-            del = num;
-        }
-    }
-
-    (files, ins, del)
-}
+use crate::error::SprtError;
+use crate::time::DayClock;
+
+use serde::{Deserialize, Serialize};
+use git2::{DiffOptions, Repository, Sort};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitActivity {
+    pub repo_path: String,
+    pub repo_name: String,
+    pub branch: String,
+    pub commits: Vec<GitCommit>,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    /// Commit counts per local branch, busiest first
+    #[serde(default)]
+    pub branches: Vec<BranchActivity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchActivity {
+    pub name: String,
+    pub commits: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommit {
+    pub hash: String,
+    pub message: String,
+    pub author: String,
+    pub timestamp: String,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    /// Local branch the commit is attributed to
+    #[serde(default)]
+    pub branch: String,
+}
+
+/// Decode a Claude projects directory name to a filesystem path.
+/// e.g. "-Users-sooyoungbae-butter" → "/Users/sooyoungbae/butter"
+pub fn decode_project_path(dir_name: &str) -> String {
+    if dir_name.is_empty() {
+        return String::new();
+    }
+    // The directory name is the absolute path with "/" replaced by "-"
+    // e.g., "-Users-sooyoungbae-butter" represents "/Users/sooyoungbae/butter"
+    // We try to reconstruct by greedily matching existing directories.
+    let parts: Vec<&str> = dir_name.split('-').collect();
+    // Windows paths encode the drive too: C:\Users\me → "C--Users-me"
+    let is_drive = parts.len() >= 2
+        && parts[1].is_empty()
+        && parts[0].len() == 1
+        && parts[0].chars().all(|c| c.is_ascii_alphabetic());
+    let (root, segments): (PathBuf, Vec<&str>) = if is_drive {
+        (PathBuf::from(format!("{}:\\", parts[0])), parts[2..].to_vec())
+    } else if parts.first() == Some(&"") {
+        // Skip first empty segment (leading dash)
+        (PathBuf::from("/"), parts[1..].to_vec())
+    } else {
+        (PathBuf::from("/"), parts.clone())
+    };
+
+    // Greedy path reconstruction: try longest matching segments
+    let mut path = root;
+    let mut i = 0;
+    while i < segments.len() {
+        // Try joining multiple segments (for names containing dashes)
+        let mut best_len = 0;
+        for j in (i + 1..=segments.len()).rev() {
+            let candidate = segments[i..j].join("-");
+            let test_path = path.join(&candidate);
+            if test_path.exists() {
+                path = test_path;
+                best_len = j - i;
+                break;
+            }
+        }
+        if best_len == 0 {
+            // No match — just use single segment
+            path = path.join(segments[i]);
+            i += 1;
+        } else {
+            i += best_len;
+        }
+    }
+    path.to_string_lossy().to_string()
+}
+
+/// Discover project paths from ~/.claude/projects/
+pub fn discover_project_paths() -> Vec<(String, String)> {
+    let claude_dir = match crate::claude::claude_dir() {
+        Some(d) => d.join("projects"),
+        None => return vec![],
+    };
+    if !claude_dir.exists() {
+        return vec![];
+    }
+
+    let mut results = vec![];
+    if let Ok(entries) = std::fs::read_dir(&claude_dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                let dir_name = entry.file_name().to_string_lossy().to_string();
+                let decoded = decode_project_path(&dir_name);
+                if Path::new(&decoded).join(".git").exists() {
+                    results.push((dir_name, decoded));
+                }
+            }
+        }
+    }
+    results
+}
+
+/// Expand a leading "~/" to the user's home directory
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), crate::paths::get().home.as_ref()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Find git repos under `root`, at most `depth` levels deep. Doesn't descend into repos,
+/// hidden directories, or node_modules.
+fn scan_for_repos(root: &Path, depth: u32, results: &mut Vec<PathBuf>) {
+    if root.join(".git").exists() {
+        results.push(root.to_path_buf());
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name == "node_modules" {
+                continue;
+            }
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                scan_for_repos(&entry.path(), depth - 1, results);
+            }
+        }
+    }
+}
+
+/// All repos to collect git activity from: Claude projects, plus watched repos and
+/// repos found under the scan root from settings. Deduplicated by canonical path.
+pub fn discover_repo_paths() -> Vec<String> {
+    let settings = crate::settings::load();
+
+    let mut candidates: Vec<PathBuf> = discover_project_paths()
+        .into_iter()
+        .map(|(_, path)| PathBuf::from(path))
+        .collect();
+    for repo in &settings.watched_repos {
+        let path = expand_home(repo);
+        if path.join(".git").exists() {
+            candidates.push(path);
+        }
+    }
+    if let Some(root) = &settings.repo_scan_root {
+        scan_for_repos(&expand_home(root), 3, &mut candidates);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|p| seen.insert(p.canonicalize().unwrap_or_else(|_| p.clone())))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Get current branch for a git repo ("HEAD" when detached, like `git rev-parse --abbrev-ref`)
+fn get_branch(repo: &Repository) -> String {
+    match repo.head() {
+        Ok(head) if head.is_branch() => head.shorthand().unwrap_or("unknown").to_string(),
+        Ok(_) => "HEAD".to_string(),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+/// Whether `path` is in a git work tree: a repo root, a directory inside one, or a linked
+/// worktree (where `.git` is a file)
+pub fn is_repo(path: &str) -> bool {
+    Repository::discover(path).is_ok()
+}
+
+/// Unix time of the newest commit on any local branch of the repo containing `repo_path`
+pub fn latest_commit_time(repo_path: &str) -> Option<i64> {
+    let repo = Repository::discover(repo_path).ok()?;
+    branch_tips(&repo)
+        .into_iter()
+        .filter_map(|(_, oid)| repo.find_commit(oid).ok())
+        .map(|c| c.time().seconds())
+        .max()
+}
+
+/// URL of a named remote (e.g. "origin"), if the repo has one
+pub fn remote_url(repo_path: &str, remote: &str) -> Option<String> {
+    let repo = Repository::open(repo_path).ok()?;
+    let remote = repo.find_remote(remote).ok()?;
+    remote.url().map(|u| u.to_string())
+}
+
+/// Get the repo name from path (last component)
+fn repo_name_from_path(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Collect git activity for a specific date across all known projects.
+/// Repos are read in parallel on a small pool of scoped threads.
+pub fn collect_git_activity(date: &str) -> Vec<GitActivity> {
+    let repos = discover_repo_paths();
+    let author_emails = crate::settings::load().author_emails;
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(repos.len().max(1));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<(usize, GitActivity)>> = Mutex::new(vec![]);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(repo_path) = repos.get(idx) else {
+                    break;
+                };
+                if let Some(activity) = collect_repo_activity(repo_path, date, &author_emails) {
+                    if !activity.commits.is_empty() {
+                        results
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((idx, activity));
+                    }
+                }
+            });
+        }
+    });
+
+    // Keep discovery order so output is stable across runs
+    let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, a)| a).collect()
+}
+
+/// Cache key: (repo path, date, fingerprint of every branch tip + author filter, including
+/// the user.email fallback).
+/// Any new commit moves a tip, so stale entries are never served.
+type GitCacheKey = (String, String, String);
+
+static GIT_ACTIVITY_CACHE: LazyLock<Mutex<HashMap<GitCacheKey, Option<GitActivity>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const GIT_CACHE_MAX_ENTRIES: usize = 256;
+
+fn repo_fingerprint(repo: &Repository, author_emails: &[String]) -> String {
+    let mut parts: Vec<String> = branch_tips(repo)
+        .into_iter()
+        .map(|(name, oid)| format!("{}={}", name, oid))
+        .collect();
+    // The resolved filter, so a changed user.email isn't served the old author's commits
+    parts.push(author_filter(repo, author_emails).unwrap_or_default().join(","));
+    parts.join(";")
+}
+
+fn collect_repo_activity(repo_path: &str, date: &str, author_emails: &[String]) -> Option<GitActivity> {
+    let repo = Repository::open(repo_path).ok()?;
+    let clock = DayClock::load();
+    let (start, end) = clock.day_range(crate::time::parse_date(date)?)?;
+    let since = start.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let until = (end - chrono::Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%S").to_string();
+
+    // Keyed by the resolved window, so changing the rollover hour doesn't serve stale days
+    let key = (
+        repo_path.to_string(),
+        since.clone(),
+        repo_fingerprint(&repo, author_emails),
+    );
+
+    if let Some(cached) = GIT_ACTIVITY_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+    {
+        return cached.clone();
+    }
+
+    let activity = collect_repo_activity_range(&repo, repo_path, &since, &until, author_emails);
+
+    let mut cache = GIT_ACTIVITY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= GIT_CACHE_MAX_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(key, activity.clone());
+    activity
+}
+
+/// Emails whose commits count as the user's own work: the settings list if set, otherwise
+/// the repo's configured user.email. None means no identity is known, so nothing is filtered.
+fn author_filter(repo: &Repository, author_emails: &[String]) -> Option<Vec<String>> {
+    if !author_emails.is_empty() {
+        return Some(author_emails.iter().map(|e| e.trim().to_lowercase()).collect());
+    }
+    repo.config()
+        .and_then(|c| c.get_string("user.email"))
+        .ok()
+        .map(|e| vec![e.trim().to_lowercase()])
+}
+
+/// Local branch tips to walk, in attribution order. A commit reachable from several branches
+/// is credited to the first one walked, so long-lived default branches go last — commits
+/// stay credited to their feature branch even after it's merged.
+fn branch_tips(repo: &Repository) -> Vec<(String, git2::Oid)> {
+    let mut tips: Vec<(String, git2::Oid)> = repo
+        .branches(Some(git2::BranchType::Local))
+        .map(|branches| {
+            branches
+                .flatten()
+                .filter_map(|(b, _)| {
+                    let name = b.name().ok().flatten()?.to_string();
+                    let oid = b.get().target()?;
+                    Some((name, oid))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let is_default = |name: &str| matches!(name, "main" | "master" | "trunk" | "develop");
+    tips.sort_by(|a, b| is_default(&a.0).cmp(&is_default(&b.0)).then(a.0.cmp(&b.0)));
+
+    // Detached HEAD isn't a branch, but its commits still count
+    if let Ok(head) = repo.head() {
+        if !head.is_branch() {
+            if let Some(oid) = head.target() {
+                tips.push(("HEAD".to_string(), oid));
+            }
+        }
+    }
+    tips
+}
+
+/// Parse a local "YYYY-MM-DDTHH:MM:SS" bound into a unix timestamp
+fn local_timestamp(s: &str) -> Option<i64> {
+    let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok()?;
+    naive
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// Format a git time as strict ISO 8601 in the author's own offset (same as `%aI`)
+fn format_git_time(time: git2::Time) -> String {
+    chrono::FixedOffset::east_opt(time.offset_minutes() * 60)
+        .and_then(|tz| chrono::DateTime::from_timestamp(time.seconds(), 0).map(|dt| dt.with_timezone(&tz)))
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Files changed / insertions / deletions against the first parent (or the empty tree for
+/// root commits). Merge commits report zero, matching `git log --shortstat`.
+fn commit_stats(repo: &Repository, commit: &git2::Commit) -> (u32, u32, u32) {
+    if commit.parent_count() > 1 {
+        return (0, 0, 0);
+    }
+    let tree = match commit.tree() {
+        Ok(t) => t,
+        Err(_) => return (0, 0, 0),
+    };
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let mut opts = DiffOptions::new();
+    repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))
+        .and_then(|d| d.stats())
+        .map(|st| (st.files_changed() as u32, st.insertions() as u32, st.deletions() as u32))
+        .unwrap_or((0, 0, 0))
+}
+
+fn collect_repo_activity_range(
+    repo: &Repository,
+    repo_path: &str,
+    since: &str,
+    until: &str,
+    author_emails: &[String],
+) -> Option<GitActivity> {
+    let since_ts = local_timestamp(since)?;
+    let until_ts = local_timestamp(until)?;
+
+    let branch = get_branch(repo);
+    let authors = author_filter(repo, author_emails);
+
+    let mut commits: Vec<(i64, GitCommit)> = vec![];
+    let mut total_files: u32 = 0;
+    let mut total_ins: u32 = 0;
+    let mut total_del: u32 = 0;
+    let mut seen = std::collections::HashSet::new();
+    let mut branch_counts: Vec<BranchActivity> = vec![];
+
+    // Walk every local branch (like `git log --all`), crediting each commit once
+    for (branch_name, tip) in branch_tips(repo) {
+        let mut revwalk = match repo.revwalk() {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        if revwalk.push(tip).is_err() {
+            continue;
+        }
+        let _ = revwalk.set_sorting(Sort::TIME);
+        let mut branch_commits: u32 = 0;
+
+        for oid in revwalk.flatten() {
+            let commit = match repo.find_commit(oid) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            // Like `git log --since/--until`, filter on committer time. Not a cutoff: rebases
+            // and clock skew leave older commits above newer ones in the walk.
+            let commit_ts = commit.time().seconds();
+            if commit_ts > until_ts || commit_ts < since_ts {
+                continue;
+            }
+            if !seen.insert(oid) {
+                continue;
+            }
+
+            let author = commit.author();
+            if let Some(emails) = &authors {
+                let email = author.email().unwrap_or("").to_lowercase();
+                if !emails.contains(&email) {
+                    continue;
+                }
+            }
+
+            let (fc, ins, del) = commit_stats(repo, &commit);
+            total_files += fc;
+            total_ins += ins;
+            total_del += del;
+            branch_commits += 1;
+
+            commits.push((
+                commit_ts,
+                GitCommit {
+                    hash: oid.to_string(),
+                    message: commit.summary().unwrap_or("").to_string(),
+                    author: author.name().unwrap_or("").to_string(),
+                    timestamp: format_git_time(author.when()),
+                    files_changed: fc,
+                    insertions: ins,
+                    deletions: del,
+                    branch: branch_name.clone(),
+                },
+            ));
+        }
+
+        if branch_commits > 0 {
+            branch_counts.push(BranchActivity {
+                name: branch_name,
+                commits: branch_commits,
+            });
+        }
+    }
+
+    // Newest first, as `git log` would list them
+    commits.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+    let commits = commits.into_iter().map(|(_, c)| c).collect();
+    branch_counts.sort_by_key(|b| std::cmp::Reverse(b.commits));
+
+    Some(GitActivity {
+        repo_path: repo_path.to_string(),
+        repo_name: repo_name_from_path(repo_path),
+        branch,
+        commits,
+        files_changed: total_files,
+        insertions: total_ins,
+        deletions: total_del,
+        branches: branch_counts,
+    })
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChurn {
+    pub path: String,
+    pub commits: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoHistory {
+    pub total_commits: u32,
+    /// Date key → commits, on the configured day boundary
+    pub commits_by_day: std::collections::BTreeMap<String, u32>,
+    /// Most-changed files, by commits touching them
+    pub top_files: Vec<FileChurn>,
+    pub first_commit: Option<String>,
+    pub last_commit: Option<String>,
+}
+
+/// Per-file diffs are only computed for this many of the newest commits
+const HISTORY_MAX_DIFFED_COMMITS: usize = 1000;
+
+/// The user's commits across all local branches, as in collect_git_activity but unbounded
+/// in time, for the repo containing `repo_path`. File churn covers the newest
+/// HISTORY_MAX_DIFFED_COMMITS commits.
+pub fn repo_history(repo_path: &str, top_files: usize) -> Result<RepoHistory, SprtError> {
+    let repo = Repository::discover(repo_path)
+        .map_err(|e| SprtError::InvalidInput(format!("Not a git repository: {}", e)))?;
+    let authors = author_filter(&repo, &crate::settings::load().author_emails);
+    let clock = DayClock::load();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut commits: Vec<git2::Oid> = vec![];
+    for (_, tip) in branch_tips(&repo) {
+        let Ok(mut revwalk) = repo.revwalk() else {
+            continue;
+        };
+        if revwalk.push(tip).is_err() {
+            continue;
+        }
+        for oid in revwalk.flatten() {
+            if seen.insert(oid) {
+                commits.push(oid);
+            }
+        }
+    }
+
+    let mut history = RepoHistory::default();
+    let mut dated: Vec<(i64, git2::Oid)> = vec![];
+    for oid in commits {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        if let Some(emails) = &authors {
+            let email = commit.author().email().unwrap_or("").to_lowercase();
+            if !emails.contains(&email) {
+                continue;
+            }
+        }
+        let ts = commit.time().seconds();
+        if let Some(dt) = chrono::DateTime::from_timestamp(ts, 0) {
+            *history.commits_by_day.entry(clock.date_key(&dt)).or_insert(0) += 1;
+        }
+        dated.push((ts, oid));
+    }
+    dated.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+    history.total_commits = dated.len() as u32;
+
+    let when = |oid: &git2::Oid| {
+        repo.find_commit(*oid)
+            .ok()
+            .map(|c| format_git_time(c.author().when()))
+    };
+    history.last_commit = dated.first().and_then(|(_, oid)| when(oid));
+    history.first_commit = dated.last().and_then(|(_, oid)| when(oid));
+
+    let mut churn: HashMap<String, FileChurn> = HashMap::new();
+    for (_, oid) in dated.iter().take(HISTORY_MAX_DIFFED_COMMITS) {
+        let Ok(commit) = repo.find_commit(*oid) else {
+            continue;
+        };
+        // Merges would double-count their branch's changes
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let Ok(tree) = commit.tree() else {
+            continue;
+        };
+        let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+        let Ok(diff) = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) else {
+            continue;
+        };
+        for idx in 0..diff.deltas().len() {
+            let Ok(Some(patch)) = git2::Patch::from_diff(&diff, idx) else {
+                continue;
+            };
+            let delta = patch.delta();
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+            let (_, ins, del) = patch.line_stats().unwrap_or((0, 0, 0));
+            let path = path.to_string_lossy().to_string();
+            let entry = churn.entry(path.clone()).or_insert_with(|| FileChurn {
+                path,
+                commits: 0,
+                insertions: 0,
+                deletions: 0,
+            });
+            entry.commits += 1;
+            entry.insertions += ins as u32;
+            entry.deletions += del as u32;
+        }
+    }
+    let mut files: Vec<FileChurn> = churn.into_values().collect();
+    files.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then((b.insertions + b.deletions).cmp(&(a.insertions + a.deletions)))
+            .then(a.path.cmp(&b.path))
+    });
+    files.truncate(top_files);
+    history.top_files = files;
+
+    Ok(history)
+}
+
+/// Commits reachable from HEAD, newest first, from every author: those after `since_ref`
+/// (a tag, branch or hash; exclusive) and/or committed within the local days [from, to].
+pub fn release_commits(
+    repo_path: &str,
+    since_ref: Option<&str>,
+    days: Option<(chrono::NaiveDate, chrono::NaiveDate)>,
+) -> Result<Vec<GitCommit>, SprtError> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| SprtError::InvalidInput(format!("Not a git repository: {}", e)))?;
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| SprtError::Internal(format!("Git error: {}", e)))?;
+    revwalk
+        .push_head()
+        .map_err(|e| SprtError::NoData(format!("Repository has no commits: {}", e)))?;
+    if let Some(r) = since_ref {
+        let since = repo
+            .revparse_single(r)
+            .and_then(|o| o.peel_to_commit())
+            .map_err(|e| SprtError::InvalidInput(format!("Unknown ref {}: {}", r, e)))?;
+        revwalk
+            .hide(since.id())
+            .map_err(|e| SprtError::Internal(format!("Git error: {}", e)))?;
+    }
+    let _ = revwalk.set_sorting(Sort::TIME);
+
+    let clock = DayClock::load();
+    let bounds = match days {
+        Some((from, to)) => {
+            let start = clock.day_start(from).map(|t| t.timestamp());
+            let end = to.succ_opt().and_then(|d| clock.day_start(d)).map(|t| t.timestamp());
+            Some((start.unwrap_or(i64::MIN), end.unwrap_or(i64::MAX)))
+        }
+        None => None,
+    };
+
+    let mut commits = vec![];
+    for oid in revwalk.flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let ts = commit.time().seconds();
+        // Filtered, not cut off: the time sort isn't strict after rebases or with clock skew
+        if bounds.is_some_and(|(start, end)| ts < start || ts >= end) {
+            continue;
+        }
+        let (fc, ins, del) = commit_stats(&repo, &commit);
+        let author = commit.author();
+        commits.push(GitCommit {
+            hash: oid.to_string(),
+            message: commit.message().unwrap_or("").trim().to_string(),
+            author: author.name().unwrap_or("").to_string(),
+            timestamp: format_git_time(author.when()),
+            files_changed: fc,
+            insertions: ins,
+            deletions: del,
+            branch: get_branch(&repo),
+        });
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh repository under the temp directory, removed by the caller
+    fn fixture_repo() -> (PathBuf, Repository) {
+        let dir = std::env::temp_dir().join(format!("sprt-git-{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init(&dir).unwrap();
+        (dir, repo)
+    }
+
+    fn commit_files(repo: &Repository, files: &[(&str, &str)], message: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        for (name, content) in files {
+            std::fs::write(workdir.join(name), content).unwrap();
+        }
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::new("Dev", "dev@example.com", &git2::Time::new(1_740_819_600, 0)).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn commit_stats_diff_against_parent() {
+        let (dir, repo) = fixture_repo();
+        let root = commit_files(&repo, &[("a.txt", "one\ntwo\nthree\n")], "Add a");
+        let second = commit_files(&repo, &[("a.txt", "one\n2\nthree\n"), ("b.txt", "new\n")], "Edit a, add b");
+
+        assert_eq!(commit_stats(&repo, &repo.find_commit(root).unwrap()), (1, 3, 0));
+        assert_eq!(commit_stats(&repo, &repo.find_commit(second).unwrap()), (2, 2, 1));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn activity_range_totals_commits() {
+        let (dir, repo) = fixture_repo();
+        commit_files(&repo, &[("a.txt", "one\ntwo\nthree\n")], "Add a");
+        commit_files(&repo, &[("a.txt", "one\n2\nthree\n"), ("b.txt", "new\n")], "Edit a, add b");
+        let path = dir.to_string_lossy().to_string();
+
+        let activity = collect_repo_activity_range(
+            &repo,
+            &path,
+            "2000-01-01T00:00:00",
+            "2100-01-01T00:00:00",
+            &["dev@example.com".to_string()],
+        )
+        .unwrap();
+        assert_eq!(activity.commits.len(), 2);
+        assert_eq!(
+            (activity.files_changed, activity.insertions, activity.deletions),
+            (3, 5, 1)
+        );
+
+        // Someone else's commits aren't the user's work
+        let others = collect_repo_activity_range(
+            &repo,
+            &path,
+            "2000-01-01T00:00:00",
+            "2100-01-01T00:00:00",
+            &["someone@example.com".to_string()],
+        )
+        .unwrap();
+        assert!(others.commits.is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
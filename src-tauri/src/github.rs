@@ -0,0 +1,159 @@
+use crate::error::SprtError;
+use crate::git;
+use crate::http;
+use crate::secrets;
+use crate::settings;
+use crate::time::{self, DayClock};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubItem {
+    pub kind: String, // "pr_opened", "pr_merged", "pr_reviewed", "issue_closed"
+    pub repo: String, // "owner/name"
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+}
+
+const GITHUB_API: &str = "https://api.github.com";
+
+/// Search qualifiers per item kind. Review time isn't searchable, so reviewed PRs are those
+/// the user reviewed that were updated on the date.
+const SEARCHES: &[(&str, &str)] = &[
+    ("pr_opened", "type:pr author:{login} created:{range}"),
+    ("pr_merged", "type:pr author:{login} merged:{range}"),
+    ("pr_reviewed", "type:pr reviewed-by:{login} -author:{login} updated:{range}"),
+    ("issue_closed", "type:issue involves:{login} closed:{range}"),
+];
+
+/// Extract "owner/name" from a GitHub remote URL (https or ssh form)
+pub(crate) fn parse_github_remote(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .or_else(|| url.strip_prefix("https://github.com/"))
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let rest = rest.trim_end_matches('/').trim_end_matches(".git");
+    let mut parts = rest.splitn(2, '/');
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let name = parts.next().filter(|s| !s.is_empty())?;
+    Some(format!("{}/{}", owner, name).to_lowercase())
+}
+
+/// GitHub "owner/name" for each discovered repo with a github.com origin
+fn discovered_github_repos() -> HashSet<String> {
+    git::discover_repo_paths()
+        .iter()
+        .filter_map(|path| git::remote_url(path, "origin"))
+        .filter_map(|url| parse_github_remote(&url))
+        .collect()
+}
+
+/// Search range covering the day (with rollover), e.g. "2025-01-02T00:00:00+09:00..2025-01-02T23:59:59+09:00"
+fn local_day_range(date: &str) -> Option<String> {
+    let (start, end) = DayClock::load().day_range(time::parse_date(date)?)?;
+    let end = end - chrono::Duration::seconds(1);
+    Some(format!(
+        "{}..{}",
+        start.format("%Y-%m-%dT%H:%M:%S%:z"),
+        end.format("%Y-%m-%dT%H:%M:%S%:z")
+    ))
+}
+
+pub(crate) async fn get_json(token: &str, url: &str) -> Result<serde_json::Value, SprtError> {
+    let resp = http::send("GitHub request", |client| {
+        client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+    })
+    .await?;
+
+    let status = resp.status();
+    let retry_hint = http::retry_hint(resp.headers());
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| SprtError::Parse(format!("Cannot parse GitHub response: {}", e)))?;
+
+    if !status.is_success() {
+        let msg = body
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Unknown GitHub error");
+        let message = format!("GitHub error ({}): {}{}", status, msg, retry_hint);
+        return Err(if status == reqwest::StatusCode::UNAUTHORIZED {
+            SprtError::NotAuthenticated(message)
+        } else {
+            SprtError::Api(message)
+        });
+    }
+    Ok(body)
+}
+
+/// Fetch the user's PR and issue activity on `date` for discovered repos.
+/// Returns an empty list when no token is configured.
+pub async fn collect_github_activity(date: &str) -> Result<Vec<GithubItem>, SprtError> {
+    let token = match secrets::resolve(secrets::GITHUB_TOKEN, settings::load().github_token) {
+        Some(t) => t,
+        None => return Ok(vec![]),
+    };
+    let range = local_day_range(date)
+        .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", date)))?;
+    let repos = tauri::async_runtime::spawn_blocking(discovered_github_repos)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    if repos.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let user = get_json(&token, &format!("{}/user", GITHUB_API)).await?;
+    let login = user
+        .get("login")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SprtError::Parse("No login in GitHub user response".to_string()))?
+        .to_string();
+
+    let mut items = vec![];
+    for (kind, template) in SEARCHES {
+        let query = template.replace("{login}", &login).replace("{range}", &range);
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}/search/issues", GITHUB_API),
+            &[("q", query.as_str()), ("per_page", "100")],
+        )
+        .map_err(|e| SprtError::Internal(format!("Invalid GitHub URL: {}", e)))?;
+        let body = get_json(&token, url.as_str()).await?;
+
+        let results = body.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        for item in results {
+            // repository_url: "https://api.github.com/repos/owner/name"
+            let repo = item
+                .get("repository_url")
+                .and_then(|v| v.as_str())
+                .and_then(|u| u.split("/repos/").nth(1))
+                .unwrap_or("")
+                .to_lowercase();
+            if !repos.contains(&repo) {
+                continue;
+            }
+            items.push(GithubItem {
+                kind: kind.to_string(),
+                repo,
+                number: item.get("number").and_then(|v| v.as_u64()).unwrap_or(0),
+                title: item.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                url: item.get("html_url").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            });
+        }
+    }
+
+    Ok(items)
+}
+
+#[tauri::command]
+pub async fn get_github_activity(date: String) -> Result<Vec<GithubItem>, SprtError> {
+    collect_github_activity(&date).await
+}
@@ -0,0 +1,257 @@
+use crate::cache::{self, CacheEntryInfo};
+use crate::diagnostics;
+use crate::error::SprtError;
+use crate::monitor::{self, MonitoringStatus};
+use crate::probe::{self, ProbeStats};
+use crate::storage::{self, SessionQuery};
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+
+pub const RATE_LIMITS: &str = "rate_limits";
+pub const SESSION_INDEX: &str = "session_index";
+
+/// A fetch older than this means the tray numbers are frozen; a couple of missed
+/// probes at the slowest interval
+const RATE_LIMITS_STALE_SECS: i64 = 30 * 60;
+/// The watcher wakes up at least every 2s; silence past this means its thread is gone
+const WATCHER_STALE_SECS: i64 = 30;
+
+#[derive(Default)]
+struct Tracker {
+    runs: u64,
+    failures: u64,
+    last_success: Option<chrono::DateTime<chrono::Utc>>,
+    last_error: Option<(chrono::DateTime<chrono::Utc>, String)>,
+    last_duration_ms: u64,
+}
+
+#[derive(Default)]
+struct Watcher {
+    started: bool,
+    stopped: bool,
+    last_heartbeat: Option<chrono::DateTime<chrono::Utc>>,
+    last_event: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+static TRACKERS: LazyLock<Mutex<HashMap<&'static str, Tracker>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static WATCHER: LazyLock<Mutex<Watcher>> = LazyLock::new(|| Mutex::new(Watcher::default()));
+static STARTED: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Start the uptime clock; called once at launch
+pub fn init() {
+    LazyLock::force(&STARTED);
+}
+
+/// Note the outcome and duration of one run of a subsystem
+pub fn record<T, E: Display>(name: &'static str, started: Instant, result: &Result<T, E>) {
+    let now = chrono::Utc::now();
+    let mut trackers = TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+    let t = trackers.entry(name).or_default();
+    t.runs += 1;
+    t.last_duration_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(_) => t.last_success = Some(now),
+        Err(e) => {
+            t.failures += 1;
+            t.last_error = Some((now, e.to_string()));
+        }
+    }
+}
+
+/// Called by the file watcher thread on every wake-up; `event` when a change arrived
+pub fn watcher_heartbeat(event: bool) {
+    let now = chrono::Utc::now();
+    let mut w = WATCHER.lock().unwrap_or_else(|e| e.into_inner());
+    w.started = true;
+    w.last_heartbeat = Some(now);
+    if event {
+        w.last_event = Some(now);
+    }
+}
+
+pub fn watcher_stopped() {
+    WATCHER.lock().unwrap_or_else(|e| e.into_inner()).stopped = true;
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemHealth {
+    pub name: String,
+    /// "ok", "stale", "error", "never" (not run since launch) or "paused" (rate limits
+    /// not polled while monitoring is paused or on battery)
+    pub status: String,
+    pub runs: u64,
+    pub failures: u64,
+    /// RFC 3339
+    pub last_success_at: Option<String>,
+    pub last_success_age_secs: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+    pub last_duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherHealth {
+    /// "ok", "stale", "stopped" or "not_started"
+    pub status: String,
+    pub last_heartbeat_at: Option<String>,
+    /// Last change seen under ~/.claude
+    pub last_event_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexHealth {
+    pub indexed_sessions: u64,
+    pub database_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    /// "ok" when every part is, otherwise "degraded"
+    pub status: String,
+    pub checked_at: String,
+    pub uptime_secs: u64,
+    pub version: String,
+    pub monitoring: MonitoringStatus,
+    pub watcher: WatcherHealth,
+    pub subsystems: Vec<SubsystemHealth>,
+    pub probes: ProbeStats,
+    pub caches: Vec<CacheEntryInfo>,
+    pub index: Option<IndexHealth>,
+    /// Problems reading Claude's files (see get_diagnostics)
+    pub diagnostics: usize,
+}
+
+fn subsystem(
+    name: &str,
+    t: Option<&Tracker>,
+    now: chrono::DateTime<chrono::Utc>,
+    tray_paused: bool,
+) -> SubsystemHealth {
+    let age = t
+        .and_then(|t| t.last_success)
+        .map(|s| (now - s).num_seconds());
+    let last_failed = t.is_some_and(|t| match (&t.last_error, t.last_success) {
+        (Some((at, _)), Some(ok)) => *at > ok,
+        (Some(_), None) => true,
+        _ => false,
+    });
+    let status = match t {
+        None => "never",
+        Some(_) if last_failed => "error",
+        Some(_) if name == RATE_LIMITS && age.is_some_and(|a| a > RATE_LIMITS_STALE_SECS) => {
+            // Old numbers are expected when nothing is polling them
+            if tray_paused {
+                "paused"
+            } else {
+                "stale"
+            }
+        }
+        Some(_) => "ok",
+    };
+    SubsystemHealth {
+        name: name.to_string(),
+        status: status.to_string(),
+        runs: t.map(|t| t.runs).unwrap_or(0),
+        failures: t.map(|t| t.failures).unwrap_or(0),
+        last_success_at: t.and_then(|t| t.last_success).map(|s| s.to_rfc3339()),
+        last_success_age_secs: age,
+        last_error: t
+            .and_then(|t| t.last_error.as_ref())
+            .map(|(_, e)| e.clone()),
+        last_error_at: t
+            .and_then(|t| t.last_error.as_ref())
+            .map(|(at, _)| at.to_rfc3339()),
+        last_duration_ms: t.map(|t| t.last_duration_ms).unwrap_or(0),
+    }
+}
+
+fn watcher(now: chrono::DateTime<chrono::Utc>) -> WatcherHealth {
+    let w = WATCHER.lock().unwrap_or_else(|e| e.into_inner());
+    let status = if w.stopped {
+        "stopped"
+    } else if !w.started {
+        "not_started"
+    } else if w
+        .last_heartbeat
+        .is_some_and(|h| (now - h).num_seconds() > WATCHER_STALE_SECS)
+    {
+        "stale"
+    } else {
+        "ok"
+    };
+    WatcherHealth {
+        status: status.to_string(),
+        last_heartbeat_at: w.last_heartbeat.map(|t| t.to_rfc3339()),
+        last_event_at: w.last_event.map(|t| t.to_rfc3339()),
+    }
+}
+
+fn index() -> Option<IndexHealth> {
+    let (_, indexed_sessions) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: None,
+        to: None,
+        device: None,
+        sort: String::new(),
+        offset: 0,
+        limit: 1,
+    })
+    .ok()?;
+    let database_bytes = storage::sprt_dir()
+        .and_then(|d| std::fs::metadata(d.join("sprt.db")).ok())
+        .map(|m| m.len())
+        .unwrap_or(0);
+    Some(IndexHealth {
+        indexed_sessions,
+        database_bytes,
+    })
+}
+
+pub fn health() -> Health {
+    let now = chrono::Utc::now();
+    let monitoring = monitor::get_monitoring_status();
+    let subsystems: Vec<SubsystemHealth> = {
+        let trackers = TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+        [RATE_LIMITS, SESSION_INDEX]
+            .iter()
+            .map(|name| subsystem(name, trackers.get(name), now, monitoring.tray_paused))
+            .collect()
+    };
+    let watcher = watcher(now);
+    let degraded = watcher.status != "ok"
+        || subsystems
+            .iter()
+            .any(|s| s.status == "error" || s.status == "stale");
+
+    Health {
+        status: if degraded { "degraded" } else { "ok" }.to_string(),
+        checked_at: now.to_rfc3339(),
+        uptime_secs: STARTED.elapsed().as_secs(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        monitoring,
+        watcher,
+        subsystems,
+        probes: probe::stats(),
+        caches: cache::snapshot(),
+        index: index(),
+        diagnostics: diagnostics::get_diagnostics().len(),
+    }
+}
+
+/// Per-subsystem status for bug reports ("the numbers look frozen"): watcher liveness,
+/// last rate-limit fetch, index refreshes, cache ages and scan times.
+#[tauri::command]
+pub async fn get_health() -> Result<Health, SprtError> {
+    tauri::async_runtime::spawn_blocking(health)
+        .await
+        .map_err(|e| SprtError::Internal(format!("Task join error: {}", e)))
+}
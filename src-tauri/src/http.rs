@@ -0,0 +1,121 @@
+use crate::error::SprtError;
+use crate::settings;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+/// First backoff delay; doubles with each retry, plus up to the same again in jitter
+const BASE_DELAY_MS: u64 = 500;
+/// A server asking for a longer wait than this gets reported instead of waited out
+const MAX_RETRY_AFTER_SECS: u64 = 30;
+
+/// Shared client, rebuilt when the configured timeout changes
+static CLIENT: LazyLock<Mutex<Option<(u64, Client)>>> = LazyLock::new(|| Mutex::new(None));
+
+pub fn client() -> Result<Client, SprtError> {
+    let timeout = settings::load()
+        .http
+        .timeout_secs
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .max(1);
+    let mut cached = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((secs, client)) = cached.as_ref() {
+        if *secs == timeout {
+            return Ok(client.clone());
+        }
+    }
+    let client = Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .user_agent("SPRT")
+        .build()
+        .map_err(|e| SprtError::Internal(format!("HTTP client error: {}", e)))?;
+    *cached = Some((timeout, client.clone()));
+    Ok(client)
+}
+
+/// Seconds from a Retry-After header (delta-seconds form only)
+pub fn retry_after_secs(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse().ok())
+}
+
+/// " (retry after Ns)" when the response says when to come back, for error messages
+pub fn retry_hint(headers: &HeaderMap) -> String {
+    retry_after_secs(headers)
+        .map(|s| format!(" (retry after {}s)", s))
+        .unwrap_or_default()
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    // 529: Anthropic API overloaded
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() || status.as_u16() == 529
+}
+
+fn backoff(attempt: u32) -> Duration {
+    let base = BASE_DELAY_MS << attempt.min(6);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    Duration::from_millis(base + nanos % (base + 1))
+}
+
+async fn send_with(
+    label: &str,
+    build: impl Fn(&Client) -> RequestBuilder,
+    max_retries: u32,
+) -> Result<Response, SprtError> {
+    let client = client()?;
+    let mut attempt = 0;
+    loop {
+        let delay = match build(&client).send().await {
+            Ok(resp) if is_retryable(resp.status()) && attempt < max_retries => {
+                match retry_after_secs(resp.headers()) {
+                    Some(secs) if secs > MAX_RETRY_AFTER_SECS => return Ok(resp),
+                    Some(secs) => Duration::from_secs(secs),
+                    None => backoff(attempt),
+                }
+            }
+            Ok(resp) => return Ok(resp),
+            Err(e) if (e.is_timeout() || e.is_connect()) && attempt < max_retries => {
+                backoff(attempt)
+            }
+            Err(e) if e.is_timeout() => {
+                return Err(SprtError::Network(format!("{} timed out: {}", label, e)))
+            }
+            Err(e) => return Err(SprtError::Network(format!("{} failed: {}", label, e))),
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Send a request built by `build`, retrying timeouts, connection failures, 429s and 5xx
+/// with exponential backoff and jitter (or the server's Retry-After). The last response is
+/// returned as-is, so callers still check the status.
+pub async fn send(
+    label: &str,
+    build: impl Fn(&Client) -> RequestBuilder,
+) -> Result<Response, SprtError> {
+    let max_retries = settings::load()
+        .http
+        .max_retries
+        .unwrap_or(DEFAULT_MAX_RETRIES);
+    send_with(label, build, max_retries).await
+}
+
+/// Like `send`, but never retried: every HTTP status, 429 included, is an answer. For
+/// requests that must not repeat (a refresh token is single-use, so a retry after a timed-out
+/// but accepted POST would present a spent one) or that the UI waits on (the rate-limit probe)
+pub async fn send_once(
+    label: &str,
+    build: impl Fn(&Client) -> RequestBuilder,
+) -> Result<Response, SprtError> {
+    send_with(label, build, 0).await
+}
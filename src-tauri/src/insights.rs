@@ -0,0 +1,501 @@
+use crate::budget;
+use crate::claude;
+use crate::devlog;
+use crate::error::SprtError;
+use crate::git;
+use crate::storage::{self, DevLog, DevLogStats};
+use crate::time::{self, DayClock};
+
+use chrono::Timelike;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+const INSIGHTS_SYSTEM_PROMPT: &str = r#"You are a productivity coach for SPRT (Sprint), a developer productivity tool.
+Given aggregated Claude Code usage and git statistics for a developer, analyze their work patterns and give coaching-style feedback.
+
+Respond ONLY with valid JSON (no markdown fences, no extra text) in this exact format:
+{
+  "summary": "2-3 sentence overview of how the period went",
+  "highlights": ["observation or suggestion 1", "observation or suggestion 2", ...]
+}
+
+Guidelines:
+- Cover busiest hours, token efficiency (tokens per commit, cache use, model mix) and project balance.
+- highlights: 4-6 items. Each names a pattern seen in the data and one concrete thing to try.
+- Be encouraging but honest. Don't invent numbers that aren't in the data.
+- This is not a journal: don't list what was built.
+- Write in English. Keep it concise."#;
+
+/// Usage window the rules look at
+const LOOKBACK_DAYS: i64 = 14;
+/// A model family "dominates" a day above this share of its cost
+const DOMINANT_SHARE: f64 = 0.6;
+/// Days per family needed before comparing commit output
+const MIN_DAYS_TO_COMPARE: usize = 2;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Insight {
+    /// Stable rule id, e.g. "opus-heavy"
+    pub id: String,
+    /// "info", "suggestion" or "warning"
+    pub severity: String,
+    pub title: String,
+    pub detail: String,
+}
+
+fn insight(id: &str, severity: &str, title: String, detail: String) -> Insight {
+    Insight {
+        id: id.to_string(),
+        severity: severity.to_string(),
+        title,
+        detail,
+    }
+}
+
+fn family(model: &str) -> &'static str {
+    ["opus", "sonnet", "haiku"]
+        .into_iter()
+        .find(|f| model.contains(f))
+        .unwrap_or("other")
+}
+
+#[derive(Default)]
+struct Usage {
+    /// date key → family → cost
+    daily_cost: HashMap<String, HashMap<&'static str, f64>>,
+    week_cost: HashMap<&'static str, f64>,
+    week_tokens: u64,
+    week_subagent_tokens: u64,
+    week_prompt_tokens: u64,
+    week_cache_read: u64,
+}
+
+fn collect_usage(clock: &DayClock) -> Result<Usage, SprtError> {
+    let since = clock
+        .day_start(clock.today() - chrono::Duration::days(LOOKBACK_DAYS - 1))
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|| chrono::Utc::now() - chrono::Duration::days(LOOKBACK_DAYS));
+    let week_start = clock.week_start().unwrap_or(since);
+
+    let mut usage = Usage::default();
+    claude::scan_usage(since, |e| {
+        let fam = family(e.model);
+        let cost = e.cost_usd();
+        *usage
+            .daily_cost
+            .entry(clock.date_key(&e.timestamp))
+            .or_default()
+            .entry(fam)
+            .or_insert(0.0) += cost;
+        if e.timestamp >= week_start {
+            *usage.week_cost.entry(fam).or_insert(0.0) += cost;
+            usage.week_tokens += e.total_tokens();
+            usage.week_prompt_tokens += e.input + e.cache_read + e.cache_creation;
+            usage.week_cache_read += e.cache_read;
+            if e.sidechain {
+                usage.week_subagent_tokens += e.total_tokens();
+            }
+        }
+    })?;
+    Ok(usage)
+}
+
+/// Opus share of the week's spend, scaled onto the 7-day limit when it's known
+fn opus_heavy(usage: &Usage, seven_day_pct: Option<f64>) -> Option<Insight> {
+    let total: f64 = usage.week_cost.values().sum();
+    let opus = usage.week_cost.get("opus").copied().unwrap_or(0.0);
+    if total <= 0.0 || opus / total < DOMINANT_SHARE {
+        return None;
+    }
+    let share = opus / total * 100.0;
+    let (title, severity) = match seven_day_pct {
+        Some(pct) if pct >= 50.0 => (
+            format!("Opus is consuming about {:.0}% of your 7-day budget", pct * opus / total),
+            "suggestion",
+        ),
+        _ => (format!("Opus is {:.0}% of this week's usage", share), "info"),
+    };
+    Some(insight(
+        "opus-heavy",
+        severity,
+        title,
+        "Sonnet is cheaper per token and stretches your limits further. Routine edits, \
+         tests and refactors rarely need Opus."
+            .to_string(),
+    ))
+}
+
+/// Average commits on days dominated by Opus vs by Sonnet, from daily devlogs
+fn sonnet_output(usage: &Usage) -> Result<Option<Insight>, SprtError> {
+    let logs = storage::list_devlogs("daily", LOOKBACK_DAYS as usize)?;
+    let mut commits: HashMap<&'static str, Vec<u32>> = HashMap::new();
+    for log in logs {
+        let Some(costs) = usage.daily_cost.get(&log.date) else {
+            continue;
+        };
+        let total: f64 = costs.values().sum();
+        let dominant = costs
+            .iter()
+            .find(|(_, c)| total > 0.0 && **c / total >= DOMINANT_SHARE)
+            .map(|(f, _)| *f);
+        if let Some(fam @ ("opus" | "sonnet")) = dominant {
+            commits.entry(fam).or_default().push(log.stats.total_commits);
+        }
+    }
+
+    let avg = |v: &Vec<u32>| v.iter().sum::<u32>() as f64 / v.len() as f64;
+    let (Some(opus), Some(sonnet)) = (commits.get("opus"), commits.get("sonnet")) else {
+        return Ok(None);
+    };
+    if opus.len() < MIN_DAYS_TO_COMPARE || sonnet.len() < MIN_DAYS_TO_COMPARE {
+        return Ok(None);
+    }
+    let (opus_avg, sonnet_avg) = (avg(opus), avg(sonnet));
+    if opus_avg <= 0.0 || sonnet_avg < opus_avg * 0.8 {
+        return Ok(None);
+    }
+    Ok(Some(insight(
+        "sonnet-similar-output",
+        "suggestion",
+        "Sonnet sessions achieved similar commit output".to_string(),
+        format!(
+            "Sonnet-heavy days averaged {:.1} commits vs {:.1} on Opus-heavy days over the last {} days.",
+            sonnet_avg, opus_avg, LOOKBACK_DAYS
+        ),
+    )))
+}
+
+fn low_cache_hits(usage: &Usage) -> Option<Insight> {
+    if usage.week_prompt_tokens < 1_000_000 {
+        return None;
+    }
+    let rate = usage.week_cache_read as f64 / usage.week_prompt_tokens as f64;
+    if rate >= 0.5 {
+        return None;
+    }
+    Some(insight(
+        "low-cache-hits",
+        "suggestion",
+        format!("Only {:.0}% of prompt tokens came from cache this week", rate * 100.0),
+        "Frequent /clear, new sessions or edits to CLAUDE.md invalidate the prompt cache. \
+         Continuing a session keeps its context cached."
+            .to_string(),
+    ))
+}
+
+fn subagent_heavy(usage: &Usage) -> Option<Insight> {
+    if usage.week_tokens == 0 {
+        return None;
+    }
+    let share = usage.week_subagent_tokens as f64 / usage.week_tokens as f64;
+    (share >= 0.4).then(|| {
+        insight(
+            "subagent-heavy",
+            "info",
+            format!("Subagents used {:.0}% of this week's tokens", share * 100.0),
+            "Each Task-tool subagent starts with a fresh context. Narrower prompts keep them cheap."
+                .to_string(),
+        )
+    })
+}
+
+fn limit_warnings(five_hour_pct: Option<f64>, seven_day_pct: Option<f64>) -> Vec<Insight> {
+    let mut out = vec![];
+    if let Some(pct) = five_hour_pct.filter(|p| *p >= 80.0) {
+        out.push(insight(
+            "five-hour-near-limit",
+            "warning",
+            format!("5-hour window at {:.0}%", pct),
+            "Switching to Sonnet for the rest of this window stretches what's left.".to_string(),
+        ));
+    }
+    if let Some(pct) = seven_day_pct.filter(|p| *p >= 80.0) {
+        out.push(insight(
+            "seven-day-near-limit",
+            "warning",
+            format!("7-day limit at {:.0}%", pct),
+            "Save Opus for planning and hard debugging until the weekly window resets.".to_string(),
+        ));
+    }
+    out
+}
+
+fn budget_pace() -> Option<Insight> {
+    let status = budget::budget_status().ok().flatten()?;
+    if status.on_pace {
+        return None;
+    }
+    Some(insight(
+        "over-budget-pace",
+        "warning",
+        "Ahead of your weekly budget".to_string(),
+        format!(
+            "{} used against {} allowed by day {} of 7.",
+            format_amount(status.used, &status.mode),
+            format_amount(status.allowed_to_date, &status.mode),
+            status.day_of_week
+        ),
+    ))
+}
+
+fn format_amount(value: f64, mode: &str) -> String {
+    match mode {
+        "utilization" => format!("{:.0}%", value),
+        _ => format!("{:.0} tokens", value),
+    }
+}
+
+pub fn insights() -> Result<Vec<Insight>, SprtError> {
+    let clock = DayClock::load();
+    let usage = collect_usage(&clock)?;
+    let limits = claude::get_cached_rate_limits();
+    let pct = |c: Option<&claude::UsageClaim>| c.map(|c| c.utilization * 100.0);
+    let five_hour = pct(limits.as_ref().and_then(|l| l.five_hour.as_ref()));
+    let seven_day = pct(limits.as_ref().and_then(|l| l.seven_day.as_ref()));
+
+    let mut out = limit_warnings(five_hour, seven_day);
+    out.extend(budget_pace());
+    out.extend(opus_heavy(&usage, seven_day));
+    out.extend(sonnet_output(&usage)?);
+    out.extend(low_cache_hits(&usage));
+    out.extend(subagent_heavy(&usage));
+
+    let rank = |s: &str| match s {
+        "warning" => 0,
+        "suggestion" => 1,
+        _ => 2,
+    };
+    out.sort_by_key(|i| rank(&i.severity));
+    Ok(out)
+}
+
+/// Rule-based advice from local usage, limits and devlogs; no AI call
+#[tauri::command]
+pub async fn get_insights() -> Result<Vec<Insight>, SprtError> {
+    tauri::async_runtime::spawn_blocking(insights)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ── AI coaching insights (stored with log_type "insights") ──
+
+#[derive(Default)]
+struct Aggregates {
+    /// Local hour → assistant messages
+    hours: [u64; 24],
+    daily_tokens: BTreeMap<String, u64>,
+    daily_messages: BTreeMap<String, u64>,
+    model_tokens: HashMap<String, u64>,
+    /// Project name → tokens
+    project_tokens: HashMap<String, u64>,
+    messages: u64,
+    tokens: u64,
+    prompt_tokens: u64,
+    cache_read: u64,
+    output_tokens: u64,
+    cost_usd: f64,
+}
+
+fn project_name(dir: &str) -> String {
+    let path = git::decode_project_path(dir);
+    std::path::Path::new(&path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(path)
+}
+
+fn build_insights_prompt(days: u32, agg: &Aggregates, logs: &[DevLog], rules: &[Insight]) -> String {
+    let mut prompt = format!("Analyze my work patterns over the last {} days.\n\n", days);
+
+    prompt.push_str(&format!(
+        "## Totals\n- {} assistant messages, {} tokens (~${:.2} at API prices)\n- Cache hit rate: {:.0}% of prompt tokens\n- Output tokens: {}\n",
+        agg.messages,
+        agg.tokens,
+        agg.cost_usd,
+        if agg.prompt_tokens > 0 { agg.cache_read as f64 / agg.prompt_tokens as f64 * 100.0 } else { 0.0 },
+        agg.output_tokens
+    ));
+    let commits: u32 = logs.iter().map(|l| l.stats.total_commits).sum();
+    let hours: f64 = logs.iter().map(|l| l.stats.active_hours).sum();
+    if commits > 0 {
+        prompt.push_str(&format!(
+            "- {} commits, {:.1} focused hours ({} tokens per commit)\n",
+            commits,
+            hours,
+            agg.tokens / commits as u64
+        ));
+    }
+
+    prompt.push_str("\n## Messages by hour (local)\n");
+    for (hour, count) in agg.hours.iter().enumerate().filter(|(_, c)| **c > 0) {
+        prompt.push_str(&format!("- {:02}:00 — {}\n", hour, count));
+    }
+
+    prompt.push_str("\n## By day\n");
+    for (date, tokens) in &agg.daily_tokens {
+        let score = logs
+            .iter()
+            .find(|l| &l.date == date)
+            .map(|l| format!(", score {}/100, {} commits", l.sprint_score, l.stats.total_commits))
+            .unwrap_or_default();
+        prompt.push_str(&format!(
+            "- {}: {} messages, {} tokens{}\n",
+            date,
+            agg.daily_messages.get(date).copied().unwrap_or(0),
+            tokens,
+            score
+        ));
+    }
+
+    prompt.push_str("\n## Models (tokens)\n");
+    let mut models: Vec<_> = agg.model_tokens.iter().collect();
+    models.sort_by_key(|(_, t)| std::cmp::Reverse(**t));
+    for (model, tokens) in models {
+        prompt.push_str(&format!("- {}: {}\n", model, tokens));
+    }
+
+    let mut project_commits: HashMap<&str, u32> = HashMap::new();
+    for log in logs {
+        for pw in &log.projects_worked {
+            *project_commits.entry(pw.name.as_str()).or_insert(0) += pw.commits;
+        }
+    }
+    prompt.push_str("\n## Projects\n");
+    let mut projects: Vec<_> = agg.project_tokens.iter().collect();
+    projects.sort_by_key(|(_, t)| std::cmp::Reverse(**t));
+    for (name, tokens) in projects {
+        let share = if agg.tokens > 0 { *tokens as f64 / agg.tokens as f64 * 100.0 } else { 0.0 };
+        prompt.push_str(&format!(
+            "- {}: {:.0}% of tokens, {} commits\n",
+            name,
+            share,
+            project_commits.get(name.as_str()).copied().unwrap_or(0)
+        ));
+    }
+
+    if !rules.is_empty() {
+        prompt.push_str("\n## Automatic checks\n");
+        for r in rules {
+            prompt.push_str(&format!("- {}: {}\n", r.title, r.detail));
+        }
+    }
+    prompt
+}
+
+pub async fn generate(days: u32) -> Result<DevLog, SprtError> {
+    let days = days.clamp(1, 90);
+    let (agg, logs, rules) = tauri::async_runtime::spawn_blocking(move || {
+        let clock = DayClock::load();
+        let first_day = clock.today() - chrono::Duration::days(days as i64 - 1);
+        let since = clock
+            .day_start(first_day)
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|| chrono::Utc::now() - chrono::Duration::days(days as i64));
+
+        let mut agg = Aggregates::default();
+        let mut names: HashMap<String, String> = HashMap::new();
+        claude::scan_usage(since, |e| {
+            let tokens = e.total_tokens();
+            let date = clock.date_key(&e.timestamp);
+            agg.hours[e.timestamp.with_timezone(&chrono::Local).hour() as usize] += 1;
+            *agg.daily_tokens.entry(date.clone()).or_insert(0) += tokens;
+            *agg.daily_messages.entry(date).or_insert(0) += 1;
+            *agg.model_tokens.entry(e.model.to_string()).or_insert(0) += tokens;
+            let name = names
+                .entry(e.project.to_string())
+                .or_insert_with(|| project_name(e.project));
+            *agg.project_tokens.entry(name.clone()).or_insert(0) += tokens;
+            agg.messages += 1;
+            agg.tokens += tokens;
+            agg.prompt_tokens += e.input + e.cache_read + e.cache_creation;
+            agg.cache_read += e.cache_read;
+            agg.output_tokens += e.output;
+            agg.cost_usd += e.cost_usd();
+        })?;
+
+        let first = time::format_date(first_day);
+        let logs: Vec<DevLog> = storage::list_devlogs("daily", days as usize)?
+            .into_iter()
+            .filter(|l| l.date >= first)
+            .collect();
+        let rules = insights().unwrap_or_default();
+        Ok::<_, SprtError>((agg, logs, rules))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    if agg.messages == 0 {
+        return Err(SprtError::NoData(format!(
+            "No Claude Code activity in the last {} days.",
+            days
+        )));
+    }
+
+    let prompt = build_insights_prompt(days, &agg, &logs, &rules);
+    let ai_response = devlog::call_claude_api(INSIGHTS_SYSTEM_PROMPT, &prompt).await?;
+    let parsed: serde_json::Value = serde_json::from_str(&ai_response)
+        .map_err(|e| SprtError::Parse(format!("Failed to parse AI response: {}", e)))?;
+
+    let summary = parsed
+        .get("summary")
+        .and_then(|v| v.as_str())
+        .unwrap_or("No summary generated.")
+        .to_string();
+    let highlights: Vec<String> = parsed
+        .get("highlights")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let date = DayClock::load().today_key();
+    let log = DevLog {
+        id: format!("i-{}-{}", date, chrono::Utc::now().timestamp_millis() % 10000),
+        date,
+        log_type: "insights".to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        summary,
+        highlights,
+        projects_worked: vec![],
+        stats: DevLogStats {
+            total_commits: logs.iter().map(|l| l.stats.total_commits).sum(),
+            total_messages: agg.messages,
+            total_tokens: agg.tokens,
+            total_files_changed: logs.iter().map(|l| l.stats.total_files_changed).sum(),
+            total_insertions: logs.iter().map(|l| l.stats.total_insertions).sum(),
+            total_deletions: logs.iter().map(|l| l.stats.total_deletions).sum(),
+            active_hours: logs.iter().map(|l| l.stats.active_hours).sum(),
+            projects_count: agg.project_tokens.len() as u32,
+            commit_messages: Default::default(),
+            devices: vec![],
+            provider_tokens: HashMap::new(),
+            ai_assisted_hours: logs
+                .iter()
+                .map(|l| l.stats.active_hours - l.stats.manual_hours)
+                .sum(),
+            manual_hours: logs.iter().map(|l| l.stats.manual_hours).sum(),
+        },
+        // Coaching isn't scored; sprint scores come from daily logs
+        sprint_score: 0,
+        github_activity: vec![],
+        checkin: None,
+        value: None,
+        issues_touched: vec![],
+        prompt_truncation: None,
+        plan_completion: None,
+        candidate_todos: vec![],
+    };
+    storage::save_devlog(&log)?;
+    Ok(log)
+}
+
+/// AI coaching on usage patterns over the last `days` days (default 7). Replaces today's
+/// insights if generated again; past ones are listed with list_devlogs("insights").
+#[tauri::command]
+pub async fn generate_insights(days: Option<u32>) -> Result<DevLog, SprtError> {
+    generate(days.unwrap_or(7)).await
+}
@@ -0,0 +1,303 @@
+use crate::git::{self, GitActivity};
+use crate::github;
+use crate::http;
+use crate::secrets;
+use crate::settings::{self, IssueTrackerSettings};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+
+const LINEAR_API: &str = "https://api.linear.app/graphql";
+
+/// Distinct keys looked up per devlog; the most-referenced win
+const MAX_LOOKUPS: usize = 40;
+
+/// An issue referenced by commit messages, resolved against its tracker
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueRef {
+    /// "ABC-123", or "owner/name#456" for GitHub
+    pub key: String,
+    /// "linear", "jira" or "github"
+    pub tracker: String,
+    pub title: String,
+    pub url: String,
+    /// Tracker's state name ("In Progress", "closed", ...)
+    pub state: Option<String>,
+    /// Commits in the period that mention it
+    pub commits: u32,
+}
+
+/// Lookups by key, misses included, so regenerating a devlog doesn't refetch
+static RESOLVED: LazyLock<Mutex<HashMap<String, Option<IssueRef>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Prefixes that look like issue keys but name standards and versions: "UTF-8", "SHA-256"
+const NOT_PROJECTS: &[&str] = &[
+    "AES", "CP", "CVE", "ECMA", "GPT", "HTTP", "IEEE", "IPV", "ISO", "MD", "PEP", "RFC", "RSA",
+    "SHA", "SSL", "TLS", "UTF", "WIN",
+];
+
+/// "ABC-123" style key: 2–10 uppercase letters or digits starting with a letter, a dash, digits.
+/// With `projects` set, the prefix must be one of them; otherwise it must not be a known acronym.
+fn is_tracker_key(project: &str, number: &str, projects: &[String]) -> bool {
+    let known = if projects.is_empty() {
+        !NOT_PROJECTS.contains(&project)
+    } else {
+        projects
+            .iter()
+            .any(|p| p.trim().eq_ignore_ascii_case(project))
+    };
+    known
+        && (2..=10).contains(&project.len())
+        && project.starts_with(|c: char| c.is_ascii_uppercase())
+        && project
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && (1..=7).contains(&number.len())
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Issue keys in a commit message, each once in order of first mention: "ABC-123" for
+/// Linear/Jira (limited to `projects` when non-empty) and "#456" for GitHub
+pub fn issue_keys(message: &str, projects: &[String]) -> Vec<String> {
+    let mut keys: Vec<String> = vec![];
+    let words = message.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '#'));
+    for word in words.filter(|w| !w.is_empty()) {
+        if let Some(n) = word.strip_prefix('#') {
+            if (1..=7).contains(&n.len()) && n.chars().all(|c| c.is_ascii_digit()) {
+                keys.push(word.to_string());
+            }
+            continue;
+        }
+        // "feat/ABC-123-login" splits into parts; a key is two adjacent ones
+        let parts: Vec<&str> = word.split('-').collect();
+        for pair in parts.windows(2) {
+            if is_tracker_key(pair[0], pair[1], projects) {
+                keys.push(format!("{}-{}", pair[0], pair[1]));
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    keys.retain(|k| seen.insert(k.clone()));
+    keys
+}
+
+async fn resolve_linear(api_key: &str, key: &str) -> Result<Option<IssueRef>, String> {
+    let body = serde_json::json!({
+        "query": "query($id: String!) { issue(id: $id) { identifier title url state { name } } }",
+        "variables": { "id": key },
+    });
+    let resp = http::send("Linear request", |client| {
+        client
+            .post(LINEAR_API)
+            .header("Authorization", api_key)
+            .header("content-type", "application/json")
+            .json(&body)
+    })
+    .await?;
+    let json: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("Cannot parse Linear response: {}", e))?;
+    // Unknown identifiers come back as errors with a null issue
+    let Some(issue) = json.pointer("/data/issue").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    Ok(Some(IssueRef {
+        key: key.to_string(),
+        tracker: "linear".to_string(),
+        title: issue
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        url: issue
+            .get("url")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        state: issue
+            .pointer("/state/name")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        commits: 0,
+    }))
+}
+
+async fn resolve_jira(
+    config: &IssueTrackerSettings,
+    key: &str,
+) -> Result<Option<IssueRef>, String> {
+    let (Some(base), Some(email), Some(token)) = (
+        config.jira_url.as_deref(),
+        config.jira_email.as_deref(),
+        config.jira_token.as_deref(),
+    ) else {
+        return Ok(None);
+    };
+    let base = base.trim_end_matches('/');
+    let url = format!("{}/rest/api/3/issue/{}?fields=summary,status", base, key);
+    let resp = http::send("Jira request", |client| {
+        client
+            .get(&url)
+            .basic_auth(email, Some(token))
+            .header("Accept", "application/json")
+    })
+    .await?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        return Err(format!("Jira returned {} for {}", resp.status(), key));
+    }
+    let json: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("Cannot parse Jira response: {}", e))?;
+    Ok(Some(IssueRef {
+        key: key.to_string(),
+        tracker: "jira".to_string(),
+        title: json
+            .pointer("/fields/summary")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        url: format!("{}/browse/{}", base, key),
+        state: json
+            .pointer("/fields/status/name")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        commits: 0,
+    }))
+}
+
+/// `key` is "owner/name#456"
+async fn resolve_github(token: &str, key: &str) -> Result<Option<IssueRef>, String> {
+    let Some((repo, number)) = key.split_once('#') else {
+        return Ok(None);
+    };
+    let url = format!("https://api.github.com/repos/{}/issues/{}", repo, number);
+    // Issue numbers that don't exist come back as errors too; treat them as unresolved
+    let Ok(issue) = github::get_json(token, &url).await else {
+        return Ok(None);
+    };
+    Ok(Some(IssueRef {
+        key: key.to_string(),
+        tracker: "github".to_string(),
+        title: issue
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        url: issue
+            .get("html_url")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        state: issue
+            .get("state")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        commits: 0,
+    }))
+}
+
+async fn resolve(
+    key: &str,
+    config: &IssueTrackerSettings,
+    github_token: Option<&str>,
+) -> Result<Option<IssueRef>, String> {
+    if key.contains('#') {
+        return match github_token {
+            Some(token) => resolve_github(token, key).await,
+            None => Ok(None),
+        };
+    }
+    // Linear and Jira keys look alike; ask Linear first, then Jira
+    if let Some(api_key) = config.linear_api_key.as_deref().filter(|k| !k.is_empty()) {
+        if let Some(issue) = resolve_linear(api_key, key).await? {
+            return Ok(Some(issue));
+        }
+    }
+    resolve_jira(config, key).await
+}
+
+/// Issues mentioned by the commits, resolved through whichever trackers have credentials.
+/// Keys no tracker recognizes are dropped, so "UTF-8" in a message doesn't become an issue.
+pub async fn collect_issues(git_data: &[GitActivity]) -> Vec<IssueRef> {
+    let all = settings::load();
+    let mut config = all.issue_trackers;
+    config.linear_api_key = secrets::resolve(secrets::LINEAR_API_KEY, config.linear_api_key);
+    config.jira_token = secrets::resolve(secrets::JIRA_TOKEN, config.jira_token);
+    let github_token = secrets::resolve(secrets::GITHUB_TOKEN, all.github_token);
+    if config.linear_api_key.is_none() && config.jira_url.is_none() && github_token.is_none() {
+        return vec![];
+    }
+
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for g in git_data {
+        // GitHub numbers only mean something within the repo's own origin
+        let repo = git::remote_url(&g.repo_path, "origin")
+            .and_then(|url| github::parse_github_remote(&url));
+        for c in &g.commits {
+            for key in issue_keys(&c.message, &config.project_keys) {
+                let key = match (key.strip_prefix('#'), &repo) {
+                    (Some(n), Some(repo)) => format!("{}#{}", repo, n),
+                    (Some(_), None) => continue,
+                    (None, _) => key,
+                };
+                *counts.entry(key).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut keys: Vec<(String, u32)> = counts.into_iter().collect();
+    keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    keys.truncate(MAX_LOOKUPS);
+
+    let mut issues = vec![];
+    for (key, commits) in keys {
+        let cached = RESOLVED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .cloned();
+        let resolved = match cached {
+            Some(r) => r,
+            None => match resolve(&key, &config, github_token.as_deref()).await {
+                Ok(r) => {
+                    RESOLVED
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(key.clone(), r.clone());
+                    r
+                }
+                // Not cached: a network failure shouldn't hide the issue next time
+                Err(e) => {
+                    log::warn!("Cannot resolve issue {key}: {e}");
+                    None
+                }
+            },
+        };
+        if let Some(mut issue) = resolved {
+            issue.commits = commits;
+            issues.push(issue);
+        }
+    }
+    issues
+}
+
+/// Combine daily lists, summing commits per key
+pub fn merge(into: &mut Vec<IssueRef>, from: &[IssueRef]) {
+    for issue in from {
+        match into.iter_mut().find(|i| i.key == issue.key) {
+            Some(existing) => {
+                existing.commits += issue.commits;
+                // Later days know the more recent state
+                existing.state = issue.state.clone();
+            }
+            None => into.push(issue.clone()),
+        }
+    }
+}
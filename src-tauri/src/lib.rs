@@ -1,92 +1,287 @@
+mod achievements;
+mod analysis;
+mod api;
+mod archive;
+mod backup;
+mod briefing;
+mod budget;
+mod cache;
+mod cache_stats;
+mod calendar;
+mod checkin;
 mod claude;
+mod clipboard;
+mod commit_nudge;
+pub mod cli;
+mod countdown;
+mod crypto;
+mod data_events;
+mod deep_link;
+mod demo;
+mod delivery;
+mod devices;
+mod diagnostics;
+mod disk_usage;
+mod dock;
+mod editor;
+mod email;
+mod environment;
+mod error;
+mod focus;
 mod devlog;
 mod git;
+mod github;
+mod health;
+mod http;
+mod insights;
+mod issues;
+mod limits_tray;
+mod logging;
+mod mcp;
+mod metrics;
+mod models;
+mod monitor;
+mod notifications;
+mod notion;
+mod overage;
+mod paths;
+mod plan;
+mod popover;
+mod power;
+mod pricing;
+mod privacy;
+mod probe;
+mod project_detail;
+mod providers;
+mod quick_open;
+mod release;
+mod remaining;
+mod retention;
+mod scheduler;
+mod secrets;
+mod server;
+mod session_end;
+mod session_export;
+mod session_index;
+mod session_search;
+mod settings;
+mod standup;
+mod stats_history;
+mod stats_query;
 mod storage;
+mod tags;
+mod time;
+mod time_report;
+mod timeline;
+mod todos;
+mod transcript;
+mod tray;
+mod tray_chart;
+mod trends;
+mod value;
+mod velocity;
+mod wake;
+mod window_breakdown;
+mod window_state;
 
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager, PhysicalPosition,
+    Manager,
 };
 
 #[tauri::command]
 fn update_tray_title(app: tauri::AppHandle, title: String) {
-    if let Some(tray) = app.tray_by_id("main-tray") {
-        let _ = tray.set_title(Some(&title));
+    if !monitor::is_paused() && !focus::is_active() {
+        tray::set_tray_text(&app, &title);
     }
 }
 
 #[tauri::command]
 fn open_dashboard(app: tauri::AppHandle) {
-    if let Some(p) = app.get_webview_window("popover") {
-        let _ = p.hide();
-    }
-    if let Some(w) = app.get_webview_window("main") {
-        let _ = w.center();
-        let _ = w.show();
-        let _ = w.set_focus();
-    }
+    window_state::show_dashboard(&app);
+}
+
+/// Run as an MCP server on stdio instead of launching the menu bar app
+pub fn run_mcp() {
+    paths::init(paths::DataPaths::from_env());
+    logging::init();
+    mcp::run_stdio();
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    paths::init(paths::DataPaths::from_env());
+    logging::init();
+    health::init();
     tauri::Builder::default()
+        // Must come first: a second launch (e.g. a sprt:// link on Windows or Linux) hands its
+        // arguments to this instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            window_state::show_dashboard(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .invoke_handler(tauri::generate_handler![
             claude::get_stats_cache,
             claude::get_active_sessions,
             claude::get_project_usage,
+            clipboard::copy_devlog_markdown,
+            clipboard::copy_today_stats,
+            clipboard::copy_standup,
+            cache_stats::get_cache_stats,
             claude::get_realtime_stats,
+            velocity::get_token_velocity,
             claude::get_rate_limits,
+            probe::get_probe_stats,
+            window_breakdown::get_window_breakdown,
+            remaining::get_remaining_estimate,
+            analysis::get_efficiency_stats,
             devlog::generate_devlog,
+            devlog::get_devlog_generations,
+            devlog::generate_devlog_draft,
+            devlog::approve_devlog,
+            models::get_available_models,
+            checkin::set_daily_checkin,
+            checkin::get_checkin_history,
+            plan::set_plan,
+            plan::get_plan,
+            todos::get_open_todos,
+            todos::set_todo_done,
+            achievements::get_achievements,
+            tags::set_project_tags,
+            tags::set_devlog_tags,
+            tags::list_tags,
+            tags::get_stats_by_tag,
+            devices::get_stats_by_device,
+            stats_query::query_stats,
+            editor::get_editor_activity,
+            time_report::generate_time_report,
+            briefing::get_morning_briefing,
+            value::get_value_report,
+            calendar::export_calendar,
+            session_export::export_session,
+            session_search::search_sessions,
             devlog::get_devlog,
             devlog::list_devlogs,
+            devlog::export_devlogs_json,
+            devlog::check_devlog_storage,
+            devlog::repair_devlog_storage,
             devlog::get_git_activity,
+            delivery::test_webhook,
+            notion::backfill_notion,
+            email::send_test_email,
+            crypto::get_encryption_status,
+            crypto::set_encryption,
+            crypto::migrate_devlog_encryption,
+            crypto::decrypt_export,
+            secrets::get_secret_status,
+            secrets::set_secret,
+            secrets::delete_secret,
+            secrets::copy_api_token,
+            devlog::preview_prompt,
+            diagnostics::get_diagnostics,
+            health::get_health,
+            paths::get_data_paths,
+            logging::get_recent_logs,
+            diagnostics::clear_diagnostics,
+            disk_usage::get_disk_usage,
+            dock::set_dock_visibility,
+            dock::set_badge,
+            archive::archive_sessions,
+            timeline::get_timeline,
+            environment::check_environment,
+            focus::start_focus,
+            focus::stop_focus,
+            focus::get_focus_status,
+            github::get_github_activity,
+            insights::get_insights,
+            insights::generate_insights,
+            monitor::pause_monitoring,
+            popover::get_popover_summary,
+            project_detail::get_project_detail,
+            quick_open::open_in_editor,
+            quick_open::open_in_terminal,
+            quick_open::reveal_in_finder,
+            session_index::list_sessions,
+            session_end::get_session_history,
+            monitor::resume_monitoring,
+            monitor::get_monitoring_status,
+            notifications::send_notification,
+            overage::get_overage_history,
+            backup::export_all_data,
+            backup::import_all_data,
+            budget::get_budget_status,
+            release::generate_release_notes,
+            retention::purge_data,
+            settings::get_settings,
+            settings::update_settings,
+            standup::generate_standup,
+            stats_history::get_stats_history,
+            stats_history::get_time_saved,
+            trends::get_score_history,
+            deep_link::take_pending_navigation,
             update_tray_title,
             open_dashboard,
         ])
         .setup(|app| {
-            // Hide from dock, show only in menu bar
+            // Menu bar only unless the Dock icon is turned on
             #[cfg(target_os = "macos")]
             {
-                app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+                app.set_activation_policy(dock::activation_policy(settings::load().dock.visible));
             }
 
+            diagnostics::init(app.handle().clone());
+            notifications::init(app.handle().clone());
+
             // Right-click menu
             let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
             let show = MenuItemBuilder::with_id("show", "Open Dashboard").build(app)?;
+            let pause = MenuItemBuilder::with_id("pause", "Pause Monitoring").build(app)?;
+            let copy_devlog = MenuItemBuilder::with_id("copy_devlog", "Copy Today's Devlog").build(app)?;
+            let copy_stats = MenuItemBuilder::with_id("copy_stats", "Copy Today's Stats").build(app)?;
+            let copy_standup = MenuItemBuilder::with_id("copy_standup", "Copy Standup").build(app)?;
+            let recent = SubmenuBuilder::with_id(app, "recent_projects", "Recent Projects").build()?;
             let menu = MenuBuilder::new(app)
                 .item(&show)
+                .item(&pause)
+                .separator()
+                .item(&copy_devlog)
+                .item(&copy_stats)
+                .item(&copy_standup)
+                .separator()
+                .item(&recent)
                 .separator()
                 .item(&quit)
                 .build()?;
+            app.manage(monitor::TrayMenuItems { pause, recent });
 
             // Build tray with dummy icon, then remove it
             let icon_data: Vec<u8> = vec![0; 4];
             let icon = Image::new(&icon_data, 1, 1);
 
-            let _tray = TrayIconBuilder::with_id("main-tray")
+            // Linux AppIndicator trays don't deliver click events — the menu is the only
+            // way in, so let left-click open it there
+            let _tray = TrayIconBuilder::with_id(tray::MAIN_TRAY_ID)
                 .icon(icon)
                 .title("—")
                 .tooltip("SPRT")
                 .menu(&menu)
-                .show_menu_on_left_click(false)
+                .show_menu_on_left_click(cfg!(target_os = "linux"))
                 .on_menu_event(|app_handle, event| match event.id().0.as_str() {
-                    "quit" => app_handle.exit(0),
-                    "show" => {
-                        // Hide popover, show main dashboard
-                        if let Some(p) = app_handle.get_webview_window("popover") {
-                            let _ = p.hide();
-                        }
-                        if let Some(w) = app_handle.get_webview_window("main") {
-                            let _ = w.center();
-                            let _ = w.show();
-                            let _ = w.set_focus();
-                        }
+                    "quit" => {
+                        window_state::remember_on_quit(app_handle);
+                        app_handle.exit(0);
                     }
-                    _ => {}
+                    "pause" => monitor::set_paused(app_handle, !monitor::is_paused()),
+                    "copy_devlog" => clipboard::copy_from_tray(app_handle, "devlog"),
+                    "copy_stats" => clipboard::copy_from_tray(app_handle, "stats"),
+                    "copy_standup" => clipboard::copy_from_tray(app_handle, "standup"),
+                    "show" => window_state::show_dashboard(app_handle),
+                    id => quick_open::on_menu_event(app_handle, id),
                 })
                 .on_tray_icon_event(|tray_icon, event| {
                     match event {
@@ -94,16 +289,7 @@ pub fn run() {
                         button: MouseButton::Left,
                         ..
                     } => {
-                        let app = tray_icon.app_handle();
-                        // Hide popover, open dashboard
-                        if let Some(p) = app.get_webview_window("popover") {
-                            let _ = p.hide();
-                        }
-                        if let Some(w) = app.get_webview_window("main") {
-                            let _ = w.center();
-                            let _ = w.show();
-                            let _ = w.set_focus();
-                        }
+                        window_state::show_dashboard(tray_icon.app_handle());
                     }
                     TrayIconEvent::Click {
                         button: MouseButton::Left,
@@ -120,25 +306,7 @@ pub fn run() {
                                 return;
                             }
 
-                            // Position below tray icon, centered
-                            let tray_x = match rect.position {
-                                tauri::Position::Physical(p) => p.x as f64,
-                                tauri::Position::Logical(p) => p.x,
-                            };
-                            let tray_y = match rect.position {
-                                tauri::Position::Physical(p) => p.y as f64,
-                                tauri::Position::Logical(p) => p.y,
-                            };
-                            let tray_h = match rect.size {
-                                tauri::Size::Physical(s) => s.height as f64,
-                                tauri::Size::Logical(s) => s.height,
-                            };
-
-                            let pop_w = 250.0;
-                            let x = (tray_x - pop_w / 2.0).max(8.0);
-                            let y = tray_y + tray_h + 4.0;
-
-                            let _ = w.set_position(PhysicalPosition::new(x as i32, y as i32));
+                            tray::position_popover(&w, &rect);
                             let _ = w.show();
                             let _ = w.set_focus();
                         }
@@ -149,27 +317,21 @@ pub fn run() {
                 .build(app)?;
 
             // Set tray icon from bundled resource
-            if let Some(tray) = app.tray_by_id("main-tray") {
-                let icon_bytes = include_bytes!("../icons/tray-icon.png");
-                if let Ok(img) = image::load_from_memory(icon_bytes) {
-                    let rgba = img.to_rgba8();
-                    let (w, h) = rgba.dimensions();
-                    let tray_img = Image::new(rgba.as_raw(), w, h);
-                    let _ = tray.set_icon(Some(tray_img));
-                    let _ = tray.set_icon_as_template(true);
-                }
-            }
+            tray::set_default_icon(app.handle());
 
-            // Hide main on close (instead of destroy) — don't show on launch (stay in menu bar only)
+            // Hide main on close (instead of destroy), remembering where it was
             if let Some(main_win) = app.get_webview_window("main") {
                 let mw = main_win.clone();
                 main_win.on_window_event(move |event| {
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                         api.prevent_close();
+                        window_state::remember(&mw, false);
                         let _ = mw.hide();
                     }
                 });
             }
+            // Stay in the menu bar only, unless the dashboard was open at last quit
+            window_state::restore_on_launch(app.handle());
 
             // Popover: hide on focus lost
             if let Some(popover) = app.get_webview_window("popover") {
@@ -181,60 +343,128 @@ pub fn run() {
                 });
             }
 
-            // Tray title updater — reads from rate limit cache every 5s
+            // Tray title updater — reads from rate limit cache every 5s (60s in quiet hours),
+            // paused on battery (see power.rs)
             let tray_app = app.handle().clone();
             std::thread::spawn(move || {
                 loop {
-                    std::thread::sleep(std::time::Duration::from_secs(5));
-                    let title = claude::get_cached_utilization()
-                        .map(|pct| format!("{}%", (pct * 100.0).round() as u32))
-                        .unwrap_or_else(|| "—".to_string());
-                    if let Some(tray) = tray_app.tray_by_id("main-tray") {
-                        let _ = tray.set_title(Some(&title));
+                    std::thread::sleep(std::time::Duration::from_secs(monitor::tray_poll_secs()));
+                    popover::tick();
+                    if power::is_throttled() {
+                        continue;
+                    }
+                    limits_tray::tick(&tray_app);
+                    dock::tick(&tray_app);
+                    // A running focus timer or an unlock flourish owns the title
+                    if monitor::is_paused() || focus::is_active() || achievements::flourish_active() {
+                        continue;
                     }
+                    let text = match settings::load().tray_text.as_deref() {
+                        Some("remaining") => remaining::tray_text(),
+                        _ => tray::utilization_text(),
+                    };
+                    tray::set_tray_text(&tray_app, &text);
+                    tray_chart::tick(&tray_app);
+                }
+            });
+
+            // Rate limits fetched in the background, so the tray has them with no window open
+            probe::start();
+
+            // Stale caches, limits and watchers after the machine sleeps
+            wake::start(app.handle().clone());
+
+            // Rate-limit reset countdown (from cached limits only)
+            countdown::start(app.handle().clone());
+
+            // Weekly budget pacing warnings
+            budget::start();
+
+            // Reminders to commit during long sessions
+            commit_nudge::start();
+
+            // Summaries of sessions that went quiet
+            session_end::start();
+
+            // Summary of yesterday each morning
+            briefing::start();
+
+            // Last week's devlog by email on Mondays
+            email::start();
+
+            // Badge unlocks
+            achievements::start(app.handle().clone());
+
+            // Tray "Recent Projects" submenu
+            quick_open::start(app.handle().clone());
+
+            // Tokens saved in settings.json by older versions move to the keychain
+            secrets::migrate_settings();
+
+            // Local metrics/API listener (off unless enabled in settings)
+            server::start();
+
+            // Retention cleanup — shortly after launch, then daily
+            std::thread::spawn(|| {
+                std::thread::sleep(std::time::Duration::from_secs(60));
+                loop {
+                    retention::run_scheduled_cleanup();
+                    std::thread::sleep(std::time::Duration::from_secs(24 * 3600));
                 }
             });
 
             // File watcher — 2s debounce to prevent freeze during heavy Claude Code usage
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
-                use notify::{Config, RecursiveMode, Watcher};
                 use std::time::{Duration, Instant};
 
                 let (tx, rx) = std::sync::mpsc::channel();
-                let mut watcher = match notify::RecommendedWatcher::new(tx, Config::default()) {
-                    Ok(w) => w,
-                    Err(e) => {
-                        eprintln!("Failed to create file watcher: {e}");
-                        return;
-                    }
+                let Some(mut watcher) = data_events::watch(tx.clone()) else {
+                    return;
                 };
+                let mut wakes = wake::count();
 
-                if let Some(cd) = dirs::home_dir().map(|h| h.join(".claude")) {
-                    // Watch parent dir so we catch file creation (stats-cache.json may not exist yet)
-                    let _ = watcher.watch(&cd, RecursiveMode::NonRecursive);
-                    let pd = cd.join("projects");
-                    if pd.exists() {
-                        let _ = watcher.watch(&pd, RecursiveMode::Recursive);
-                    }
-                }
-
-                // Debounce: only emit at most once per 2 seconds
+                // Debounce: changes are batched per domain and emitted at most once per
+                // 2 seconds; a quiet period flushes whatever is left
+                let mut pending = data_events::Pending::new();
                 let mut last_emit = Instant::now() - Duration::from_secs(10);
                 loop {
-                    match rx.recv_timeout(Duration::from_secs(2)) {
-                        Ok(_) => {
-                            if last_emit.elapsed() >= Duration::from_secs(2) {
-                                last_emit = Instant::now();
-                                let _ = app_handle.emit("claude-data-changed", ());
+                    // FSEvents streams can go silent across sleep; start over with fresh ones
+                    if wake::count() != wakes {
+                        wakes = wake::count();
+                        drop(watcher);
+                        match data_events::watch(tx.clone()) {
+                            Some(w) => watcher = w,
+                            None => break,
+                        }
+                        log::info!("File watcher re-armed after wake");
+                    }
+                    let received = rx.recv_timeout(Duration::from_secs(2));
+                    health::watcher_heartbeat(received.is_ok());
+                    match received {
+                        Ok(event) => {
+                            if monitor::is_paused() {
+                                continue;
+                            }
+                            if let Ok(event) = &event {
+                                for path in &event.paths {
+                                    pending.on_file_changed(path);
+                                }
                             }
                         }
-                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
                         Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
                     }
+                    if !pending.is_empty() && last_emit.elapsed() >= Duration::from_secs(2) {
+                        last_emit = Instant::now();
+                        pending.flush(&app_handle);
+                    }
                 }
+                health::watcher_stopped();
             });
 
+            deep_link::init(app.handle());
+
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,124 @@
+use crate::claude::{self, RateLimitInfo, UsageClaim};
+use crate::countdown;
+use crate::monitor;
+use crate::settings::{self, LimitsTraySettings};
+use crate::tray::{self, LIMITS_TRAY_ID};
+use crate::window_state;
+
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+
+fn percent(claim: Option<&UsageClaim>) -> String {
+    claim
+        .map(|c| format!("{}", (c.utilization * 100.0).round() as u32))
+        .unwrap_or_else(|| "—".to_string())
+}
+
+/// Fill the title template from the cached limits; "—" before the first fetch
+fn title(format: &str, info: Option<&RateLimitInfo>) -> String {
+    let reset = info
+        .and_then(|i| i.five_hour.as_ref())
+        .and_then(|c| c.reset)
+        .map(|r| {
+            countdown::format_remaining(r.saturating_sub(chrono::Utc::now().timestamp() as u64))
+        })
+        .unwrap_or_else(|| "—".to_string());
+    format
+        .replace("{5h}", &percent(info.and_then(|i| i.five_hour.as_ref())))
+        .replace("{7d}", &percent(info.and_then(|i| i.seven_day.as_ref())))
+        .replace(
+            "{sonnet}",
+            &percent(info.and_then(|i| i.seven_day_sonnet.as_ref())),
+        )
+        .replace("{reset}", &reset)
+}
+
+fn on_click(app: &AppHandle, rect: &tauri::Rect) {
+    let click = settings::load().limits_tray.click;
+    match click.as_str() {
+        "dashboard" => window_state::show_dashboard(app),
+        "refresh" => {
+            tauri::async_runtime::spawn(async {
+                let _ = claude::get_rate_limits(Some(true)).await;
+            });
+        }
+        _ => {
+            if let Some(w) = app.get_webview_window("popover") {
+                if w.is_visible().unwrap_or(false) {
+                    let _ = w.hide();
+                    return;
+                }
+                tray::position_popover(&w, rect);
+                let _ = w.show();
+                let _ = w.set_focus();
+            }
+        }
+    }
+}
+
+fn create(app: &AppHandle) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(LIMITS_TRAY_ID)
+        .title("—")
+        .tooltip("SPRT rate limits")
+        .on_tray_icon_event(|tray_icon, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                rect,
+                ..
+            } = event
+            {
+                on_click(tray_icon.app_handle(), &rect);
+            }
+        });
+    if let Some(icon) = tray::bundled_icon() {
+        builder = builder.icon(icon);
+        #[cfg(target_os = "macos")]
+        {
+            builder = builder.icon_as_template(true);
+        }
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+fn set_text(app: &AppHandle, config: &LimitsTraySettings) {
+    let Some(tray) = app.tray_by_id(LIMITS_TRAY_ID) else {
+        return;
+    };
+    let text = if monitor::is_paused() {
+        monitor::PAUSED_GLYPH.to_string()
+    } else {
+        title(&config.format, claude::get_cached_rate_limits().as_ref())
+    };
+    let _ = tray.set_title(Some(&text));
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = tray.set_tooltip(Some(format!("SPRT rate limits — {}", text)));
+    }
+}
+
+/// Called from the tray thread: creates or removes the icon to match settings,
+/// then refreshes its title
+pub fn tick(app: &AppHandle) {
+    let config = settings::load().limits_tray;
+    let exists = app.tray_by_id(LIMITS_TRAY_ID).is_some();
+    if config.enabled != exists {
+        let handle = app.clone();
+        let enabled = config.enabled;
+        // Tray icons belong to the main thread
+        let _ = app.run_on_main_thread(move || {
+            if enabled {
+                if let Err(e) = create(&handle) {
+                    log::warn!("Failed to create limits tray icon: {e}");
+                }
+            } else {
+                let _ = handle.remove_tray_by_id(LIMITS_TRAY_ID);
+            }
+        });
+        return;
+    }
+    if config.enabled {
+        set_text(app, &config);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::error::SprtError;
+use crate::settings;
+use crate::storage::sprt_dir;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const LOG_FILE: &str = "sprt.log";
+/// The current file is rotated to sprt.log.1 past this size
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// sprt.log plus sprt.log.1 … sprt.log.4
+const KEEP_FILES: usize = 5;
+const DEFAULT_RECENT: u32 = 200;
+const MAX_RECENT: u32 = 5000;
+/// Targets of our own modules start with this; dependencies only log warnings and up
+const CRATE_TARGET: &str = "claude_monitor_lib";
+
+struct LogFile {
+    file: File,
+    bytes: u64,
+}
+
+struct Logger {
+    level: LevelFilter,
+    out: Mutex<Option<LogFile>>,
+}
+
+static LOGGER: Logger = Logger {
+    level: LevelFilter::Info,
+    out: Mutex::new(None),
+};
+
+fn logs_dir() -> Option<PathBuf> {
+    sprt_dir().map(|d| d.join("logs"))
+}
+
+/// Oldest first, current file last
+fn log_files(dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = (1..KEEP_FILES)
+        .rev()
+        .map(|i| dir.join(format!("{}.{}", LOG_FILE, i)))
+        .collect();
+    files.push(dir.join(LOG_FILE));
+    files
+}
+
+fn open_current() -> Option<LogFile> {
+    let dir = logs_dir()?;
+    fs::create_dir_all(&dir).ok()?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOG_FILE))
+        .ok()?;
+    let bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Some(LogFile { file, bytes })
+}
+
+/// sprt.log → sprt.log.1 → … ; the oldest falls off
+fn rotate() -> Option<LogFile> {
+    let files = log_files(&logs_dir()?);
+    for pair in files.windows(2) {
+        if pair[1].exists() {
+            let _ = fs::rename(&pair[1], &pair[0]);
+        }
+    }
+    open_current()
+}
+
+fn short_target(target: &str) -> &str {
+    target
+        .strip_prefix(CRATE_TARGET)
+        .map(|t| t.trim_start_matches("::"))
+        .filter(|t| !t.is_empty())
+        .unwrap_or(target)
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if metadata.target().starts_with(CRATE_TARGET) {
+            metadata.level() <= log::max_level()
+        } else {
+            metadata.level() <= Level::Warn
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // Continuation lines are indented so readers can tell where an entry ends
+        let message = record.args().to_string().replace('\n', "\n    ");
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            record.level(),
+            short_target(record.target()),
+            message
+        );
+        eprint!("{}", line);
+
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        if out
+            .as_ref()
+            .is_some_and(|f| f.bytes + line.len() as u64 > MAX_FILE_BYTES)
+        {
+            *out = rotate();
+        }
+        // Unbuffered and written whole, so a crash right after loses nothing
+        if let Some(f) = out.as_mut() {
+            if f.file.write_all(line.as_bytes()).is_ok() {
+                f.bytes += line.len() as u64;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(f) = self.out.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let _ = f.file.flush();
+        }
+    }
+}
+
+/// Start logging to ~/.claude/sprt/logs (and stderr), and record panics before the
+/// process goes down. settings.logLevel: "error", "warn", "info" (default) or "debug".
+pub fn init() {
+    *LOGGER.out.lock().unwrap_or_else(|e| e.into_inner()) = open_current();
+    let level = settings::load()
+        .log_level
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(LOGGER.level);
+    if log::set_logger(&LOGGER).is_err() {
+        return;
+    }
+    log::set_max_level(level);
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!("{info}");
+        default_hook(info);
+    }));
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// RFC 3339
+    pub at: String,
+    pub level: String,
+    /// Module that logged it
+    pub target: String,
+    pub message: String,
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let (at, rest) = line.split_once(' ')?;
+    chrono::DateTime::parse_from_rfc3339(at).ok()?;
+    let (level, rest) = rest.trim_start().split_once(' ')?;
+    level.parse::<Level>().ok()?;
+    let (target, message) = rest.trim_start().split_once(": ")?;
+    Some(LogEntry {
+        at: at.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: message.to_string(),
+    })
+}
+
+pub fn recent_logs(limit: u32, min_level: Option<&str>) -> Result<Vec<LogEntry>, SprtError> {
+    let min_level: Level = match min_level {
+        Some(l) => l
+            .parse()
+            .map_err(|_| SprtError::InvalidInput(format!("Unknown log level: {}", l)))?,
+        None => Level::Trace,
+    };
+    let Some(dir) = logs_dir() else {
+        return Ok(vec![]);
+    };
+
+    let mut entries: Vec<LogEntry> = vec![];
+    for path in log_files(&dir) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in content.lines() {
+            match parse_line(line) {
+                Some(entry) => entries.push(entry),
+                None => {
+                    if let Some(last) = entries.last_mut() {
+                        last.message.push('\n');
+                        last.message.push_str(line.trim_start());
+                    }
+                }
+            }
+        }
+    }
+    entries.retain(|e| e.level.parse::<Level>().is_ok_and(|l| l <= min_level));
+    let skip = entries
+        .len()
+        .saturating_sub(limit.clamp(1, MAX_RECENT) as usize);
+    Ok(entries.split_off(skip))
+}
+
+/// Newest log entries, oldest first, for the in-app log viewer. `level` keeps only
+/// entries at least that severe ("error", "warn", "info", "debug").
+#[tauri::command]
+pub async fn get_recent_logs(
+    limit: Option<u32>,
+    level: Option<String>,
+) -> Result<Vec<LogEntry>, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        recent_logs(limit.unwrap_or(DEFAULT_RECENT), level.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
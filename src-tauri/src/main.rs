@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if std::env::args().any(|a| a == "--mcp") {
+        claude_monitor_lib::run_mcp();
+        return;
+    }
     claude_monitor_lib::run()
 }
@@ -0,0 +1,125 @@
+use crate::claude;
+use crate::storage;
+
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "get_usage_stats",
+            "description": "Today's and this week's Claude Code usage: messages, tokens by model, active sessions, plan type.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "get_rate_limits",
+            "description": "Current plan rate-limit utilization (5-hour and 7-day windows) with reset times. Use this to answer how much budget is left.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "force": { "type": "boolean", "description": "Bypass the 60s cache and query the API now" }
+                }
+            }
+        },
+        {
+            "name": "get_recent_devlogs",
+            "description": "Recent SPRT development logs (summary, highlights, sprint score, per-project work).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "logType": { "type": "string", "enum": ["daily", "weekly", "monthly"] },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 30 }
+                }
+            }
+        }
+    ])
+}
+
+fn call_tool(name: &str, args: &Value) -> Result<Value, String> {
+    match name {
+        "get_usage_stats" => {
+            let stats = claude::compute_realtime_stats()?;
+            serde_json::to_value(stats).map_err(|e| e.to_string())
+        }
+        "get_rate_limits" => {
+            let force = args.get("force").and_then(|v| v.as_bool());
+            let info = tauri::async_runtime::block_on(claude::get_rate_limits(force))?;
+            serde_json::to_value(info).map_err(|e| e.to_string())
+        }
+        "get_recent_devlogs" => {
+            let log_type = args.get("logType").and_then(|v| v.as_str()).unwrap_or("daily");
+            let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(7).clamp(1, 30);
+            let logs = storage::list_devlogs(log_type, limit as usize)?;
+            serde_json::to_value(logs).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+/// Handle one JSON-RPC message. Notifications (no id) get no response.
+fn handle_message(msg: &Value) -> Option<Value> {
+    let id = msg.get("id")?.clone();
+    let method = msg.get("method").and_then(|m| m.as_str()).unwrap_or("");
+    let params = msg.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "sprt", "version": env!("CARGO_PKG_VERSION") }
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let args = params.get("arguments").cloned().unwrap_or(json!({}));
+            // Tool failures are reported in-band so the model can see them
+            Ok(match call_tool(name, &args) {
+                Ok(v) => json!({
+                    "content": [{ "type": "text", "text": serde_json::to_string_pretty(&v).unwrap_or_default() }]
+                }),
+                Err(e) => json!({
+                    "content": [{ "type": "text", "text": e }],
+                    "isError": true
+                }),
+            })
+        }
+        _ => Err(json!({ "code": -32601, "message": format!("Method not found: {}", method) })),
+    };
+
+    Some(match result {
+        Ok(r) => json!({ "jsonrpc": "2.0", "id": id, "result": r }),
+        Err(e) => json!({ "jsonrpc": "2.0", "id": id, "error": e }),
+    })
+}
+
+/// Serve MCP over stdio (newline-delimited JSON-RPC) until stdin closes.
+/// Register with Claude Code as: `claude mcp add sprt -- /path/to/SPRT --mcp`
+pub fn run_stdio() {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(msg) => handle_message(&msg),
+            Err(e) => Some(json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": format!("Parse error: {}", e) }
+            })),
+        };
+        if let Some(resp) = response {
+            let _ = writeln!(stdout, "{}", resp);
+            let _ = stdout.flush();
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::claude;
+use crate::server::Response;
+use crate::storage;
+
+use std::fmt::Write;
+
+/// Escape a label value per the Prometheus text format
+fn escape_label(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn gauge_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+/// Render /metrics in the Prometheus text exposition format
+pub fn render() -> Response {
+    let mut out = String::new();
+
+    // Rate limits come from the in-memory cache; absent until the app has checked them once
+    if let Some(info) = claude::get_cached_rate_limits() {
+        gauge_header(
+            &mut out,
+            "sprt_rate_limit_utilization",
+            "Plan rate-limit window utilization (0-1).",
+        );
+        for (window, c) in &info.claims {
+            let _ = writeln!(out, "sprt_rate_limit_utilization{{window=\"{}\"}} {}", escape_label(window), c.utilization);
+        }
+    }
+
+    if let Ok(stats) = claude::compute_realtime_stats() {
+        gauge_header(&mut out, "sprt_tokens_today", "Tokens used today, by model.");
+        let mut models: Vec<_> = stats.today_model_tokens.iter().collect();
+        models.sort();
+        for (model, tokens) in models {
+            let _ = writeln!(out, "sprt_tokens_today{{model=\"{}\"}} {}", escape_label(model), tokens);
+        }
+
+        gauge_header(&mut out, "sprt_messages_today", "Assistant messages today.");
+        let _ = writeln!(out, "sprt_messages_today {}", stats.today_messages);
+
+        gauge_header(&mut out, "sprt_active_sessions", "Sessions active in the last 5 hours.");
+        let _ = writeln!(out, "sprt_active_sessions {}", stats.active_sessions);
+    }
+
+    if let Some(log) = storage::list_devlogs("daily", 1).ok().and_then(|l| l.into_iter().next()) {
+        gauge_header(&mut out, "sprt_last_devlog_score", "Sprint score of the latest daily devlog.");
+        let _ = writeln!(out, "sprt_last_devlog_score{{date=\"{}\"}} {}", escape_label(&log.date), log.sprint_score);
+    }
+
+    Response {
+        status: 200,
+        content_type: "text/plain; version=0.0.4; charset=utf-8",
+        body: out,
+    }
+}
@@ -0,0 +1,94 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::http;
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The model list rarely changes; refetched after this long, or when a model isn't in it
+const CACHE_SECS: u64 = 6 * 3600;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(alias = "display_name")]
+    pub display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelInfo>,
+}
+
+static CACHE: Mutex<Option<(Instant, Vec<ModelInfo>)>> = Mutex::new(None);
+
+async fn fetch() -> Result<Vec<ModelInfo>, SprtError> {
+    let token = claude::get_access_token().await?;
+    let resp = http::send("Model list", |client| {
+        client
+            .get("https://api.anthropic.com/v1/models?limit=1000")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("anthropic-version", "2023-06-01")
+            .header("anthropic-beta", "oauth-2025-04-20")
+    })
+    .await?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(SprtError::Api(format!("Model list request failed ({})", status)));
+    }
+    let list: ModelList = resp
+        .json()
+        .await
+        .map_err(|e| SprtError::Parse(format!("Failed to read model list: {}", e)))?;
+    Ok(list.data)
+}
+
+/// Models the account can use, from the models endpoint (cached for CACHE_SECS)
+pub async fn available(force: bool) -> Result<Vec<ModelInfo>, SprtError> {
+    if !force {
+        let cached = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((at, models)) = cached.as_ref() {
+            if at.elapsed() < Duration::from_secs(CACHE_SECS) {
+                return Ok(models.clone());
+            }
+        }
+    }
+    let models = fetch().await?;
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), models.clone()));
+    Ok(models)
+}
+
+/// Fail with InvalidInput when `model` isn't in the account's model list. A list that can't
+/// be fetched doesn't block generation: the messages call reports a bad model itself.
+pub async fn validate(model: &str) -> Result<(), SprtError> {
+    if crate::demo::active() {
+        return Ok(());
+    }
+    let listed = |models: &[ModelInfo]| models.is_empty() || models.iter().any(|m| m.id == model);
+    let models = match available(false).await {
+        Ok(models) if listed(&models) => return Ok(()),
+        // Possibly released since the list was cached
+        Ok(_) => available(true).await,
+        Err(e) => Err(e),
+    };
+    match models {
+        Ok(models) if listed(&models) => Ok(()),
+        Ok(models) => Err(SprtError::InvalidInput(format!(
+            "Unknown model {}; available: {}",
+            model,
+            models.iter().map(|m| m.id.as_str()).collect::<Vec<_>>().join(", ")
+        ))),
+        Err(e) => {
+            log::warn!("Cannot fetch the model list, not validating {model}: {e}");
+            Ok(())
+        }
+    }
+}
+
+/// Models to offer for devlogModel.model and devlogModel.summaryModel
+#[tauri::command]
+pub async fn get_available_models(force: Option<bool>) -> Result<Vec<ModelInfo>, SprtError> {
+    available(force.unwrap_or(false)).await
+}
@@ -0,0 +1,147 @@
+use crate::power::{self, PowerState};
+use crate::settings::{self, QuietHours};
+use crate::tray;
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::menu::{MenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+/// Shown in the tray while monitoring is paused
+pub const PAUSED_GLYPH: &str = "⏸";
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Tray menu items whose labels or contents change at runtime
+pub struct TrayMenuItems {
+    pub pause: MenuItem<Wry>,
+    /// Filled by quick_open
+    pub recent: Submenu<Wry>,
+}
+
+/// Tray title refresh interval, normal and during quiet hours
+const TRAY_POLL_SECS: u64 = 5;
+const QUIET_TRAY_POLL_SECS: u64 = 60;
+
+/// How long cached rate limits are served before probing the API again
+const RATE_LIMIT_CACHE_SECS: u64 = 60;
+const QUIET_RATE_LIMIT_CACHE_SECS: u64 = 15 * 60;
+
+/// On battery (see power.rs), where tray updates pause; quiet hours still win when slower
+const LOW_POWER_RATE_LIMIT_CACHE_SECS: u64 = 10 * 60;
+
+/// While paused: no rate-limit API probes, no file-change events, no tray title updates
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+
+    if let Some(items) = app.try_state::<TrayMenuItems>() {
+        let label = if paused { "Resume Monitoring" } else { "Pause Monitoring" };
+        let _ = items.pause.set_text(label);
+    }
+    if paused {
+        tray::set_tray_text(app, PAUSED_GLYPH);
+    }
+    let _ = app.emit("monitoring-paused-changed", paused);
+}
+
+#[tauri::command]
+pub fn pause_monitoring(app: AppHandle) {
+    set_paused(&app, true);
+}
+
+#[tauri::command]
+pub fn resume_monitoring(app: AppHandle) {
+    set_paused(&app, false);
+}
+
+fn parse_hhmm(s: &str) -> Option<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(s.trim(), "%H:%M").ok()
+}
+
+fn in_quiet_hours(q: &QuietHours, now: chrono::NaiveTime) -> bool {
+    if !q.enabled {
+        return false;
+    }
+    match (parse_hhmm(&q.start), parse_hhmm(&q.end)) {
+        (Some(start), Some(end)) if start <= end => now >= start && now < end,
+        // Window spans midnight, e.g. 22:00–08:00
+        (Some(start), Some(end)) => now >= start || now < end,
+        _ => false,
+    }
+}
+
+pub fn is_quiet_now() -> bool {
+    in_quiet_hours(&settings::load().quiet_hours, chrono::Local::now().time())
+}
+
+pub fn tray_poll_secs() -> u64 {
+    if is_quiet_now() {
+        QUIET_TRAY_POLL_SECS
+    } else {
+        TRAY_POLL_SECS
+    }
+}
+
+pub fn rate_limit_cache_secs() -> u64 {
+    if is_quiet_now() {
+        QUIET_RATE_LIMIT_CACHE_SECS
+    } else if power::is_throttled() {
+        LOW_POWER_RATE_LIMIT_CACHE_SECS
+    } else {
+        RATE_LIMIT_CACHE_SECS
+    }
+}
+
+/// Whether a notification may be shown right now
+pub fn notifications_allowed() -> bool {
+    !is_paused() && !is_quiet_now()
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringStatus {
+    pub mode: String, // "active", "quiet", "low_power", "paused"
+    pub paused: bool,
+    pub quiet_hours_active: bool,
+    /// Background work slowed down for battery power
+    pub low_power_active: bool,
+    pub power: PowerState,
+    pub notifications_suppressed: bool,
+    /// No tray title, chart or limit updates: monitoring paused, or on battery
+    pub tray_paused: bool,
+    pub tray_poll_secs: u64,
+    pub rate_limit_cache_secs: u64,
+}
+
+#[tauri::command]
+pub fn get_monitoring_status() -> MonitoringStatus {
+    let paused = is_paused();
+    let quiet = is_quiet_now();
+    let low_power = power::is_throttled();
+    let mode = if paused {
+        "paused"
+    } else if quiet {
+        "quiet"
+    } else if low_power {
+        "low_power"
+    } else {
+        "active"
+    };
+    MonitoringStatus {
+        mode: mode.to_string(),
+        paused,
+        quiet_hours_active: quiet,
+        low_power_active: low_power,
+        power: power::state(),
+        notifications_suppressed: paused || quiet,
+        tray_paused: paused || low_power,
+        tray_poll_secs: tray_poll_secs(),
+        rate_limit_cache_secs: crate::probe::interval_secs(
+            crate::claude::get_cached_rate_limits().as_ref(),
+        ),
+    }
+}
@@ -0,0 +1,37 @@
+use crate::monitor;
+
+use std::sync::OnceLock;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Keep a handle for alerts raised from code that has none (e.g. rate-limit polling)
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+/// Show a desktop notification unless monitoring is paused or quiet hours are active.
+/// Returns whether it was shown.
+pub fn notify<R: Runtime>(app: &AppHandle<R>, title: &str, body: &str) -> bool {
+    if !monitor::notifications_allowed() {
+        return false;
+    }
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .is_ok()
+}
+
+/// `notify` through the handle given to `init`. Outside the app (CLI, MCP) nothing is shown.
+pub fn notify_app(title: &str, body: &str) -> bool {
+    APP.get().is_some_and(|app| notify(app, title, body))
+}
+
+/// Frontend entry point, so webview-triggered alerts honor quiet hours too
+#[tauri::command]
+pub fn send_notification(app: AppHandle, title: String, body: String) -> bool {
+    notify(&app, &title, &body)
+}
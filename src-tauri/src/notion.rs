@@ -0,0 +1,224 @@
+use crate::delivery;
+use crate::error::SprtError;
+use crate::http;
+use crate::secrets;
+use crate::settings::{self, NotionSettings};
+use crate::storage::{self, DevLog};
+
+use serde::Serialize;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Notion rejects rich text over 2000 characters per block
+const MAX_TEXT_CHARS: usize = 2000;
+/// Notion averages 3 requests/s per integration; backfill stays under it
+const BACKFILL_DELAY_MS: u64 = 400;
+
+/// Stable per devlog period, so a regenerated log replaces its page
+fn page_key(log: &DevLog) -> String {
+    format!("{}:{}", log.log_type, log.date)
+}
+
+fn text(content: &str) -> serde_json::Value {
+    let content: String = content.chars().take(MAX_TEXT_CHARS).collect();
+    serde_json::json!([{ "type": "text", "text": { "content": content } }])
+}
+
+/// Page properties; the database needs these columns (see NotionSettings)
+fn properties(log: &DevLog) -> serde_json::Value {
+    serde_json::json!({
+        "Name": { "title": text(&delivery::title_for(log)) },
+        "Date": { "date": { "start": log.date } },
+        "Type": { "select": { "name": log.log_type } },
+        "Score": { "number": log.sprint_score },
+        "Commits": { "number": log.stats.total_commits },
+        "Tokens": { "number": log.stats.total_tokens },
+        "Key": { "rich_text": text(&page_key(log)) },
+    })
+}
+
+fn body_blocks(log: &DevLog) -> Vec<serde_json::Value> {
+    let mut blocks = vec![serde_json::json!({
+        "object": "block",
+        "type": "paragraph",
+        "paragraph": { "rich_text": text(&log.summary) }
+    })];
+    if !log.highlights.is_empty() {
+        blocks.push(serde_json::json!({
+            "object": "block",
+            "type": "heading_3",
+            "heading_3": { "rich_text": text("Highlights") }
+        }));
+        blocks.extend(log.highlights.iter().map(|h| {
+            serde_json::json!({
+                "object": "block",
+                "type": "bulleted_list_item",
+                "bulleted_list_item": { "rich_text": text(h) }
+            })
+        }));
+    }
+    let changes: Vec<String> = log
+        .projects_worked
+        .iter()
+        .flat_map(|p| {
+            p.key_changes
+                .iter()
+                .map(move |c| format!("{}: {}", p.name, c))
+        })
+        .collect();
+    if !changes.is_empty() {
+        blocks.push(serde_json::json!({
+            "object": "block",
+            "type": "heading_3",
+            "heading_3": { "rich_text": text("Projects") }
+        }));
+        blocks.extend(changes.iter().map(|c| {
+            serde_json::json!({
+                "object": "block",
+                "type": "bulleted_list_item",
+                "bulleted_list_item": { "rich_text": text(c) }
+            })
+        }));
+    }
+    // A page is created with at most 100 children
+    blocks.truncate(100);
+    blocks
+}
+
+async fn request(
+    token: &str,
+    method: reqwest::Method,
+    url: &str,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let resp = http::send("Notion request", |client| {
+        client
+            .request(method.clone(), url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Notion-Version", NOTION_VERSION)
+            .json(body)
+    })
+    .await?;
+    let status = resp.status();
+    let json: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("Cannot parse Notion response: {}", e))?;
+    if !status.is_success() {
+        let msg = json
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Unknown Notion error");
+        return Err(format!("Notion error ({}): {}", status, msg));
+    }
+    Ok(json)
+}
+
+/// Create the devlog's page, archiving any earlier page for the same period
+async fn push(config: &NotionSettings, log: &DevLog) -> Result<(), String> {
+    let (Some(token), Some(database_id)) = (config.token.as_deref(), config.database_id.as_deref())
+    else {
+        return Err("Notion token and database id are required".to_string());
+    };
+
+    let existing = request(
+        token,
+        reqwest::Method::POST,
+        &format!("{}/databases/{}/query", NOTION_API, database_id),
+        &serde_json::json!({
+            "filter": { "property": "Key", "rich_text": { "equals": page_key(log) } }
+        }),
+    )
+    .await?;
+    let old_pages = existing
+        .get("results")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for page in old_pages {
+        if let Some(id) = page.get("id").and_then(|v| v.as_str()) {
+            request(
+                token,
+                reqwest::Method::PATCH,
+                &format!("{}/pages/{}", NOTION_API, id),
+                &serde_json::json!({ "archived": true }),
+            )
+            .await?;
+        }
+    }
+
+    request(
+        token,
+        reqwest::Method::POST,
+        &format!("{}/pages", NOTION_API),
+        &serde_json::json!({
+            "parent": { "database_id": database_id },
+            "properties": properties(log),
+            "children": body_blocks(log),
+        }),
+    )
+    .await?;
+    Ok(())
+}
+
+fn load_config() -> NotionSettings {
+    let mut config = settings::load().notion;
+    config.token = secrets::resolve(secrets::NOTION_TOKEN, config.token);
+    config
+}
+
+/// Push a freshly generated devlog when the Notion export is enabled. Failures are logged —
+/// like webhooks, the export never affects generation.
+pub async fn export_devlog(log: &DevLog) {
+    let config = load_config();
+    if !config.enabled {
+        return;
+    }
+    if let Err(e) = push(&config, log).await {
+        log::warn!("Notion export failed for {}: {e}", page_key(log));
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotionBackfill {
+    pub pushed: u32,
+    /// "<type>:<date>: <error>" per devlog that failed
+    pub failed: Vec<String>,
+}
+
+/// Push stored devlogs of one type (default "daily"), newest first, up to `limit`
+/// (default 365). Works whether or not the automatic export is enabled.
+#[tauri::command]
+pub async fn backfill_notion(
+    log_type: Option<String>,
+    limit: Option<usize>,
+) -> Result<NotionBackfill, SprtError> {
+    let config = load_config();
+    if config.token.is_none() || config.database_id.is_none() {
+        return Err(SprtError::InvalidInput(
+            "Set a Notion token and database id first".to_string(),
+        ));
+    }
+    let log_type = log_type.unwrap_or_else(|| "daily".to_string());
+    let logs = storage::list_devlogs(&log_type, limit.unwrap_or(365))?;
+    if logs.is_empty() {
+        return Err(SprtError::NoData(format!(
+            "No {} devlogs to export.",
+            log_type
+        )));
+    }
+
+    let mut result = NotionBackfill {
+        pushed: 0,
+        failed: vec![],
+    };
+    for log in &logs {
+        match push(&config, log).await {
+            Ok(()) => result.pushed += 1,
+            Err(e) => result.failed.push(format!("{}: {}", page_key(log), e)),
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(BACKFILL_DELAY_MS)).await;
+    }
+    Ok(result)
+}
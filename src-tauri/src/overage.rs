@@ -0,0 +1,121 @@
+use crate::claude::RateLimitInfo;
+use crate::error::SprtError;
+use crate::notifications;
+use crate::settings;
+use crate::storage::{self, OverageEpisode};
+
+use serde::Serialize;
+
+/// Without a rate-limit check for this long, an episode is closed at the last sighting
+/// instead of now (the app was probably closed or paused in between)
+const STALE_EPISODE_SECS: i64 = 30 * 60;
+
+/// Limits exhausted and overage allowed: requests are being billed as extra usage
+fn in_overage(info: &RateLimitInfo) -> bool {
+    let overage_allowed = matches!(
+        info.overage_status.as_deref(),
+        Some("allowed") | Some("allowed_warning")
+    );
+    let limits_hit = info.status == "rejected"
+        || info.utilizations().any(|u| u >= 1.0);
+    overage_allowed && limits_hit
+}
+
+fn parse_time(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    s.parse().ok()
+}
+
+fn record(info: &RateLimitInfo) -> Result<(), SprtError> {
+    let now = info.checked_at.as_str();
+    let open = storage::open_overage_episode()?;
+
+    match (in_overage(info), open) {
+        (true, None) => {
+            storage::start_overage_episode(now, info.fallback_percentage)?;
+            if settings::load().notify_on_overage {
+                notifications::notify_app(
+                    "Overage started",
+                    "You've reached your plan limit — further usage is billed as extra usage.",
+                );
+            }
+        }
+        (true, Some(ep)) => {
+            let max = match (ep.max_fallback_percentage, info.fallback_percentage) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+            storage::touch_overage_episode(ep.id, now, max)?;
+        }
+        (false, Some(ep)) => {
+            let stale = match (parse_time(&ep.last_seen_at), parse_time(now)) {
+                (Some(seen), Some(n)) => (n - seen).num_seconds() > STALE_EPISODE_SECS,
+                _ => false,
+            };
+            let ended_at = if stale { ep.last_seen_at.as_str() } else { now };
+            storage::end_overage_episode(ep.id, ended_at)?;
+        }
+        (false, None) => {}
+    }
+    Ok(())
+}
+
+/// Called with every fresh rate-limit response. Errors are logged; they never fail the fetch.
+pub fn observe(info: &RateLimitInfo) {
+    if let Err(e) = record(info) {
+        log::warn!("Overage tracking failed: {e}");
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverageEpisodeView {
+    #[serde(flatten)]
+    pub episode: OverageEpisode,
+    pub duration_secs: u64,
+    pub ongoing: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverageHistory {
+    pub episodes: Vec<OverageEpisodeView>,
+    pub total_episodes: usize,
+    pub total_secs: u64,
+    pub in_overage_now: bool,
+}
+
+pub fn overage_history(days: u32) -> Result<OverageHistory, SprtError> {
+    let since = (chrono::Utc::now() - chrono::Duration::days(days.max(1) as i64)).to_rfc3339();
+    let now = chrono::Utc::now();
+
+    let episodes: Vec<OverageEpisodeView> = storage::overage_episodes_since(&since)?
+        .into_iter()
+        .map(|ep| {
+            let ongoing = ep.ended_at.is_none();
+            let end = ep.ended_at.as_deref().and_then(parse_time).unwrap_or(now);
+            let duration_secs = parse_time(&ep.started_at)
+                .map(|start| (end - start).num_seconds().max(0) as u64)
+                .unwrap_or(0);
+            OverageEpisodeView {
+                episode: ep,
+                duration_secs,
+                ongoing,
+            }
+        })
+        .collect();
+
+    Ok(OverageHistory {
+        total_episodes: episodes.len(),
+        total_secs: episodes.iter().map(|e| e.duration_secs).sum(),
+        in_overage_now: episodes.iter().any(|e| e.ongoing),
+        episodes,
+    })
+}
+
+#[tauri::command]
+pub async fn get_overage_history(days: Option<u32>) -> Result<OverageHistory, SprtError> {
+    let days = days.unwrap_or(30);
+    tauri::async_runtime::spawn_blocking(move || overage_history(days))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,109 @@
+use crate::demo;
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Where the data layer reads Claude Code's files and keeps its own. Resolved once by the
+/// entry point (from the environment, or a fixtures directory in tests) and installed with
+/// `init`. It is process-wide rather than Tauri managed state: the data layer also runs
+/// without a Tauri app (the CLI, the MCP server, background threads), so everything reads
+/// it through `get`. Functions worth testing against other directories take `&DataPaths`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataPaths {
+    /// $SPRT_HOME, else the user's home (dirs::home_dir: %USERPROFILE% on Windows,
+    /// $HOME on macOS/Linux)
+    pub home: Option<PathBuf>,
+    /// Claude Code's config directory: $CLAUDE_CONFIG_DIR, else <home>/.claude
+    pub claude_dir: Option<PathBuf>,
+    /// SPRT's own storage: $SPRT_DATA_DIR, else <home>/.claude/sprt
+    pub sprt_dir: Option<PathBuf>,
+    /// OpenAI Codex CLI data: $CODEX_HOME, else <home>/.codex
+    pub codex_dir: Option<PathBuf>,
+    /// Gemini CLI data: <home>/.gemini
+    pub gemini_dir: Option<PathBuf>,
+    /// Pointed at a generated demo home (see demo)
+    pub demo: bool,
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+}
+
+impl DataPaths {
+    pub fn from_env() -> Self {
+        let real = DataPaths::real_from_env();
+        if demo::requested(&real) {
+            let root = demo::root();
+            // Entry points resolve paths before logging::init (the log file lives under
+            // them), so there is no logger yet: report straight to stderr
+            if let Err(e) = demo::prepare(&root) {
+                eprintln!("Cannot generate demo data: {e}");
+            }
+            return DataPaths {
+                claude_dir: Some(root.join(".claude")),
+                sprt_dir: Some(root.join(".claude").join("sprt")),
+                codex_dir: None,
+                gemini_dir: None,
+                home: Some(root),
+                demo: true,
+            };
+        }
+        real
+    }
+
+    /// The user's own directories, ignoring demo mode. Demo mode's on/off flag is kept here
+    /// (see demo::requested), never in the generated demo home.
+    pub fn real_from_env() -> Self {
+        let home = env_path("SPRT_HOME").or_else(dirs::home_dir);
+        DataPaths {
+            claude_dir: env_path("CLAUDE_CONFIG_DIR")
+                .or_else(|| home.as_ref().map(|h| h.join(".claude"))),
+            sprt_dir: env_path("SPRT_DATA_DIR")
+                .or_else(|| home.as_ref().map(|h| h.join(".claude").join("sprt"))),
+            codex_dir: env_path("CODEX_HOME").or_else(|| home.as_ref().map(|h| h.join(".codex"))),
+            gemini_dir: home.as_ref().map(|h| h.join(".gemini")),
+            home,
+            demo: false,
+        }
+    }
+}
+
+/// The value installed by `init`
+static PATHS: OnceLock<DataPaths> = OnceLock::new();
+
+/// Install the paths for this process; the first call wins. Each entry point calls this
+/// before touching any data.
+pub fn init(paths: DataPaths) -> &'static DataPaths {
+    PATHS.get_or_init(|| paths)
+}
+
+/// The installed paths, resolved from the environment if no entry point installed any
+pub fn get() -> &'static DataPaths {
+    PATHS.get_or_init(DataPaths::from_env)
+}
+
+/// Reads Claude data from tests/fixtures/home and keeps SPRT's own storage in a fresh
+/// temporary directory, shared by every test in the process
+#[cfg(test)]
+pub(crate) fn init_for_tests() -> &'static DataPaths {
+    let home = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/home");
+    let sprt_dir = std::env::temp_dir().join(format!("sprt-test-{}", std::process::id()));
+    init(DataPaths {
+        claude_dir: Some(home.join(".claude")),
+        sprt_dir: Some(sprt_dir),
+        codex_dir: None,
+        gemini_dir: None,
+        home: Some(home),
+        demo: false,
+    })
+}
+
+/// Directories the app is reading from and writing to
+#[tauri::command]
+pub fn get_data_paths() -> DataPaths {
+    get().clone()
+}
@@ -0,0 +1,81 @@
+use crate::error::SprtError;
+use crate::storage::{self, Plan, PlanCompletion, PlannedItem};
+use crate::time;
+
+/// Items beyond this many are dropped
+const MAX_ITEMS: usize = 20;
+
+/// Replace the plan for `date`; blank items are dropped and an empty list clears the plan
+pub fn save(date: &str, items: Vec<String>) -> Result<Option<Plan>, SprtError> {
+    if time::parse_date(date).is_none() {
+        return Err(SprtError::InvalidInput(format!("Invalid date: {}", date)));
+    }
+    let items: Vec<String> = items
+        .into_iter()
+        .map(|i| i.trim().to_string())
+        .filter(|i| !i.is_empty())
+        .take(MAX_ITEMS)
+        .collect();
+    if items.is_empty() {
+        storage::delete_plan(date)?;
+        return Ok(None);
+    }
+    let plan = Plan {
+        date: date.to_string(),
+        items,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    storage::save_plan(&plan)?;
+    Ok(Some(plan))
+}
+
+/// Devlog prompt section listing the plan; the AI answers with plan_status
+pub fn prompt_section(plan: &Plan) -> String {
+    let mut section = String::from("\n## Plan for the Day (set the evening before)\n");
+    for (i, item) in plan.items.iter().enumerate() {
+        section.push_str(&format!("{}. {}\n", i + 1, item));
+    }
+    section
+}
+
+/// Pair the plan with the AI's `plan_status` array; missing entries count as not done
+pub fn completion(plan: &Plan, status: Option<&serde_json::Value>) -> PlanCompletion {
+    let status = status.and_then(|s| s.as_array());
+    let items: Vec<PlannedItem> = plan
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let entry = status.and_then(|s| s.get(i));
+            PlannedItem {
+                text: text.clone(),
+                done: entry
+                    .and_then(|e| e.get("done"))
+                    .and_then(|d| d.as_bool())
+                    .unwrap_or(false),
+                note: entry
+                    .and_then(|e| e.get("note"))
+                    .and_then(|n| n.as_str())
+                    .map(|n| n.trim().to_string())
+                    .filter(|n| !n.is_empty()),
+            }
+        })
+        .collect();
+    PlanCompletion {
+        done: items.iter().filter(|i| i.done).count() as u32,
+        planned: items.len() as u32,
+        items,
+    }
+}
+
+/// Plan `date` (usually tomorrow). Its devlog compares the plan with what got done.
+/// Returns None when the list was empty and the plan was cleared.
+#[tauri::command]
+pub fn set_plan(date: String, items: Vec<String>) -> Result<Option<Plan>, SprtError> {
+    save(&date, items)
+}
+
+#[tauri::command]
+pub fn get_plan(date: String) -> Result<Option<Plan>, SprtError> {
+    storage::get_plan(&date)
+}
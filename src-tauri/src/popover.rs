@@ -0,0 +1,136 @@
+use crate::claude::{self, UsageClaim};
+use crate::countdown;
+use crate::storage;
+use crate::time::{self, DayClock};
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How far back the streak query looks
+const STREAK_LOOKBACK_DAYS: i64 = 365;
+/// The tray thread recomputes the streak at most this often
+const STREAK_REFRESH_SECS: u64 = 60;
+
+/// (computed at, streak). Kept by the tray thread so the popover never waits on settings.json
+/// or the database lock.
+static STREAK: Mutex<Option<(Instant, u32)>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PopoverSummary {
+    /// 0–100, from the last rate-limit check
+    pub five_hour_utilization: Option<f64>,
+    pub seven_day_utilization: Option<f64>,
+    /// "2h 05m" until the 5-hour window resets
+    pub reset_in: Option<String>,
+    pub reset_in_secs: Option<u64>,
+    pub today_messages: u64,
+    pub today_tokens: u64,
+    pub today_cost_usd: f64,
+    /// Last path segment of the most recent project
+    pub current_project: Option<String>,
+    pub last_activity_secs_ago: Option<u64>,
+    /// Consecutive days with a daily devlog, ending today (or yesterday if today isn't written yet)
+    pub sprint_streak: u32,
+    /// False until the first realtime scan has run; the stats fields are zero until then
+    pub stats_ready: bool,
+}
+
+fn utilization(claim: Option<&UsageClaim>) -> Option<f64> {
+    claim.map(|c| c.utilization * 100.0)
+}
+
+fn sprint_streak(clock: &DayClock) -> u32 {
+    let today = clock.today();
+    let since = time::format_date(today - chrono::Duration::days(STREAK_LOOKBACK_DAYS));
+    let dates: std::collections::HashSet<String> = match storage::daily_scores_since(&since) {
+        Ok(rows) => rows.into_iter().map(|(d, _)| d).collect(),
+        Err(_) => return 0,
+    };
+
+    let mut day = today;
+    if !dates.contains(&time::format_date(day)) {
+        day -= chrono::Duration::days(1);
+    }
+    let mut streak = 0;
+    while dates.contains(&time::format_date(day)) {
+        streak += 1;
+        day -= chrono::Duration::days(1);
+    }
+    streak
+}
+
+/// Recompute the cached streak when it's older than STREAK_REFRESH_SECS; called every
+/// tray tick. The day clock is read here too, so a rollover shows within a minute.
+pub fn tick() {
+    let fresh = STREAK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some_and(|(at, _)| at.elapsed() < Duration::from_secs(STREAK_REFRESH_SECS));
+    if fresh {
+        return;
+    }
+    let streak = sprint_streak(&DayClock::load());
+    *STREAK.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), streak));
+}
+
+/// Everything the tray popover shows, from in-memory caches only: no file reads, no
+/// database, no API.
+pub fn popover_summary() -> PopoverSummary {
+    let now = chrono::Utc::now();
+    let limits = claude::get_cached_rate_limits();
+    let stats = claude::get_cached_realtime_stats();
+
+    let five_hour = limits.as_ref().and_then(|i| i.five_hour.as_ref());
+    let reset_in_secs = five_hour
+        .and_then(|c| c.reset)
+        .map(|r| r.saturating_sub(now.timestamp().max(0) as u64));
+
+    let (today_messages, today_tokens, today_cost_usd, current_project, last_activity_secs_ago) =
+        match &stats {
+            Some(s) => {
+                let t = &s.today_tokens;
+                let project = s.last_project.as_deref().and_then(|p| {
+                    std::path::Path::new(p)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                });
+                let ago = s
+                    .last_activity
+                    .as_deref()
+                    .and_then(|la| la.parse::<chrono::DateTime<chrono::Utc>>().ok())
+                    .map(|la| (now - la).num_seconds().max(0) as u64);
+                (
+                    s.today_messages,
+                    t.input + t.output + t.cache_read + t.cache_creation,
+                    s.today_cost_usd,
+                    project,
+                    ago,
+                )
+            }
+            None => (0, 0, 0.0, None, None),
+        };
+
+    PopoverSummary {
+        five_hour_utilization: utilization(five_hour),
+        seven_day_utilization: utilization(limits.as_ref().and_then(|i| i.seven_day.as_ref())),
+        reset_in: reset_in_secs.map(countdown::format_remaining),
+        reset_in_secs,
+        today_messages,
+        today_tokens,
+        today_cost_usd,
+        current_project,
+        last_activity_secs_ago,
+        sprint_streak: STREAK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map_or(0, |(_, streak)| streak),
+        stats_ready: stats.is_some(),
+    }
+}
+
+#[tauri::command]
+pub fn get_popover_summary() -> PopoverSummary {
+    popover_summary()
+}
@@ -0,0 +1,88 @@
+use crate::settings;
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Power source is re-read at most this often; the tray loop asks every tick
+const CHECK_SECS: u64 = 30;
+
+#[derive(Debug, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// macOS Low Power Mode
+    pub low_power_mode: bool,
+}
+
+static STATE: Mutex<Option<(Instant, PowerState)>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+fn read() -> PowerState {
+    let pmset = |args: &[&str]| {
+        std::process::Command::new("pmset")
+            .args(args)
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+            .unwrap_or_default()
+    };
+    PowerState {
+        // "Now drawing from 'Battery Power'"
+        on_battery: pmset(&["-g", "batt"]).contains("'Battery Power'"),
+        low_power_mode: pmset(&["-g"]).lines().any(|l| {
+            let mut parts = l.split_whitespace();
+            parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+        }),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read() -> PowerState {
+    let supplies: Vec<std::path::PathBuf> = std::fs::read_dir("/sys/class/power_supply")
+        .map(|dir| dir.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    let attr = |path: &std::path::Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let has_battery = supplies.iter().any(|p| attr(p, "type") == "Battery");
+    let on_ac = supplies
+        .iter()
+        .any(|p| attr(p, "type") == "Mains" && attr(p, "online") == "1");
+    PowerState {
+        on_battery: has_battery && !on_ac,
+        low_power_mode: false,
+    }
+}
+
+/// Elsewhere: assume AC power
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn read() -> PowerState {
+    PowerState::default()
+}
+
+pub fn state() -> PowerState {
+    let previous = *STATE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, state)) = previous {
+        if at.elapsed() < Duration::from_secs(CHECK_SECS) {
+            return state;
+        }
+    }
+    // Read without the lock: pmset can take a while, and other threads keep the cached value
+    let state = read();
+    if previous.is_some_and(|(_, old)| old != state) {
+        log::info!("Power state changed: {:?}", state);
+    }
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), state));
+    state
+}
+
+/// On battery or in Low Power Mode, with `lowPower.enabled`: background work slows down
+pub fn is_throttled() -> bool {
+    if !settings::load().low_power.enabled {
+        return false;
+    }
+    let s = state();
+    s.on_battery || s.low_power_mode
+}
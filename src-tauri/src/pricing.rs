@@ -0,0 +1,122 @@
+/// API list prices in USD per million tokens
+#[derive(Debug, Clone, Copy)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+const fn price(input: f64, output: f64, cache_write: f64, cache_read: f64) -> ModelPrice {
+    ModelPrice {
+        input,
+        output,
+        cache_write,
+        cache_read,
+    }
+}
+
+const FAMILIES: &[&str] = &["opus", "sonnet", "haiku"];
+
+/// Price tiers per family, oldest first: (family, from version, price). A model pays the
+/// newest tier its version reaches, so a newer release than listed gets the current price.
+const PRICES: &[(&str, (u32, u32), ModelPrice)] = &[
+    ("opus", (0, 0), price(15.0, 75.0, 18.75, 1.50)),
+    ("opus", (4, 5), price(5.0, 25.0, 6.25, 0.50)),
+    ("sonnet", (0, 0), price(3.0, 15.0, 3.75, 0.30)),
+    ("haiku", (0, 0), price(0.25, 1.25, 0.30, 0.03)),
+    ("haiku", (3, 5), price(0.80, 4.0, 1.0, 0.08)),
+    ("haiku", (4, 5), price(1.0, 5.0, 1.25, 0.10)),
+];
+
+/// Family and version in a model id, with either naming: "claude-opus-4-5-20251101" is
+/// ("opus", (4, 5)) and "claude-3-5-sonnet-20241022" is ("sonnet", (3, 5)). Dates, provider
+/// prefixes and suffixes like "[1m]" are skipped; None for the version when there is none.
+fn parse_model(model: &str) -> Option<(&str, Option<(u32, u32)>)> {
+    let model = model.split('[').next().unwrap_or(model);
+    let parts: Vec<&str> = model.split(['-', '@']).collect();
+    let i = parts.iter().position(|p| FAMILIES.contains(p))?;
+    // Version numbers are short; an 8-digit date isn't one
+    let number = |p: &&str| p.len() <= 2 && p.chars().all(|c| c.is_ascii_digit());
+    let mut version: Vec<u32> = parts[i + 1..]
+        .iter()
+        .copied()
+        .take_while(number)
+        .filter_map(|p| p.parse().ok())
+        .collect();
+    if version.is_empty() {
+        version = parts[..i]
+            .iter()
+            .rev()
+            .copied()
+            .take_while(number)
+            .filter_map(|p| p.parse().ok())
+            .collect();
+        version.reverse();
+    }
+    let version = version
+        .first()
+        .map(|&major| (major, version.get(1).copied().unwrap_or(0)));
+    Some((parts[i], version))
+}
+
+pub fn price_for(model: &str) -> Option<ModelPrice> {
+    let (family, version) = parse_model(model)?;
+    PRICES
+        .iter()
+        .rev()
+        .find(|(f, from, _)| *f == family && version.is_none_or(|v| v >= *from))
+        .map(|(_, _, p)| *p)
+}
+
+/// What caching changed the bill by, versus sending the same prompt tokens uncached:
+/// reads are billed below the input rate, writes above it. Positive means money saved.
+pub fn cache_savings_usd(model: &str, cache_read: u64, cache_creation: u64) -> f64 {
+    let Some(p) = price_for(model) else {
+        return 0.0;
+    };
+    (cache_read as f64 * (p.input - p.cache_read) - cache_creation as f64 * (p.cache_write - p.input))
+        / 1_000_000.0
+}
+
+/// Estimated cost of one response. Unknown models (e.g. "<synthetic>") cost nothing.
+pub fn cost_usd(model: &str, input: u64, output: u64, cache_read: u64, cache_creation: u64) -> f64 {
+    let Some(p) = price_for(model) else {
+        return 0.0;
+    };
+    (input as f64 * p.input
+        + output as f64 * p.output
+        + cache_read as f64 * p.cache_read
+        + cache_creation as f64 * p.cache_write)
+        / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_price(model: &str) -> Option<f64> {
+        price_for(model).map(|p| p.input)
+    }
+
+    #[test]
+    fn matches_families_and_versions() {
+        assert_eq!(input_price("claude-opus-4-5-20251101"), Some(5.0));
+        assert_eq!(input_price("claude-opus-4-1-20250805"), Some(15.0));
+        assert_eq!(input_price("claude-3-opus-20240229"), Some(15.0));
+        assert_eq!(input_price("claude-3-5-haiku-20241022"), Some(0.80));
+        assert_eq!(input_price("claude-3-haiku-20240307"), Some(0.25));
+        assert_eq!(input_price("claude-haiku-4-5-20251001"), Some(1.0));
+        assert_eq!(input_price("claude-3-7-sonnet-20250219"), Some(3.0));
+        assert_eq!(input_price("<synthetic>"), None);
+    }
+
+    #[test]
+    fn newer_releases_get_the_current_price() {
+        assert_eq!(input_price("claude-opus-4-6"), Some(5.0));
+        assert_eq!(input_price("claude-opus-4-6[1m]"), Some(5.0));
+        assert_eq!(input_price("claude-opus-5-20270101"), Some(5.0));
+        assert_eq!(input_price("us.anthropic.claude-opus-4-5-20251101-v1:0"), Some(5.0));
+        assert_eq!(input_price("claude-opus-4-5@20251101"), Some(5.0));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::git;
+use crate::settings::{self, PrivacySettings};
+
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Pseudonym {
+    pub name: String,
+    pub alias: String,
+}
+
+/// Applies settings.privacy to text bound for the API, and maps aliases in the answer back
+pub struct Scrubber {
+    /// Every known project name, longest first so "app-server" wins over "app"; case-insensitive,
+    /// so "SPRT" in text is caught for a repo named "sprt"
+    names: Option<Regex>,
+    aliases: HashMap<String, String>,
+    /// Lowercased name → alias, for matches in any case
+    aliases_lower: HashMap<String, String>,
+    redactions: Vec<Regex>,
+    /// Patterns in settings that don't compile, reported by preview
+    pub invalid_patterns: Vec<String>,
+}
+
+pub struct Scrubbed {
+    pub text: String,
+    pub redactions: u32,
+    /// Names that were replaced, in order of first alias
+    pub pseudonyms: Vec<Pseudonym>,
+}
+
+/// Directory names of Claude projects and discovered repos
+fn project_names() -> BTreeSet<String> {
+    let paths = git::discover_project_paths()
+        .into_iter()
+        .map(|(_, path)| path)
+        .chain(git::discover_repo_paths());
+    paths
+        .filter_map(|p| {
+            Path::new(&p)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        // One- and two-letter names would mangle ordinary words
+        .filter(|n| n.chars().count() > 2)
+        .collect()
+}
+
+/// `name` as a whole word; `\b` only where the name starts or ends with a word character
+fn word_pattern(name: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    format!(
+        "{}{}{}",
+        if is_word(name.chars().next()) {
+            r"\b"
+        } else {
+            ""
+        },
+        regex::escape(name),
+        if is_word(name.chars().last()) {
+            r"\b"
+        } else {
+            ""
+        }
+    )
+}
+
+impl Scrubber {
+    pub fn load() -> Self {
+        Self::new(&settings::load().privacy)
+    }
+
+    pub fn new(config: &PrivacySettings) -> Self {
+        let mut invalid_patterns = vec![];
+        let redactions = config
+            .redact_patterns
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .filter_map(|p| match Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    log::warn!("Ignoring redaction pattern {p:?}: {e}");
+                    invalid_patterns.push(p.clone());
+                    None
+                }
+            })
+            .collect();
+
+        let (mut names, mut aliases) = (None, HashMap::new());
+        let mut aliases_lower: HashMap<String, String> = HashMap::new();
+        if config.pseudonymize_projects {
+            // Sorted names get stable aliases while the set of projects doesn't change
+            let known = project_names();
+            for (i, name) in known.iter().enumerate() {
+                let alias = format!("project-{}", i + 1);
+                aliases_lower
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| alias.clone());
+                aliases.insert(name.clone(), alias);
+            }
+            let mut by_length: Vec<&String> = known.iter().collect();
+            by_length.sort_by_key(|n| std::cmp::Reverse(n.len()));
+            let alternation: Vec<String> = by_length.iter().map(|n| word_pattern(n)).collect();
+            if !alternation.is_empty() {
+                names = Regex::new(&format!("(?i){}", alternation.join("|"))).ok();
+            }
+        }
+
+        Scrubber {
+            names,
+            aliases,
+            aliases_lower,
+            redactions,
+            invalid_patterns,
+        }
+    }
+
+    /// Redact, then pseudonymize. One pass per step, so an alias is never rewritten again.
+    pub fn scrub(&self, text: &str) -> Scrubbed {
+        let mut text = text.to_string();
+        let mut redactions = 0;
+        for re in &self.redactions {
+            let count = re.find_iter(&text).count();
+            if count > 0 {
+                redactions += count as u32;
+                text = re.replace_all(&text, REDACTED).into_owned();
+            }
+        }
+
+        let mut pseudonyms: Vec<Pseudonym> = vec![];
+        if let Some(names) = &self.names {
+            text = names
+                .replace_all(&text, |c: &Captures| {
+                    let name = &c[0];
+                    let alias = self
+                        .aliases
+                        .get(name)
+                        .or_else(|| self.aliases_lower.get(&name.to_lowercase()))
+                        .cloned()
+                        .unwrap_or_default();
+                    if !pseudonyms.iter().any(|p| p.name == name) {
+                        pseudonyms.push(Pseudonym {
+                            name: name.to_string(),
+                            alias: alias.clone(),
+                        });
+                    }
+                    alias
+                })
+                .into_owned();
+        }
+
+        Scrubbed {
+            text,
+            redactions,
+            pseudonyms,
+        }
+    }
+
+    /// Put real project names back into the API's answer. Redactions stay redacted.
+    pub fn restore(&self, text: &str) -> String {
+        if self.aliases.is_empty() {
+            return text.to_string();
+        }
+        let by_alias: HashMap<&str, &str> = self
+            .aliases
+            .iter()
+            .map(|(name, alias)| (alias.as_str(), name.as_str()))
+            .collect();
+        let Ok(re) = Regex::new(r"\bproject-\d+\b") else {
+            return text.to_string();
+        };
+        re.replace_all(text, |c: &Captures| {
+            by_alias
+                .get(&c[0])
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| c[0].to_string())
+        })
+        .into_owned()
+    }
+}
+
+/// A commit message as the prompt should carry it: the subject line alone when
+/// settings.privacy.stripCommitBodies is on
+pub fn commit_message(message: &str, strip_body: bool) -> &str {
+    if strip_body {
+        message.lines().next().unwrap_or("").trim_end()
+    } else {
+        message
+    }
+}
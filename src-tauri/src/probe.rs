@@ -0,0 +1,143 @@
+use crate::claude::RateLimitInfo;
+use crate::monitor;
+use crate::settings;
+use crate::time::DayClock;
+
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Real rate-limit requests allowed per day unless settings say otherwise
+const DEFAULT_DAILY_CAP: u32 = 240;
+
+/// (utilization below, seconds between probes): far from any limit, probe rarely
+const BACKOFF: &[(f64, u64)] = &[(0.5, 10 * 60), (0.75, 5 * 60), (0.9, 2 * 60)];
+/// Shortest configurable polling interval
+const MIN_POLL_SECS: u64 = 30;
+/// How often the poller checks whether a fetch is due
+const POLL_TICK_SECS: u64 = 15;
+
+#[derive(Default)]
+struct Counter {
+    /// Day key the count belongs to
+    date: String,
+    probes: u32,
+    /// Probes skipped because the cap was reached
+    skipped: u32,
+    last_probe_at: Option<String>,
+}
+
+static COUNTER: LazyLock<Mutex<Counter>> = LazyLock::new(|| Mutex::new(Counter::default()));
+
+fn daily_cap() -> u32 {
+    settings::load()
+        .rate_limit_probe_cap
+        .unwrap_or(DEFAULT_DAILY_CAP)
+}
+
+/// Highest utilization across the rate-limit windows
+fn max_utilization(info: &RateLimitInfo) -> f64 {
+    info.utilizations().fold(0.0, f64::max)
+}
+
+/// How long `info` stays fresh: longer the further usage is from a limit, never shorter
+/// than the monitor's interval (which grows during quiet hours)
+pub fn interval_secs(info: Option<&RateLimitInfo>) -> u64 {
+    let floor = monitor::rate_limit_cache_secs();
+    if let Some(secs) = settings::load().rate_limit_polling.interval_secs {
+        return secs.max(MIN_POLL_SECS).max(floor);
+    }
+    let Some(info) = info else {
+        return floor;
+    };
+    if info.status == "rejected" {
+        return floor;
+    }
+    let utilization = max_utilization(info);
+    BACKOFF
+        .iter()
+        .find(|(below, _)| utilization < *below)
+        .map(|(_, secs)| *secs)
+        .unwrap_or(floor)
+        .max(floor)
+}
+
+fn roll_over(counter: &mut Counter) {
+    let today = DayClock::load().today_key();
+    if counter.date != today {
+        *counter = Counter {
+            date: today,
+            ..Counter::default()
+        };
+    }
+}
+
+/// Count a probe about to be sent. False when today's cap is used up, unless `force`
+/// (an explicit user refresh), which is always allowed and still counted.
+pub fn try_acquire(force: bool) -> bool {
+    let mut counter = COUNTER.lock().unwrap_or_else(|e| e.into_inner());
+    roll_over(&mut counter);
+    if !force && counter.probes >= daily_cap() {
+        counter.skipped += 1;
+        return false;
+    }
+    counter.probes += 1;
+    counter.last_probe_at = Some(chrono::Utc::now().to_rfc3339());
+    true
+}
+
+/// Background rate-limit fetches at `interval_secs`, so the tray title doesn't wait for a
+/// window to call get_rate_limits. Off while paused or when `rateLimitPolling.enabled` is false.
+/// Wake from sleep is handled by wake.rs.
+pub fn start() {
+    std::thread::spawn(|| {
+        let mut next = Instant::now();
+        loop {
+            let cfg = settings::load().rate_limit_polling;
+            if cfg.enabled && !monitor::is_paused() && Instant::now() >= next {
+                let result =
+                    tauri::async_runtime::block_on(crate::claude::get_rate_limits(Some(false)));
+                if let Err(e) = &result {
+                    log::debug!("Background rate-limit fetch failed: {e}");
+                }
+                // A failed fetch waits the same interval, so it can't burn the daily cap
+                next = Instant::now() + Duration::from_secs(interval_secs(result.ok().as_ref()));
+            }
+            std::thread::sleep(Duration::from_secs(POLL_TICK_SECS));
+        }
+    });
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeStats {
+    pub date: String,
+    pub probes_today: u32,
+    pub daily_cap: u32,
+    pub skipped_today: u32,
+    pub capped: bool,
+    pub last_probe_at: Option<String>,
+    /// Current spacing between probes given the last known utilization
+    pub interval_secs: u64,
+}
+
+pub fn stats() -> ProbeStats {
+    let mut counter = COUNTER.lock().unwrap_or_else(|e| e.into_inner());
+    roll_over(&mut counter);
+    let cap = daily_cap();
+    ProbeStats {
+        date: counter.date.clone(),
+        probes_today: counter.probes,
+        daily_cap: cap,
+        skipped_today: counter.skipped,
+        capped: counter.probes >= cap,
+        last_probe_at: counter.last_probe_at.clone(),
+        interval_secs: interval_secs(crate::claude::get_cached_rate_limits().as_ref()),
+    }
+}
+
+/// Rate-limit API requests made today against the daily cap
+#[tauri::command]
+pub fn get_probe_stats() -> ProbeStats {
+    stats()
+}
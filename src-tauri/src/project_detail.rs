@@ -0,0 +1,178 @@
+use crate::error::SprtError;
+use crate::git::{self, FileChurn};
+use crate::session_index::{self, SessionListItem};
+use crate::storage;
+use crate::time::DayClock;
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+const TOP_FILES: usize = 20;
+const RECENT_SESSIONS: usize = 20;
+/// Daily devlogs searched for mentions
+const DEVLOG_LOOKBACK: usize = 365;
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDay {
+    pub sessions: u64,
+    pub messages: u64,
+    pub tokens: u64,
+    pub commits: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevlogMention {
+    pub date: String,
+    pub sprint_score: u32,
+    pub commits: u32,
+    pub messages: u64,
+    pub key_changes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDetail {
+    pub project: String,
+    pub project_path: String,
+    pub name: String,
+    pub total_sessions: u64,
+    pub total_messages: u64,
+    pub total_tokens: u64,
+    pub total_commits: u32,
+    /// Date key → activity; sessions count on the day they started
+    pub days: BTreeMap<String, ProjectDay>,
+    pub tokens_by_model: HashMap<String, u64>,
+    pub top_files: Vec<FileChurn>,
+    /// Newest first
+    pub devlog_mentions: Vec<DevlogMention>,
+    pub recent_sessions: Vec<SessionListItem>,
+    /// Earliest/latest of session messages and commits, RFC 3339
+    pub first_activity: Option<String>,
+    pub last_activity: Option<String>,
+}
+
+fn parse_time(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc))
+}
+
+pub fn project_detail(project: &str) -> Result<ProjectDetail, SprtError> {
+    session_index::refresh()?;
+    let sessions = storage::project_sessions(project)?;
+
+    let project_path = sessions
+        .last()
+        .map(|s| s.project_path.clone())
+        .unwrap_or_else(|| project.to_string());
+    let history = if git::is_repo(&project_path) {
+        git::repo_history(&project_path, TOP_FILES)
+            .inspect_err(|e| log::warn!("No git history for {}: {e}", project_path))
+            .ok()
+    } else {
+        None
+    };
+    if sessions.is_empty() && history.is_none() {
+        return Err(SprtError::NoData(format!(
+            "No activity found for {}",
+            project
+        )));
+    }
+
+    let clock = DayClock::load();
+    let mut days: BTreeMap<String, ProjectDay> = BTreeMap::new();
+    let mut tokens_by_model: HashMap<String, u64> = HashMap::new();
+    for s in &sessions {
+        let Some(started) = s
+            .first_message_at
+            .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        else {
+            continue;
+        };
+        let day = days.entry(clock.date_key(&started)).or_default();
+        day.sessions += 1;
+        day.messages += s.message_count;
+        day.tokens += s.total_tokens;
+        for (model, tokens) in &s.model_tokens {
+            *tokens_by_model.entry(model.clone()).or_insert(0) += tokens;
+        }
+    }
+    if let Some(h) = &history {
+        for (date, commits) in &h.commits_by_day {
+            days.entry(date.clone()).or_default().commits += commits;
+        }
+    }
+
+    let name = Path::new(&project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.clone());
+    let devlog_mentions = storage::list_devlogs("daily", DEVLOG_LOOKBACK)?
+        .into_iter()
+        .filter_map(|log| {
+            let pw = log
+                .projects_worked
+                .iter()
+                .find(|p| p.path == project_path || (p.path.is_empty() && p.name == name))?;
+            Some(DevlogMention {
+                date: log.date.clone(),
+                sprint_score: log.sprint_score,
+                commits: pw.commits,
+                messages: pw.messages,
+                key_changes: pw.key_changes.clone(),
+            })
+        })
+        .collect();
+
+    let session_bounds = sessions
+        .iter()
+        .filter_map(|s| s.first_message_at.zip(s.last_message_at))
+        .filter_map(|(f, l)| {
+            chrono::DateTime::from_timestamp(f, 0).zip(chrono::DateTime::from_timestamp(l, 0))
+        });
+    let commit_bounds = history.iter().filter_map(|h| {
+        h.first_commit
+            .as_deref()
+            .and_then(parse_time)
+            .zip(h.last_commit.as_deref().and_then(parse_time))
+    });
+    let bounds: Vec<_> = session_bounds.chain(commit_bounds).collect();
+    let first_activity = bounds.iter().map(|(f, _)| *f).min().map(|t| t.to_rfc3339());
+    let last_activity = bounds.iter().map(|(_, l)| *l).max().map(|t| t.to_rfc3339());
+
+    Ok(ProjectDetail {
+        project: sessions
+            .first()
+            .map(|s| s.project.clone())
+            .unwrap_or_else(|| project.to_string()),
+        name,
+        total_sessions: sessions.len() as u64,
+        total_messages: sessions.iter().map(|s| s.message_count).sum(),
+        total_tokens: sessions.iter().map(|s| s.total_tokens).sum(),
+        total_commits: history.as_ref().map(|h| h.total_commits).unwrap_or(0),
+        days,
+        tokens_by_model,
+        top_files: history.map(|h| h.top_files).unwrap_or_default(),
+        devlog_mentions,
+        recent_sessions: sessions
+            .into_iter()
+            .rev()
+            .take(RECENT_SESSIONS)
+            .map(SessionListItem::from)
+            .collect(),
+        project_path,
+        first_activity,
+        last_activity,
+    })
+}
+
+/// Full history of one project. `project` is a projects dir name or a decoded path.
+#[tauri::command]
+pub async fn get_project_detail(project: String) -> Result<ProjectDetail, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || project_detail(&project))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
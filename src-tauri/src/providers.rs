@@ -0,0 +1,219 @@
+use crate::paths;
+use crate::settings;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+pub const CODEX: &str = "codex";
+pub const GEMINI: &str = "gemini";
+
+/// One model response from another AI coding CLI, in common terms
+pub struct ProviderEntry<'a> {
+    /// "codex" or "gemini"
+    pub provider: &'static str,
+    pub session_id: &'a str,
+    /// Working directory of the session, when the log records one
+    pub cwd: Option<&'a str>,
+    pub timestamp: DateTime<Utc>,
+    pub model: &'a str,
+    /// Uncached input
+    pub input: u64,
+    /// Output, reasoning included
+    pub output: u64,
+    pub cache_read: u64,
+}
+
+impl ProviderEntry<'_> {
+    pub fn total_tokens(&self) -> u64 {
+        self.input + self.output + self.cache_read
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStats {
+    pub today_messages: u64,
+    pub today_tokens: u64,
+    pub week_messages: u64,
+    pub week_tokens: u64,
+    pub week_sessions: u64,
+    pub week_model_tokens: HashMap<String, u64>,
+    /// RFC 3339
+    pub last_activity: Option<String>,
+    /// Working directory of the latest response, when the CLI records it
+    pub last_project: Option<String>,
+}
+
+/// Every file under `dir` with extension `ext`, at any depth
+fn files_with_ext(dir: &Path, ext: &str, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            files_with_ext(&path, ext, out);
+        } else if path.extension().is_some_and(|e| e == ext) {
+            out.push(path);
+        }
+    }
+}
+
+fn modified_since(path: &Path, since: DateTime<Utc>) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|t| DateTime::<Utc>::from(t) >= since)
+}
+
+fn parse_time(v: Option<&serde_json::Value>) -> Option<DateTime<Utc>> {
+    v.and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+}
+
+/// Codex CLI rollouts: ~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl. Each turn ends with a
+/// token_count event whose last_token_usage is that turn's usage; the model and cwd come
+/// from the preceding turn_context (or session_meta).
+fn scan_codex(dir: &Path, since: DateTime<Utc>, f: &mut impl FnMut(&ProviderEntry)) {
+    let mut files = vec![];
+    files_with_ext(&dir.join("sessions"), "jsonl", &mut files);
+    for path in files {
+        if !modified_since(&path, since) {
+            continue;
+        }
+        let Ok(file) = fs::File::open(&path) else {
+            continue;
+        };
+        let mut session_id = path
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut cwd: Option<String> = None;
+        let mut model = "unknown".to_string();
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let payload = entry.get("payload");
+            let field = |key: &str| {
+                payload
+                    .and_then(|p| p.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+            };
+            match entry.get("type").and_then(|v| v.as_str()) {
+                Some("session_meta") => {
+                    if let Some(id) = field("id") {
+                        session_id = id;
+                    }
+                    cwd = field("cwd").or(cwd);
+                }
+                Some("turn_context") => {
+                    cwd = field("cwd").or(cwd);
+                    if let Some(m) = field("model") {
+                        model = m;
+                    }
+                }
+                Some("event_msg") if field("type").as_deref() == Some("token_count") => {
+                    let Some(usage) = payload
+                        .and_then(|p| p.get("info"))
+                        .and_then(|i| i.get("last_token_usage"))
+                    else {
+                        continue;
+                    };
+                    let Some(timestamp) = parse_time(entry.get("timestamp")) else {
+                        continue;
+                    };
+                    if timestamp < since {
+                        continue;
+                    }
+                    let tokens = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                    // input_tokens includes the cached part
+                    let cached = tokens("cached_input_tokens");
+                    f(&ProviderEntry {
+                        provider: CODEX,
+                        session_id: &session_id,
+                        cwd: cwd.as_deref(),
+                        timestamp,
+                        model: &model,
+                        input: tokens("input_tokens").saturating_sub(cached),
+                        output: tokens("output_tokens") + tokens("reasoning_output_tokens"),
+                        cache_read: cached,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Gemini CLI chats: ~/.gemini/tmp/<project hash>/chats/session-*.json, one JSON document
+/// per session whose "gemini" messages carry token counts
+fn scan_gemini(dir: &Path, since: DateTime<Utc>, f: &mut impl FnMut(&ProviderEntry)) {
+    let mut files = vec![];
+    files_with_ext(&dir.join("tmp"), "json", &mut files);
+    for path in files {
+        let in_chats = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|n| n == "chats");
+        if !in_chats || !modified_since(&path, since) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(chat) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        let session_id = chat
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let messages = chat.get("messages").and_then(|v| v.as_array());
+        for m in messages.into_iter().flatten() {
+            if m.get("type").and_then(|v| v.as_str()) != Some("gemini") {
+                continue;
+            }
+            let (Some(tokens), Some(timestamp)) = (m.get("tokens"), parse_time(m.get("timestamp")))
+            else {
+                continue;
+            };
+            if timestamp < since {
+                continue;
+            }
+            let count = |key: &str| tokens.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+            let cached = count("cached");
+            f(&ProviderEntry {
+                provider: GEMINI,
+                session_id,
+                cwd: None,
+                timestamp,
+                model: m.get("model").and_then(|v| v.as_str()).unwrap_or("unknown"),
+                input: count("input").saturating_sub(cached),
+                output: count("output") + count("thoughts"),
+                cache_read: cached,
+            });
+        }
+    }
+}
+
+/// Call `f` for every response at or after `since` from the enabled providers other than
+/// Claude Code. Missing directories are skipped: most people have one CLI, not all.
+pub fn scan(since: DateTime<Utc>, mut f: impl FnMut(&ProviderEntry)) {
+    let cfg = settings::load().providers;
+    let paths = paths::get();
+    if cfg.codex {
+        if let Some(dir) = &paths.codex_dir {
+            scan_codex(dir, since, &mut f);
+        }
+    }
+    if cfg.gemini {
+        if let Some(dir) = &paths.gemini_dir {
+            scan_gemini(dir, since, &mut f);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::deep_link::{self, Navigation};
+use crate::error::SprtError;
+use crate::monitor::TrayMenuItems;
+use crate::session_index;
+use crate::settings;
+use crate::storage::{self, SessionQuery};
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::menu::MenuItemBuilder;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_opener::OpenerExt;
+
+/// Tray menu ids for recent projects are this prefix plus the project path
+pub const MENU_PREFIX: &str = "open_project:";
+const RECENT_PROJECTS: usize = 5;
+/// Sessions scanned for distinct projects, newest first
+const RECENT_SESSIONS: u64 = 200;
+const MENU_REFRESH_SECS: u64 = 60;
+
+/// Projects currently in the submenu, so it's only rebuilt when the list changes
+static LISTED: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+fn project_dir(project_path: &str) -> Result<PathBuf, SprtError> {
+    let dir = PathBuf::from(project_path);
+    if !dir.is_dir() {
+        return Err(SprtError::InvalidInput(format!(
+            "Not a directory: {}",
+            project_path
+        )));
+    }
+    Ok(dir)
+}
+
+fn open_failed(what: &str, e: impl std::fmt::Display) -> SprtError {
+    SprtError::Internal(format!("Cannot open {}: {}", what, e))
+}
+
+fn default_terminal() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Terminal"
+    } else if cfg!(target_os = "windows") {
+        "cmd"
+    } else {
+        "x-terminal-emulator"
+    }
+}
+
+pub fn open_editor<R: Runtime>(app: &AppHandle<R>, project_path: &str) -> Result<(), SprtError> {
+    let dir = project_dir(project_path)?;
+    let editor = settings::load().editor.filter(|e| !e.is_empty());
+    app.opener()
+        .open_path(dir.to_string_lossy(), editor.as_deref())
+        .map_err(|e| open_failed("editor", e))
+}
+
+pub fn open_terminal<R: Runtime>(app: &AppHandle<R>, project_path: &str) -> Result<(), SprtError> {
+    let dir = project_dir(project_path)?;
+    let terminal = settings::load()
+        .terminal
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| default_terminal().to_string());
+    // `open -a` starts macOS terminals in the given folder; elsewhere terminals take the
+    // path as a program to run, so launch them in the folder instead
+    if cfg!(target_os = "macos") {
+        return app
+            .opener()
+            .open_path(dir.to_string_lossy(), Some(terminal))
+            .map_err(|e| open_failed("terminal", e));
+    }
+    std::process::Command::new(&terminal)
+        .current_dir(&dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| open_failed("terminal", e))
+}
+
+/// Open the project folder in the configured editor (settings.editor), or the system
+/// default for folders
+#[tauri::command]
+pub fn open_in_editor(app: AppHandle, project_path: String) -> Result<(), SprtError> {
+    open_editor(&app, &project_path)
+}
+
+/// Open a terminal in the project folder (settings.terminal, or the platform default)
+#[tauri::command]
+pub fn open_in_terminal(app: AppHandle, project_path: String) -> Result<(), SprtError> {
+    open_terminal(&app, &project_path)
+}
+
+/// Show the project folder in Finder / Explorer / the file manager
+#[tauri::command]
+pub fn reveal_in_finder(app: AppHandle, project_path: String) -> Result<(), SprtError> {
+    let dir = project_dir(&project_path)?;
+    app.opener()
+        .reveal_item_in_dir(dir)
+        .map_err(|e| open_failed("file manager", e))
+}
+
+/// Project paths with the most recent sessions, newest first, skipping deleted folders
+fn recent_projects() -> Result<Vec<String>, SprtError> {
+    session_index::refresh_in_background()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: None,
+        to: None,
+        device: None,
+        sort: "recent".to_string(),
+        offset: 0,
+        limit: RECENT_SESSIONS,
+    })?;
+    let mut seen = HashSet::new();
+    Ok(sessions
+        .into_iter()
+        .map(|s| s.project_path)
+        .filter(|p| Path::new(p).is_dir() && seen.insert(p.clone()))
+        .take(RECENT_PROJECTS)
+        .collect())
+}
+
+fn refresh_menu(app: &AppHandle) -> Result<(), SprtError> {
+    let Some(items) = app.try_state::<TrayMenuItems>() else {
+        return Ok(());
+    };
+    let projects = recent_projects()?;
+    let mut listed = LISTED.lock().unwrap_or_else(|e| e.into_inner());
+    if listed.as_ref() == Some(&projects) {
+        return Ok(());
+    }
+    let menu_err = |e: tauri::Error| SprtError::Internal(format!("Tray menu error: {}", e));
+    while items.recent.remove_at(0).map_err(menu_err)?.is_some() {}
+    if projects.is_empty() {
+        let none = MenuItemBuilder::new("No projects yet")
+            .enabled(false)
+            .build(app)
+            .map_err(menu_err)?;
+        items.recent.append(&none).map_err(menu_err)?;
+    }
+    for path in &projects {
+        let name = Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let item = MenuItemBuilder::with_id(format!("{}{}", MENU_PREFIX, path), name)
+            .build(app)
+            .map_err(menu_err)?;
+        items.recent.append(&item).map_err(menu_err)?;
+    }
+    *listed = Some(projects);
+    Ok(())
+}
+
+/// Tray menu click on a recent project, handled per settings.recent_project_action
+pub fn on_menu_event(app: &AppHandle, id: &str) {
+    let Some(path) = id.strip_prefix(MENU_PREFIX) else {
+        return;
+    };
+    let action = settings::load().recent_project_action;
+    let result = match action.as_deref() {
+        Some("folder") => app
+            .opener()
+            .open_path(path, None::<&str>)
+            .map_err(|e| open_failed("folder", e)),
+        Some("editor") => open_editor(app, path),
+        _ => {
+            deep_link::navigate(
+                app,
+                Navigation {
+                    view: "project".to_string(),
+                    target: Some(path.to_string()),
+                },
+            );
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        log::warn!("{e}");
+    }
+}
+
+/// Fill the tray's Recent Projects submenu now, then keep it current
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        if let Err(e) = refresh_menu(&app) {
+            log::warn!("Recent projects menu refresh failed: {e}");
+        }
+        std::thread::sleep(std::time::Duration::from_secs(MENU_REFRESH_SECS));
+    });
+}
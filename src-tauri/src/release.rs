@@ -0,0 +1,277 @@
+use crate::analysis;
+use crate::devlog;
+use crate::error::SprtError;
+use crate::git::{self, GitCommit};
+use crate::privacy;
+use crate::settings;
+use crate::storage::{self, DevLog, DevLogStats, ProjectWork};
+use crate::time::{self, DayClock};
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const RELEASE_SYSTEM_PROMPT: &str = r#"You are a release manager for SPRT (Sprint), a developer productivity tool.
+Given a list of git commits for one repository, write user-facing release notes.
+
+Respond ONLY with valid JSON (no markdown fences, no extra text) in this exact format:
+{
+  "summary": "1-2 sentence overview of the release",
+  "features": ["new capability 1", ...],
+  "fixes": ["fixed problem 1", ...],
+  "chores": ["refactor, dependency or tooling change 1", ...]
+}
+
+Guidelines:
+- Group related commits into one entry; don't list every commit.
+- Describe changes from the user's point of view. Skip merge commits and version bumps.
+- Leave a category empty ([]) if nothing belongs there.
+- Write in English. Keep entries short."#;
+
+/// Commit messages beyond this many are left out of the prompt
+const MAX_PROMPT_COMMITS: usize = 300;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseRange {
+    /// Inclusive, YYYY-MM-DD
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNotes {
+    pub repo_name: String,
+    /// "since v1.2.0", "2025-01-01 – 2025-01-31"
+    pub range: String,
+    pub summary: String,
+    pub features: Vec<String>,
+    pub fixes: Vec<String>,
+    pub chores: Vec<String>,
+    pub markdown: String,
+    /// Stored copy, listed with list_devlogs("release")
+    pub devlog: DevLog,
+}
+
+fn parse_range(
+    range: &ReleaseRange,
+) -> Result<Option<(chrono::NaiveDate, chrono::NaiveDate)>, SprtError> {
+    let parse = |s: &str| {
+        time::parse_date(s).ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", s)))
+    };
+    match (&range.from, &range.to) {
+        (None, None) => Ok(None),
+        (from, to) => {
+            let from = from
+                .as_deref()
+                .map(parse)
+                .transpose()?
+                .unwrap_or(chrono::NaiveDate::MIN);
+            let to = match to.as_deref() {
+                Some(t) => parse(t)?,
+                None => DayClock::load().today(),
+            };
+            Ok(Some((from, to)))
+        }
+    }
+}
+
+fn build_prompt(repo_name: &str, range: &str, commits: &[GitCommit]) -> String {
+    let strip_bodies = settings::load().privacy.strip_commit_bodies;
+    let mut prompt = format!(
+        "Release notes for {} ({}), {} commits:\n\n",
+        repo_name,
+        range,
+        commits.len()
+    );
+    for c in commits.iter().take(MAX_PROMPT_COMMITS) {
+        let short = c.hash.get(..7).unwrap_or(&c.hash);
+        prompt.push_str(&format!(
+            "- {} {} (+{} -{})\n",
+            short,
+            privacy::commit_message(&c.message, strip_bodies).replace('\n', " "),
+            c.insertions,
+            c.deletions
+        ));
+    }
+    if commits.len() > MAX_PROMPT_COMMITS {
+        prompt.push_str(&format!(
+            "...and {} older commits\n",
+            commits.len() - MAX_PROMPT_COMMITS
+        ));
+    }
+    prompt
+}
+
+fn string_list(v: &serde_json::Value, key: &str) -> Vec<String> {
+    v.get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn render_markdown(
+    repo_name: &str,
+    range: &str,
+    summary: &str,
+    sections: &[(&str, &[String])],
+) -> String {
+    let mut md = format!("## {} — {}\n\n{}\n", repo_name, range, summary);
+    for (title, items) in sections.iter().filter(|(_, items)| !items.is_empty()) {
+        md.push_str(&format!("\n### {}\n", title));
+        for item in *items {
+            md.push_str(&format!("- {}\n", item));
+        }
+    }
+    md
+}
+
+pub async fn generate(
+    repo_path: &str,
+    since_ref: Option<&str>,
+    range: ReleaseRange,
+) -> Result<ReleaseNotes, SprtError> {
+    let days = parse_range(&range)?;
+    if since_ref.is_none() && days.is_none() {
+        return Err(SprtError::InvalidInput(
+            "Give a since ref or a date range".to_string(),
+        ));
+    }
+    let commits = {
+        let (path, since) = (repo_path.to_string(), since_ref.map(String::from));
+        tauri::async_runtime::spawn_blocking(move || {
+            git::release_commits(&path, since.as_deref(), days)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??
+    };
+    if commits.is_empty() {
+        return Err(SprtError::NoData("No commits in this range.".to_string()));
+    }
+
+    let repo_name = Path::new(repo_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| repo_path.to_string());
+    let mut label = vec![];
+    if let Some(r) = since_ref {
+        label.push(format!("since {}", r));
+    }
+    if let (Some(from), Some(to)) = (&range.from, &range.to) {
+        label.push(format!("{} – {}", from, to));
+    } else if let Some(from) = &range.from {
+        label.push(format!("from {}", from));
+    } else if let Some(to) = &range.to {
+        label.push(format!("until {}", to));
+    }
+    let label = label.join(", ");
+
+    let ai_response = devlog::call_claude_api(
+        RELEASE_SYSTEM_PROMPT,
+        &build_prompt(&repo_name, &label, &commits),
+    )
+    .await?;
+    let parsed: serde_json::Value = serde_json::from_str(&ai_response)
+        .map_err(|e| SprtError::Parse(format!("Failed to parse AI response: {}", e)))?;
+    let summary = parsed
+        .get("summary")
+        .and_then(|v| v.as_str())
+        .unwrap_or("No summary generated.")
+        .to_string();
+    let (features, fixes, chores) = (
+        string_list(&parsed, "features"),
+        string_list(&parsed, "fixes"),
+        string_list(&parsed, "chores"),
+    );
+
+    let markdown = render_markdown(
+        &repo_name,
+        &label,
+        &summary,
+        &[
+            ("Features", &features),
+            ("Fixes", &fixes),
+            ("Chores", &chores),
+        ],
+    );
+    // Highlights carry the category so the stored log stands on its own
+    let highlights = [("Feature", &features), ("Fix", &fixes), ("Chore", &chores)]
+        .iter()
+        .flat_map(|(kind, items)| items.iter().map(move |i| format!("{}: {}", kind, i)))
+        .collect();
+
+    let devlog = DevLog {
+        // Same repo and range regenerates in place
+        id: format!("r-{}-{}", repo_name, label),
+        date: DayClock::load().today_key(),
+        log_type: "release".to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        summary: summary.clone(),
+        highlights,
+        projects_worked: vec![ProjectWork {
+            name: repo_name.clone(),
+            path: repo_path.to_string(),
+            commits: commits.len() as u32,
+            messages: 0,
+            tokens: 0,
+            duration_minutes: 0,
+            key_changes: vec![],
+        }],
+        stats: DevLogStats {
+            total_commits: commits.len() as u32,
+            total_messages: 0,
+            total_tokens: 0,
+            total_files_changed: commits.iter().map(|c| c.files_changed).sum(),
+            total_insertions: commits.iter().map(|c| c.insertions).sum(),
+            total_deletions: commits.iter().map(|c| c.deletions).sum(),
+            active_hours: 0.0,
+            projects_count: 1,
+            commit_messages: analysis::commit_message_stats(
+                commits.iter().map(|c| c.message.as_str()),
+            ),
+            devices: vec![],
+            provider_tokens: Default::default(),
+            ai_assisted_hours: 0.0,
+            manual_hours: 0.0,
+        },
+        sprint_score: 0,
+        github_activity: vec![],
+        checkin: None,
+        value: None,
+        issues_touched: vec![],
+        prompt_truncation: None,
+        plan_completion: None,
+        candidate_todos: vec![],
+    };
+    storage::save_devlog(&devlog)?;
+
+    Ok(ReleaseNotes {
+        repo_name,
+        range: label,
+        summary,
+        features,
+        fixes,
+        chores,
+        markdown,
+        devlog,
+    })
+}
+
+/// Categorized release notes for the commits after `since_ref` and/or within `date_range`
+#[tauri::command]
+pub async fn generate_release_notes(
+    repo_path: String,
+    since_ref: Option<String>,
+    date_range: Option<ReleaseRange>,
+) -> Result<ReleaseNotes, SprtError> {
+    generate(
+        &repo_path,
+        since_ref.as_deref(),
+        date_range.unwrap_or_default(),
+    )
+    .await
+}
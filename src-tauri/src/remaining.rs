@@ -0,0 +1,166 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::window_breakdown;
+
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Responses this far back set the typical cost of a Sonnet or Opus message
+const LOOKBACK_DAYS: i64 = 7;
+/// Below this much 5-hour utilization the cost-per-point ratio is mostly noise
+const MIN_UTILIZATION: f64 = 0.05;
+/// The tray reuses an estimate this long instead of rescanning
+const TRAY_REFRESH: Duration = Duration::from_secs(60);
+
+/// When the tray estimate was made, and the estimate (None if it failed)
+type TrayEstimate = (Instant, Option<RemainingEstimate>);
+
+static TRAY_CACHE: LazyLock<Mutex<Option<TrayEstimate>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FamilyEstimate {
+    /// "sonnet" or "opus"
+    pub family: String,
+    /// Average cost of one assistant response over the lookback, or None without samples
+    pub avg_cost_usd: Option<f64>,
+    pub samples: u64,
+    pub messages_remaining: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemainingEstimate {
+    /// RFC 3339
+    pub window_start: String,
+    pub window_end: String,
+    /// False when no reset time is known and the last 5 hours stand in for the window
+    pub aligned: bool,
+    /// Reported 5-hour utilization, 0–1
+    pub utilization: Option<f64>,
+    /// Local cost of this window's responses
+    pub window_cost_usd: f64,
+    /// Window cost implied by 100% utilization (window cost ÷ utilization)
+    pub capacity_usd: Option<f64>,
+    pub remaining_usd: Option<f64>,
+    /// False while utilization is too low for the capacity to mean much
+    pub reliable: bool,
+    pub sonnet: FamilyEstimate,
+    pub opus: FamilyEstimate,
+}
+
+/// How many more Sonnet or Opus messages fit in the current 5-hour window. The reported
+/// utilization against what this window's responses cost locally gives the window's
+/// capacity in dollars; what's left of it is divided by the typical cost of a message.
+/// Usage from other machines counts toward utilization but not the local cost, so the
+/// capacity is underestimated rather than over.
+pub fn remaining_estimate() -> Result<RemainingEstimate, SprtError> {
+    let now = chrono::Utc::now().timestamp();
+    let (start, end, aligned) = window_breakdown::current_window(now);
+    let since = chrono::Utc::now() - chrono::Duration::days(LOOKBACK_DAYS);
+
+    let mut window_cost_usd = 0.0;
+    // (cost, responses) for sonnet and opus
+    let mut sonnet = (0.0, 0u64);
+    let mut opus = (0.0, 0u64);
+    claude::scan_usage(since, |e| {
+        let cost = e.cost_usd();
+        if e.timestamp.timestamp() >= start {
+            window_cost_usd += cost;
+        }
+        if e.sidechain {
+            return;
+        }
+        let model = e.model.to_lowercase();
+        let family = if model.contains("opus") {
+            &mut opus
+        } else if model.contains("sonnet") {
+            &mut sonnet
+        } else {
+            return;
+        };
+        family.0 += cost;
+        family.1 += 1;
+    })?;
+
+    let utilization = claude::get_cached_utilization();
+    let capacity_usd = utilization
+        .filter(|u| *u > 0.0 && window_cost_usd > 0.0)
+        .map(|u| window_cost_usd / u);
+    let remaining_usd = capacity_usd.map(|c| (c - window_cost_usd).max(0.0));
+    let family = |name: &str, (cost, samples): (f64, u64)| {
+        let avg_cost_usd = (samples > 0 && cost > 0.0).then(|| cost / samples as f64);
+        FamilyEstimate {
+            family: name.to_string(),
+            avg_cost_usd,
+            samples,
+            messages_remaining: remaining_usd
+                .zip(avg_cost_usd)
+                .map(|(r, avg)| (r / avg).floor() as u64),
+        }
+    };
+
+    let rfc3339 = |t: i64| {
+        chrono::DateTime::from_timestamp(t, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default()
+    };
+    Ok(RemainingEstimate {
+        window_start: rfc3339(start),
+        window_end: rfc3339(end),
+        aligned,
+        utilization,
+        window_cost_usd,
+        capacity_usd,
+        remaining_usd,
+        reliable: utilization.is_some_and(|u| u >= MIN_UTILIZATION),
+        sonnet: family("sonnet", sonnet),
+        opus: family("opus", opus),
+    })
+}
+
+fn compact(n: u64) -> String {
+    if n >= 1000 {
+        format!("{:.1}k", n as f64 / 1000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+/// Tray text for the "remaining" mode, e.g. "≈120 S · 24 O". Falls back to utilization while
+/// there is too little usage in the window to estimate from.
+pub fn tray_text() -> String {
+    let mut cached = TRAY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let fresh = cached
+        .as_ref()
+        .is_some_and(|(at, _)| at.elapsed() < TRAY_REFRESH);
+    if !fresh {
+        let estimate = remaining_estimate()
+            .inspect_err(|e| log::warn!("Cannot estimate remaining messages: {e}"))
+            .ok();
+        *cached = Some((Instant::now(), estimate));
+    }
+    let estimate = cached.as_ref().and_then(|(_, e)| e.as_ref());
+    let parts: Vec<String> = estimate
+        .filter(|e| e.reliable)
+        .into_iter()
+        .flat_map(|e| [("S", &e.sonnet), ("O", &e.opus)])
+        .filter_map(|(tag, f)| {
+            f.messages_remaining
+                .map(|n| format!("{} {tag}", compact(n)))
+        })
+        .collect();
+    if parts.is_empty() {
+        return crate::tray::utilization_text();
+    }
+    format!("≈{}", parts.join(" · "))
+}
+
+/// Estimated Sonnet and Opus messages left in the current 5-hour window
+#[tauri::command]
+pub async fn get_remaining_estimate() -> Result<RemainingEstimate, SprtError> {
+    tauri::async_runtime::spawn_blocking(remaining_estimate)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
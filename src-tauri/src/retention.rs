@@ -0,0 +1,243 @@
+use crate::error::SprtError;
+use crate::settings::{self, RetentionSettings};
+use crate::storage::{self, sprt_dir};
+
+use serde::Serialize;
+use std::fs;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeItem {
+    pub category: String,
+    /// Data older than this date (YYYY-MM-DD) is removed
+    pub cutoff: String,
+    pub count: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub items: Vec<PurgeItem>,
+    pub total_count: u64,
+    pub total_bytes: u64,
+}
+
+/// How a table stores the time a row ages from
+enum Age {
+    /// Unix seconds
+    Unix,
+    /// RFC 3339 timestamp
+    Rfc3339,
+    /// Local YYYY-MM-DD
+    Date,
+}
+
+/// A database table whose rows are purged by age. A feature adding a table that grows
+/// without bound registers it here along with a setting for its window.
+struct TablePolicy {
+    category: &'static str,
+    table: &'static str,
+    column: &'static str,
+    age: Age,
+    /// Further condition on the rows that may go
+    filter: Option<&'static str>,
+    days: fn(&RetentionSettings) -> Option<u32>,
+}
+
+const TABLES: &[TablePolicy] = &[
+    TablePolicy {
+        category: "rate_limit_history",
+        table: "timeline_events",
+        column: "at",
+        age: Age::Unix,
+        filter: Some("kind = 'limit'"),
+        days: |p| p.rate_limit_history_days,
+    },
+    TablePolicy {
+        category: "rate_limit_history",
+        table: "overage_episodes",
+        column: "last_seen_at",
+        age: Age::Rfc3339,
+        // The episode in progress stays, however old its start
+        filter: Some("ended_at IS NOT NULL"),
+        days: |p| p.rate_limit_history_days,
+    },
+    TablePolicy {
+        category: "timeline",
+        table: "timeline_events",
+        column: "at",
+        age: Age::Unix,
+        filter: Some("kind != 'limit'"),
+        days: |p| p.timeline_days,
+    },
+    TablePolicy {
+        category: "stats_snapshots",
+        table: "stats_snapshots",
+        column: "date",
+        age: Age::Date,
+        filter: None,
+        days: |p| p.stats_snapshots_days,
+    },
+    TablePolicy {
+        category: "session_history",
+        table: "session_history",
+        column: "ended_at",
+        age: Age::Unix,
+        filter: None,
+        days: |p| p.session_history_days,
+    },
+    TablePolicy {
+        category: "editor_heartbeats",
+        table: "editor_heartbeats",
+        column: "at",
+        age: Age::Unix,
+        filter: None,
+        days: |p| p.editor_heartbeats_days,
+    },
+];
+
+fn cutoff_date(days: u32) -> String {
+    crate::time::format_date(crate::time::DayClock::load().today() - chrono::Duration::days(days as i64))
+}
+
+/// Apply one table policy, adding to the category's item when it already has one
+fn purge_table(
+    t: &TablePolicy,
+    days: u32,
+    dry_run: bool,
+    items: &mut Vec<PurgeItem>,
+) -> Result<(), SprtError> {
+    use rusqlite::types::Value;
+    let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+    let (older, cutoff) = match t.age {
+        Age::Unix => (format!("{} < ?1", t.column), Value::Integer(since.timestamp())),
+        // julianday copes with the mixed offsets and precision RFC 3339 allows
+        Age::Rfc3339 => (
+            format!("julianday({}) < julianday(?1)", t.column),
+            Value::Text(since.to_rfc3339()),
+        ),
+        Age::Date => (format!("{} < ?1", t.column), Value::Text(cutoff_date(days))),
+    };
+    let condition = match t.filter {
+        Some(f) => format!("{} AND {}", older, f),
+        None => older,
+    };
+    let (count, bytes) = storage::purge_rows(t.table, &condition, cutoff, dry_run)?;
+    match items.iter_mut().find(|i| i.category == t.category) {
+        Some(item) => {
+            item.count += count;
+            item.bytes += bytes;
+        }
+        None => items.push(PurgeItem {
+            category: t.category.to_string(),
+            cutoff: cutoff_date(days),
+            count,
+            bytes,
+        }),
+    }
+    Ok(())
+}
+
+/// Remove backup archives last modified before the cutoff. Returns (files, bytes).
+fn purge_backups(days: u32, dry_run: bool) -> (u64, u64) {
+    let dir = match sprt_dir() {
+        Some(d) => d.join("backups"),
+        None => return (0, 0),
+    };
+    let max_age = std::time::Duration::from_secs(days as u64 * 86400);
+    let mut count = 0;
+    let mut bytes = 0;
+    for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+        let meta = match entry.metadata() {
+            Ok(m) if m.is_file() => m,
+            _ => continue,
+        };
+        let age = meta.modified().ok().and_then(|m| m.elapsed().ok()).unwrap_or_default();
+        if age <= max_age {
+            continue;
+        }
+        if dry_run || fs::remove_file(entry.path()).is_ok() {
+            count += 1;
+            bytes += meta.len();
+        }
+    }
+    (count, bytes)
+}
+
+/// Apply the retention policy. With `dry_run`, nothing is deleted and the report shows what
+/// would be.
+pub fn purge(policy: &RetentionSettings, dry_run: bool) -> Result<PurgeReport, SprtError> {
+    let mut items = vec![];
+
+    let devlog_policies = [
+        ("daily", policy.daily_logs_days),
+        ("weekly", policy.weekly_logs_days),
+        ("monthly", policy.monthly_logs_days),
+    ];
+    for (log_type, days) in devlog_policies {
+        if let Some(days) = days {
+            let cutoff = cutoff_date(days);
+            let (count, bytes) = storage::purge_devlogs(log_type, &cutoff, dry_run)?;
+            items.push(PurgeItem {
+                category: format!("{}_logs", log_type),
+                cutoff,
+                count,
+                bytes,
+            });
+        }
+    }
+
+    if let Some(days) = policy.backups_days {
+        let (count, bytes) = purge_backups(days, dry_run);
+        items.push(PurgeItem {
+            category: "backups".to_string(),
+            cutoff: cutoff_date(days),
+            count,
+            bytes,
+        });
+    }
+
+    for t in TABLES {
+        if let Some(days) = (t.days)(policy) {
+            purge_table(t, days, dry_run, &mut items)?;
+        }
+    }
+
+    let total_count = items.iter().map(|i| i.count).sum();
+    let total_bytes = items.iter().map(|i| i.bytes).sum();
+    if !dry_run && total_count > 0 {
+        storage::vacuum_database()?;
+    }
+
+    Ok(PurgeReport {
+        dry_run,
+        items,
+        total_count,
+        total_bytes,
+    })
+}
+
+/// Periodic cleanup entry point for the background thread
+pub fn run_scheduled_cleanup() {
+    match purge(&settings::load().retention, false) {
+        Ok(report) if report.total_count > 0 => {
+            log::info!(
+                "Retention cleanup removed {} items ({} bytes)",
+                report.total_count, report.total_bytes
+            );
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Retention cleanup failed: {e}"),
+    }
+}
+
+/// Apply the retention policy now. Defaults to a dry run that only reports.
+#[tauri::command]
+pub async fn purge_data(dry_run: Option<bool>) -> Result<PurgeReport, SprtError> {
+    let dry_run = dry_run.unwrap_or(true);
+    tauri::async_runtime::spawn_blocking(move || purge(&settings::load().retention, dry_run))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,63 @@
+use crate::error::SprtError;
+use crate::monitor;
+
+use std::sync::{LazyLock, Mutex, Once};
+use std::time::{Duration, Instant};
+
+/// How often the scheduler thread looks for due checks
+const TICK_SECS: u64 = 15;
+
+type Check = fn() -> Result<(), SprtError>;
+
+struct Job {
+    /// Names the check in the failure log: "{label} failed: ..."
+    label: &'static str,
+    interval: Duration,
+    next: Instant,
+    check: Check,
+}
+
+static JOBS: LazyLock<Mutex<Vec<Job>>> = LazyLock::new(|| Mutex::new(vec![]));
+static STARTED: Once = Once::new();
+
+/// Run `check` every `interval_secs` on the one background thread shared by the periodic
+/// checks (briefing, budget, email, ...), first after one interval. Skipped while monitoring
+/// is paused. Checks run one after another, so a slow one delays the rest.
+pub fn every(label: &'static str, interval_secs: u64, check: Check) {
+    let interval = Duration::from_secs(interval_secs);
+    JOBS.lock().unwrap_or_else(|e| e.into_inner()).push(Job {
+        label,
+        interval,
+        next: Instant::now() + interval,
+        check,
+    });
+    STARTED.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(Duration::from_secs(TICK_SECS));
+            run_due();
+        });
+    });
+}
+
+fn run_due() {
+    let now = Instant::now();
+    // Taken out of the lock first, so a slow check doesn't block registration
+    let due: Vec<(&'static str, Check)> = {
+        let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.iter_mut()
+            .filter(|j| now >= j.next)
+            .map(|j| {
+                j.next = now + j.interval;
+                (j.label, j.check)
+            })
+            .collect()
+    };
+    if monitor::is_paused() {
+        return;
+    }
+    for (label, check) in due {
+        if let Err(e) = check() {
+            log::warn!("{label} failed: {e}");
+        }
+    }
+}
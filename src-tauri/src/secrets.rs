@@ -0,0 +1,255 @@
+use crate::error::SprtError;
+use crate::settings::{self, ServerSettings, Settings, WebhookConfig};
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// Keychain service every SPRT secret is stored under; the name is the account
+const SERVICE: &str = "SPRT";
+
+pub const GITHUB_TOKEN: &str = "github-token";
+pub const LINEAR_API_KEY: &str = "linear-api-key";
+pub const JIRA_TOKEN: &str = "jira-token";
+pub const NOTION_TOKEN: &str = "notion-token";
+pub const SMTP_PASSWORD: &str = "smtp-password";
+/// Bearer token for the local API and editor heartbeats (server.apiToken)
+pub const API_TOKEN: &str = "api-token";
+/// Managed by crypto::set_encryption, which re-encrypts stored devlogs when it changes
+pub const ENCRYPTION_PASSPHRASE: &str = "devlog-encryption";
+
+/// Secrets the set/delete commands accept
+const NAMES: &[&str] = &[
+    GITHUB_TOKEN,
+    LINEAR_API_KEY,
+    JIRA_TOKEN,
+    NOTION_TOKEN,
+    SMTP_PASSWORD,
+    API_TOKEN,
+];
+
+/// Slack and Discord webhook URLs carry their secret in the path, so each is stored under
+/// the webhook's id
+fn webhook_name(id: &str) -> String {
+    format!("webhook-{}", id)
+}
+
+/// Keychain reads by name, misses included. macOS may prompt on keychain access, so each
+/// secret is read once per run.
+static CACHE: LazyLock<Mutex<HashMap<String, Option<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn entry(name: &str) -> Result<keyring::Entry, SprtError> {
+    keyring::Entry::new(SERVICE, name)
+        .map_err(|e| SprtError::Internal(format!("Keychain unavailable: {}", e)))
+}
+
+/// A secret from the keychain (macOS Keychain, Secret Service on Linux)
+pub fn get(name: &str) -> Result<Option<String>, SprtError> {
+    if let Some(cached) = CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(name) {
+        return Ok(cached.clone());
+    }
+    let value = match entry(name)?.get_password() {
+        Ok(v) => Some(v),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            return Err(SprtError::Internal(format!(
+                "Cannot read the keychain: {}",
+                e
+            )))
+        }
+    };
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), value.clone());
+    Ok(value)
+}
+
+pub fn set(name: &str, value: &str) -> Result<(), SprtError> {
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| SprtError::Internal(format!("Cannot write the keychain: {}", e)))?;
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), Some(value.to_string()));
+    Ok(())
+}
+
+pub fn delete(name: &str) -> Result<(), SprtError> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => {
+            return Err(SprtError::Internal(format!(
+                "Cannot write the keychain: {}",
+                e
+            )))
+        }
+    }
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), None);
+    Ok(())
+}
+
+/// The keychain's value for `name`, else `legacy` — a plaintext setting that couldn't be
+/// moved because the keychain was unavailable
+pub fn resolve(name: &str, legacy: Option<String>) -> Option<String> {
+    let stored = get(name).unwrap_or_else(|e| {
+        log::warn!("{e}");
+        None
+    });
+    stored.or(legacy).filter(|v| !v.trim().is_empty())
+}
+
+/// The API token: keychain first, then a server.apiToken left in settings
+pub fn api_token(config: &ServerSettings) -> Option<String> {
+    resolve(API_TOKEN, config.api_token.clone())
+}
+
+/// A webhook's URL: keychain first, then a url left in settings
+pub fn webhook_url(hook: &WebhookConfig) -> Option<String> {
+    let legacy = Some(hook.url.clone()).filter(|u| !u.is_empty());
+    if hook.id.is_empty() {
+        return legacy;
+    }
+    resolve(&webhook_name(&hook.id), legacy)
+}
+
+/// Move plaintext secrets out of `settings` into the keychain, clearing each field that
+/// moved. Fields stay put when the keychain can't be written, so nothing is lost.
+pub fn take_from_settings(settings: &mut Settings) {
+    for hook in &mut settings.webhooks {
+        if hook.id.is_empty() {
+            hook.id = uuid::Uuid::new_v4().to_string();
+        }
+        if hook.url.trim().is_empty() {
+            continue;
+        }
+        match set(&webhook_name(&hook.id), hook.url.trim()) {
+            Ok(()) => hook.url.clear(),
+            Err(e) => log::warn!("Keeping webhook '{}' URL in settings: {e}", hook.name),
+        }
+    }
+    let fields: [(&str, &mut Option<String>); 6] = [
+        (GITHUB_TOKEN, &mut settings.github_token),
+        (LINEAR_API_KEY, &mut settings.issue_trackers.linear_api_key),
+        (JIRA_TOKEN, &mut settings.issue_trackers.jira_token),
+        (NOTION_TOKEN, &mut settings.notion.token),
+        (SMTP_PASSWORD, &mut settings.email.password),
+        (API_TOKEN, &mut settings.server.api_token),
+    ];
+    for (name, field) in fields {
+        let Some(value) = field.as_deref() else {
+            continue;
+        };
+        let moved = if value.trim().is_empty() {
+            Ok(())
+        } else {
+            set(name, value)
+        };
+        match moved {
+            Ok(()) => *field = None,
+            Err(e) => log::warn!("Keeping {name} in settings: {e}"),
+        }
+    }
+}
+
+/// Drop the stored URLs of webhooks no longer in settings
+pub fn forget_removed_webhooks(before: &[WebhookConfig], after: &[WebhookConfig]) {
+    for hook in before.iter().filter(|h| !h.id.is_empty()) {
+        if after.iter().any(|h| h.id == hook.id) {
+            continue;
+        }
+        if let Err(e) = delete(&webhook_name(&hook.id)) {
+            log::warn!("Cannot remove webhook '{}' URL: {e}", hook.name);
+        }
+    }
+}
+
+/// Move secrets left in settings.json by older versions into the keychain
+pub fn migrate_settings() {
+    let s = settings::load();
+    let plaintext = [
+        &s.github_token,
+        &s.issue_trackers.linear_api_key,
+        &s.issue_trackers.jira_token,
+        &s.notion.token,
+        &s.email.password,
+        &s.server.api_token,
+    ];
+    let webhooks = s.webhooks.iter().any(|h| !h.url.is_empty() || h.id.is_empty());
+    if plaintext.iter().any(|v| v.is_some()) || webhooks {
+        if let Err(e) = settings::save(&s) {
+            log::warn!("Cannot migrate secrets out of settings: {e}");
+        }
+    }
+}
+
+fn check_name(name: &str) -> Result<(), SprtError> {
+    if NAMES.contains(&name) {
+        Ok(())
+    } else {
+        Err(SprtError::InvalidInput(format!("Unknown secret: {}", name)))
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStatus {
+    pub name: String,
+    /// Values are never sent back to the UI
+    pub set: bool,
+}
+
+/// Which secrets are stored
+#[tauri::command]
+pub async fn get_secret_status() -> Result<Vec<SecretStatus>, SprtError> {
+    tauri::async_runtime::spawn_blocking(|| {
+        NAMES
+            .iter()
+            .map(|name| {
+                Ok(SecretStatus {
+                    name: name.to_string(),
+                    set: get(name)?.is_some(),
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Store a token for an integration: "github-token", "linear-api-key", "jira-token",
+/// "notion-token", "smtp-password" or "api-token"
+#[tauri::command]
+pub async fn set_secret(name: String, value: String) -> Result<(), SprtError> {
+    check_name(&name)?;
+    if value.trim().is_empty() {
+        return Err(SprtError::InvalidInput("Secret is empty".to_string()));
+    }
+    tauri::async_runtime::spawn_blocking(move || set(&name, value.trim()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn delete_secret(name: String) -> Result<(), SprtError> {
+    check_name(&name)?;
+    tauri::async_runtime::spawn_blocking(move || delete(&name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Put the local API token on the clipboard, for scripts and WakaTime clients; like every
+/// other secret, it is never sent to the UI
+#[tauri::command]
+pub async fn copy_api_token(app: tauri::AppHandle) -> Result<(), SprtError> {
+    let token = tauri::async_runtime::spawn_blocking(|| api_token(&settings::load().server))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .ok_or_else(|| SprtError::NoData("No API token yet: enable the API first".to_string()))?;
+    crate::clipboard::copy(&app, &token)
+}
@@ -0,0 +1,187 @@
+use crate::api;
+use crate::editor;
+use crate::metrics;
+use crate::secrets;
+use crate::settings;
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 9477;
+
+/// Largest request body accepted; nothing SPRT serves needs more
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into(),
+        }
+    }
+
+    pub fn json<T: serde::Serialize>(status: u16, value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Response {
+                status,
+                content_type: "application/json",
+                body,
+            },
+            Err(e) => Response::error(500, &format!("Serialize error: {}", e)),
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Response::json(status, &serde_json::json!({ "error": message }))
+    }
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+fn parse_query(qs: &str) -> HashMap<String, String> {
+    qs.split('&')
+        .filter_map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (!k.is_empty()).then(|| (k.to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request, Response> {
+    let bad = || Response::text(400, "Bad request\n");
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(|_| bad())?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or_else(bad)?.to_string();
+    let target = parts.next().ok_or_else(bad)?;
+    let (path, qs) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|_| bad())? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let len: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if len > MAX_BODY_BYTES {
+        return Err(Response::text(413, "Payload too large\n"));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).map_err(|_| bad())?;
+
+    Ok(Request {
+        method,
+        path: path.to_string(),
+        query: parse_query(qs),
+        headers,
+        body,
+    })
+}
+
+fn write_response(stream: &mut TcpStream, resp: &Response) {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        resp.status,
+        status_text(resp.status),
+        resp.content_type,
+        resp.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(resp.body.as_bytes());
+}
+
+fn route(req: &Request) -> Response {
+    let config = settings::load().server;
+    match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/metrics") if config.metrics_enabled => metrics::render(),
+        ("POST", path) if config.heartbeats_enabled && editor::is_heartbeat_path(path) => {
+            editor::handle(req, &config)
+        }
+        _ if config.api_enabled => api::handle(req, &config),
+        _ => Response::text(404, "Not found\n"),
+    }
+}
+
+fn handle(mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let resp = match read_request(&mut stream) {
+        Ok(req) => route(&req),
+        Err(resp) => resp,
+    };
+    write_response(&mut stream, &resp);
+}
+
+/// Start the localhost listener if metrics, the API or editor heartbeats are enabled in
+/// settings. Port and enable flags are read at launch; changing them takes effect on the
+/// next start.
+pub fn start() {
+    let mut all = settings::load();
+    let authenticated = all.server.api_enabled || all.server.heartbeats_enabled;
+    if !all.server.metrics_enabled && !authenticated {
+        return;
+    }
+    // The API is never served without a token — mint one on first enable
+    if authenticated && secrets::api_token(&all.server).is_none() {
+        all.server.api_token = Some(uuid::Uuid::new_v4().to_string());
+        if let Err(e) = settings::save(&all) {
+            log::warn!("Cannot save API token: {e}");
+            return;
+        }
+    }
+    let port = all.server.port.unwrap_or(DEFAULT_PORT);
+
+    std::thread::spawn(move || {
+        // Loopback only — never expose usage data on the network
+        let listener = match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("Cannot start local server on port {port}: {e}");
+                return;
+            }
+        };
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || handle(stream));
+        }
+    });
+}
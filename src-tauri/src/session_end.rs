@@ -0,0 +1,127 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::notifications;
+use crate::scheduler;
+use crate::session_index;
+use crate::settings;
+use crate::storage::{self, IndexedSession, SessionEnding, SessionQuery};
+
+use std::collections::HashMap;
+use std::path::Path;
+
+const CHECK_INTERVAL_SECS: u64 = 2 * 60;
+/// Sessions that went quiet longer ago than this are left alone (e.g. on first launch)
+const LOOKBACK_SECS: i64 = 24 * 3600;
+
+fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        t if t >= 1_000_000 => format!("{:.1}M", t as f64 / 1_000_000.0),
+        t if t >= 1_000 => format!("{}k", t / 1_000),
+        t => t.to_string(),
+    }
+}
+
+fn announce(s: &SessionEnding) {
+    let project = Path::new(&s.project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| s.project_path.clone());
+    let body = format!(
+        "{} min, {} tokens (${:.2}) on {}",
+        (s.ended_at - s.started_at) / 60,
+        format_tokens(s.tokens),
+        s.cost_usd,
+        project
+    );
+    notifications::notify_app("Session ended", &body);
+}
+
+/// Tokens and cost per session id, from every response since `since`
+fn usage_since(since: i64) -> Result<HashMap<String, (u64, f64)>, SprtError> {
+    let since = chrono::DateTime::from_timestamp(since, 0).unwrap_or_default();
+    let mut usage: HashMap<String, (u64, f64)> = HashMap::new();
+    claude::scan_usage(since, |e| {
+        let u = usage.entry(e.session_id.to_string()).or_default();
+        u.0 += e.total_tokens();
+        u.1 += e.cost_usd();
+    })?;
+    Ok(usage)
+}
+
+/// Record sessions whose last message is older than the quiet period. A session resumed
+/// after being recorded is summarized again once it goes quiet a second time.
+fn check() -> Result<(), SprtError> {
+    let cfg = settings::load().session_end;
+    let quiet = cfg.quiet_minutes.max(1) as i64 * 60;
+    let now = chrono::Utc::now().timestamp();
+
+    session_index::refresh_in_background()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(now - LOOKBACK_SECS),
+        to: None,
+        device: None,
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: 1000,
+    })?;
+    let recorded = storage::session_ends_since(now - LOOKBACK_SECS)?;
+    let ended: Vec<&IndexedSession> = sessions
+        .iter()
+        .filter(|s| {
+            s.last_message_at.is_some_and(|last| {
+                now - last >= quiet && recorded.get(&s.session_id).is_none_or(|&e| e < last)
+            })
+        })
+        .collect();
+    let Some(since) = ended.iter().filter_map(|s| s.first_message_at).min() else {
+        return Ok(());
+    };
+
+    let usage = usage_since(since)?;
+    for s in ended {
+        let (Some(started_at), Some(ended_at)) = (s.first_message_at, s.last_message_at) else {
+            continue;
+        };
+        let (tokens, cost_usd) = usage
+            .get(&s.session_id)
+            .copied()
+            .unwrap_or((s.total_tokens, 0.0));
+        let ending = SessionEnding {
+            session_id: s.session_id.clone(),
+            project_path: s.project_path.clone(),
+            title: s.title.clone(),
+            device: s.device.clone(),
+            started_at,
+            ended_at,
+            messages: s.message_count,
+            tokens,
+            cost_usd,
+        };
+        storage::save_session_ending(&ending)?;
+        // Only sessions that just went quiet are announced, not a backlog after a pause
+        let just_ended = now - ended_at < quiet + 2 * CHECK_INTERVAL_SECS as i64;
+        if cfg.notify && just_ended && ended_at - started_at >= cfg.min_minutes as i64 * 60 {
+            announce(&ending);
+        }
+    }
+    Ok(())
+}
+
+/// Background detection of sessions that went quiet; see `sessionEnd` settings
+pub fn start() {
+    scheduler::every("Session end check", CHECK_INTERVAL_SECS, check);
+}
+
+/// Ended sessions with their final duration, tokens and cost, most recent first
+#[tauri::command]
+pub async fn get_session_history(
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<SessionEnding>, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        storage::list_session_history(offset.unwrap_or(0), limit.unwrap_or(50))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,326 @@
+use crate::crypto;
+use crate::error::SprtError;
+use crate::storage::{self, IndexedSession};
+use crate::transcript::{self, Block, Message};
+
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Tool output past this many characters is cut; builds and test runs can print megabytes
+const MAX_RESULT_CHARS: usize = 20_000;
+/// Length of the input shown in a collapsed tool call's summary
+const MAX_SUMMARY_CHARS: usize = 80;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExport {
+    pub path: String,
+    /// "markdown" or "html"
+    pub format: String,
+    pub messages: usize,
+}
+
+fn local_time(ts: Option<&str>) -> String {
+    ts.and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn shorten(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(max).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn truncate_result(content: &str) -> String {
+    let total = content.chars().count();
+    if total <= MAX_RESULT_CHARS {
+        return content.to_string();
+    }
+    let cut: String = content.chars().take(MAX_RESULT_CHARS).collect();
+    format!("{}\n… ({} more characters)", cut, total - MAX_RESULT_CHARS)
+}
+
+/// "Bash: cargo test" — the tool name and its most telling argument
+fn tool_summary(name: &str, input: &serde_json::Value) -> String {
+    let arg = [
+        "command",
+        "file_path",
+        "pattern",
+        "path",
+        "url",
+        "description",
+    ]
+    .iter()
+    .find_map(|k| input.get(*k).and_then(|v| v.as_str()))
+    .and_then(|a| a.lines().next());
+    match arg {
+        Some(a) => format!("{}: {}", name, shorten(a, MAX_SUMMARY_CHARS)),
+        None => name.to_string(),
+    }
+}
+
+fn title(session: &IndexedSession) -> String {
+    session
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Session {}", session.session_id))
+}
+
+/// Subagent turns are left out; the Task tool call and its result already summarize them
+fn visible(messages: &[Message]) -> impl Iterator<Item = &Message> {
+    messages.iter().filter(|m| !m.sidechain)
+}
+
+/// Messages holding only tool results continue the assistant's turn rather than start one
+fn starts_turn(m: &Message) -> bool {
+    m.blocks
+        .iter()
+        .any(|b| !matches!(b, Block::ToolResult { .. }))
+}
+
+fn heading(m: &Message) -> String {
+    let role = if m.role == "assistant" {
+        "Assistant"
+    } else {
+        "User"
+    };
+    let mut parts = vec![role.to_string()];
+    let time = local_time(m.timestamp.as_deref());
+    if !time.is_empty() {
+        parts.push(time);
+    }
+    if let Some(model) = &m.model {
+        parts.push(model.clone());
+    }
+    parts.join(" · ")
+}
+
+/// A code fence longer than any backtick run in `content`, so the content can't close it
+fn fence(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn markdown(session: &IndexedSession, messages: &[Message]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", title(session));
+    let _ = writeln!(out, "- **Project:** {}", session.project_path);
+    let _ = writeln!(out, "- **Session:** {}", session.session_id);
+    let started = session
+        .first_message_at
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| t.to_rfc3339());
+    if let Some(started) = started {
+        let _ = writeln!(out, "- **Started:** {}", local_time(Some(&started)));
+    }
+    let _ = writeln!(
+        out,
+        "- **Messages:** {} · **Tokens:** {}\n",
+        session.message_count, session.total_tokens
+    );
+
+    for m in visible(messages) {
+        if starts_turn(m) {
+            let _ = writeln!(out, "---\n\n### {}\n", heading(m));
+        }
+        for block in &m.blocks {
+            match block {
+                Block::Text { text } => {
+                    let _ = writeln!(out, "{}\n", text.trim());
+                }
+                Block::Thinking { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>Thinking</summary>\n\n{}\n\n</details>\n",
+                        text.trim()
+                    );
+                }
+                Block::ToolUse { name, input, .. } => {
+                    let json = serde_json::to_string_pretty(input).unwrap_or_default();
+                    let f = fence(&json);
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>{}</summary>\n\n{f}json\n{}\n{f}\n\n</details>\n",
+                        tool_summary(name, input),
+                        json
+                    );
+                }
+                Block::ToolResult {
+                    content, is_error, ..
+                } => {
+                    let content = truncate_result(content);
+                    let f = fence(&content);
+                    let label = if *is_error { "Error" } else { "Result" };
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>{}</summary>\n\n{f}\n{}\n{f}\n\n</details>\n",
+                        label, content
+                    );
+                }
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body{font:15px/1.5 -apple-system,system-ui,sans-serif;max-width:860px;margin:2em auto;padding:0 1em;color:#222}
+h1{font-size:1.5em}.meta{color:#666;font-size:.9em}
+.turn{border-top:1px solid #ddd;padding-top:.5em;margin-top:1.5em}
+.turn h3{font-size:.95em;color:#555;margin:.2em 0 .6em}
+.user h3{color:#0a58ca}.text{white-space:pre-wrap}
+details{margin:.5em 0;border:1px solid #e3e3e3;border-radius:6px;padding:.3em .6em;background:#fafafa}
+summary{cursor:pointer;font-family:ui-monospace,monospace;font-size:.85em}
+pre{overflow-x:auto;font-size:.85em;white-space:pre-wrap}.error summary{color:#b00020}
+@media (prefers-color-scheme:dark){body{background:#1e1e1e;color:#ddd}details{background:#262626;border-color:#3a3a3a}.turn{border-color:#3a3a3a}}";
+
+fn html(session: &IndexedSession, messages: &[Message]) -> String {
+    let mut out = String::new();
+    let title = escape(&title(session));
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n<style>{STYLE}</style></head><body>"
+    );
+    let _ = writeln!(out, "<h1>{title}</h1>");
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">{} · {} · {} messages · {} tokens</p>",
+        escape(&session.project_path),
+        escape(&session.session_id),
+        session.message_count,
+        session.total_tokens
+    );
+
+    let mut open = false;
+    for m in visible(messages) {
+        if starts_turn(m) {
+            if open {
+                out.push_str("</section>\n");
+            }
+            let _ = writeln!(
+                out,
+                "<section class=\"turn {}\"><h3>{}</h3>",
+                escape(&m.role),
+                escape(&heading(m))
+            );
+            open = true;
+        }
+        for block in &m.blocks {
+            match block {
+                Block::Text { text } => {
+                    let _ = writeln!(out, "<div class=\"text\">{}</div>", escape(text.trim()));
+                }
+                Block::Thinking { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>Thinking</summary><div class=\"text\">{}</div></details>",
+                        escape(text.trim())
+                    );
+                }
+                Block::ToolUse { name, input, .. } => {
+                    let json = serde_json::to_string_pretty(input).unwrap_or_default();
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>{}</summary><pre>{}</pre></details>",
+                        escape(&tool_summary(name, input)),
+                        escape(&json)
+                    );
+                }
+                Block::ToolResult {
+                    content, is_error, ..
+                } => {
+                    let (class, label) = if *is_error {
+                        (" class=\"error\"", "Error")
+                    } else {
+                        ("", "Result")
+                    };
+                    let _ = writeln!(
+                        out,
+                        "<details{class}><summary>{label}</summary><pre>{}</pre></details>",
+                        escape(&truncate_result(content))
+                    );
+                }
+            }
+        }
+    }
+    if open {
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+pub fn export(
+    session_id: &str,
+    format: Option<String>,
+    path: Option<String>,
+) -> Result<SessionExport, SprtError> {
+    let format = match format.as_deref().unwrap_or("markdown") {
+        "markdown" | "md" => "markdown",
+        "html" => "html",
+        other => {
+            return Err(SprtError::InvalidInput(format!(
+                "Unknown export format: {}",
+                other
+            )))
+        }
+    };
+    let (session, messages) = transcript::load(session_id)?;
+    if messages.is_empty() {
+        return Err(SprtError::NoData(format!(
+            "Session {} has no messages",
+            session_id
+        )));
+    }
+    let (content, ext) = match format {
+        "html" => (html(&session, &messages), "html"),
+        _ => (markdown(&session, &messages), "md"),
+    };
+
+    let dest = match path {
+        Some(p) => PathBuf::from(p),
+        None => storage::sprt_dir()
+            .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?
+            .join("export")
+            .join(format!("session-{}.{}", session.session_id, ext)),
+    };
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| SprtError::Storage(format!("Cannot create directory: {}", e)))?;
+    }
+    let (dest, content) = crypto::protect_export(dest, &content)?;
+    storage::write_atomic(&dest, &content)?;
+
+    Ok(SessionExport {
+        path: dest.to_string_lossy().to_string(),
+        format: format.to_string(),
+        messages: visible(&messages).count(),
+    })
+}
+
+/// Write a session's conversation as a readable transcript. format: "markdown" (default) or
+/// "html"; tool calls and their output are collapsible. Returns where it was written.
+#[tauri::command]
+pub async fn export_session(
+    session_id: String,
+    format: Option<String>,
+    path: Option<String>,
+) -> Result<SessionExport, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || export(&session_id, format, path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,363 @@
+use crate::claude::{self, SessionTitle};
+use crate::devices;
+use crate::diagnostics;
+use crate::error::SprtError;
+use crate::git;
+use crate::health;
+use crate::power;
+use crate::storage::{self, IndexedSession, SessionQuery};
+use crate::time::{self, DayClock};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 500;
+
+/// Held while re-indexing so concurrent callers don't scan the same files twice
+static REFRESH: Mutex<()> = Mutex::new(());
+
+/// Size of each session log when this process last indexed it, to tell the logs it watched
+/// grow from ones that showed up whole
+static WATCHED: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn file_stamp(path: &Path) -> Option<(u64, i64)> {
+    let meta = fs::metadata(path).ok()?;
+    let modified: chrono::DateTime<chrono::Utc> = meta.modified().ok()?.into();
+    Some((meta.len(), modified.timestamp()))
+}
+
+fn scan_file(path: &Path, stamp: (u64, i64)) -> Option<IndexedSession> {
+    let file = match fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) => {
+            let msg = format!("Cannot open session log: {}", e);
+            diagnostics::warn("session-log", path, None, diagnostics::io_kind(&e), msg);
+            return None;
+        }
+    };
+    let project = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let session_id = path
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut cwd: Option<String> = None;
+    let mut first: Option<i64> = None;
+    let mut last: Option<i64> = None;
+    let mut message_count = 0;
+    let mut total_tokens = 0;
+    let mut model_tokens: HashMap<String, u64> = HashMap::new();
+    let mut title = SessionTitle::default();
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        title.observe(&line);
+        if !line.contains("\"type\":\"assistant\"") {
+            continue;
+        }
+        let entry: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if entry.get("type").and_then(|v| v.as_str()) != Some("assistant") {
+            continue;
+        }
+        let Some(ts) = entry
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<chrono::DateTime<chrono::Utc>>().ok())
+            .map(|t| t.timestamp())
+        else {
+            continue;
+        };
+        if cwd.is_none() {
+            cwd = entry.get("cwd").and_then(|v| v.as_str()).map(String::from);
+        }
+        first = Some(first.map_or(ts, |f| f.min(ts)));
+        last = Some(last.map_or(ts, |l| l.max(ts)));
+        message_count += 1;
+
+        let message = entry.get("message");
+        if let Some(usage) = message.and_then(|m| m.get("usage")) {
+            let tokens: u64 = [
+                "input_tokens",
+                "output_tokens",
+                "cache_read_input_tokens",
+                "cache_creation_input_tokens",
+            ]
+            .iter()
+            .filter_map(|k| usage.get(*k).and_then(|v| v.as_u64()))
+            .sum();
+            total_tokens += tokens;
+            let model = message
+                .and_then(|m| m.get("model"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            *model_tokens.entry(model.to_string()).or_insert(0) += tokens;
+        }
+    }
+
+    Some(IndexedSession {
+        path: path.to_string_lossy().to_string(),
+        session_id,
+        project_path: cwd.unwrap_or_else(|| git::decode_project_path(&project)),
+        project,
+        file_size: stamp.0,
+        modified_at: stamp.1,
+        first_message_at: first,
+        last_message_at: last,
+        message_count,
+        total_tokens,
+        model_tokens,
+        title: title.into_title(),
+        device: None,
+    })
+}
+
+/// `refresh` for background checks: deferred while on battery (see power.rs), in which
+/// case they work from what's already indexed. Commands the user is waiting on call `refresh`.
+pub fn refresh_in_background() -> Result<(), SprtError> {
+    if power::is_throttled() {
+        return Ok(());
+    }
+    refresh()
+}
+
+/// Bring the index up to date: rescan files whose size or mtime changed, drop deleted ones.
+/// Unchanged files cost one stat each.
+pub fn refresh() -> Result<(), SprtError> {
+    let _guard = REFRESH.lock().unwrap_or_else(|e| e.into_inner());
+    let started = std::time::Instant::now();
+    let result = refresh_locked();
+    health::record(health::SESSION_INDEX, started, &result);
+    result
+}
+
+/// Whether a changed session log is being written on this machine. Logs carry no hostname,
+/// so this goes by what the session records and what this process saw: the log grew since
+/// the last refresh (a synced or copied-in log usually shows up whole), its newest message is
+/// live, and its working directory exists here. A synced ~/.claude shared with a machine
+/// that has the same paths can still slip through; the index keeps the first device set.
+fn runs_here(session: &IndexedSession, watched_size: Option<u64>, live_since: i64) -> bool {
+    let grew = watched_size.is_some_and(|size| session.file_size > size);
+    let live = session.last_message_at.is_some_and(|t| t >= live_since);
+    grew && live && Path::new(&session.project_path).is_dir()
+}
+
+fn refresh_locked() -> Result<(), SprtError> {
+    let paths = claude::session_logs()?;
+
+    let known = storage::session_index_stamps()?;
+    let mut seen = HashSet::new();
+    let mut changed = vec![];
+    let live_since = chrono::Utc::now().timestamp() - devices::LIVE_WITHIN_SECS;
+    let mut device: Option<String> = None;
+    let mut watched = WATCHED.lock().unwrap_or_else(|e| e.into_inner());
+    for (source, path) in &paths {
+        let key = path.to_string_lossy().to_string();
+        let Some(stamp) = file_stamp(path) else {
+            continue;
+        };
+        if known.get(&key) != Some(&stamp) {
+            if let Some(mut session) = scan_file(path, stamp) {
+                if source == claude::LOCAL_SOURCE
+                    && runs_here(&session, watched.get(&key).copied(), live_since)
+                {
+                    session.device = Some(device.get_or_insert_with(devices::current).clone());
+                }
+                changed.push(session);
+            }
+        }
+        watched.insert(key.clone(), stamp.0);
+        seen.insert(key);
+    }
+    for key in known.keys().filter(|k| !seen.contains(*k)) {
+        watched.remove(key);
+    }
+    let removed: Vec<String> = known.into_keys().filter(|k| !seen.contains(k)).collect();
+
+    if !changed.is_empty() || !removed.is_empty() {
+        storage::update_session_index(&changed, &removed)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    /// Inclusive, YYYY-MM-DD
+    pub from: Option<String>,
+    /// Inclusive, YYYY-MM-DD
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionListItem {
+    pub session_id: String,
+    pub project: String,
+    pub project_path: String,
+    pub title: Option<String>,
+    pub first_message: Option<String>,
+    pub last_message: Option<String>,
+    pub duration_minutes: u64,
+    pub message_count: u64,
+    pub total_tokens: u64,
+    pub model_tokens: HashMap<String, u64>,
+}
+
+impl From<IndexedSession> for SessionListItem {
+    fn from(s: IndexedSession) -> Self {
+        let rfc3339 = |t: Option<i64>| {
+            t.and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                .map(|t| t.to_rfc3339())
+        };
+        let duration_minutes = match (s.first_message_at, s.last_message_at) {
+            (Some(f), Some(l)) => ((l - f).max(0) / 60) as u64,
+            _ => 0,
+        };
+        SessionListItem {
+            session_id: s.session_id,
+            project: s.project,
+            project_path: s.project_path,
+            title: s.title,
+            first_message: rfc3339(s.first_message_at),
+            last_message: rfc3339(s.last_message_at),
+            duration_minutes,
+            message_count: s.message_count,
+            total_tokens: s.total_tokens,
+            model_tokens: s.model_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPage {
+    pub sessions: Vec<SessionListItem>,
+    /// Matching sessions across all pages
+    pub total: u64,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// Start of `date` as unix seconds, on the configured day boundary
+pub(crate) fn day_bound(clock: &DayClock, date: &str, next_day: bool) -> Result<i64, SprtError> {
+    let mut day = time::parse_date(date)
+        .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", date)))?;
+    if next_day {
+        day = day
+            .succ_opt()
+            .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", date)))?;
+    }
+    clock
+        .day_start(day)
+        .map(|t| t.timestamp())
+        .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", date)))
+}
+
+pub fn list(
+    offset: u32,
+    limit: u32,
+    project: Option<String>,
+    date_range: Option<DateRange>,
+    device: Option<String>,
+    sort: Option<String>,
+) -> Result<SessionPage, SprtError> {
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let clock = DayClock::load();
+    let range = date_range.unwrap_or_default();
+    let query = SessionQuery {
+        project: project.filter(|p| !p.is_empty()),
+        from: range.from.as_deref().map(|d| day_bound(&clock, d, false)).transpose()?,
+        to: range.to.as_deref().map(|d| day_bound(&clock, d, true)).transpose()?,
+        device: device.filter(|d| !d.is_empty()),
+        sort: sort.unwrap_or_default(),
+        offset: offset as u64,
+        limit: limit as u64,
+    };
+
+    refresh()?;
+    let (sessions, total) = storage::query_sessions(&query)?;
+    Ok(SessionPage {
+        sessions: sessions.into_iter().map(SessionListItem::from).collect(),
+        total,
+        offset,
+        limit,
+    })
+}
+
+/// Page through every session on record, newest first by default.
+/// sort: "recent", "oldest", "messages", "tokens" or "duration". device: a machine name, or
+/// "unknown" for sessions that couldn't be attributed.
+#[tauri::command]
+pub async fn list_sessions(
+    offset: Option<u32>,
+    limit: Option<u32>,
+    project: Option<String>,
+    date_range: Option<DateRange>,
+    device: Option<String>,
+    sort: Option<String>,
+) -> Result<SessionPage, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        list(
+            offset.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+            project,
+            date_range,
+            device,
+            sort,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture_log() -> PathBuf {
+        crate::paths::init_for_tests()
+            .claude_dir
+            .as_ref()
+            .unwrap()
+            .join("projects/-Users-dev-sprt-app/0b7d5c3e-1111-4a2b-9c3d-5e6f7a8b9c0d.jsonl")
+    }
+
+    fn ts(s: &str) -> i64 {
+        s.parse::<chrono::DateTime<chrono::Utc>>().unwrap().timestamp()
+    }
+
+    #[test]
+    fn scan_file_reads_fixture_session() {
+        let session = scan_file(&fixture_log(), (42, 7)).unwrap();
+        assert_eq!(session.session_id, "0b7d5c3e-1111-4a2b-9c3d-5e6f7a8b9c0d");
+        assert_eq!(session.project, "-Users-dev-sprt-app");
+        assert_eq!(session.project_path, "/Users/dev/sprt-app");
+        assert_eq!((session.file_size, session.modified_at), (42, 7));
+        assert_eq!(session.title.as_deref(), Some("Parse session logs from fixtures"));
+        // The truncated line is skipped; the subagent response counts
+        assert_eq!(session.message_count, 3);
+        assert_eq!(session.total_tokens, 1350 + 2 + 15);
+        assert_eq!(session.model_tokens["claude-sonnet-4-20250514"], 1352);
+        assert_eq!(session.model_tokens["claude-opus-4-20250514"], 15);
+        assert_eq!(session.first_message_at, Some(ts("2025-03-01T09:00:05Z")));
+        assert_eq!(session.last_message_at, Some(ts("2025-03-01T09:30:00Z")));
+    }
+
+    #[test]
+    fn scan_file_skips_missing_file() {
+        let path = fixture_log().with_file_name("missing.jsonl");
+        assert!(scan_file(&path, (0, 0)).is_none());
+    }
+}
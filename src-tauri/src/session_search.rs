@@ -0,0 +1,190 @@
+use crate::error::SprtError;
+use crate::session_index::{self, DateRange, SessionListItem};
+use crate::storage::{self, SessionQuery};
+use crate::time::DayClock;
+use crate::transcript::{self, Block};
+
+use serde::Serialize;
+use std::io::BufRead;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+const SNIPPETS_PER_SESSION: usize = 3;
+/// Characters of context on each side of a match
+const SNIPPET_CONTEXT: usize = 60;
+/// Stop scanning after this long and return what was found, flagged as truncated
+const TIME_BUDGET: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    /// "user" or "assistant"
+    pub role: String,
+    /// RFC 3339
+    pub timestamp: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub session: SessionListItem,
+    /// Matching messages in the session
+    pub matches: u64,
+    pub snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub query: String,
+    /// Newest first
+    pub hits: Vec<SearchHit>,
+    pub sessions_scanned: u64,
+    /// More sessions may match: the result limit or time budget was reached
+    pub truncated: bool,
+}
+
+/// `text` around the first match, cut on char boundaries and collapsed to one line
+fn snippet(text: &str, lower: &str, term: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    // Lowercasing can change byte lengths, so locate the match by char index
+    let at = lower
+        .find(term)
+        .map(|b| lower[..b].chars().count())
+        .unwrap_or(0)
+        .min(chars.len());
+    let start = at.saturating_sub(SNIPPET_CONTEXT);
+    let end = (at + term.chars().count() + SNIPPET_CONTEXT).min(chars.len());
+    let body: String = chars[start..end].iter().collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        body,
+        if end < chars.len() { "…" } else { "" }
+    )
+}
+
+/// Matching messages in one session log. A message matches when its text (not tool
+/// input or output) contains every term, case-insensitively.
+fn search_file(path: &str, terms: &[String]) -> (u64, Vec<Snippet>) {
+    let reader = match transcript::open(Path::new(path)) {
+        Ok(reader) => reader,
+        Err(e) => {
+            log::warn!("Cannot search {path}: {e}");
+            return (0, vec![]);
+        }
+    };
+    let mut matches = 0;
+    let mut snippets = vec![];
+    for line in reader.lines().map_while(Result::ok) {
+        // Cheap check on the raw line before parsing any JSON
+        let raw = line.to_lowercase();
+        if !terms.iter().all(|t| raw.contains(t.as_str())) {
+            continue;
+        }
+        for m in transcript::parse(line.as_bytes()) {
+            let text: Vec<&str> = m
+                .blocks
+                .iter()
+                .filter_map(|b| match b {
+                    Block::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            let text = text.join("\n");
+            let lower = text.to_lowercase();
+            if !terms.iter().all(|t| lower.contains(t.as_str())) {
+                continue;
+            }
+            matches += 1;
+            if snippets.len() < SNIPPETS_PER_SESSION {
+                snippets.push(Snippet {
+                    role: m.role,
+                    timestamp: m.timestamp,
+                    text: snippet(&text, &lower, &terms[0]),
+                });
+            }
+        }
+    }
+    (matches, snippets)
+}
+
+pub fn search(
+    query: &str,
+    date_range: Option<DateRange>,
+    project: Option<String>,
+    limit: u32,
+) -> Result<SearchResults, SprtError> {
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Err(SprtError::InvalidInput("Search query is empty".to_string()));
+    }
+    let limit = limit.clamp(1, MAX_LIMIT) as usize;
+    let clock = DayClock::load();
+    let range = date_range.unwrap_or_default();
+    let bound = |d: Option<&str>, next_day| {
+        d.map(|d| session_index::day_bound(&clock, d, next_day))
+            .transpose()
+    };
+    let q = SessionQuery {
+        project: project.filter(|p| !p.is_empty()),
+        from: bound(range.from.as_deref(), false)?,
+        to: bound(range.to.as_deref(), true)?,
+        device: None,
+        sort: "recent".to_string(),
+        offset: 0,
+        limit: i64::MAX as u64,
+    };
+
+    session_index::refresh()?;
+    let (sessions, _) = storage::query_sessions(&q)?;
+    let archived = storage::archived_session_paths()?;
+    let started = Instant::now();
+    let mut hits = vec![];
+    let mut sessions_scanned = 0;
+    let mut truncated = false;
+    for s in sessions {
+        if hits.len() >= limit || started.elapsed() > TIME_BUDGET {
+            truncated = true;
+            break;
+        }
+        sessions_scanned += 1;
+        let path = transcript::readable_path(&s.path, archived.get(&s.path).map(String::as_str));
+        let (matches, snippets) = search_file(&path, &terms);
+        if matches > 0 {
+            hits.push(SearchHit {
+                session: SessionListItem::from(s),
+                matches,
+                snippets,
+            });
+        }
+    }
+
+    Ok(SearchResults {
+        query: query.to_string(),
+        hits,
+        sessions_scanned,
+        truncated,
+    })
+}
+
+/// Sessions whose user or assistant messages contain every word of `query`, newest first,
+/// with a few snippets each. Scans the logs directly, newest session first, and stops at
+/// `limit` results (default 50) or after 10 seconds.
+#[tauri::command]
+pub async fn search_sessions(
+    query: String,
+    date_range: Option<DateRange>,
+    project: Option<String>,
+    limit: Option<u32>,
+) -> Result<SearchResults, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        search(&query, date_range, project, limit.unwrap_or(DEFAULT_LIMIT))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,598 @@
+use crate::error::SprtError;
+use crate::paths::DataPaths;
+use crate::storage::{sprt_dir, write_atomic};
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// User settings persisted at ~/.claude/sprt/settings.json.
+/// Every field has a default so older settings files keep loading as new options are added.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Extra git repos to include in activity even if Claude never touched them
+    pub watched_repos: Vec<String>,
+    /// Optional root directory scanned for .git folders (e.g. ~/code)
+    pub repo_scan_root: Option<String>,
+    /// Only count commits by these author emails. Empty = each repo's configured user.email
+    pub author_emails: Vec<String>,
+    /// GitHub personal access token; enables PR/issue activity in devlogs when set.
+    /// Moved to the keychain on save — read it with secrets::resolve.
+    pub github_token: Option<String>,
+    /// Linear/Jira credentials for resolving issue keys in commit messages
+    pub issue_trackers: IssueTrackerSettings,
+    /// Destinations that receive each newly generated devlog. URLs are moved to the keychain
+    /// on save — read them with secrets::webhook_url.
+    pub webhooks: Vec<WebhookConfig>,
+    /// Notion database that receives each newly generated devlog as a page
+    pub notion: NotionSettings,
+    /// Weekly devlog by email every Monday
+    pub email: EmailSettings,
+    /// Encrypt stored devlogs and exports in the SPRT directory (for synced folders)
+    pub encryption: EncryptionSettings,
+    /// Scrubbing applied to every prompt before it is sent to the API
+    pub privacy: PrivacySettings,
+    /// How long each kind of data is kept before the cleanup task removes it
+    pub retention: RetentionSettings,
+    /// Local HTTP listener (127.0.0.1 only)
+    pub server: ServerSettings,
+    /// Do-not-disturb window: notifications suppressed, polling slowed down
+    pub quiet_hours: QuietHours,
+    /// Local hour (0–23) when a new day starts for stats, sessions, devlogs and git activity.
+    /// 4 keeps late-night work on the day it started; 0 is plain midnight.
+    pub day_rollover_hour: u32,
+    /// Notify when rate limits are exhausted and usage starts drawing on overage
+    pub notify_on_overage: bool,
+    /// Badges for milestones like a 7-day streak (see achievements.rs)
+    pub achievements: AchievementSettings,
+    /// Other AI coding CLIs whose usage is tracked alongside Claude Code
+    pub providers: ProviderSettings,
+    /// More Claude data directories to read alongside this machine's (network mounts,
+    /// rsynced copies); sessions from them are labeled with their source
+    pub extra_claude_dirs: Vec<ExtraClaudeDir>,
+    /// Name recorded for this machine's sessions. None = the hostname.
+    pub device_name: Option<String>,
+    /// Monthly subscription price for value reports. None = the plan's list price.
+    pub subscription_usd: Option<f64>,
+    /// Weekly usage budget with per-day pacing
+    pub budget: BudgetSettings,
+    /// Message gaps longer than this (minutes) are idle time, not active work. None = 15.
+    pub idle_gap_minutes: Option<u32>,
+    /// Reminder to commit during long sessions that haven't produced one
+    pub commit_nudge: CommitNudgeSettings,
+    /// When a session counts as ended, and whether to announce it
+    pub session_end: SessionEndSettings,
+    /// Notification summarizing yesterday against the previous week
+    pub morning_briefing: MorningBriefingSettings,
+    /// Most rate-limit API requests per day; forced refreshes are exempt. None = 240.
+    pub rate_limit_probe_cap: Option<u32>,
+    /// Background fetching of rate limits, so the tray is filled without opening a window
+    pub rate_limit_polling: RateLimitPollingSettings,
+    /// Slower background work on battery (see power.rs)
+    pub low_power: LowPowerSettings,
+    /// Timeout and retries for every outgoing HTTP request
+    pub http: HttpSettings,
+    /// Generated devlogs are drafts: nothing is stored or delivered until approved
+    pub devlog_drafts: bool,
+    /// Busy days summarize each project with a cheaper model before composing the devlog
+    pub two_stage_devlogs: TwoStageSettings,
+    /// Models behind devlogs, insights and release notes; checked against the models endpoint
+    pub devlog_model: DevlogModelSettings,
+    /// Tray icon as a mini bar chart: "tokens" (per hour, last 12h) or "utilization"
+    /// (5-hour limit over the last 5h). None = the normal icon.
+    pub tray_chart: Option<String>,
+    /// Tray title: "utilization" (5-hour %) or "remaining" (estimated Sonnet/Opus messages
+    /// left in the window). None = utilization.
+    pub tray_text: Option<String>,
+    /// Second tray icon dedicated to rate limits
+    pub limits_tray: LimitsTraySettings,
+    /// Dashboard window placement and launch behavior
+    pub window: WindowSettings,
+    /// macOS Dock icon and badge
+    pub dock: DockSettings,
+    /// App used by "Open in editor", e.g. "Visual Studio Code" (macOS app name) or "code".
+    /// None = the system default for folders.
+    pub editor: Option<String>,
+    /// Terminal used by "Open in terminal", e.g. "iTerm" or "alacritty". None = Terminal on
+    /// macOS, cmd on Windows, x-terminal-emulator on Linux.
+    pub terminal: Option<String>,
+    /// Tray "Recent Projects" click: "dashboard" (project view), "folder" or "editor"
+    pub recent_project_action: Option<String>,
+    /// Log verbosity for ~/.claude/sprt/logs: "error", "warn", "info" or "debug". None = info.
+    pub log_level: Option<String>,
+    /// Run on generated sample data instead of ~/.claude (takes effect at next launch;
+    /// $SPRT_DEMO=1 does the same)
+    pub demo_mode: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DockSettings {
+    /// Show the app in the Dock instead of the menu bar only
+    pub visible: bool,
+    /// Badge while visible: "utilization" (5-hour %) or "devlogs" (generated since the
+    /// dashboard was last opened). None = no badge.
+    pub badge: Option<String>,
+    /// When the dashboard was last opened, RFC 3339; maintained by the app
+    pub devlogs_seen_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowSettings {
+    /// Launch with only the tray icon, even if the dashboard was open at last quit
+    pub start_hidden: bool,
+    /// Whether the dashboard was open when the app last quit; maintained by the app
+    pub dashboard_open: bool,
+    /// Last dashboard position and size; None = centered at the default size
+    pub bounds: Option<WindowBounds>,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings {
+            start_hidden: true,
+            dashboard_open: false,
+            bounds: None,
+        }
+    }
+}
+
+/// Physical pixels
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LimitsTraySettings {
+    pub enabled: bool,
+    /// Title template: {5h}, {7d} and {sonnet} are utilization percents, {reset} the time
+    /// until the 5-hour window resets
+    pub format: String,
+    /// Left-click action: "popover", "dashboard" or "refresh" (re-fetch limits now)
+    pub click: String,
+}
+
+impl Default for LimitsTraySettings {
+    fn default() -> Self {
+        LimitsTraySettings {
+            enabled: false,
+            format: "5h {5h}% · 7d {7d}%".to_string(),
+            click: "popover".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpSettings {
+    /// Per-request timeout, seconds. None = 60.
+    pub timeout_secs: Option<u64>,
+    /// Retries after a timeout, connection failure, 429 or 5xx. None = 3, 0 disables.
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitNudgeSettings {
+    pub enabled: bool,
+    /// Session length (hours) without a commit in its repo before nudging
+    pub after_hours: f64,
+}
+
+impl Default for CommitNudgeSettings {
+    fn default() -> Self {
+        CommitNudgeSettings {
+            enabled: true,
+            after_hours: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AchievementSettings {
+    /// Notification and tray flourish on unlock; badges are recorded either way
+    pub notify: bool,
+}
+
+impl Default for AchievementSettings {
+    fn default() -> Self {
+        AchievementSettings { notify: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LowPowerSettings {
+    /// On battery or in Low Power Mode: tray updates pause, rate-limit polling slows down and
+    /// background session-index refreshes wait for AC power
+    pub enabled: bool,
+}
+
+impl Default for LowPowerSettings {
+    fn default() -> Self {
+        LowPowerSettings { enabled: true }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RateLimitPollingSettings {
+    pub enabled: bool,
+    /// Seconds between fetches (at least 30). None = back off from 1 to 10 minutes as
+    /// utilization drops. Quiet hours stretch either to 15 minutes.
+    pub interval_secs: Option<u64>,
+    /// After sleep, refetch even if the cached limits still look fresh or the daily cap is used up
+    pub force_on_wake: bool,
+}
+
+impl Default for RateLimitPollingSettings {
+    fn default() -> Self {
+        RateLimitPollingSettings {
+            enabled: true,
+            interval_secs: None,
+            force_on_wake: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionEndSettings {
+    /// Minutes without a new message before a session is considered ended
+    pub quiet_minutes: u32,
+    /// Notify with the session's duration, tokens and cost when it ends
+    pub notify: bool,
+    /// Shorter sessions are recorded but not announced
+    pub min_minutes: u32,
+}
+
+impl Default for SessionEndSettings {
+    fn default() -> Self {
+        SessionEndSettings {
+            quiet_minutes: 15,
+            notify: false,
+            min_minutes: 5,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderSettings {
+    /// OpenAI Codex CLI (~/.codex/sessions)
+    pub codex: bool,
+    /// Gemini CLI (~/.gemini/tmp/*/chats)
+    pub gemini: bool,
+}
+
+impl Default for ProviderSettings {
+    fn default() -> Self {
+        ProviderSettings {
+            codex: true,
+            gemini: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExtraClaudeDir {
+    /// A directory laid out like ~/.claude; "~/" is expanded
+    pub path: String,
+    /// Source label. None = the directory's name.
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MorningBriefingSettings {
+    pub enabled: bool,
+    /// Local hour (0–23) from which the briefing is sent
+    pub hour: u32,
+    /// Day key of the last briefing, so each day gets one
+    pub last_sent: Option<String>,
+}
+
+impl Default for MorningBriefingSettings {
+    fn default() -> Self {
+        MorningBriefingSettings {
+            enabled: false,
+            hour: 9,
+            last_sent: None,
+        }
+    }
+}
+
+/// Set one limit; tokens win when both are set. Neither set = budgeting off.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BudgetSettings {
+    /// Tokens (input + output + cache) per week
+    pub weekly_tokens: Option<u64>,
+    /// Percent of the 7-day rate limit to stay under, 0–100
+    pub weekly_utilization_pct: Option<f64>,
+    /// Notify once a day when today goes over its share
+    pub notify: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// Local time "HH:MM"; a start after the end spans midnight
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QuietHours {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "08:00".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerSettings {
+    /// Serve Prometheus metrics at /metrics
+    pub metrics_enabled: bool,
+    /// Serve the authenticated scripting API (/stats, /rate-limits, /devlogs/generate)
+    pub api_enabled: bool,
+    /// Accept editor heartbeats from WakaTime plugins pointed at this server
+    /// (api_url = http://127.0.0.1:<port>/api/v1, api_key = api_token); see editor.rs
+    pub heartbeats_enabled: bool,
+    /// Bearer token for the API and heartbeats; generated on first start when missing.
+    /// Moved to the keychain on save — read it with secrets::api_token.
+    pub api_token: Option<String>,
+    /// Listen port; None uses server::DEFAULT_PORT
+    pub port: Option<u16>,
+}
+
+/// Trackers asked for the titles of issue keys ("ABC-123") found in commit messages;
+/// GitHub "#123" references use github_token
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IssueTrackerSettings {
+    /// Linear personal API key (moved to the keychain on save)
+    pub linear_api_key: Option<String>,
+    /// Jira site, e.g. https://acme.atlassian.net
+    pub jira_url: Option<String>,
+    pub jira_email: Option<String>,
+    /// Jira API token for jira_email (moved to the keychain on save)
+    pub jira_token: Option<String>,
+    /// Project prefixes ("ABC", "WEB") that issue keys must use. Empty accepts any prefix
+    /// except common acronyms like "UTF-8" or "SHA-256".
+    pub project_keys: Vec<String>,
+}
+
+/// Retention in days per data kind. None keeps data forever, the default for everything
+/// except rate-limit history.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionSettings {
+    pub daily_logs_days: Option<u32>,
+    pub weekly_logs_days: Option<u32>,
+    pub monthly_logs_days: Option<u32>,
+    /// Archives under ~/.claude/sprt/backups
+    pub backups_days: Option<u32>,
+    /// Limit threshold crossings on the timeline and finished overage episodes. Default 30.
+    pub rate_limit_history_days: Option<u32>,
+    /// Timeline events other than limit crossings
+    pub timeline_days: Option<u32>,
+    /// Daily stats-cache.json snapshots behind the stats history
+    pub stats_snapshots_days: Option<u32>,
+    /// Session-end summaries
+    pub session_history_days: Option<u32>,
+    /// Raw editor heartbeats; stored devlogs keep the hours computed from them. Default 90.
+    pub editor_heartbeats_days: Option<u32>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        RetentionSettings {
+            daily_logs_days: None,
+            weekly_logs_days: None,
+            monthly_logs_days: None,
+            backups_days: None,
+            rate_limit_history_days: Some(30),
+            timeline_days: None,
+            stats_snapshots_days: None,
+            session_history_days: None,
+            editor_heartbeats_days: Some(90),
+        }
+    }
+}
+
+/// The database needs these properties: Name (title), Date (date), Type (select),
+/// Score, Commits and Tokens (number), Key (text; used to replace regenerated logs)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotionSettings {
+    pub enabled: bool,
+    /// Internal integration token; the database must be shared with the integration.
+    /// Moved to the keychain on save.
+    pub token: Option<String>,
+    pub database_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TwoStageSettings {
+    /// "auto" (days with at least `min_projects` projects), "always" or "off"
+    pub mode: String,
+    pub min_projects: u32,
+}
+
+impl Default for TwoStageSettings {
+    fn default() -> Self {
+        TwoStageSettings {
+            mode: "auto".to_string(),
+            min_projects: 6,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DevlogModelSettings {
+    /// Writes the devlog. None = claude-sonnet-4-20250514
+    pub model: Option<String>,
+    /// Response cap for those calls. None = 2048
+    pub max_tokens: Option<u32>,
+    /// Per-project summaries in two-stage generation. None = claude-3-5-haiku-20241022
+    pub summary_model: Option<String>,
+}
+
+/// Preview the result with devlog::preview_prompt
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacySettings {
+    /// Replace project and repo names with "project-N"; names are restored in the answer
+    pub pseudonymize_projects: bool,
+    /// Regexes whose matches are replaced with "[redacted]" (customer names, hostnames, ...)
+    pub redact_patterns: Vec<String>,
+    /// Send only commit subject lines, never bodies
+    pub strip_commit_bodies: bool,
+}
+
+/// The passphrase lives in the OS keychain, never here; see crypto.rs
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EncryptionSettings {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailSettings {
+    pub enabled: bool,
+    pub smtp_host: Option<String>,
+    /// None = the default for `security` (587 STARTTLS, 465 TLS, 25 plain)
+    pub smtp_port: Option<u16>,
+    /// "starttls", "tls" (implicit TLS) or "none"
+    pub security: String,
+    pub username: Option<String>,
+    /// Moved to the keychain on save
+    pub password: Option<String>,
+    /// Sender address. None = username.
+    pub from: Option<String>,
+    pub recipients: Vec<EmailRecipient>,
+    /// Local hour (0–23) on Monday from which the email is sent
+    pub hour: u32,
+    /// Day key of the last Monday sent, so each week gets one
+    pub last_sent: Option<String>,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        EmailSettings {
+            enabled: false,
+            smtp_host: None,
+            smtp_port: None,
+            security: "starttls".to_string(),
+            username: None,
+            password: None,
+            from: None,
+            recipients: vec![],
+            hour: 9,
+            last_sent: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailRecipient {
+    pub address: String,
+    pub name: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Summary and highlights only, without project notes and stats (e.g. for a manager)
+    #[serde(default)]
+    pub summary_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    /// Key of the URL in the keychain; assigned on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Empty once moved to the keychain
+    #[serde(default)]
+    pub url: String,
+    /// Payload format: "json", "slack", or "discord"
+    pub kind: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn settings_path() -> Option<PathBuf> {
+    sprt_dir().map(|d| d.join("settings.json"))
+}
+
+/// When settings.json last changed, for caches of values derived from settings
+pub fn modified() -> Option<std::time::SystemTime> {
+    fs::metadata(settings_path()?).and_then(|m| m.modified()).ok()
+}
+
+/// Load settings, falling back to defaults when the file is missing or unreadable.
+/// In demo mode these are the demo copy's, except demoMode, which comes from the real file.
+pub fn load() -> Settings {
+    let mut settings: Settings = settings_path()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    if crate::demo::active() {
+        settings.demo_mode = crate::demo::flag(&DataPaths::real_from_env());
+    }
+    settings
+}
+
+/// Tokens and passwords are moved to the keychain (see secrets.rs), never written here
+pub fn save(settings: &Settings) -> Result<(), SprtError> {
+    let path = settings_path()
+        .ok_or_else(|| SprtError::Storage("Cannot determine storage directory".to_string()))?;
+    let mut settings = settings.clone();
+    crate::secrets::take_from_settings(&mut settings);
+    crate::secrets::forget_removed_webhooks(&load().webhooks, &settings.webhooks);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| SprtError::Storage(format!("Cannot create directory: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| SprtError::Internal(format!("Serialize error: {}", e)))?;
+    write_atomic(&path, &content)
+}
+
+#[tauri::command]
+pub fn get_settings() -> Settings {
+    load()
+}
+
+#[tauri::command]
+pub fn update_settings(settings: Settings) -> Result<Settings, SprtError> {
+    // The demo's settings file is thrown away; demoMode has to land in the real one
+    if crate::demo::active() {
+        crate::demo::set_flag(&DataPaths::real_from_env(), settings.demo_mode)?;
+    }
+    save(&settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,144 @@
+use crate::error::SprtError;
+use crate::git::{self, GitActivity};
+use crate::insights;
+use crate::storage::{self, DevLog};
+use crate::time::{self, DayClock};
+
+use serde::Serialize;
+
+/// How far back to look for the last day with activity (covers weekends and days off)
+const MAX_LOOKBACK_DAYS: i64 = 7;
+/// Items per section
+const MAX_ITEMS: usize = 5;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Standup {
+    pub date: String,
+    /// Day the "yesterday" section covers: the last day before `date` with activity
+    pub previous_date: Option<String>,
+    pub yesterday: Vec<String>,
+    pub today: Vec<String>,
+    pub blockers: Vec<String>,
+    /// Plain-text rendering, ready to paste
+    pub text: String,
+}
+
+fn commit_lines(activity: &[GitActivity]) -> Vec<String> {
+    activity
+        .iter()
+        .flat_map(|a| {
+            a.commits
+                .iter()
+                .map(move |c| format!("{}: {}", a.repo_name, c.message))
+        })
+        .collect()
+}
+
+fn yesterday_items(log: Option<&DevLog>, commits: &[GitActivity]) -> Vec<String> {
+    if let Some(log) = log.filter(|l| !l.highlights.is_empty()) {
+        return log.highlights.iter().take(MAX_ITEMS).cloned().collect();
+    }
+    commit_lines(commits).into_iter().take(MAX_ITEMS).collect()
+}
+
+/// What's already happened today, else carry on with yesterday's busiest projects
+fn today_items(today_commits: &[GitActivity], previous: &[GitActivity], log: Option<&DevLog>) -> Vec<String> {
+    let done = commit_lines(today_commits);
+    if !done.is_empty() {
+        return done.into_iter().take(MAX_ITEMS).collect();
+    }
+    let mut projects: Vec<(String, u32)> = match log {
+        Some(l) => l
+            .projects_worked
+            .iter()
+            .map(|p| (p.name.clone(), p.commits + p.messages as u32))
+            .collect(),
+        None => previous
+            .iter()
+            .map(|a| (a.repo_name.clone(), a.commits.len() as u32))
+            .collect(),
+    };
+    projects.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+    projects
+        .into_iter()
+        .take(3)
+        .map(|(name, _)| format!("Continue on {}", name))
+        .collect()
+}
+
+fn render(s: &Standup) -> String {
+    let section = |title: &str, items: &[String], empty: &str| {
+        let mut out = format!("{}:\n", title);
+        if items.is_empty() {
+            out.push_str(&format!("- {}\n", empty));
+        }
+        for item in items {
+            out.push_str(&format!("- {}\n", item));
+        }
+        out
+    };
+    format!(
+        "{}\n{}\n{}",
+        section("Yesterday", &s.yesterday, "No recorded activity"),
+        section("Today", &s.today, "Planning"),
+        section("Blockers", &s.blockers, "None"),
+    )
+    .trim_end()
+    .to_string()
+}
+
+pub fn standup(date: Option<&str>) -> Result<Standup, SprtError> {
+    let clock = DayClock::load();
+    let day = match date {
+        Some(d) => time::parse_date(d).ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", d)))?,
+        None => clock.today(),
+    };
+
+    let mut previous = None;
+    for back in 1..=MAX_LOOKBACK_DAYS {
+        let key = time::format_date(day - chrono::Duration::days(back));
+        let log = storage::get_devlog(&key, "daily")?;
+        let commits = git::collect_git_activity(&key);
+        if log.is_some() || !commits.is_empty() {
+            previous = Some((key, log, commits));
+            break;
+        }
+    }
+    let (previous_date, log, previous_commits) = match previous {
+        Some((d, l, c)) => (Some(d), l, c),
+        None => (None, None, vec![]),
+    };
+    let today_commits = git::collect_git_activity(&time::format_date(day));
+
+    // Limits only mean something for the current day
+    let blockers = if day == clock.today() {
+        insights::insights()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|i| i.severity == "warning")
+            .map(|i| i.title)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let mut standup = Standup {
+        date: time::format_date(day),
+        previous_date,
+        yesterday: yesterday_items(log.as_ref(), &previous_commits),
+        today: today_items(&today_commits, &previous_commits, log.as_ref()),
+        blockers,
+        text: String::new(),
+    };
+    standup.text = render(&standup);
+    Ok(standup)
+}
+
+/// Short yesterday / today / blockers summary for `date` (default today). No AI call.
+#[tauri::command]
+pub async fn generate_standup(date: Option<String>) -> Result<Standup, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || standup(date.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
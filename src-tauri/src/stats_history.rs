@@ -0,0 +1,201 @@
+use crate::claude::{self, StatsCache};
+use crate::error::SprtError;
+use crate::storage::{self, StatsSnapshot};
+use crate::time::{self, DayClock};
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyTimeSaved {
+    pub date: String,
+    pub saved_ms: u64,
+    pub saved_pretty: String,
+}
+
+/// Time saved by speculative decoding. Today and week stay None until a snapshot from before
+/// the period exists to diff against.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSaved {
+    pub total_ms: u64,
+    pub total_pretty: String,
+    pub today_ms: Option<u64>,
+    pub today_pretty: Option<String>,
+    pub week_ms: Option<u64>,
+    pub week_pretty: Option<String>,
+    /// One entry per snapshot after the first, oldest first
+    pub daily: Vec<DailyTimeSaved>,
+}
+
+/// Usage between one snapshot and the previous one, attributed to the later snapshot's date
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsDelta {
+    pub date: String,
+    pub sessions: u64,
+    pub messages: u64,
+    pub total_tokens: u64,
+    pub model_tokens: HashMap<String, u64>,
+    pub speculation_time_saved_ms: u64,
+}
+
+/// "2h 05m", "4m 10s", "12s", "350ms"
+pub fn format_duration_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (h, m, s) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m", h, m)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else if secs > 0 {
+        format!("{}s", s)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+/// Save today's snapshot of the cumulative totals in `cache`
+pub fn record_snapshot(cache: &StatsCache) -> Result<(), SprtError> {
+    // Missing or never-computed stats-cache.json; a zero baseline would make the next diff
+    // look like all-time usage happened in one day
+    if cache.last_computed_date.is_none() {
+        return Ok(());
+    }
+    storage::save_stats_snapshot(&StatsSnapshot {
+        date: DayClock::load().today_key(),
+        taken_at: chrono::Utc::now().to_rfc3339(),
+        speculation_time_saved_ms: cache.total_speculation_time_saved_ms.unwrap_or(0),
+        total_sessions: cache.total_sessions,
+        total_messages: cache.total_messages,
+        model_tokens: cache
+            .model_usage
+            .iter()
+            .map(|(model, u)| {
+                let total = u.input_tokens
+                    + u.output_tokens
+                    + u.cache_read_input_tokens.unwrap_or(0)
+                    + u.cache_creation_input_tokens.unwrap_or(0);
+                (model.clone(), total)
+            })
+            .collect(),
+    })
+}
+
+/// File watcher hook: snapshot whenever Claude Code rewrites stats-cache.json
+pub fn on_file_changed(path: &Path) {
+    if path.file_name().and_then(|n| n.to_str()) != Some("stats-cache.json") {
+        return;
+    }
+    if let Err(e) = claude::read_stats_cache().and_then(|c| record_snapshot(&c)) {
+        log::warn!("{e}");
+    }
+}
+
+/// Latest snapshot dated strictly before `date`
+fn baseline_before<'a>(snapshots: &'a [StatsSnapshot], date: &str) -> Option<&'a StatsSnapshot> {
+    snapshots.iter().rev().find(|s| s.date.as_str() < date)
+}
+
+pub fn time_saved() -> Result<TimeSaved, SprtError> {
+    let cache = claude::read_stats_cache()?;
+    record_snapshot(&cache)?;
+    let total_ms = cache.total_speculation_time_saved_ms.unwrap_or(0);
+    let snapshots = storage::list_stats_snapshots()?;
+
+    let today = DayClock::load().today();
+    let today_str = time::format_date(today);
+    let week_start = time::format_date(today - chrono::Duration::days(6));
+
+    // Counters can go backwards when Claude Code rebuilds its cache; treat that as no time saved
+    let since = |date: &str| {
+        baseline_before(&snapshots, date).map(|b| total_ms.saturating_sub(b.speculation_time_saved_ms))
+    };
+    let today_ms = since(&today_str);
+    let week_ms = since(&week_start).or_else(|| {
+        snapshots
+            .first()
+            .filter(|s| s.date < today_str)
+            .map(|s| total_ms.saturating_sub(s.speculation_time_saved_ms))
+    });
+
+    let daily = snapshots
+        .windows(2)
+        .map(|w| {
+            let saved_ms = w[1]
+                .speculation_time_saved_ms
+                .saturating_sub(w[0].speculation_time_saved_ms);
+            DailyTimeSaved {
+                date: w[1].date.clone(),
+                saved_ms,
+                saved_pretty: format_duration_ms(saved_ms),
+            }
+        })
+        .collect();
+
+    Ok(TimeSaved {
+        total_ms,
+        total_pretty: format_duration_ms(total_ms),
+        today_ms,
+        today_pretty: today_ms.map(format_duration_ms),
+        week_ms,
+        week_pretty: week_ms.map(format_duration_ms),
+        daily,
+    })
+}
+
+fn delta(prev: &StatsSnapshot, cur: &StatsSnapshot) -> StatsDelta {
+    // A model missing from the previous snapshot is new that day; counters that went
+    // backwards (cache rebuilt) count as zero
+    let model_tokens: HashMap<String, u64> = cur
+        .model_tokens
+        .iter()
+        .map(|(model, &total)| {
+            let before = prev.model_tokens.get(model).copied().unwrap_or(0);
+            (model.clone(), total.saturating_sub(before))
+        })
+        .filter(|(_, t)| *t > 0)
+        .collect();
+    StatsDelta {
+        date: cur.date.clone(),
+        sessions: cur.total_sessions.saturating_sub(prev.total_sessions),
+        messages: cur.total_messages.saturating_sub(prev.total_messages),
+        total_tokens: model_tokens.values().sum(),
+        model_tokens,
+        speculation_time_saved_ms: cur
+            .speculation_time_saved_ms
+            .saturating_sub(prev.speculation_time_saved_ms),
+    }
+}
+
+/// Per-day deltas for the last `days` days, oldest first. Days without a snapshot are
+/// folded into the next day that has one.
+pub fn stats_history(days: u32) -> Result<Vec<StatsDelta>, SprtError> {
+    if let Ok(cache) = claude::read_stats_cache() {
+        record_snapshot(&cache)?;
+    }
+    let start = time::format_date(DayClock::load().today() - chrono::Duration::days(days.max(1) as i64 - 1));
+    let snapshots = storage::list_stats_snapshots()?;
+    Ok(snapshots
+        .windows(2)
+        .filter(|w| w[1].date >= start)
+        .map(|w| delta(&w[0], &w[1]))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_stats_history(days: Option<u32>) -> Result<Vec<StatsDelta>, SprtError> {
+    let days = days.unwrap_or(30);
+    tauri::async_runtime::spawn_blocking(move || stats_history(days))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_time_saved() -> Result<TimeSaved, SprtError> {
+    tauri::async_runtime::spawn_blocking(time_saved)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,256 @@
+use crate::error::SprtError;
+use crate::session_index::{self, DateRange};
+use crate::storage::{self, IndexedSession, SessionQuery};
+use crate::time::{self, DayClock};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const METRICS: &[&str] = &["sessions", "messages", "tokens", "hours"];
+const GROUPS: &[&str] = &["day", "project", "model", "device"];
+/// Filled-in days are capped so an open-ended range can't produce years of zero rows
+const MAX_DAY_ROWS: i64 = 366;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatsFilters {
+    /// Encoded dir name or decoded path
+    pub project: Option<String>,
+    /// Device name; "unknown" matches sessions without one
+    pub device: Option<String>,
+    /// Case-insensitive substring of the model id, e.g. "opus". Keeps sessions that used a
+    /// matching model; their tokens count only the matching models.
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatsQuery {
+    /// Any of "sessions", "messages", "tokens", "hours". Empty = all four.
+    pub metrics: Vec<String>,
+    /// "day", "project", "model" or "device". None = one row of totals.
+    pub group_by: Option<String>,
+    pub filters: StatsFilters,
+    /// None = all time
+    pub date_range: Option<DateRange>,
+    /// Keep the busiest N groups by the first metric; days are never cut
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsRow {
+    /// Date (YYYY-MM-DD), project path, model id or device name; "total" when ungrouped
+    pub key: String,
+    pub values: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsQueryResult {
+    pub metrics: Vec<String>,
+    pub group_by: Option<String>,
+    /// Days ascending (missing days filled with zeros when the range is bounded),
+    /// other groups busiest first
+    pub rows: Vec<StatsRow>,
+    /// Sum over all rows, including those cut by `limit`
+    pub totals: BTreeMap<String, f64>,
+}
+
+fn validate(query: &StatsQuery) -> Result<Vec<String>, SprtError> {
+    if let Some(g) = query.group_by.as_deref() {
+        if !GROUPS.contains(&g) {
+            return Err(SprtError::InvalidInput(format!(
+                "Unknown group_by '{}' (expected one of {})",
+                g,
+                GROUPS.join(", ")
+            )));
+        }
+    }
+    if let Some(m) = query
+        .metrics
+        .iter()
+        .find(|m| !METRICS.contains(&m.as_str()))
+    {
+        return Err(SprtError::InvalidInput(format!(
+            "Unknown metric '{}' (expected any of {})",
+            m,
+            METRICS.join(", ")
+        )));
+    }
+    Ok(if query.metrics.is_empty() {
+        METRICS.iter().map(|m| m.to_string()).collect()
+    } else {
+        query.metrics.clone()
+    })
+}
+
+/// A session's metrics scaled by `share` (its fraction of tokens, when split by model)
+fn add(
+    values: &mut BTreeMap<String, f64>,
+    metrics: &[String],
+    s: &IndexedSession,
+    tokens: u64,
+    share: f64,
+) {
+    for m in metrics {
+        let v = match m.as_str() {
+            "sessions" => 1.0,
+            "messages" => s.message_count as f64 * share,
+            "tokens" => tokens as f64,
+            "hours" => match (s.first_message_at, s.last_message_at) {
+                (Some(f), Some(l)) => (l - f).max(0) as f64 / 3600.0 * share,
+                _ => 0.0,
+            },
+            _ => 0.0,
+        };
+        *values.entry(m.clone()).or_insert(0.0) += v;
+    }
+}
+
+pub fn run(query: &StatsQuery) -> Result<StatsQueryResult, SprtError> {
+    let metrics = validate(query)?;
+    let clock = DayClock::load();
+    let range = query.date_range.clone().unwrap_or_default();
+    let from = range
+        .from
+        .as_deref()
+        .map(|d| session_index::day_bound(&clock, d, false))
+        .transpose()?;
+    let to = range
+        .to
+        .as_deref()
+        .map(|d| session_index::day_bound(&clock, d, true))
+        .transpose()?;
+
+    session_index::refresh()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: query.filters.project.clone().filter(|p| !p.is_empty()),
+        from,
+        to,
+        device: query.filters.device.clone().filter(|d| !d.is_empty()),
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: u32::MAX as u64,
+    })?;
+
+    let model_filter = query
+        .filters
+        .model
+        .as_deref()
+        .filter(|m| !m.is_empty())
+        .map(str::to_lowercase);
+    let model_matches = |model: &str| {
+        model_filter
+            .as_deref()
+            .is_none_or(|f| model.to_lowercase().contains(f))
+    };
+
+    let group_by = query.group_by.as_deref();
+    let mut groups: HashMap<String, BTreeMap<String, f64>> = HashMap::new();
+    for s in &sessions {
+        let models: Vec<(&String, u64)> = s
+            .model_tokens
+            .iter()
+            .filter(|(m, _)| model_matches(m))
+            .map(|(m, t)| (m, *t))
+            .collect();
+        if model_filter.is_some() && models.is_empty() {
+            continue;
+        }
+        let model_total: u64 = models.iter().map(|(_, t)| t).sum();
+        if group_by == Some("model") {
+            // Messages and hours are split by each model's share of the session's tokens
+            for (model, tokens) in models {
+                let share = if model_total > 0 {
+                    tokens as f64 / model_total as f64
+                } else {
+                    0.0
+                };
+                add(
+                    groups.entry(model.clone()).or_default(),
+                    &metrics,
+                    s,
+                    tokens,
+                    share,
+                );
+            }
+            continue;
+        }
+        let key = match group_by {
+            Some("day") => match s
+                .first_message_at
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+            {
+                Some(t) => clock.date_key(&t),
+                None => continue,
+            },
+            Some("project") => s.project_path.clone(),
+            Some("device") => s.device.clone().unwrap_or_else(|| "unknown".to_string()),
+            _ => "total".to_string(),
+        };
+        let tokens = if model_filter.is_some() {
+            model_total
+        } else {
+            s.total_tokens
+        };
+        add(groups.entry(key).or_default(), &metrics, s, tokens, 1.0);
+    }
+
+    let mut totals: BTreeMap<String, f64> = metrics.iter().map(|m| (m.clone(), 0.0)).collect();
+    for values in groups.values() {
+        for (m, v) in values {
+            *totals.entry(m.clone()).or_insert(0.0) += v;
+        }
+    }
+
+    if group_by == Some("day") {
+        let first = range.from.as_deref().and_then(time::parse_date);
+        let last = range
+            .to
+            .as_deref()
+            .and_then(time::parse_date)
+            .or_else(|| first.map(|_| clock.today()));
+        if let (Some(first), Some(last)) = (first, last) {
+            let days = (last - first).num_days().min(MAX_DAY_ROWS);
+            for offset in 0..=days {
+                let day = time::format_date(first + chrono::Duration::days(offset));
+                groups
+                    .entry(day)
+                    .or_insert_with(|| metrics.iter().map(|m| (m.clone(), 0.0)).collect());
+            }
+        }
+    }
+
+    let mut rows: Vec<StatsRow> = groups
+        .into_iter()
+        .map(|(key, values)| StatsRow { key, values })
+        .collect();
+    if group_by == Some("day") {
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        let first = &metrics[0];
+        let value = |r: &StatsRow| r.values.get(first).copied().unwrap_or(0.0);
+        rows.sort_by(|a, b| value(b).total_cmp(&value(a)).then(a.key.cmp(&b.key)));
+        if let Some(limit) = query.limit {
+            rows.truncate(limit as usize);
+        }
+    }
+
+    Ok(StatsQueryResult {
+        metrics,
+        group_by: query.group_by.clone(),
+        rows,
+        totals,
+    })
+}
+
+/// Aggregate the session index by a small structured query, for charts that don't need a
+/// dedicated command. Example: `{ metrics: ["tokens"], groupBy: "model",
+/// dateRange: { from: "2025-03-01" }, filters: { project: "/Users/me/app" } }`.
+#[tauri::command]
+pub async fn query_stats(query: StatsQuery) -> Result<StatsQueryResult, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || run(&query))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
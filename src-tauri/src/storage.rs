@@ -1,126 +1,1619 @@
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct DevLog {
-    pub id: String,
-    pub date: String,
-    pub log_type: String,
-    pub generated_at: String,
-    pub summary: String,
-    pub highlights: Vec<String>,
-    pub projects_worked: Vec<ProjectWork>,
-    pub stats: DevLogStats,
-    pub sprint_score: u32,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ProjectWork {
-    pub name: String,
-    pub path: String,
-    pub commits: u32,
-    pub messages: u64,
-    pub tokens: u64,
-    pub duration_minutes: u64,
-    pub key_changes: Vec<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct DevLogStats {
-    pub total_commits: u32,
-    pub total_messages: u64,
-    pub total_tokens: u64,
-    pub total_files_changed: u32,
-    pub total_insertions: u32,
-    pub total_deletions: u32,
-    pub active_hours: f64,
-    pub projects_count: u32,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SessionSummary {
-    pub session_id: String,
-    pub project: String,
-    pub project_path: String,
-    pub message_count: u64,
-    pub input_tokens: u64,
-    pub output_tokens: u64,
-    pub cache_read: u64,
-    pub duration_minutes: u64,
-    pub first_message: Option<String>,
-    pub last_message: Option<String>,
-}
-
-fn sprt_dir() -> Option<PathBuf> {
-    dirs::home_dir().map(|h| h.join(".claude").join("sprt"))
-}
-
-fn devlogs_dir(log_type: &str) -> Option<PathBuf> {
-    sprt_dir().map(|d| d.join("devlogs").join(log_type))
-}
-
-fn filename_for_log(date: &str, log_type: &str) -> String {
-    match log_type {
-        "monthly" => format!("{}.json", date.get(..7).unwrap_or(date)),
-        _ => format!("{}.json", date),
-    }
-}
-
-pub fn save_devlog(log: &DevLog) -> Result<(), String> {
-    let dir = devlogs_dir(&log.log_type).ok_or("Cannot determine storage directory")?;
-    fs::create_dir_all(&dir).map_err(|e| format!("Cannot create directory: {}", e))?;
-
-    let filename = filename_for_log(&log.date, &log.log_type);
-    let path = dir.join(filename);
-    let content = serde_json::to_string_pretty(log).map_err(|e| format!("Serialize error: {}", e))?;
-    fs::write(path, content).map_err(|e| format!("Write error: {}", e))
-}
-
-pub fn get_devlog(date: &str, log_type: &str) -> Result<Option<DevLog>, String> {
-    let dir = devlogs_dir(log_type).ok_or("Cannot determine storage directory")?;
-    let filename = filename_for_log(date, log_type);
-    let path = dir.join(filename);
-
-    if !path.exists() {
-        return Ok(None);
-    }
-
-    let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
-    let log: DevLog =
-        serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))?;
-    Ok(Some(log))
-}
-
-pub fn list_devlogs(log_type: &str, limit: usize) -> Result<Vec<DevLog>, String> {
-    let dir = devlogs_dir(log_type).ok_or("Cannot determine storage directory")?;
-    if !dir.exists() {
-        return Ok(vec![]);
-    }
-
-    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
-        .map_err(|e| format!("Read dir error: {}", e))?
-        .filter_map(|e| e.ok())
-        .map(|e| e.path())
-        .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
-        .collect();
-
-    // Sort by filename descending (newest first)
-    files.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
-    files.truncate(limit);
-
-    let mut logs = vec![];
-    for path in files {
-        if let Ok(content) = fs::read_to_string(&path) {
-            if let Ok(log) = serde_json::from_str::<DevLog>(&content) {
-                logs.push(log);
-            }
-        }
-    }
-
-    Ok(logs)
-}
+use crate::crypto;
+use crate::error::SprtError;
+use crate::github::GithubItem;
+use crate::issues::IssueRef;
+use crate::value::ValueReport;
+
+use serde::{Deserialize, Serialize};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevLog {
+    pub id: String,
+    pub date: String,
+    pub log_type: String,
+    pub generated_at: String,
+    pub summary: String,
+    pub highlights: Vec<String>,
+    pub projects_worked: Vec<ProjectWork>,
+    pub stats: DevLogStats,
+    pub sprint_score: u32,
+    #[serde(default)]
+    pub github_activity: Vec<GithubItem>,
+    /// How the day felt, when the user checked in (daily logs only)
+    #[serde(default)]
+    pub checkin: Option<Checkin>,
+    /// API-price value of the period's usage vs. the subscription fee (weekly logs only)
+    #[serde(default)]
+    pub value: Option<ValueReport>,
+    /// Issues referenced by the period's commits, resolved via issues.rs
+    #[serde(default)]
+    pub issues_touched: Vec<IssueRef>,
+    /// What was left out of the generation prompt to keep it in budget (daily logs only)
+    #[serde(default)]
+    pub prompt_truncation: Option<PromptTruncation>,
+    /// The day's plan and which items got done, as judged by the AI (daily logs only)
+    #[serde(default)]
+    pub plan_completion: Option<PlanCompletion>,
+    /// TODO / next-step lines from the day's sessions (daily logs only; see todos.rs)
+    #[serde(default)]
+    pub candidate_todos: Vec<CandidateTodo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CandidateTodo {
+    /// "<date>-<n>", unique across devlogs
+    pub id: String,
+    pub text: String,
+    pub project: String,
+    pub session_id: String,
+    /// When the assistant wrote it, RFC 3339
+    pub found_at: Option<String>,
+    pub done: bool,
+}
+
+/// Activity cut from a devlog prompt; the stats still cover everything
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PromptTruncation {
+    pub commits_omitted: u32,
+    /// Commit messages cut to devlog::MAX_COMMIT_MESSAGE_CHARS
+    pub commit_messages_shortened: u32,
+    pub sessions_omitted: u32,
+    pub github_items_omitted: u32,
+    /// Size of the prompt as sent, at ~4 characters per token
+    pub estimated_tokens: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectWork {
+    pub name: String,
+    pub path: String,
+    pub commits: u32,
+    pub messages: u64,
+    pub tokens: u64,
+    pub duration_minutes: u64,
+    pub key_changes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DevLogStats {
+    pub total_commits: u32,
+    pub total_messages: u64,
+    pub total_tokens: u64,
+    pub total_files_changed: u32,
+    pub total_insertions: u32,
+    pub total_deletions: u32,
+    pub active_hours: f64,
+    pub projects_count: u32,
+    #[serde(default)]
+    pub commit_messages: CommitMessageStats,
+    /// Machines with sessions in the period (see devices.rs)
+    #[serde(default)]
+    pub devices: Vec<String>,
+    /// Tokens from other AI CLIs ("codex", "gemini"); Claude's are total_tokens
+    #[serde(default)]
+    pub provider_tokens: HashMap<String, u64>,
+    /// Claude session time; equals active_hours minus manual_hours
+    #[serde(default)]
+    pub ai_assisted_hours: f64,
+    /// Editor activity outside Claude sessions (see editor.rs); 0 without editor heartbeats
+    #[serde(default)]
+    pub manual_hours: f64,
+}
+
+/// Git hygiene of the user's commit subjects (see analysis::commit_message_stats)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitMessageStats {
+    pub commits: u32,
+    /// Subject line, in characters
+    pub avg_length: f64,
+    /// `type(scope): subject` per Conventional Commits
+    pub conventional: u32,
+    pub wip: u32,
+    /// fixup!/squash!/amend! commits left unsquashed
+    pub fixup: u32,
+}
+
+impl CommitMessageStats {
+    pub fn merge(&mut self, other: &CommitMessageStats) {
+        let total = self.commits + other.commits;
+        if total > 0 {
+            self.avg_length = (self.avg_length * self.commits as f64
+                + other.avg_length * other.commits as f64)
+                / total as f64;
+        }
+        self.commits = total;
+        self.conventional += other.conventional;
+        self.wip += other.wip;
+        self.fixup += other.fixup;
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub project: String,
+    pub project_path: String,
+    pub message_count: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read: u64,
+    /// Wall clock: first to last message
+    pub duration_minutes: u64,
+    /// Wall clock minus idle gaps (see Settings::idle_gap_minutes)
+    #[serde(default)]
+    pub focused_minutes: u64,
+    pub first_message: Option<String>,
+    pub last_message: Option<String>,
+    /// Session topic: the session's summary record or first user prompt
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Messages and tokens (input + output + cache read) from Task-tool subagents,
+    /// already included in the totals above
+    #[serde(default)]
+    pub subagent_messages: u64,
+    #[serde(default)]
+    pub subagent_tokens: u64,
+    /// Data root label, "local" for this machine's
+    #[serde(default)]
+    pub source: String,
+}
+
+/// Cumulative stats-cache.json totals as last seen on `date`. Claude Code rewrites the file in
+/// place, so differences between successive snapshots are the only per-day history we get.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatsSnapshot {
+    pub date: String,
+    pub taken_at: String,
+    pub speculation_time_saved_ms: u64,
+    pub total_sessions: u64,
+    pub total_messages: u64,
+    /// All-time tokens (input + output + cache) per model
+    pub model_tokens: HashMap<String, u64>,
+}
+
+pub(crate) fn sprt_dir() -> Option<PathBuf> {
+    crate::paths::get().sprt_dir.clone()
+}
+
+// ── SQLite store (~/.claude/sprt/sprt.db) ──
+
+/// Schema migrations, applied in order. Index + 1 is the schema version; never edit a
+/// shipped entry — append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: devlogs keyed by (log_type, period), full DevLog kept as JSON
+    "CREATE TABLE devlogs (
+        log_type TEXT NOT NULL,
+        period TEXT NOT NULL,
+        id TEXT NOT NULL,
+        date TEXT NOT NULL,
+        generated_at TEXT NOT NULL,
+        sprint_score INTEGER NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (log_type, period)
+    );
+    CREATE INDEX devlogs_date ON devlogs (date);",
+    // 2: last stats-cache.json aggregates seen on each local date
+    "CREATE TABLE stats_snapshots (
+        date TEXT PRIMARY KEY,
+        taken_at TEXT NOT NULL,
+        data TEXT NOT NULL
+    );",
+    // 3: periods spent running on overage (extra usage) after hitting plan limits
+    "CREATE TABLE overage_episodes (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        started_at TEXT NOT NULL,
+        last_seen_at TEXT NOT NULL,
+        ended_at TEXT,
+        max_fallback_percentage REAL
+    );
+    CREATE INDEX overage_episodes_started ON overage_episodes (started_at);",
+    // 4: focus timer blocks, keyed to the day they started on
+    "CREATE TABLE focus_blocks (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        date TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        ended_at TEXT NOT NULL,
+        planned_minutes INTEGER NOT NULL,
+        completed INTEGER NOT NULL,
+        label TEXT
+    );
+    CREATE INDEX focus_blocks_date ON focus_blocks (date);",
+    // 5: one row per session JSONL file, rescanned when its size or mtime changes
+    "CREATE TABLE sessions (
+        path TEXT PRIMARY KEY,
+        session_id TEXT NOT NULL,
+        project TEXT NOT NULL,
+        project_path TEXT NOT NULL,
+        file_size INTEGER NOT NULL,
+        modified_at INTEGER NOT NULL,
+        first_message_at INTEGER,
+        last_message_at INTEGER,
+        message_count INTEGER NOT NULL,
+        total_tokens INTEGER NOT NULL,
+        model_tokens TEXT NOT NULL,
+        title TEXT
+    );
+    CREATE INDEX sessions_last_message ON sessions (last_message_at);
+    CREATE INDEX sessions_project ON sessions (project);",
+    // 6: where an archived session's file went; such rows outlive the original file
+    "ALTER TABLE sessions ADD COLUMN archived_path TEXT;",
+    // 7: activity timeline; keys derive from the source, so re-deriving an event overwrites it
+    "CREATE TABLE timeline_events (
+        key TEXT PRIMARY KEY,
+        at INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        title TEXT NOT NULL,
+        detail TEXT,
+        project TEXT
+    );
+    CREATE INDEX timeline_events_at ON timeline_events (at);",
+    // 8: one mood/energy check-in per local date
+    "CREATE TABLE checkins (
+        date TEXT PRIMARY KEY,
+        mood INTEGER NOT NULL,
+        energy INTEGER NOT NULL,
+        note TEXT,
+        updated_at TEXT NOT NULL
+    );",
+    // 9: user tags on projects (target = project path) and devlogs (target = "<type>:<date>")
+    "CREATE TABLE tags (
+        kind TEXT NOT NULL,
+        target TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (kind, target, tag)
+    );
+    CREATE INDEX tags_tag ON tags (tag);",
+    // 10: machine that indexed a session while it was live; NULL when it first appeared synced
+    "ALTER TABLE sessions ADD COLUMN device TEXT;",
+    // 11: editor heartbeats (WakaTime-compatible); resent offline heartbeats are ignored
+    "CREATE TABLE editor_heartbeats (
+        at INTEGER NOT NULL,
+        entity TEXT NOT NULL,
+        project TEXT,
+        editor TEXT NOT NULL,
+        is_write INTEGER NOT NULL,
+        PRIMARY KEY (at, entity)
+    );",
+    // 12: plan for a local date, usually written the evening before; items is a JSON array
+    "CREATE TABLE plans (
+        date TEXT PRIMARY KEY,
+        items TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );",
+    // 13: unlocked achievement badges (see achievements.rs); an unlock is never revoked
+    "CREATE TABLE achievements (
+        id TEXT PRIMARY KEY,
+        unlocked_at TEXT NOT NULL,
+        detail TEXT
+    );",
+    // 14: final summary of each session once it went quiet (see session_end.rs); a resumed
+    // session is summarized again when it ends
+    "CREATE TABLE session_history (
+        session_id TEXT PRIMARY KEY,
+        project_path TEXT NOT NULL,
+        title TEXT,
+        device TEXT,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER NOT NULL,
+        messages INTEGER NOT NULL,
+        tokens INTEGER NOT NULL,
+        cost_usd REAL NOT NULL
+    );
+    CREATE INDEX session_history_ended_at ON session_history (ended_at);",
+];
+
+static DB: LazyLock<Mutex<Option<Connection>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Apply pending migrations. Returns the schema version found before migrating.
+fn migrate(conn: &mut Connection) -> rusqlite::Result<usize> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        );",
+    )?;
+    let current: usize =
+        conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_migrations", [], |r| r.get(0))?;
+
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+            params![i + 1, chrono::Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+    }
+    Ok(current)
+}
+
+fn db_path() -> Option<PathBuf> {
+    sprt_dir().map(|d| d.join("sprt.db"))
+}
+
+fn open_db() -> Result<Connection, SprtError> {
+    let path = db_path().ok_or_else(|| storage_error("Cannot determine storage directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| storage_error(format!("Cannot create directory: {}", e)))?;
+    }
+
+    let mut conn = Connection::open(&path)
+        .map_err(|e| storage_error(format!("Cannot open database: {}", e)))?;
+    if let Err(e) = conn.pragma_update(None, "journal_mode", "WAL") {
+        log::warn!("Cannot enable WAL journal: {e}");
+    }
+    let previous = migrate(&mut conn).map_err(|e| storage_error(format!("Migration failed: {}", e)))?;
+
+    // Fresh database — bring in devlogs from the old per-file JSON store
+    if previous == 0 {
+        import_json_devlogs(&conn);
+    }
+    Ok(conn)
+}
+
+fn storage_error(message: impl Into<String>) -> SprtError {
+    SprtError::Storage(message.into())
+}
+
+/// Run `f` against the shared connection, opening (and migrating) it on first use
+pub(crate) fn with_db<T>(f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, SprtError> {
+    let mut guard = DB.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        *guard = Some(open_db()?);
+    }
+    let conn = guard.as_mut().ok_or_else(|| storage_error("Database unavailable"))?;
+    f(conn).map_err(|e| storage_error(format!("Database error: {}", e)))
+}
+
+/// (date, sprint score) for daily devlogs on or after `since`, oldest first.
+/// Reads only indexed columns, not the full DevLog payload.
+pub fn daily_scores_since(since: &str) -> Result<Vec<(String, u32)>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT date, sprint_score FROM devlogs
+             WHERE log_type = 'daily' AND date >= ?1 ORDER BY date ASC",
+        )?;
+        let rows = stmt.query_map(params![since], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect()
+    })
+}
+
+/// Delete devlogs of `log_type` dated before `cutoff` (YYYY-MM-DD). With `dry_run`, only count.
+/// Returns (rows, bytes of stored JSON).
+pub fn purge_devlogs(log_type: &str, cutoff: &str, dry_run: bool) -> Result<(u64, u64), SprtError> {
+    with_db(|conn| {
+        let (count, bytes): (u64, u64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(LENGTH(data)), 0) FROM devlogs
+             WHERE log_type = ?1 AND date < ?2",
+            params![log_type, cutoff],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        if !dry_run && count > 0 {
+            conn.execute(
+                "DELETE FROM devlogs WHERE log_type = ?1 AND date < ?2",
+                params![log_type, cutoff],
+            )?;
+        }
+        Ok((count, bytes))
+    })
+}
+
+/// Delete rows of `table` where `condition` holds (`?1` is the cutoff). Returns (rows,
+/// bytes), bytes being the summed size of the rows' values. Table and condition come from
+/// retention.rs, never from input.
+pub fn purge_rows(
+    table: &str,
+    condition: &str,
+    cutoff: rusqlite::types::Value,
+    dry_run: bool,
+) -> Result<(u64, u64), SprtError> {
+    with_db(|conn| {
+        let columns: Vec<String> = conn
+            .prepare(&format!("PRAGMA table_info({})", table))?
+            .query_map([], |r| r.get(1))?
+            .collect::<rusqlite::Result<_>>()?;
+        let size = columns
+            .iter()
+            .map(|c| format!("COALESCE(LENGTH(CAST({} AS BLOB)), 0)", c))
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let (count, bytes): (u64, u64) = conn.query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM({}), 0) FROM {} WHERE {}",
+                size, table, condition
+            ),
+            params![cutoff],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        if !dry_run && count > 0 {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE {}", table, condition),
+                params![cutoff],
+            )?;
+        }
+        Ok((count, bytes))
+    })
+}
+
+/// Return freed pages to the filesystem after large deletes
+pub fn vacuum_database() -> Result<(), SprtError> {
+    with_db(|conn| conn.execute_batch("VACUUM;"))
+}
+
+/// Write a consistent copy of the database to `dest` (which must not exist)
+pub fn snapshot_database(dest: &Path) -> Result<(), SprtError> {
+    let dest = dest.to_string_lossy().to_string();
+    with_db(|conn| conn.execute("VACUUM INTO ?1", params![dest]).map(|_| ()))
+}
+
+/// Replace the database with the file at `src`, then reopen (and migrate) it
+pub fn restore_database(src: &Path) -> Result<(), SprtError> {
+    // Validate before touching the live database
+    Connection::open(src)
+        .and_then(|c| c.query_row("SELECT COUNT(*) FROM schema_migrations", [], |r| r.get::<_, i64>(0)))
+        .map_err(|e| SprtError::InvalidInput(format!("Backup database is invalid: {}", e)))?;
+
+    let path = db_path().ok_or_else(|| storage_error("Cannot determine storage directory"))?;
+    let mut guard = DB.lock().unwrap_or_else(|e| e.into_inner());
+    // Close the current connection so its WAL is checkpointed and released
+    *guard = None;
+    let _ = fs::remove_file(with_suffix(&path, "-wal"));
+    let _ = fs::remove_file(with_suffix(&path, "-shm"));
+    fs::copy(src, &path).map_err(|e| storage_error(format!("Cannot restore database: {}", e)))?;
+    *guard = Some(open_db()?);
+    Ok(())
+}
+
+/// Store `snapshot`, replacing any earlier one for the same date
+pub fn save_stats_snapshot(snapshot: &StatsSnapshot) -> Result<(), SprtError> {
+    let data = serde_json::to_string(snapshot)
+        .map_err(|e| SprtError::Internal(format!("Serialize error: {}", e)))?;
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO stats_snapshots (date, taken_at, data) VALUES (?1, ?2, ?3)",
+            params![snapshot.date, snapshot.taken_at, data],
+        )
+        .map(|_| ())
+    })
+}
+
+/// All snapshots, oldest first
+pub fn list_stats_snapshots() -> Result<Vec<StatsSnapshot>, SprtError> {
+    let rows: Vec<String> = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT data FROM stats_snapshots ORDER BY date ASC")?;
+        let rows = stmt.query_map([], |r| r.get(0))?;
+        rows.collect()
+    })?;
+    Ok(rows.iter().filter_map(|d| serde_json::from_str(d).ok()).collect())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverageEpisode {
+    pub id: i64,
+    /// RFC 3339, UTC
+    pub started_at: String,
+    pub last_seen_at: String,
+    /// None while the episode is ongoing
+    pub ended_at: Option<String>,
+    pub max_fallback_percentage: Option<f64>,
+}
+
+fn overage_from_row(r: &rusqlite::Row) -> rusqlite::Result<OverageEpisode> {
+    Ok(OverageEpisode {
+        id: r.get(0)?,
+        started_at: r.get(1)?,
+        last_seen_at: r.get(2)?,
+        ended_at: r.get(3)?,
+        max_fallback_percentage: r.get(4)?,
+    })
+}
+
+/// The episode still in progress, if any
+pub fn open_overage_episode() -> Result<Option<OverageEpisode>, SprtError> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT id, started_at, last_seen_at, ended_at, max_fallback_percentage
+             FROM overage_episodes WHERE ended_at IS NULL ORDER BY id DESC LIMIT 1",
+            [],
+            overage_from_row,
+        )
+        .optional()
+    })
+}
+
+pub fn start_overage_episode(at: &str, fallback_percentage: Option<f64>) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO overage_episodes (started_at, last_seen_at, max_fallback_percentage)
+             VALUES (?1, ?1, ?2)",
+            params![at, fallback_percentage],
+        )
+        .map(|_| ())
+    })
+}
+
+pub fn touch_overage_episode(id: i64, at: &str, max_fallback_percentage: Option<f64>) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE overage_episodes SET last_seen_at = ?2, max_fallback_percentage = ?3 WHERE id = ?1",
+            params![id, at, max_fallback_percentage],
+        )
+        .map(|_| ())
+    })
+}
+
+pub fn end_overage_episode(id: i64, at: &str) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute("UPDATE overage_episodes SET ended_at = ?2 WHERE id = ?1", params![id, at])
+            .map(|_| ())
+    })
+}
+
+/// Episodes that started on or after `since` (RFC 3339), oldest first
+pub fn overage_episodes_since(since: &str) -> Result<Vec<OverageEpisode>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, started_at, last_seen_at, ended_at, max_fallback_percentage
+             FROM overage_episodes WHERE started_at >= ?1 ORDER BY started_at ASC",
+        )?;
+        let rows = stmt.query_map(params![since], overage_from_row)?;
+        rows.collect()
+    })
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusBlock {
+    pub date: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub planned_minutes: u32,
+    /// Ran the full planned time (false when stopped early)
+    pub completed: bool,
+    pub label: Option<String>,
+}
+
+pub fn save_focus_block(block: &FocusBlock) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO focus_blocks (date, started_at, ended_at, planned_minutes, completed, label)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                block.date,
+                block.started_at,
+                block.ended_at,
+                block.planned_minutes,
+                block.completed,
+                block.label
+            ],
+        )
+        .map(|_| ())
+    })
+}
+
+pub fn focus_blocks_on(date: &str) -> Result<Vec<FocusBlock>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT date, started_at, ended_at, planned_minutes, completed, label
+             FROM focus_blocks WHERE date = ?1 ORDER BY started_at ASC",
+        )?;
+        let rows = stmt.query_map(params![date], |r| {
+            Ok(FocusBlock {
+                date: r.get(0)?,
+                started_at: r.get(1)?,
+                ended_at: r.get(2)?,
+                planned_minutes: r.get(3)?,
+                completed: r.get(4)?,
+                label: r.get(5)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkin {
+    pub date: String,
+    /// 1 (rough) – 5 (great)
+    pub mood: u8,
+    /// 1 (drained) – 5 (energized)
+    pub energy: u8,
+    pub note: Option<String>,
+    pub updated_at: String,
+}
+
+fn checkin_from_row(r: &rusqlite::Row) -> rusqlite::Result<Checkin> {
+    Ok(Checkin {
+        date: r.get(0)?,
+        mood: r.get(1)?,
+        energy: r.get(2)?,
+        note: r.get(3)?,
+        updated_at: r.get(4)?,
+    })
+}
+
+pub fn save_checkin(checkin: &Checkin) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO checkins (date, mood, energy, note, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                checkin.date,
+                checkin.mood,
+                checkin.energy,
+                checkin.note,
+                checkin.updated_at
+            ],
+        )
+        .map(|_| ())
+    })
+}
+
+pub fn get_checkin(date: &str) -> Result<Option<Checkin>, SprtError> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT date, mood, energy, note, updated_at FROM checkins WHERE date = ?1",
+            params![date],
+            checkin_from_row,
+        )
+        .optional()
+    })
+}
+
+/// Check-ins on or after `since` (YYYY-MM-DD), oldest first
+pub fn checkins_since(since: &str) -> Result<Vec<Checkin>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT date, mood, energy, note, updated_at FROM checkins
+             WHERE date >= ?1 ORDER BY date ASC",
+        )?;
+        let rows = stmt.query_map(params![since], checkin_from_row)?;
+        rows.collect()
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Plan {
+    /// The day the plan is for
+    pub date: String,
+    pub items: Vec<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlanCompletion {
+    pub items: Vec<PlannedItem>,
+    pub done: u32,
+    pub planned: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlannedItem {
+    pub text: String,
+    pub done: bool,
+    /// Why the AI judged it (not) done
+    pub note: Option<String>,
+}
+
+pub fn save_plan(plan: &Plan) -> Result<(), SprtError> {
+    let items = serde_json::to_string(&plan.items)
+        .map_err(|e| SprtError::Internal(format!("Cannot serialize plan: {}", e)))?;
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO plans (date, items, updated_at) VALUES (?1, ?2, ?3)",
+            params![plan.date, items, plan.updated_at],
+        )
+        .map(|_| ())
+    })
+}
+
+pub fn delete_plan(date: &str) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM plans WHERE date = ?1", params![date])
+            .map(|_| ())
+    })
+}
+
+pub fn get_plan(date: &str) -> Result<Option<Plan>, SprtError> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT date, items, updated_at FROM plans WHERE date = ?1",
+            params![date],
+            |r| {
+                let items: String = r.get(1)?;
+                Ok(Plan {
+                    date: r.get(0)?,
+                    items: serde_json::from_str(&items).unwrap_or_default(),
+                    updated_at: r.get(2)?,
+                })
+            },
+        )
+        .optional()
+    })
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockedAchievement {
+    pub id: String,
+    pub unlocked_at: String,
+    /// What earned it, e.g. "132k tokens on 2025-03-04"
+    pub detail: Option<String>,
+}
+
+pub fn unlocked_achievements() -> Result<Vec<UnlockedAchievement>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, unlocked_at, detail FROM achievements ORDER BY unlocked_at ASC",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok(UnlockedAchievement {
+                id: r.get(0)?,
+                unlocked_at: r.get(1)?,
+                detail: r.get(2)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// Record an unlock. Returns false if it was already unlocked.
+pub fn unlock_achievement(a: &UnlockedAchievement) -> Result<bool, SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR IGNORE INTO achievements (id, unlocked_at, detail) VALUES (?1, ?2, ?3)",
+            params![a.id, a.unlocked_at, a.detail],
+        )
+        .map(|n| n > 0)
+    })
+}
+
+/// Replace the tags on one project or devlog
+pub fn set_tags(kind: &str, target: &str, tags: &[String]) -> Result<(), SprtError> {
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM tags WHERE kind = ?1 AND target = ?2",
+            params![kind, target],
+        )?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (kind, target, tag) VALUES (?1, ?2, ?3)",
+                params![kind, target, tag],
+            )?;
+        }
+        tx.commit()
+    })
+}
+
+/// target → tags for every tagged target of `kind`
+pub fn tags_of_kind(kind: &str) -> Result<HashMap<String, Vec<String>>, SprtError> {
+    let rows: Vec<(String, String)> = with_db(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT target, tag FROM tags WHERE kind = ?1 ORDER BY target, tag")?;
+        let rows = stmt.query_map(params![kind], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect()
+    })?;
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+    for (target, tag) in rows {
+        map.entry(target).or_default().push(tag);
+    }
+    Ok(map)
+}
+
+/// A session in the index. Timestamps are unix seconds.
+#[derive(Debug, Clone)]
+pub struct IndexedSession {
+    pub path: String,
+    pub session_id: String,
+    /// Encoded projects dir name
+    pub project: String,
+    pub project_path: String,
+    pub file_size: u64,
+    pub modified_at: i64,
+    pub first_message_at: Option<i64>,
+    pub last_message_at: Option<i64>,
+    /// Assistant messages, subagents included
+    pub message_count: u64,
+    /// Input + output + cache tokens
+    pub total_tokens: u64,
+    pub model_tokens: HashMap<String, u64>,
+    pub title: Option<String>,
+    /// Hostname (or configured device name) of the machine the session ran on, if known
+    pub device: Option<String>,
+}
+
+/// Per-project totals over the session index, archived sessions included
+pub struct ProjectTotals {
+    /// Encoded projects dir name
+    pub project: String,
+    pub sessions: u64,
+    pub messages: u64,
+    pub tokens: u64,
+    /// Newest log modification, unix seconds
+    pub modified_at: i64,
+}
+
+pub fn project_totals() -> Result<Vec<ProjectTotals>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT project, COUNT(*), SUM(message_count), SUM(total_tokens), MAX(modified_at)
+             FROM sessions GROUP BY project",
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok(ProjectTotals {
+                project: r.get(0)?,
+                sessions: r.get(1)?,
+                messages: r.get(2)?,
+                tokens: r.get(3)?,
+                modified_at: r.get(4)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// path → (file_size, modified_at) for every indexed file that hasn't been archived
+pub fn session_index_stamps() -> Result<HashMap<String, (u64, i64)>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT path, file_size, modified_at FROM sessions WHERE archived_path IS NULL",
+        )?;
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, (r.get(1)?, r.get(2)?))))?;
+        rows.collect()
+    })
+}
+
+/// path → archived_path for every session archive.rs has copied away
+pub fn archived_session_paths() -> Result<HashMap<String, String>, SprtError> {
+    with_db(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT path, archived_path FROM sessions WHERE archived_path IS NOT NULL")?;
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect()
+    })
+}
+
+/// Replace index rows in one transaction, dropping rows for `removed` paths. A session's
+/// device, once known, is kept, also when its row moves to another path (data roots holding
+/// copies of the same session: see claude::session_logs).
+pub fn update_session_index(sessions: &[IndexedSession], removed: &[String]) -> Result<(), SprtError> {
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        for s in sessions {
+            let model_tokens = serde_json::to_string(&s.model_tokens)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            tx.execute(
+                "INSERT INTO sessions (path, session_id, project, project_path, file_size,
+                 modified_at, first_message_at, last_message_at, message_count, total_tokens,
+                 model_tokens, title, device)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, COALESCE(?13,
+                 (SELECT device FROM sessions WHERE session_id = ?2 AND device IS NOT NULL LIMIT 1)))
+                 ON CONFLICT (path) DO UPDATE SET session_id = excluded.session_id,
+                 project = excluded.project, project_path = excluded.project_path,
+                 file_size = excluded.file_size, modified_at = excluded.modified_at,
+                 first_message_at = excluded.first_message_at,
+                 last_message_at = excluded.last_message_at,
+                 message_count = excluded.message_count, total_tokens = excluded.total_tokens,
+                 model_tokens = excluded.model_tokens, title = excluded.title,
+                 archived_path = NULL, device = COALESCE(sessions.device, excluded.device)",
+                params![
+                    s.path,
+                    s.session_id,
+                    s.project,
+                    s.project_path,
+                    s.file_size,
+                    s.modified_at,
+                    s.first_message_at,
+                    s.last_message_at,
+                    s.message_count,
+                    s.total_tokens,
+                    model_tokens,
+                    s.title,
+                    s.device
+                ],
+            )?;
+        }
+        for path in removed {
+            tx.execute("DELETE FROM sessions WHERE path = ?1", params![path])?;
+        }
+        tx.commit()
+    })
+}
+
+/// Keep the row after its file is gone, pointing at the archived copy
+pub fn mark_session_archived(path: &str, archived_path: &str) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE sessions SET archived_path = ?2 WHERE path = ?1",
+            params![path, archived_path],
+        )
+        .map(|_| ())
+    })
+}
+
+pub struct SessionQuery {
+    /// Encoded dir name or decoded path
+    pub project: Option<String>,
+    /// Sessions with messages in [from, to), unix seconds
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Hostname or device name; "unknown" matches sessions without one
+    pub device: Option<String>,
+    /// "recent" (default), "oldest", "messages", "tokens", "duration"
+    pub sort: String,
+    pub offset: u64,
+    pub limit: u64,
+}
+
+fn indexed_session_from_row(r: &rusqlite::Row) -> rusqlite::Result<IndexedSession> {
+    let model_tokens: String = r.get(10)?;
+    Ok(IndexedSession {
+        path: r.get(0)?,
+        session_id: r.get(1)?,
+        project: r.get(2)?,
+        project_path: r.get(3)?,
+        file_size: r.get(4)?,
+        modified_at: r.get(5)?,
+        first_message_at: r.get(6)?,
+        last_message_at: r.get(7)?,
+        message_count: r.get(8)?,
+        total_tokens: r.get(9)?,
+        model_tokens: serde_json::from_str(&model_tokens).unwrap_or_default(),
+        title: r.get(11)?,
+        device: r.get(12)?,
+    })
+}
+
+/// Every indexed session with messages for a project (dir name or path), oldest first
+pub fn project_sessions(project: &str) -> Result<Vec<IndexedSession>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT path, session_id, project, project_path, file_size, modified_at, first_message_at,
+             last_message_at, message_count, total_tokens, model_tokens, title, device
+             FROM sessions WHERE (project = ?1 OR project_path = ?1) AND message_count > 0
+             ORDER BY first_message_at ASC",
+        )?;
+        let rows = stmt.query_map(params![project], indexed_session_from_row)?;
+        rows.collect()
+    })
+}
+
+/// An indexed session by id, with the archived copy's path if it has been archived
+pub fn find_session(session_id: &str) -> Result<Option<(IndexedSession, Option<String>)>, SprtError> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT path, session_id, project, project_path, file_size, modified_at, first_message_at,
+             last_message_at, message_count, total_tokens, model_tokens, title, device, archived_path
+             FROM sessions WHERE session_id = ?1 ORDER BY modified_at DESC LIMIT 1",
+            params![session_id],
+            |r| Ok((indexed_session_from_row(r)?, r.get(13)?)),
+        )
+        .optional()
+    })
+}
+
+/// One page of indexed sessions with at least one message, plus the total matching count
+pub fn query_sessions(q: &SessionQuery) -> Result<(Vec<IndexedSession>, u64), SprtError> {
+    let mut conditions = vec!["message_count > 0".to_string()];
+    let mut args: Vec<rusqlite::types::Value> = vec![];
+    if let Some(project) = &q.project {
+        args.push(project.clone().into());
+        conditions.push(format!("(project = ?{0} OR project_path = ?{0})", args.len()));
+    }
+    if let Some(from) = q.from {
+        args.push(from.into());
+        conditions.push(format!("last_message_at >= ?{}", args.len()));
+    }
+    if let Some(to) = q.to {
+        args.push(to.into());
+        conditions.push(format!("first_message_at < ?{}", args.len()));
+    }
+    match q.device.as_deref() {
+        Some("unknown") => conditions.push("device IS NULL".to_string()),
+        Some(device) => {
+            args.push(device.to_string().into());
+            conditions.push(format!("device = ?{}", args.len()));
+        }
+        None => {}
+    }
+    let order = match q.sort.as_str() {
+        "oldest" => "first_message_at ASC",
+        "messages" => "message_count DESC",
+        "tokens" => "total_tokens DESC",
+        "duration" => "(last_message_at - first_message_at) DESC",
+        _ => "last_message_at DESC",
+    };
+    let filter = conditions.join(" AND ");
+
+    with_db(|conn| {
+        let total: u64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM sessions WHERE {}", filter),
+            rusqlite::params_from_iter(args.iter()),
+            |r| r.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT path, session_id, project, project_path, file_size, modified_at, first_message_at,
+             last_message_at, message_count, total_tokens, model_tokens, title, device
+             FROM sessions WHERE {} ORDER BY {}, path LIMIT {} OFFSET {}",
+            filter, order, q.limit, q.offset
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(args.iter()), indexed_session_from_row)?;
+        Ok((rows.collect::<rusqlite::Result<Vec<_>>>()?, total))
+    })
+}
+
+/// A finished session. Times are unix seconds.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEnding {
+    pub session_id: String,
+    pub project_path: String,
+    pub title: Option<String>,
+    pub device: Option<String>,
+    pub started_at: i64,
+    /// Last message
+    pub ended_at: i64,
+    pub messages: u64,
+    /// Input + output + cache tokens
+    pub tokens: u64,
+    pub cost_usd: f64,
+}
+
+pub fn save_session_ending(s: &SessionEnding) -> Result<(), SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO session_history (session_id, project_path, title, device,
+             started_at, ended_at, messages, tokens, cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                s.session_id,
+                s.project_path,
+                s.title,
+                s.device,
+                s.started_at,
+                s.ended_at,
+                s.messages,
+                s.tokens,
+                s.cost_usd
+            ],
+        )
+        .map(|_| ())
+    })
+}
+
+/// session_id → ended_at for sessions that ended at or after `since`
+pub fn session_ends_since(since: i64) -> Result<HashMap<String, i64>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT session_id, ended_at FROM session_history WHERE ended_at >= ?1",
+        )?;
+        let rows = stmt.query_map(params![since], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect()
+    })
+}
+
+/// Most recently ended first
+pub fn list_session_history(offset: u64, limit: u64) -> Result<Vec<SessionEnding>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT session_id, project_path, title, device, started_at, ended_at, messages,
+             tokens, cost_usd FROM session_history ORDER BY ended_at DESC LIMIT ?1 OFFSET ?2",
+        )?;
+        let rows = stmt.query_map(params![limit as i64, offset as i64], |r| {
+            Ok(SessionEnding {
+                session_id: r.get(0)?,
+                project_path: r.get(1)?,
+                title: r.get(2)?,
+                device: r.get(3)?,
+                started_at: r.get(4)?,
+                ended_at: r.get(5)?,
+                messages: r.get(6)?,
+                tokens: r.get(7)?,
+                cost_usd: r.get(8)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// A timeline entry. `at` is unix seconds.
+#[derive(Debug, Clone)]
+pub struct TimelineEvent {
+    /// Stable per source event, e.g. "commit:<hash>"
+    pub key: String,
+    pub at: i64,
+    pub kind: String,
+    pub title: String,
+    pub detail: Option<String>,
+    pub project: Option<String>,
+}
+
+/// Insert events, replacing any with the same key
+pub fn upsert_timeline_events(events: &[TimelineEvent]) -> Result<(), SprtError> {
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        for e in events {
+            tx.execute(
+                "INSERT OR REPLACE INTO timeline_events (key, at, kind, title, detail, project)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![e.key, e.at, e.kind, e.title, e.detail, e.project],
+            )?;
+        }
+        tx.commit()
+    })
+}
+
+/// Insert an event unless one with its key exists, keeping the first sighting
+/// False when an event with the same key was already recorded
+pub fn insert_timeline_event_once(event: &TimelineEvent) -> Result<bool, SprtError> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR IGNORE INTO timeline_events (key, at, kind, title, detail, project)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![event.key, event.at, event.kind, event.title, event.detail, event.project],
+        )
+        .map(|n| n > 0)
+    })
+}
+
+/// Events in [from, to), unix seconds, oldest first
+pub fn timeline_events_between(from: i64, to: i64) -> Result<Vec<TimelineEvent>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT key, at, kind, title, detail, project FROM timeline_events
+             WHERE at >= ?1 AND at < ?2 ORDER BY at ASC, key ASC",
+        )?;
+        let rows = stmt.query_map(params![from, to], |r| {
+            Ok(TimelineEvent {
+                key: r.get(0)?,
+                at: r.get(1)?,
+                kind: r.get(2)?,
+                title: r.get(3)?,
+                detail: r.get(4)?,
+                project: r.get(5)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// One editor heartbeat. `at` is unix seconds.
+#[derive(Debug, Clone)]
+pub struct EditorHeartbeat {
+    pub at: i64,
+    /// File being edited
+    pub entity: String,
+    pub project: Option<String>,
+    /// "vscode", "cursor", ... (see editor::editor_name)
+    pub editor: String,
+    pub is_write: bool,
+}
+
+/// Record heartbeats, skipping ones already seen. Returns how many were new.
+pub fn insert_editor_heartbeats(beats: &[EditorHeartbeat]) -> Result<usize, SprtError> {
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        let mut added = 0;
+        for b in beats {
+            added += tx.execute(
+                "INSERT OR IGNORE INTO editor_heartbeats (at, entity, project, editor, is_write)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![b.at, b.entity, b.project, b.editor, b.is_write],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    })
+}
+
+/// Heartbeats in [from, to), unix seconds, oldest first
+pub fn editor_heartbeats_between(from: i64, to: i64) -> Result<Vec<EditorHeartbeat>, SprtError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT at, entity, project, editor, is_write FROM editor_heartbeats
+             WHERE at >= ?1 AND at < ?2 ORDER BY at ASC",
+        )?;
+        let rows = stmt.query_map(params![from, to], |r| {
+            Ok(EditorHeartbeat {
+                at: r.get(0)?,
+                entity: r.get(1)?,
+                project: r.get(2)?,
+                editor: r.get(3)?,
+                is_write: r.get(4)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+fn period_for_log(date: &str, log_type: &str) -> String {
+    match log_type {
+        "monthly" => date.get(..7).unwrap_or(date).to_string(),
+        _ => date.to_string(),
+    }
+}
+
+/// Release notes are per repo and range, not per day, so their id is the key
+fn period_of(log: &DevLog) -> String {
+    match log.log_type.as_str() {
+        "release" => log.id.clone(),
+        _ => period_for_log(&log.date, &log.log_type),
+    }
+}
+
+fn upsert_devlog(conn: &Connection, log: &DevLog) -> rusqlite::Result<()> {
+    let data = serde_json::to_string(log)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let data = if crypto::enabled() {
+        crypto::encrypt(&data).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?
+    } else {
+        data
+    };
+    conn.execute(
+        "INSERT OR REPLACE INTO devlogs (log_type, period, id, date, generated_at, sprint_score, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            log.log_type,
+            period_of(log),
+            log.id,
+            log.date,
+            log.generated_at,
+            log.sprint_score,
+            data
+        ],
+    )?;
+    Ok(())
+}
+
+/// Decode the `data` column; rows that no longer deserialize (or decrypt) are skipped by callers
+fn parse_devlog_row(data: &str) -> Option<DevLog> {
+    serde_json::from_str(&crypto::decrypt(data).ok()?).ok()
+}
+
+pub fn save_devlog(log: &DevLog) -> Result<(), SprtError> {
+    with_db(|conn| upsert_devlog(conn, log))
+}
+
+pub fn get_devlog(date: &str, log_type: &str) -> Result<Option<DevLog>, SprtError> {
+    let period = period_for_log(date, log_type);
+    let data: Option<String> = with_db(|conn| {
+        conn.query_row(
+            "SELECT data FROM devlogs WHERE log_type = ?1 AND period = ?2",
+            params![log_type, period],
+            |r| r.get(0),
+        )
+        .optional()
+    })?;
+    match data {
+        Some(d) => serde_json::from_str(&crypto::decrypt(&d)?)
+            .map(Some)
+            .map_err(|e| SprtError::Parse(format!("Parse error: {}", e))),
+        None => Ok(None),
+    }
+}
+
+pub fn list_devlogs(log_type: &str, limit: usize) -> Result<Vec<DevLog>, SprtError> {
+    // Newest first by date; release periods are ids, which don't sort by time
+    let rows: Vec<String> = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT data FROM devlogs WHERE log_type = ?1
+             ORDER BY date DESC, generated_at DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![log_type, limit as i64], |r| r.get(0))?;
+        rows.collect()
+    })?;
+    Ok(rows.iter().filter_map(|d| parse_devlog_row(d)).collect())
+}
+
+/// (encrypted, plaintext) devlog rows; encrypted ones start with `prefix`
+pub fn devlog_encryption_counts(prefix: &str) -> Result<(u64, u64), SprtError> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT COALESCE(SUM(substr(data, 1, length(?1)) = ?1), 0), COUNT(*) FROM devlogs",
+            params![prefix],
+            |r| {
+                let (encrypted, total): (u64, u64) = (r.get(0)?, r.get(1)?);
+                Ok((encrypted, total - encrypted))
+            },
+        )
+    })
+}
+
+/// Rewrite devlog rows so all are encrypted (`encrypt`) or all plaintext. Rows changed by
+/// someone else meanwhile are left alone. Returns how many were rewritten.
+pub fn migrate_devlog_encryption(encrypt: bool) -> Result<u64, SprtError> {
+    let rows: Vec<(String, String, String)> = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT log_type, period, data FROM devlogs")?;
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?;
+        rows.collect()
+    })?;
+    // Crypto runs outside the database lock
+    let mut updates = vec![];
+    for (log_type, period, data) in rows {
+        if crypto::is_encrypted(&data) == encrypt {
+            continue;
+        }
+        let new_data = if encrypt {
+            crypto::encrypt(&data)?
+        } else {
+            crypto::decrypt(&data)?
+        };
+        updates.push((log_type, period, data, new_data));
+    }
+    with_db(|conn| {
+        let tx = conn.transaction()?;
+        let mut changed = 0;
+        for (log_type, period, old, new) in &updates {
+            changed += tx.execute(
+                "UPDATE devlogs SET data = ?1 WHERE log_type = ?2 AND period = ?3 AND data = ?4",
+                params![new, log_type, period, old],
+            )? as u64;
+        }
+        tx.commit()?;
+        Ok(changed)
+    })
+}
+
+/// Devlogs generated after `generated_at` (RFC 3339)
+pub fn count_devlogs_since(generated_at: &str) -> Result<u64, SprtError> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT COUNT(*) FROM devlogs WHERE generated_at > ?1",
+            params![generated_at],
+            |r| r.get::<_, i64>(0),
+        )
+    })
+    .map(|n| n as u64)
+}
+
+// ── JSON files: legacy store (imported on first run) and portable export ──
+
+fn legacy_devlogs_root() -> Option<PathBuf> {
+    sprt_dir().map(|d| d.join("devlogs"))
+}
+
+/// Sibling path with an extra suffix, e.g. "2025-01-02.json" → "2025-01-02.json.bak"
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_os_string();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// Write via temp file + rename so a crash mid-write never leaves a half-written file.
+/// The previous version is kept as `<file>.bak`.
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<(), SprtError> {
+    let tmp = with_suffix(path, ".tmp");
+    {
+        let mut f = fs::File::create(&tmp).map_err(|e| storage_error(format!("Write error: {}", e)))?;
+        f.write_all(content.as_bytes())
+            .and_then(|_| f.sync_all())
+            .map_err(|e| storage_error(format!("Write error: {}", e)))?;
+    }
+    if path.exists() {
+        if let Err(e) = fs::copy(path, with_suffix(path, ".bak")) {
+            log::warn!("Cannot back up {}: {e}", path.display());
+        }
+    }
+    fs::rename(&tmp, path).map_err(|e| storage_error(format!("Write error: {}", e)))
+}
+
+fn read_devlog_file(path: &Path) -> Result<DevLog, SprtError> {
+    let content = fs::read_to_string(path).map_err(|e| storage_error(format!("Read error: {}", e)))?;
+    serde_json::from_str(&content).map_err(|e| SprtError::Parse(format!("Parse error: {}", e)))
+}
+
+/// Read a devlog, falling back to its .bak when the main file is corrupt
+fn read_devlog_with_backup(path: &Path) -> Result<DevLog, SprtError> {
+    read_devlog_file(path).or_else(|e| read_devlog_file(&with_suffix(path, ".bak")).map_err(|_| e))
+}
+
+fn legacy_devlog_files() -> Vec<PathBuf> {
+    let root = match legacy_devlogs_root() {
+        Some(r) => r,
+        None => return vec![],
+    };
+    let pattern = root.join("*/*.json").to_string_lossy().to_string();
+    match glob::glob(&pattern) {
+        Ok(p) => p.filter_map(|p| p.ok()).collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Import legacy JSON devlogs. Unreadable files stay put for check_devlog_storage to report.
+fn import_json_devlogs(conn: &Connection) {
+    for path in legacy_devlog_files() {
+        if let Ok(log) = read_devlog_with_backup(&path) {
+            if let Err(e) = upsert_devlog(conn, &log) {
+                log::warn!("Cannot import {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+/// Write every stored devlog `keep` accepts to `<dir>/<log_type>/<period>.json`.
+/// Unparseable rows are written as-is when there is no filter. Returns the number written.
+pub fn export_devlogs_json(
+    dir: &Path,
+    keep: Option<&dyn Fn(&DevLog) -> bool>,
+) -> Result<usize, SprtError> {
+    let rows: Vec<(String, String, String)> = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT log_type, period, data FROM devlogs")?;
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?;
+        rows.collect()
+    })?;
+
+    let mut written = 0;
+    for (log_type, period, data) in &rows {
+        let log = parse_devlog_row(data);
+        if let Some(keep) = keep {
+            if !log.as_ref().is_some_and(keep) {
+                continue;
+            }
+        }
+        let type_dir = dir.join(log_type);
+        fs::create_dir_all(&type_dir)
+            .map_err(|e| storage_error(format!("Cannot create directory: {}", e)))?;
+        // Re-serialize pretty so exports are readable
+        let content = log
+            .and_then(|log| serde_json::to_string_pretty(&log).ok())
+            .unwrap_or_else(|| data.clone());
+        let (dest, content) =
+            crypto::protect_export(type_dir.join(format!("{}.json", period)), &content)?;
+        write_atomic(&dest, &content)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+pub fn default_export_dir() -> Option<PathBuf> {
+    sprt_dir().map(|d| d.join("export").join("devlogs"))
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptDevLog {
+    pub path: String,
+    pub log_type: String,
+    pub error: String,
+    pub has_backup: bool,
+}
+
+/// Legacy JSON devlog files that fail to parse (and so were never imported)
+pub fn find_corrupt_devlogs() -> Result<Vec<CorruptDevLog>, SprtError> {
+    let mut corrupt = vec![];
+    for path in legacy_devlog_files() {
+        if let Err(error) = read_devlog_file(&path) {
+            let log_type = path
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            corrupt.push(CorruptDevLog {
+                has_backup: read_devlog_file(&with_suffix(&path, ".bak")).is_ok(),
+                path: path.to_string_lossy().to_string(),
+                log_type,
+                error: error.to_string(),
+            });
+        }
+    }
+    Ok(corrupt)
+}
+
+/// Restore corrupt files from their .bak when valid (and import them if the database doesn't
+/// have that devlog yet); otherwise move them aside to `<file>.corrupt`. Returns what was found.
+pub fn repair_devlogs() -> Result<Vec<CorruptDevLog>, SprtError> {
+    let corrupt = find_corrupt_devlogs()?;
+    for c in &corrupt {
+        let path = PathBuf::from(&c.path);
+        if c.has_backup {
+            fs::copy(with_suffix(&path, ".bak"), &path)
+                .map_err(|e| storage_error(format!("Cannot repair {}: {}", c.path, e)))?;
+            let log = read_devlog_file(&path)?;
+            if get_devlog(&log.date, &log.log_type)?.is_none() {
+                save_devlog(&log)?;
+            }
+        } else {
+            fs::rename(&path, with_suffix(&path, ".corrupt"))
+                .map_err(|e| storage_error(format!("Cannot repair {}: {}", c.path, e)))?;
+        }
+    }
+    Ok(corrupt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn devlog(date: &str, summary: &str) -> DevLog {
+        serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "date": date,
+            "logType": "daily",
+            "generatedAt": "2025-03-01T18:00:00Z",
+            "summary": summary,
+            "highlights": ["Fixture tests"],
+            "projectsWorked": [],
+            "stats": {
+                "totalCommits": 2,
+                "totalMessages": 3,
+                "totalTokens": 1367,
+                "totalFilesChanged": 3,
+                "totalInsertions": 5,
+                "totalDeletions": 1,
+                "activeHours": 0.5,
+                "projectsCount": 1
+            },
+            "sprintScore": 64
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn migrations_apply_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap(), 0);
+        assert_eq!(migrate(&mut conn).unwrap(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn devlog_round_trip() {
+        crate::paths::init_for_tests();
+        assert!(get_devlog("2025-03-01", "daily").unwrap().is_none());
+
+        save_devlog(&devlog("2025-03-01", "First draft")).unwrap();
+        save_devlog(&devlog("2025-03-01", "Rewritten")).unwrap();
+
+        let stored = get_devlog("2025-03-01", "daily").unwrap().unwrap();
+        assert_eq!(stored.summary, "Rewritten");
+        assert_eq!(stored.highlights, vec!["Fixture tests"]);
+        assert_eq!(stored.stats.total_insertions, 5);
+        assert_eq!(stored.sprint_score, 64);
+        assert!(daily_scores_since("2025-03-01")
+            .unwrap()
+            .contains(&("2025-03-01".to_string(), 64)));
+    }
+
+    #[test]
+    fn session_history_round_trip() {
+        crate::paths::init_for_tests();
+        let ending = SessionEnding {
+            session_id: "0b7d5c3e-1111-4a2b-9c3d-5e6f7a8b9c0d".to_string(),
+            project_path: "/Users/dev/sprt-app".to_string(),
+            title: Some("Parse session logs from fixtures".to_string()),
+            device: None,
+            started_at: 1_740_819_605,
+            ended_at: 1_740_821_400,
+            messages: 3,
+            tokens: 1367,
+            cost_usd: 0.25,
+        };
+        save_session_ending(&ending).unwrap();
+
+        let ends = session_ends_since(ending.started_at).unwrap();
+        assert_eq!(ends.get(&ending.session_id), Some(&ending.ended_at));
+        let history = list_session_history(0, 10).unwrap();
+        let stored = history.iter().find(|s| s.session_id == ending.session_id).unwrap();
+        assert_eq!(stored.title, ending.title);
+        assert_eq!((stored.messages, stored.tokens), (3, 1367));
+    }
+}
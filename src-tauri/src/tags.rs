@@ -0,0 +1,319 @@
+use crate::error::SprtError;
+use crate::session_index;
+use crate::storage::{self, DevLog, SessionQuery};
+use crate::time::{self, DayClock};
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+pub const PROJECT: &str = "project";
+pub const DEVLOG: &str = "devlog";
+
+const MAX_TAG_LEN: usize = 40;
+
+/// Lowercase, trimmed, inner whitespace as dashes; duplicates and empties dropped
+fn normalize(tags: Vec<String>) -> Result<Vec<String>, SprtError> {
+    let mut out = BTreeSet::new();
+    for tag in tags {
+        let tag = tag
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase();
+        if tag.is_empty() {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_LEN {
+            return Err(SprtError::InvalidInput(format!(
+                "Tag too long (max {} characters): {}",
+                MAX_TAG_LEN, tag
+            )));
+        }
+        out.insert(tag);
+    }
+    Ok(out.into_iter().collect())
+}
+
+pub fn devlog_target(log_type: &str, date: &str) -> String {
+    format!("{}:{}", log_type, date)
+}
+
+/// A bare project name like "sprt", as opposed to a path
+fn is_name_only(target: &str) -> bool {
+    !target.contains(['/', '\\'])
+}
+
+fn dir_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+/// Project and devlog tags, loaded once for filtering many devlogs or sessions
+pub struct TagIndex {
+    projects: HashMap<String, Vec<String>>,
+    devlogs: HashMap<String, Vec<String>>,
+}
+
+impl TagIndex {
+    pub fn load() -> Result<Self, SprtError> {
+        Ok(TagIndex {
+            projects: storage::tags_of_kind(PROJECT)?,
+            devlogs: storage::tags_of_kind(DEVLOG)?,
+        })
+    }
+
+    /// Tags on a project, matched by path. Directory names stand in for paths only when one
+    /// side is a bare name: a path finds tags set on its name, and a name finds the tags of
+    /// the one tagged path ending in it (none if several repos share the name).
+    pub fn project_tags(&self, path: &str) -> &[String] {
+        if let Some(tags) = self.projects.get(path) {
+            return tags;
+        }
+        if !is_name_only(path) {
+            return self
+                .projects
+                .get(dir_name(path))
+                .map(|tags| tags.as_slice())
+                .unwrap_or(&[]);
+        }
+        let mut same_name = self
+            .projects
+            .iter()
+            .filter(|(target, _)| !is_name_only(target) && dir_name(target) == path);
+        match (same_name.next(), same_name.next()) {
+            (Some((_, tags)), None) => tags,
+            _ => &[],
+        }
+    }
+
+    /// Tags set on the devlog itself
+    pub fn devlog_tags(&self, log: &DevLog) -> &[String] {
+        self.devlogs
+            .get(&devlog_target(&log.log_type, &log.date))
+            .map(|t| t.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Tagged directly, or one of its projects is
+    pub fn devlog_has(&self, log: &DevLog, tag: &str) -> bool {
+        self.devlog_tags(log).iter().any(|t| t == tag)
+            || log.projects_worked.iter().any(|pw| {
+                let key = if pw.path.is_empty() {
+                    &pw.name
+                } else {
+                    &pw.path
+                };
+                self.project_tags(key).iter().any(|t| t == tag)
+            })
+    }
+}
+
+#[tauri::command]
+pub fn set_project_tags(project: String, tags: Vec<String>) -> Result<Vec<String>, SprtError> {
+    if project.trim().is_empty() {
+        return Err(SprtError::InvalidInput("Project is required".to_string()));
+    }
+    let tags = normalize(tags)?;
+    storage::set_tags(PROJECT, &project, &tags)?;
+    Ok(tags)
+}
+
+#[tauri::command]
+pub fn set_devlog_tags(
+    date: String,
+    log_type: String,
+    tags: Vec<String>,
+) -> Result<Vec<String>, SprtError> {
+    if time::parse_date(&date).is_none() {
+        return Err(SprtError::InvalidInput(format!("Invalid date: {}", date)));
+    }
+    let tags = normalize(tags)?;
+    storage::set_tags(DEVLOG, &devlog_target(&log_type, &date), &tags)?;
+    Ok(tags)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSummary {
+    pub tag: String,
+    /// Project paths
+    pub projects: Vec<String>,
+    /// "<type>:<date>"
+    pub devlogs: Vec<String>,
+}
+
+/// Every tag in use with what it's attached to, alphabetical
+#[tauri::command]
+pub fn list_tags() -> Result<Vec<TagSummary>, SprtError> {
+    let mut by_tag: BTreeMap<String, TagSummary> = BTreeMap::new();
+    for (kind, targets) in [
+        (PROJECT, storage::tags_of_kind(PROJECT)?),
+        (DEVLOG, storage::tags_of_kind(DEVLOG)?),
+    ] {
+        for (target, tags) in targets {
+            for tag in tags {
+                let summary = by_tag.entry(tag.clone()).or_insert_with(|| TagSummary {
+                    tag,
+                    projects: vec![],
+                    devlogs: vec![],
+                });
+                if kind == PROJECT {
+                    summary.projects.push(target.clone());
+                } else {
+                    summary.devlogs.push(target.clone());
+                }
+            }
+        }
+    }
+    Ok(by_tag.into_values().collect())
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TagStats {
+    pub tag: String,
+    /// Project paths with sessions in the period
+    pub projects: Vec<String>,
+    pub sessions: u64,
+    pub messages: u64,
+    pub tokens: u64,
+    /// First to last message of each session
+    pub session_hours: f64,
+    /// From daily devlogs: commits in tagged projects, or all commits on tagged days
+    pub commits: u32,
+    pub devlogs: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsByTag {
+    pub days: u32,
+    /// Busiest first (by tokens)
+    pub tags: Vec<TagStats>,
+    /// Sessions in projects without tags
+    pub untagged: TagStats,
+}
+
+/// With `device`, sessions are limited to that machine; devlog commits aren't per device
+pub fn stats_by_tag(days: u32, device: Option<String>) -> Result<StatsByTag, SprtError> {
+    let days = days.max(1);
+    let clock = DayClock::load();
+    let first_day = clock.today() - chrono::Duration::days(days as i64 - 1);
+    let from = clock
+        .day_start(first_day)
+        .map(|t| t.timestamp())
+        .ok_or_else(|| SprtError::Internal("Cannot determine period start".to_string()))?;
+    let index = TagIndex::load()?;
+
+    session_index::refresh()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(from),
+        to: None,
+        device: device.filter(|d| !d.is_empty()),
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: u32::MAX as u64,
+    })?;
+
+    let mut stats: HashMap<String, TagStats> = HashMap::new();
+    let mut projects: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let mut untagged = TagStats {
+        tag: String::new(),
+        ..Default::default()
+    };
+    for s in &sessions {
+        let hours = match (s.first_message_at, s.last_message_at) {
+            (Some(f), Some(l)) => (l - f).max(0) as f64 / 3600.0,
+            _ => 0.0,
+        };
+        let tags = index.project_tags(&s.project_path);
+        for tag in tags {
+            projects
+                .entry(tag.clone())
+                .or_default()
+                .insert(s.project_path.clone());
+        }
+        let add = |t: &mut TagStats| {
+            t.sessions += 1;
+            t.messages += s.message_count;
+            t.tokens += s.total_tokens;
+            t.session_hours += hours;
+        };
+        if tags.is_empty() {
+            add(&mut untagged);
+        }
+        for tag in tags {
+            add(stats.entry(tag.clone()).or_insert_with(|| TagStats {
+                tag: tag.clone(),
+                ..Default::default()
+            }));
+        }
+    }
+
+    let since = time::format_date(first_day);
+    for log in storage::list_devlogs("daily", days as usize)? {
+        if log.date < since {
+            continue;
+        }
+        let mut commits: HashMap<&str, u32> = HashMap::new();
+        for tag in index.devlog_tags(&log) {
+            commits.insert(tag, log.stats.total_commits);
+        }
+        let mut from_projects: HashMap<&str, u32> = HashMap::new();
+        for pw in &log.projects_worked {
+            let key = if pw.path.is_empty() {
+                &pw.name
+            } else {
+                &pw.path
+            };
+            for tag in index.project_tags(key) {
+                *from_projects.entry(tag).or_insert(0) += pw.commits;
+            }
+        }
+        for (tag, n) in from_projects {
+            let c = commits.entry(tag).or_insert(0);
+            *c = (*c).max(n);
+        }
+        for (tag, n) in commits {
+            let entry = stats.entry(tag.to_string()).or_insert_with(|| TagStats {
+                tag: tag.to_string(),
+                ..Default::default()
+            });
+            entry.commits += n;
+            entry.devlogs += 1;
+        }
+    }
+
+    let mut tags: Vec<TagStats> = stats
+        .into_values()
+        .map(|mut t| {
+            t.projects = projects
+                .remove(&t.tag)
+                .map(|p| p.into_iter().collect())
+                .unwrap_or_default();
+            t
+        })
+        .collect();
+    tags.sort_by(|a, b| b.tokens.cmp(&a.tokens).then(a.tag.cmp(&b.tag)));
+    Ok(StatsByTag {
+        days,
+        tags,
+        untagged,
+    })
+}
+
+/// Usage and commits per tag over the last `days` days (default 30). A session or devlog
+/// counts toward every tag it carries.
+#[tauri::command]
+pub async fn get_stats_by_tag(
+    days: Option<u32>,
+    device: Option<String>,
+) -> Result<StatsByTag, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || stats_by_tag(days.unwrap_or(30), device))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,63 @@
+use crate::settings;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+
+/// Date keys everywhere ("YYYY-MM-DD") are local calendar days shifted by the configured
+/// rollover hour, so with a 4am rollover a commit at 01:30 counts toward the previous day.
+#[derive(Debug, Clone, Copy)]
+pub struct DayClock {
+    rollover_hour: u32,
+}
+
+impl DayClock {
+    /// Read the rollover hour from settings. Load once per scan, not per entry.
+    pub fn load() -> Self {
+        DayClock {
+            rollover_hour: settings::load().day_rollover_hour.min(23),
+        }
+    }
+
+    /// The day `ts` belongs to
+    pub fn date_of<Tz: TimeZone>(&self, ts: &DateTime<Tz>) -> NaiveDate {
+        (ts.with_timezone(&Local) - Duration::hours(self.rollover_hour as i64)).date_naive()
+    }
+
+    pub fn date_key<Tz: TimeZone>(&self, ts: &DateTime<Tz>) -> String {
+        format_date(self.date_of(ts))
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date_of(&Local::now())
+    }
+
+    pub fn today_key(&self) -> String {
+        format_date(self.today())
+    }
+
+    /// When `date` begins. A rollover time skipped by a DST jump falls back to the next hour.
+    pub fn day_start(&self, date: NaiveDate) -> Option<DateTime<Local>> {
+        let at = date.and_hms_opt(self.rollover_hour, 0, 0)?;
+        at.and_local_timezone(Local)
+            .earliest()
+            .or_else(|| (at + Duration::hours(1)).and_local_timezone(Local).earliest())
+    }
+
+    /// [start, end) of `date`
+    pub fn day_range(&self, date: NaiveDate) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        Some((self.day_start(date)?, self.day_start(date.succ_opt()?)?))
+    }
+
+    /// Start of the 7-day window ending today (today plus the six days before it)
+    pub fn week_start(&self) -> Option<DateTime<Utc>> {
+        self.day_start(self.today() - Duration::days(6))
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+pub fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+pub fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
@@ -0,0 +1,231 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::git;
+use crate::session_index::DateRange;
+use crate::tags::TagIndex;
+use crate::time::{self, DayClock};
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Each day rescans session logs and repos, so keep reports to about a quarter
+const MAX_DAYS: i64 = 93;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeReportDay {
+    pub date: String,
+    /// Session time minus idle gaps
+    pub active_hours: f64,
+    pub sessions: u32,
+    pub commits: u32,
+    /// Project names worked on that day
+    pub projects: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeReport {
+    pub from: String,
+    pub to: String,
+    pub project: Option<String>,
+    pub tag: Option<String>,
+    /// Days with activity only, oldest first
+    pub days: Vec<TimeReportDay>,
+    pub total_hours: f64,
+    pub total_sessions: u32,
+    pub total_commits: u32,
+    /// "csv" or "markdown"
+    pub format: String,
+    /// The table in `format`, ready to paste into an invoice
+    pub content: String,
+}
+
+fn dir_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+/// Which projects count: the named one, those carrying the tag, or all
+struct Filter {
+    project: Option<String>,
+    tag: Option<String>,
+    tags: TagIndex,
+}
+
+impl Filter {
+    fn matches(&self, path: &str, encoded: Option<&str>) -> bool {
+        if let Some(p) = &self.project {
+            // A bare name stands for any repo of that name; a path only for itself
+            let by_name = !p.contains(['/', '\\']) && p == dir_name(path);
+            let named = p == path || Some(p.as_str()) == encoded || by_name;
+            if !named {
+                return false;
+            }
+        }
+        match &self.tag {
+            Some(tag) => self.tags.project_tags(path).iter().any(|t| t == tag),
+            None => true,
+        }
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn render(days: &[TimeReportDay], total_hours: f64, format: &str) -> String {
+    let mut out = String::new();
+    if format == "markdown" {
+        out.push_str("| Date | Hours | Sessions | Commits | Projects |\n");
+        out.push_str("|------|------:|---------:|--------:|----------|\n");
+        for d in days {
+            out.push_str(&format!(
+                "| {} | {:.2} | {} | {} | {} |\n",
+                d.date,
+                d.active_hours,
+                d.sessions,
+                d.commits,
+                d.projects.join(", ")
+            ));
+        }
+        out.push_str(&format!(
+            "| **Total** | **{:.2}** | **{}** | **{}** | |\n",
+            total_hours,
+            days.iter().map(|d| d.sessions).sum::<u32>(),
+            days.iter().map(|d| d.commits).sum::<u32>()
+        ));
+    } else {
+        out.push_str("date,hours,sessions,commits,projects\n");
+        for d in days {
+            out.push_str(&format!(
+                "{},{:.2},{},{},{}\n",
+                d.date,
+                d.active_hours,
+                d.sessions,
+                d.commits,
+                csv_field(&d.projects.join("; "))
+            ));
+        }
+    }
+    out
+}
+
+pub fn time_report(
+    range: DateRange,
+    project: Option<String>,
+    tag: Option<String>,
+    format: Option<String>,
+) -> Result<TimeReport, SprtError> {
+    let clock = DayClock::load();
+    let parse = |d: Option<&str>, default| -> Result<chrono::NaiveDate, SprtError> {
+        match d {
+            Some(d) => time::parse_date(d)
+                .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", d))),
+            None => Ok(default),
+        }
+    };
+    let to = parse(range.to.as_deref(), clock.today())?;
+    let from = parse(range.from.as_deref(), to - chrono::Duration::days(6))?;
+    if from > to {
+        return Err(SprtError::InvalidInput(
+            "Range start is after its end".to_string(),
+        ));
+    }
+    if (to - from).num_days() >= MAX_DAYS {
+        return Err(SprtError::InvalidInput(format!(
+            "Reports cover at most {} days",
+            MAX_DAYS
+        )));
+    }
+    let format = match format.as_deref() {
+        None | Some("csv") => "csv",
+        Some("markdown") | Some("md") => "markdown",
+        Some(other) => {
+            return Err(SprtError::InvalidInput(format!(
+                "Unknown format: {} (use csv or markdown)",
+                other
+            )))
+        }
+    };
+    let filter = Filter {
+        project: project.filter(|p| !p.is_empty()),
+        tag: tag.filter(|t| !t.is_empty()),
+        tags: TagIndex::load()?,
+    };
+
+    let mut days = vec![];
+    let mut day = from;
+    while day <= to {
+        let date = time::format_date(day);
+        let mut projects = BTreeSet::new();
+        let mut minutes = 0;
+        let mut sessions = 0;
+        for s in claude::get_session_summaries(&date) {
+            if !filter.matches(&s.project_path, Some(&s.project)) {
+                continue;
+            }
+            minutes += s.focused_minutes;
+            sessions += 1;
+            projects.insert(dir_name(&s.project_path).to_string());
+        }
+        let mut commits = 0;
+        for g in git::collect_git_activity(&date) {
+            if !filter.matches(&g.repo_path, None) {
+                continue;
+            }
+            commits += g.commits.len() as u32;
+            projects.insert(g.repo_name);
+        }
+        if sessions > 0 || commits > 0 {
+            days.push(TimeReportDay {
+                date,
+                active_hours: minutes as f64 / 60.0,
+                sessions,
+                commits,
+                projects: projects.into_iter().collect(),
+            });
+        }
+        day = match day.succ_opt() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+
+    let total_hours = days.iter().map(|d| d.active_hours).sum();
+    Ok(TimeReport {
+        from: time::format_date(from),
+        to: time::format_date(to),
+        content: render(&days, total_hours, format),
+        total_sessions: days.iter().map(|d| d.sessions).sum(),
+        total_commits: days.iter().map(|d| d.commits).sum(),
+        total_hours,
+        days,
+        project: filter.project,
+        tag: filter.tag,
+        format: format.to_string(),
+    })
+}
+
+/// Per-day active hours, sessions and commits for invoicing, limited to one project (path or
+/// name) or tag if given. Range defaults to the last 7 days; format is "csv" or "markdown".
+#[tauri::command]
+pub async fn generate_time_report(
+    range: Option<DateRange>,
+    project: Option<String>,
+    tag: Option<String>,
+    format: Option<String>,
+) -> Result<TimeReport, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        time_report(range.unwrap_or_default(), project, tag, format)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,325 @@
+use crate::claude::{RateLimitInfo, UsageClaim};
+use crate::error::SprtError;
+use crate::git;
+use crate::notifications;
+use crate::session_index::{self, DateRange};
+use crate::storage::{self, SessionQuery, TimelineEvent};
+use crate::time::{self, DayClock};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+/// Utilization levels (fractions) that get an event when first reached in a window
+const LIMIT_THRESHOLDS: &[f64] = &[0.5, 0.75, 0.9, 1.0];
+/// Crossings from here up also notify, for the model-specific weekly window in use
+const NOTIFY_THRESHOLD: f64 = 0.9;
+/// A session with no message for this long counts as ended
+const SESSION_END_IDLE_SECS: i64 = 30 * 60;
+/// Most recent days of a range re-derived from sources on each request; older days are
+/// served from what was stored when they were recent
+const SYNC_DAYS: i64 = 31;
+const DEVLOG_TYPES: &[&str] = &["daily", "weekly", "monthly", "insights", "release"];
+const DEVLOG_LOOKBACK: usize = 400;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// RFC 3339
+    pub at: String,
+    /// "commit", "session_start", "session_end", "devlog", "limit", "goal"
+    pub kind: String,
+    pub title: String,
+    pub detail: Option<String>,
+    pub project: Option<String>,
+}
+
+impl From<TimelineEvent> for Event {
+    fn from(e: TimelineEvent) -> Self {
+        Event {
+            at: DateTime::from_timestamp(e.at, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            kind: e.kind,
+            title: e.title,
+            detail: e.detail,
+            project: e.project,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Timeline {
+    pub from: String,
+    pub to: String,
+    /// Oldest first
+    pub events: Vec<Event>,
+}
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn project_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn session_events(from: i64, to: i64) -> Result<Vec<TimelineEvent>, SprtError> {
+    session_index::refresh()?;
+    let query = SessionQuery {
+        project: None,
+        from: Some(from),
+        to: Some(to),
+        device: None,
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: u32::MAX as u64,
+    };
+    let (sessions, _) = storage::query_sessions(&query)?;
+    let now = Utc::now().timestamp();
+
+    let mut events = vec![];
+    for s in sessions {
+        let (Some(first), Some(last)) = (s.first_message_at, s.last_message_at) else {
+            continue;
+        };
+        let project = Some(project_name(&s.project_path));
+        let title = s
+            .title
+            .clone()
+            .unwrap_or_else(|| "Untitled session".to_string());
+        events.push(TimelineEvent {
+            key: format!("session-start:{}", s.path),
+            at: first,
+            kind: "session_start".to_string(),
+            title: format!("Started: {}", title),
+            detail: None,
+            project: project.clone(),
+        });
+        if now - last >= SESSION_END_IDLE_SECS {
+            events.push(TimelineEvent {
+                key: format!("session-end:{}", s.path),
+                at: last,
+                kind: "session_end".to_string(),
+                title: format!("Ended: {}", title),
+                detail: Some(format!(
+                    "{} messages over {} min",
+                    s.message_count,
+                    (last - first).max(0) / 60
+                )),
+                project,
+            });
+        }
+    }
+    Ok(events)
+}
+
+fn commit_events(date: &str) -> Vec<TimelineEvent> {
+    git::collect_git_activity(date)
+        .into_iter()
+        .flat_map(|activity| {
+            activity.commits.into_iter().filter_map(move |c| {
+                let at = parse_time(&c.timestamp)?.timestamp();
+                let subject = c.message.lines().next().unwrap_or("").to_string();
+                Some(TimelineEvent {
+                    key: format!("commit:{}", c.hash),
+                    at,
+                    kind: "commit".to_string(),
+                    title: subject,
+                    detail: Some(format!(
+                        "{} on {}: {} files, +{} -{}",
+                        &c.hash[..c.hash.len().min(7)],
+                        c.branch,
+                        c.files_changed,
+                        c.insertions,
+                        c.deletions
+                    )),
+                    project: Some(activity.repo_name.clone()),
+                })
+            })
+        })
+        .collect()
+}
+
+fn devlog_events(from: i64, to: i64) -> Result<Vec<TimelineEvent>, SprtError> {
+    let mut events = vec![];
+    for log_type in DEVLOG_TYPES {
+        for log in storage::list_devlogs(log_type, DEVLOG_LOOKBACK)? {
+            let Some(at) = parse_time(&log.generated_at).map(|t| t.timestamp()) else {
+                continue;
+            };
+            if at < from || at >= to {
+                continue;
+            }
+            events.push(TimelineEvent {
+                key: format!("devlog:{}:{}", log_type, log.id),
+                at,
+                kind: "devlog".to_string(),
+                title: format!("Generated {} devlog for {}", log_type, log.date),
+                detail: Some(log.summary.clone()).filter(|s| !s.is_empty()),
+                project: None,
+            });
+        }
+    }
+    Ok(events)
+}
+
+/// Focus blocks run to their planned length; the only goals SPRT tracks so far
+fn goal_events(date: &str) -> Result<Vec<TimelineEvent>, SprtError> {
+    Ok(storage::focus_blocks_on(date)?
+        .into_iter()
+        .filter(|b| b.completed)
+        .filter_map(|b| {
+            Some(TimelineEvent {
+                key: format!("goal:focus:{}", b.started_at),
+                at: parse_time(&b.ended_at)?.timestamp(),
+                kind: "goal".to_string(),
+                title: format!("Completed a {}-minute focus block", b.planned_minutes),
+                detail: b.label,
+                project: None,
+            })
+        })
+        .collect())
+}
+
+/// Re-derive events for the days in [first, last] from their sources and store them
+fn sync(clock: &DayClock, first: NaiveDate, last: NaiveDate) -> Result<(), SprtError> {
+    let last = last.min(clock.today());
+    let first = first.max(last - chrono::Duration::days(SYNC_DAYS - 1));
+    if first > last {
+        return Ok(());
+    }
+    let from = clock.day_start(first).map(|t| t.timestamp());
+    let to = last
+        .succ_opt()
+        .and_then(|d| clock.day_start(d))
+        .map(|t| t.timestamp());
+    let (Some(from), Some(to)) = (from, to) else {
+        return Ok(());
+    };
+
+    let mut events = session_events(from, to)?;
+    events.extend(devlog_events(from, to)?);
+    let mut day = first;
+    while day <= last {
+        let date = time::format_date(day);
+        events.extend(commit_events(&date));
+        events.extend(goal_events(&date)?);
+        day = match day.succ_opt() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+    storage::upsert_timeline_events(&events)
+}
+
+pub fn timeline(date_range: Option<DateRange>) -> Result<Timeline, SprtError> {
+    let clock = DayClock::load();
+    let range = date_range.unwrap_or_default();
+    let parse = |d: Option<&str>| -> Result<NaiveDate, SprtError> {
+        match d {
+            Some(d) => time::parse_date(d)
+                .ok_or_else(|| SprtError::InvalidInput(format!("Invalid date: {}", d))),
+            None => Ok(clock.today()),
+        }
+    };
+    let to = parse(range.to.as_deref())?;
+    let from = match range.from.as_deref() {
+        Some(_) => parse(range.from.as_deref())?,
+        None => to,
+    };
+    if from > to {
+        return Err(SprtError::InvalidInput(
+            "Range start is after its end".to_string(),
+        ));
+    }
+
+    sync(&clock, from, to)?;
+
+    let start = clock.day_start(from).map(|t| t.timestamp());
+    let end = to
+        .succ_opt()
+        .and_then(|d| clock.day_start(d))
+        .map(|t| t.timestamp());
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(SprtError::InvalidInput("Invalid date range".to_string()));
+    };
+    Ok(Timeline {
+        from: time::format_date(from),
+        to: time::format_date(to),
+        events: storage::timeline_events_between(start, end)?
+            .into_iter()
+            .map(Event::from)
+            .collect(),
+    })
+}
+
+fn record_limit_crossings(info: &RateLimitInfo) -> Result<(), SprtError> {
+    let at = parse_time(&info.checked_at)
+        .unwrap_or_else(Utc::now)
+        .timestamp();
+    let mut windows: Vec<(&str, String, &UsageClaim)> = [
+        ("5h", "5-hour", &info.five_hour),
+        ("7d", "7-day", &info.seven_day),
+    ]
+    .into_iter()
+    .filter_map(|(id, label, claim)| Some((id, label.to_string(), claim.as_ref()?)))
+    .collect();
+    // Model-specific weekly windows, including ones newer than this code
+    windows.extend(
+        info.model_weekly_claims()
+            .into_iter()
+            .map(|w| (w.key, w.label, w.claim)),
+    );
+    for (id, label, claim) in windows {
+        // One event per threshold per window; without a reset time, per day
+        let window = claim
+            .reset
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| DayClock::load().today_key());
+        for threshold in LIMIT_THRESHOLDS.iter().filter(|t| claim.utilization >= **t) {
+            let pct = (threshold * 100.0).round() as u32;
+            let new = storage::insert_timeline_event_once(&TimelineEvent {
+                key: format!("limit:{}:{}:{}", id, pct, window),
+                at,
+                kind: "limit".to_string(),
+                title: format!("{} limit reached {}%", label, pct),
+                detail: Some(format!("{:.0}% used", claim.utilization * 100.0)),
+                project: None,
+            })?;
+            // The weekly cap of the model actually in use is the one that can stop work
+            if new
+                && *threshold >= NOTIFY_THRESHOLD
+                && info.model_weekly_claim.as_deref() == Some(id)
+            {
+                notifications::notify_app(
+                    &format!("{} limit", label),
+                    &format!("{}% of this week's {} limit used", pct, label),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Called with every fresh rate-limit response. Errors are logged; they never fail the fetch.
+pub fn observe(info: &RateLimitInfo) {
+    if let Err(e) = record_limit_crossings(info) {
+        log::warn!("Timeline tracking failed: {e}");
+    }
+}
+
+/// Commits, sessions, devlogs, limit crossings and completed goals in a date range
+/// (default today), oldest first.
+#[tauri::command]
+pub async fn get_timeline(date_range: Option<DateRange>) -> Result<Timeline, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || timeline(date_range))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,201 @@
+use crate::error::SprtError;
+use crate::session_index;
+use crate::storage::{self, CandidateTodo, SessionQuery};
+use crate::time::{self, DayClock};
+use crate::transcript::{self, Block, Message};
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Candidates kept per session and per day; the rest are usually restatements
+const MAX_PER_SESSION: usize = 10;
+const MAX_PER_DAY: usize = 30;
+const MAX_TODO_CHARS: usize = 200;
+
+/// "Next steps:", "## Follow-ups", "**Remaining work**" — list items below become candidates
+static SECTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:#+\s*)?(?:\*\*)?(?:suggested |possible )?(?:next steps?|follow[- ]ups?|remaining (?:work|tasks|items)|todos?|still to do|left to do|open items)(?:\*\*)?:?(?:\*\*)?$")
+        .expect("valid regex")
+});
+static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+(.+)$").expect("valid regex"));
+static MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:TODO|FIXME)\b:?\s*(.+)|\bnext step(?:s)?(?: (?:is|would be|will be))?(?: to|:)\s+(.+)")
+        .expect("valid regex")
+});
+
+fn clean(text: &str) -> Option<String> {
+    let text = text.replace("**", "");
+    let text = text.trim().trim_end_matches(['.', ':', ';']).trim();
+    if text.chars().count() < 8 {
+        return None;
+    }
+    if text.chars().count() > MAX_TODO_CHARS {
+        let mut short: String = text.chars().take(MAX_TODO_CHARS - 1).collect();
+        short.push('…');
+        return Some(short);
+    }
+    Some(text.to_string())
+}
+
+/// First sentence of an inline "next step is to ..." match
+fn sentence(text: &str) -> &str {
+    text.find(". ").map_or(text, |i| &text[..i])
+}
+
+/// TODO/FIXME lines, "next step" sentences and items listed under a next-steps heading.
+/// Fenced code is skipped: TODOs there belong to the code, not the plan.
+fn candidates(text: &str) -> Vec<String> {
+    let mut found = vec![];
+    let mut in_code = false;
+    let mut in_section = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if SECTION.is_match(trimmed) {
+            in_section = true;
+            continue;
+        }
+        if in_section {
+            if let Some(c) = LIST_ITEM.captures(line) {
+                found.extend(clean(&c[1]));
+                continue;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+            in_section = false;
+        }
+        if let Some(c) = MARKER.captures(trimmed) {
+            let text = c
+                .get(1)
+                .map(|m| m.as_str())
+                .or_else(|| c.get(2).map(|m| sentence(m.as_str())))
+                .unwrap_or_default();
+            found.extend(clean(text));
+        }
+    }
+    found
+}
+
+/// The assistant's last message of each turn: followed by the user typing, or by nothing
+fn final_messages(messages: &[Message]) -> Vec<&Message> {
+    let main: Vec<&Message> = messages.iter().filter(|m| !m.sidechain).collect();
+    let typed =
+        |m: &Message| m.role == "user" && m.blocks.iter().any(|b| matches!(b, Block::Text { .. }));
+    main.iter()
+        .enumerate()
+        .filter(|(i, m)| m.role == "assistant" && main.get(i + 1).is_none_or(|next| typed(next)))
+        .map(|(_, m)| *m)
+        .collect()
+}
+
+/// Candidate TODOs from the final assistant messages written on `date`, deduplicated
+pub fn extract(date: &str) -> Result<Vec<CandidateTodo>, SprtError> {
+    let clock = DayClock::load();
+    let from = session_index::day_bound(&clock, date, false)?;
+    let to = session_index::day_bound(&clock, date, true)?;
+    session_index::refresh()?;
+    let (sessions, _) = storage::query_sessions(&SessionQuery {
+        project: None,
+        from: Some(from),
+        to: Some(to),
+        device: None,
+        sort: "oldest".to_string(),
+        offset: 0,
+        limit: u32::MAX as u64,
+    })?;
+
+    let in_day = |m: &Message| {
+        m.timestamp
+            .as_deref()
+            .and_then(|t| t.parse::<chrono::DateTime<chrono::Utc>>().ok())
+            .is_some_and(|t| (from..to).contains(&t.timestamp()))
+    };
+    let mut seen = HashSet::new();
+    let mut todos = vec![];
+    for session in sessions {
+        let messages = match transcript::read_file(Path::new(&session.path)) {
+            Ok(m) => m,
+            Err(e) => {
+                log::warn!("Skipping {} for TODOs: {e}", session.session_id);
+                continue;
+            }
+        };
+        let project = Path::new(&session.project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| session.project.clone());
+        let mut from_session = 0;
+        for m in final_messages(&messages).into_iter().filter(|m| in_day(m)) {
+            for block in &m.blocks {
+                let Block::Text { text } = block else {
+                    continue;
+                };
+                for candidate in candidates(text) {
+                    if from_session >= MAX_PER_SESSION || !seen.insert(candidate.to_lowercase()) {
+                        continue;
+                    }
+                    from_session += 1;
+                    todos.push(CandidateTodo {
+                        id: format!("{}-{}", date, todos.len() + 1),
+                        text: candidate,
+                        project: project.clone(),
+                        session_id: session.session_id.clone(),
+                        found_at: m.timestamp.clone(),
+                        done: false,
+                    });
+                }
+            }
+        }
+        if todos.len() >= MAX_PER_DAY {
+            todos.truncate(MAX_PER_DAY);
+            break;
+        }
+    }
+    Ok(todos)
+}
+
+/// Candidate TODOs not yet marked done from the daily devlogs of the last `days` days
+/// (default 14), newest first. A TODO repeated on later days is listed once.
+#[tauri::command]
+pub fn get_open_todos(days: Option<u32>) -> Result<Vec<CandidateTodo>, SprtError> {
+    let days = days.unwrap_or(14).max(1);
+    let since =
+        time::format_date(DayClock::load().today() - chrono::Duration::days(days as i64 - 1));
+    let mut seen = HashSet::new();
+    Ok(storage::list_devlogs("daily", days as usize)?
+        .into_iter()
+        .filter(|log| log.date >= since)
+        .flat_map(|log| log.candidate_todos)
+        .filter(|t| !t.done && seen.insert(t.text.to_lowercase()))
+        .collect())
+}
+
+/// Mark a candidate TODO done (or open again) in the devlog it came from
+#[tauri::command]
+pub fn set_todo_done(id: String, done: bool) -> Result<CandidateTodo, SprtError> {
+    let date = id
+        .get(..10)
+        .filter(|d| time::parse_date(d).is_some())
+        .ok_or_else(|| SprtError::InvalidInput(format!("Invalid TODO id: {}", id)))?;
+    let mut log = storage::get_devlog(date, "daily")?
+        .ok_or_else(|| SprtError::NoData(format!("No devlog for {}", date)))?;
+    let todo = log
+        .candidate_todos
+        .iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| SprtError::NoData(format!("No TODO {}", id)))?;
+    todo.done = done;
+    let todo = todo.clone();
+    storage::save_devlog(&log)?;
+    Ok(todo)
+}
@@ -0,0 +1,194 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::session_index;
+use crate::storage::{self, IndexedSession};
+
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// One piece of a message's content
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Block {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    /// "user" or "assistant"; tool results arrive as user messages
+    pub role: String,
+    /// RFC 3339
+    pub timestamp: Option<String>,
+    pub model: Option<String>,
+    /// Written by a Task-tool subagent
+    pub sidechain: bool,
+    pub blocks: Vec<Block>,
+}
+
+/// Tool results are a string or a list of text/image blocks; images become a placeholder
+fn tool_result_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(parts)) => parts
+            .iter()
+            .map(|p| match p.get("type").and_then(|t| t.as_str()) {
+                Some("text") => p
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                Some(other) => format!("[{}]", other),
+                None => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn parse_blocks(content: Option<&serde_json::Value>) -> Vec<Block> {
+    let blocks = match content {
+        Some(serde_json::Value::String(s)) => return vec![Block::Text { text: s.clone() }],
+        Some(serde_json::Value::Array(blocks)) => blocks,
+        _ => return vec![],
+    };
+    let str_field = |b: &serde_json::Value, key: &str| {
+        b.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    blocks
+        .iter()
+        .filter_map(|b| match b.get("type").and_then(|t| t.as_str())? {
+            "text" => Some(Block::Text {
+                text: str_field(b, "text"),
+            }),
+            "thinking" => Some(Block::Thinking {
+                text: str_field(b, "thinking"),
+            }),
+            "tool_use" => Some(Block::ToolUse {
+                id: str_field(b, "id"),
+                name: str_field(b, "name"),
+                input: b.get("input").cloned().unwrap_or_default(),
+            }),
+            "tool_result" => Some(Block::ToolResult {
+                tool_use_id: str_field(b, "tool_use_id"),
+                content: tool_result_text(b.get("content")),
+                is_error: b.get("is_error").and_then(|v| v.as_bool()) == Some(true),
+            }),
+            _ => None,
+        })
+        .filter(|b| !matches!(b, Block::Text { text } | Block::Thinking { text } if text.trim().is_empty()))
+        .collect()
+}
+
+/// Read a session log into its conversation. Claude Code writes one line per content block
+/// of a response, so lines sharing a message id are merged back into one message. Meta
+/// entries (injected context, not typed by the user) are left out.
+pub fn parse(reader: impl BufRead) -> Vec<Message> {
+    let mut messages: Vec<Message> = vec![];
+    let mut last_id: Option<String> = None;
+    for line in reader.lines().map_while(Result::ok) {
+        if !line.contains("\"type\":\"user\"") && !line.contains("\"type\":\"assistant\"") {
+            continue;
+        }
+        let entry: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let role = match entry.get("type").and_then(|v| v.as_str()) {
+            Some(r @ ("user" | "assistant")) => r.to_string(),
+            _ => continue,
+        };
+        if entry.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+        let message = entry.get("message");
+        let blocks = parse_blocks(message.and_then(|m| m.get("content")));
+        if blocks.is_empty() {
+            continue;
+        }
+        let id = message
+            .and_then(|m| m.get("id"))
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        if role == "assistant" && id.is_some() && id == last_id {
+            if let Some(prev) = messages.last_mut() {
+                prev.blocks.extend(blocks);
+                continue;
+            }
+        }
+        last_id = id;
+        messages.push(Message {
+            role,
+            timestamp: entry
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            model: message
+                .and_then(|m| m.get("model"))
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            sidechain: claude::is_sidechain(&entry),
+            blocks,
+        });
+    }
+    messages
+}
+
+/// A session log's contents, gunzipped if needed (archived copies may be compressed)
+pub fn open(path: &Path) -> Result<Box<dyn BufRead>, SprtError> {
+    let file = fs::File::open(path)
+        .map_err(|e| SprtError::Internal(format!("Cannot open session log: {}", e)))?;
+    if path.extension().is_some_and(|e| e == "gz") {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Parse a session log, gzipped or not
+pub fn read_file(path: &Path) -> Result<Vec<Message>, SprtError> {
+    Ok(parse(open(path)?))
+}
+
+/// Where to read a session from: its log or, once the log is gone, the archived copy
+pub fn readable_path(path: &str, archived: Option<&str>) -> String {
+    match archived {
+        Some(a) if !Path::new(path).exists() => a.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// A session's index entry and conversation, read from its log or, once the log is gone,
+/// from the archived copy
+pub fn load(session_id: &str) -> Result<(IndexedSession, Vec<Message>), SprtError> {
+    session_index::refresh()?;
+    let (session, archived) = storage::find_session(session_id)?
+        .ok_or_else(|| SprtError::NoData(format!("No session {}", session_id)))?;
+    let path = readable_path(&session.path, archived.as_deref());
+    let messages = read_file(Path::new(&path))?;
+    Ok((session, messages))
+}
@@ -0,0 +1,91 @@
+use tauri::image::Image;
+use tauri::{AppHandle, PhysicalPosition, Rect, Runtime, WebviewWindow};
+
+pub const MAIN_TRAY_ID: &str = "main-tray";
+/// Optional second icon showing rate limits only (see limits_tray)
+pub const LIMITS_TRAY_ID: &str = "limits-tray";
+
+/// The bundled tray icon, decoded
+pub fn bundled_icon() -> Option<Image<'static>> {
+    let icon_bytes = include_bytes!("../icons/tray-icon.png");
+    let rgba = image::load_from_memory(icon_bytes).ok()?.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    Some(Image::new_owned(rgba.into_raw(), w, h))
+}
+
+/// Put the bundled tray icon back (at startup, or after the chart mode is turned off)
+pub fn set_default_icon<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(MAIN_TRAY_ID) else {
+        return;
+    };
+    if let Some(icon) = bundled_icon() {
+        let _ = tray.set_icon(Some(icon));
+        // Template icons adapt to light/dark menu bars; macOS only
+        #[cfg(target_os = "macos")]
+        let _ = tray.set_icon_as_template(true);
+    }
+}
+
+/// Show text next to the tray icon. macOS and Linux (AppIndicator label) render titles;
+/// Windows has no tray title, so the text goes into the tooltip instead.
+pub fn set_tray_text<R: Runtime>(app: &AppHandle<R>, text: &str) {
+    if let Some(tray) = app.tray_by_id(MAIN_TRAY_ID) {
+        let _ = tray.set_title(Some(text));
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = tray.set_tooltip(Some(format!("SPRT — {}", text)));
+        }
+    }
+}
+
+/// Default tray text: 5-hour utilization from the rate-limit cache, "—" before the first fetch
+pub fn utilization_text() -> String {
+    crate::claude::get_cached_utilization()
+        .map(|pct| format!("{}%", (pct * 100.0).round() as u32))
+        .unwrap_or_else(|| "—".to_string())
+}
+
+/// Place the popover next to the tray icon. Menu bars (macOS, most Linux panels) sit at the
+/// top, so it opens below the icon; Windows taskbars usually sit at the bottom, so it opens
+/// above. Decided from where the icon actually is, which also handles top/side taskbars.
+pub fn position_popover<R: Runtime>(w: &WebviewWindow<R>, rect: &Rect) {
+    let scale = w.scale_factor().unwrap_or(1.0);
+    let (tray_x, tray_y) = match rect.position {
+        tauri::Position::Physical(p) => (p.x as f64, p.y as f64),
+        tauri::Position::Logical(p) => (p.x * scale, p.y * scale),
+    };
+    let (tray_w, tray_h) = match rect.size {
+        tauri::Size::Physical(s) => (s.width as f64, s.height as f64),
+        tauri::Size::Logical(s) => (s.width * scale, s.height * scale),
+    };
+    let (pop_w, pop_h) = w
+        .outer_size()
+        .map(|s| (s.width as f64, s.height as f64))
+        .unwrap_or((250.0 * scale, 100.0 * scale));
+
+    let monitor = w
+        .monitor_from_point(tray_x, tray_y)
+        .ok()
+        .flatten()
+        .or_else(|| w.primary_monitor().ok().flatten());
+    let (mon_x, mon_y, mon_w, mon_h) = monitor
+        .map(|m| {
+            let p = m.position();
+            let s = m.size();
+            (p.x as f64, p.y as f64, s.width as f64, s.height as f64)
+        })
+        .unwrap_or((0.0, 0.0, f64::MAX, f64::MAX));
+
+    let margin = 8.0 * scale;
+    let x = (tray_x + tray_w / 2.0 - pop_w / 2.0)
+        .min(mon_x + mon_w - pop_w - margin)
+        .max(mon_x + margin);
+    let icon_in_bottom_half = tray_y - mon_y > mon_h / 2.0;
+    let y = if icon_in_bottom_half {
+        tray_y - pop_h - 4.0 * scale
+    } else {
+        tray_y + tray_h + 4.0 * scale
+    };
+
+    let _ = w.set_position(PhysicalPosition::new(x as i32, y as i32));
+}
@@ -0,0 +1,134 @@
+use crate::claude;
+use crate::settings;
+use crate::tray;
+
+use image::{Rgba, RgbaImage};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::image::Image;
+use tauri::{AppHandle, Runtime};
+
+/// Icon size in pixels; twice the menu-bar point size so it stays sharp on Retina
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 36;
+const BARS: usize = 12;
+/// Hourly token buckets are rescanned at most this often
+const TOKENS_REFRESH: Duration = Duration::from_secs(60);
+/// One utilization sample per bar over the 5-hour window
+const SAMPLE_EVERY_SECS: i64 = 5 * 3600 / BARS as i64;
+
+/// (unix secs, utilization 0–1), oldest first
+static UTILIZATION: LazyLock<Mutex<VecDeque<(i64, f64)>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+/// When the buckets were scanned, and tokens per hour
+type HourlyTokens = (Instant, Vec<u64>);
+
+static TOKENS: LazyLock<Mutex<Option<HourlyTokens>>> = LazyLock::new(|| Mutex::new(None));
+/// Whether the icon currently shows a chart, so turning the mode off restores it once
+static SHOWING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_os = "macos")]
+const BAR_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+#[cfg(not(target_os = "macos"))]
+const BAR_COLOR: Rgba<u8> = Rgba([220, 220, 220, 255]);
+
+fn record_utilization() {
+    let Some(pct) = claude::get_cached_utilization() else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp();
+    let mut samples = UTILIZATION.lock().unwrap_or_else(|e| e.into_inner());
+    if samples
+        .back()
+        .is_some_and(|(t, _)| now - t < SAMPLE_EVERY_SECS)
+    {
+        return;
+    }
+    samples.push_back((now, pct));
+    while samples.len() > BARS {
+        samples.pop_front();
+    }
+}
+
+/// Tokens per hour for the last BARS hours, oldest first
+fn hourly_tokens() -> Vec<u64> {
+    let mut cached = TOKENS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, values)) = cached.as_ref() {
+        if at.elapsed() < TOKENS_REFRESH {
+            return values.clone();
+        }
+    }
+    let now = chrono::Utc::now();
+    let since = now - chrono::Duration::hours(BARS as i64);
+    let mut buckets = vec![0u64; BARS];
+    let scanned = claude::scan_usage(since, |e| {
+        let hours_ago = (now - e.timestamp).num_hours().max(0) as usize;
+        if hours_ago < BARS {
+            buckets[BARS - 1 - hours_ago] += e.total_tokens();
+        }
+    });
+    if scanned.is_err() {
+        return cached.as_ref().map(|(_, v)| v.clone()).unwrap_or_default();
+    }
+    *cached = Some((Instant::now(), buckets.clone()));
+    buckets
+}
+
+/// Bars scaled to `max`, right-aligned so the newest value is at the edge
+fn render(values: &[f64], max: f64) -> RgbaImage {
+    let mut img = RgbaImage::new(WIDTH, HEIGHT);
+    let slot = WIDTH / BARS as u32;
+    let offset = BARS.saturating_sub(values.len()) as u32;
+    for (i, v) in values.iter().enumerate() {
+        let ratio = if max > 0.0 {
+            (v / max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        // A one-pixel stub keeps idle hours visible as a baseline
+        let height = ((ratio * HEIGHT as f64).round() as u32).max(1);
+        let x0 = (offset + i as u32) * slot;
+        for x in x0..x0 + slot.saturating_sub(1) {
+            for y in HEIGHT - height..HEIGHT {
+                img.put_pixel(x, y, BAR_COLOR);
+            }
+        }
+    }
+    img
+}
+
+/// Called from the tray thread. Draws the configured chart ("tokens" or "utilization") as the
+/// tray icon, or restores the normal icon when the mode is off.
+pub fn tick<R: Runtime>(app: &AppHandle<R>) {
+    record_utilization();
+    let mode = settings::load().tray_chart;
+    let img = match mode.as_deref() {
+        Some("tokens") => {
+            let values: Vec<f64> = hourly_tokens().into_iter().map(|t| t as f64).collect();
+            let max = values.iter().cloned().fold(0.0, f64::max);
+            render(&values, max)
+        }
+        Some("utilization") => {
+            let samples = UTILIZATION.lock().unwrap_or_else(|e| e.into_inner());
+            let values: Vec<f64> = samples.iter().map(|(_, u)| *u).collect();
+            render(&values, 1.0)
+        }
+        _ => {
+            if SHOWING.swap(false, Ordering::Relaxed) {
+                tray::set_default_icon(app);
+            }
+            return;
+        }
+    };
+    if let Some(icon) = app.tray_by_id(tray::MAIN_TRAY_ID) {
+        let (w, h) = img.dimensions();
+        if let Err(e) = icon.set_icon(Some(Image::new(img.as_raw(), w, h))) {
+            log::warn!("Cannot set tray chart icon: {e}");
+        }
+        #[cfg(target_os = "macos")]
+        let _ = icon.set_icon_as_template(true);
+        SHOWING.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::error::SprtError;
+use crate::storage;
+use crate::time::{format_date, parse_date, DayClock};
+
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScorePoint {
+    pub date: String,
+    pub score: u32,
+    /// Mean score of logged days in the 7 calendar days ending on this date
+    pub rolling_avg: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreHistory {
+    pub points: Vec<ScorePoint>,
+    pub average: Option<f64>,
+    pub best: Option<ScorePoint>,
+    pub worst: Option<ScorePoint>,
+    pub this_week_avg: Option<f64>,
+    pub last_week_avg: Option<f64>,
+    /// Percent change of this week's average vs. last week's
+    pub week_over_week_change: Option<f64>,
+}
+
+fn mean(values: impl Iterator<Item = u32>) -> Option<f64> {
+    let (sum, n) = values.fold((0u64, 0u64), |(s, n), v| (s + v as u64, n + 1));
+    (n > 0).then(|| sum as f64 / n as f64)
+}
+
+/// Build the score series for the last `days` days, ending today
+pub fn score_history(days: u32) -> Result<ScoreHistory, SprtError> {
+    let today = DayClock::load().today();
+    let start = today - chrono::Duration::days(days.max(1) as i64 - 1);
+    // Load a week of lead-in so the first points have a full rolling window, and at least
+    // the two weeks the week-over-week comparison reads
+    let lead_in = (start - chrono::Duration::days(6)).min(today - chrono::Duration::days(13));
+    let rows = storage::daily_scores_since(&format_date(lead_in))?;
+    let dated: Vec<(chrono::NaiveDate, &String, u32)> = rows
+        .iter()
+        .filter_map(|(d, s)| parse_date(d).map(|nd| (nd, d, *s)))
+        .collect();
+
+    let avg_between = |from: chrono::NaiveDate, to: chrono::NaiveDate| {
+        mean(dated.iter().filter(|(d, _, _)| *d >= from && *d <= to).map(|(_, _, s)| *s))
+    };
+
+    let points: Vec<ScorePoint> = dated
+        .iter()
+        .filter(|(d, _, _)| *d >= start)
+        .map(|(d, date, score)| ScorePoint {
+            date: date.to_string(),
+            score: *score,
+            rolling_avg: avg_between(*d - chrono::Duration::days(6), *d).unwrap_or(*score as f64),
+        })
+        .collect();
+
+    let this_week_avg = avg_between(today - chrono::Duration::days(6), today);
+    let last_week_avg = avg_between(
+        today - chrono::Duration::days(13),
+        today - chrono::Duration::days(7),
+    );
+    let week_over_week_change = match (this_week_avg, last_week_avg) {
+        (Some(this), Some(last)) if last > 0.0 => Some((this - last) / last * 100.0),
+        _ => None,
+    };
+
+    // Ties go to the most recent day
+    let best = points.iter().max_by_key(|p| p.score).cloned();
+    let worst = points.iter().rev().min_by_key(|p| p.score).cloned();
+
+    Ok(ScoreHistory {
+        average: mean(points.iter().map(|p| p.score)),
+        best,
+        worst,
+        this_week_avg,
+        last_week_avg,
+        week_over_week_change,
+        points,
+    })
+}
+
+#[tauri::command]
+pub async fn get_score_history(days: Option<u32>) -> Result<ScoreHistory, SprtError> {
+    let days = days.unwrap_or(30);
+    tauri::async_runtime::spawn_blocking(move || score_history(days))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
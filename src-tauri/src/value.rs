@@ -0,0 +1,139 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::settings;
+use crate::time::{self, DayClock};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Monthly list price of a plan, or None for plans without a fixed price (enterprise, API)
+fn plan_fee(plan: &str, tier: &str) -> Option<f64> {
+    match plan {
+        "pro" => Some(20.0),
+        "max" if tier.contains("20x") => Some(200.0),
+        "max" => Some(100.0),
+        "team" => Some(30.0),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueReport {
+    /// Inclusive, YYYY-MM-DD
+    pub from: String,
+    pub to: String,
+    pub plan_type: String,
+    pub rate_limit_tier: String,
+    /// Monthly fee: `subscriptionUsd` from settings, else the plan's list price
+    pub monthly_fee_usd: Option<f64>,
+    /// The fee's share for the days covered
+    pub fee_usd: Option<f64>,
+    /// What the period's tokens would have cost at API list prices
+    pub api_cost_usd: f64,
+    /// api_cost_usd extended to the whole month at the same daily rate; month reports only
+    pub projected_api_cost_usd: Option<f64>,
+    pub tokens: u64,
+    pub cost_by_model: HashMap<String, f64>,
+    /// API cost ÷ fee; above 1 the subscription is the cheaper option
+    pub value_ratio: Option<f64>,
+    /// API cost minus fee
+    pub savings_usd: Option<f64>,
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (y, m) = (date.year(), date.month());
+    let next = if m == 12 {
+        NaiveDate::from_ymd_opt(y + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(y, m + 1, 1)
+    };
+    next.map(|n| (n - date.with_day(1).unwrap_or(date)).num_days() as u32)
+        .unwrap_or(30)
+}
+
+/// API-price value of the days `from` through `to` against the matching share of the
+/// monthly fee (prorated by the length of `from`'s month)
+pub fn report(from: NaiveDate, to: NaiveDate) -> Result<ValueReport, SprtError> {
+    if from > to {
+        return Err(SprtError::InvalidInput(
+            "Range start is after its end".to_string(),
+        ));
+    }
+    let clock = DayClock::load();
+    let start = clock.day_start(from).map(|t| t.with_timezone(&chrono::Utc));
+    let end = to
+        .succ_opt()
+        .and_then(|d| clock.day_start(d))
+        .map(|t| t.with_timezone(&chrono::Utc));
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(SprtError::InvalidInput("Invalid date range".to_string()));
+    };
+
+    let mut tokens = 0;
+    let mut cost_by_model: HashMap<String, f64> = HashMap::new();
+    claude::scan_usage(start, |e| {
+        if e.timestamp < end {
+            tokens += e.total_tokens();
+            *cost_by_model.entry(e.model.to_string()).or_insert(0.0) += e.cost_usd();
+        }
+    })?;
+    let api_cost_usd: f64 = cost_by_model.values().sum();
+
+    let (plan_type, rate_limit_tier) = claude::plan()?;
+    let monthly_fee_usd = settings::load()
+        .subscription_usd
+        .or_else(|| plan_fee(&plan_type, &rate_limit_tier));
+    let days = ((to - from).num_days() + 1) as f64;
+    let fee_usd = monthly_fee_usd.map(|f| f * days / days_in_month(from) as f64);
+
+    Ok(ValueReport {
+        from: time::format_date(from),
+        to: time::format_date(to),
+        plan_type,
+        rate_limit_tier,
+        monthly_fee_usd,
+        fee_usd,
+        api_cost_usd,
+        projected_api_cost_usd: None,
+        tokens,
+        cost_by_model,
+        value_ratio: fee_usd.filter(|f| *f > 0.0).map(|f| api_cost_usd / f),
+        savings_usd: fee_usd.map(|f| api_cost_usd - f),
+    })
+}
+
+/// One calendar month ("YYYY-MM", default the current one), up to today if it's in progress.
+/// The fee is compared against the whole month; the projection shows where the month is headed.
+pub fn month_report(month: Option<&str>) -> Result<ValueReport, SprtError> {
+    let today = DayClock::load().today();
+    let first = match month {
+        Some(m) => time::parse_date(&format!("{}-01", m))
+            .ok_or_else(|| SprtError::InvalidInput(format!("Invalid month: {}", m)))?,
+        None => today.with_day(1).unwrap_or(today),
+    };
+    let month_days = days_in_month(first);
+    let last = first + chrono::Duration::days(month_days as i64 - 1);
+    if first > today {
+        return Err(SprtError::InvalidInput(format!(
+            "{} hasn't started yet",
+            &time::format_date(first)[..7]
+        )));
+    }
+    let to = last.min(today);
+
+    let mut r = report(first, last)?;
+    r.to = time::format_date(to);
+    let elapsed = ((to - first).num_days() + 1) as f64;
+    r.projected_api_cost_usd = Some(r.api_cost_usd * month_days as f64 / elapsed);
+    Ok(r)
+}
+
+/// What a month's usage would have cost at API prices vs. the subscription fee
+#[tauri::command]
+pub async fn get_value_report(month: Option<String>) -> Result<ValueReport, SprtError> {
+    tauri::async_runtime::spawn_blocking(move || month_report(month.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,152 @@
+use crate::claude;
+use crate::error::SprtError;
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Responses older than this are dropped; also the largest window the command accepts
+const MAX_WINDOW_MINUTES: u32 = 60;
+const DEFAULT_WINDOW_MINUTES: u32 = 30;
+/// The headline rate averages this many minutes, so one big response doesn't read as a spike
+const RATE_MINUTES: u32 = 5;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenVelocity {
+    pub window_minutes: u32,
+    /// Tokens per minute, oldest first; the last entry is the minute in progress
+    pub per_minute: Vec<u64>,
+    /// Average over the last few minutes
+    pub tokens_per_minute: f64,
+    pub window_tokens: u64,
+}
+
+#[derive(Default)]
+struct Tail {
+    /// Bytes of each log already read; lines are only consumed once complete
+    offsets: HashMap<PathBuf, u64>,
+    /// (unix seconds, tokens) per assistant response, oldest first
+    samples: VecDeque<(i64, u64)>,
+    seeded: bool,
+}
+
+static TAIL: LazyLock<Mutex<Tail>> = LazyLock::new(|| Mutex::new(Tail::default()));
+
+fn cutoff() -> i64 {
+    chrono::Utc::now().timestamp() - MAX_WINDOW_MINUTES as i64 * 60
+}
+
+impl Tail {
+    /// Read the lines appended to `path` since the last call
+    fn read(&mut self, path: &Path) {
+        let Ok(mut file) = fs::File::open(path) else {
+            return;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let mut offset = self.offsets.get(path).copied().unwrap_or(0);
+        if offset > len {
+            // Rewritten from scratch
+            offset = 0;
+        }
+        if offset == len || file.seek(SeekFrom::Start(offset)).is_err() {
+            return;
+        }
+        let since = cutoff();
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        while let Ok(n) = reader.read_line(&mut line) {
+            if n == 0 || !line.ends_with('\n') {
+                break;
+            }
+            offset += n as u64;
+            if line.contains("\"type\":\"assistant\"") {
+                let at = serde_json::from_str::<serde_json::Value>(&line)
+                    .ok()
+                    .and_then(|v| v.get("timestamp")?.as_str()?.parse().ok())
+                    .map(|t: chrono::DateTime<chrono::Utc>| t.timestamp());
+                let tokens = claude::line_tokens(&line);
+                if let Some(at) = at.filter(|&at| at >= since && tokens > 0) {
+                    self.samples.push_back((at, tokens));
+                }
+            }
+            line.clear();
+        }
+        self.offsets.insert(path.to_path_buf(), offset);
+    }
+
+    /// Start every log at its end, except those written within the window
+    fn seed(&mut self) -> Result<(), SprtError> {
+        let since = std::time::SystemTime::now()
+            - std::time::Duration::from_secs(MAX_WINDOW_MINUTES as u64 * 60);
+        for (_, path) in claude::session_logs()? {
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            if meta.modified().is_ok_and(|t| t >= since) {
+                self.read(&path);
+            } else {
+                self.offsets.insert(path, meta.len());
+            }
+        }
+        self.seeded = true;
+        Ok(())
+    }
+
+    fn prune(&mut self) {
+        let since = cutoff();
+        // Lines from different logs arrive out of order, so filter rather than pop the front
+        self.samples.retain(|(at, _)| *at >= since);
+    }
+}
+
+/// Watcher hook for a changed session log; a no-op until the first velocity request
+pub fn on_file_changed(path: &Path) {
+    let mut tail = TAIL.lock().unwrap_or_else(|e| e.into_inner());
+    if tail.seeded {
+        tail.read(path);
+        tail.prune();
+    }
+}
+
+fn velocity(window_minutes: u32) -> Result<TokenVelocity, SprtError> {
+    let mut tail = TAIL.lock().unwrap_or_else(|e| e.into_inner());
+    if !tail.seeded {
+        tail.seed()?;
+    }
+    tail.prune();
+
+    let now = chrono::Utc::now().timestamp();
+    let current_minute = now.div_euclid(60);
+    let mut per_minute = vec![0u64; window_minutes as usize];
+    for (at, tokens) in &tail.samples {
+        let age = current_minute - at.div_euclid(60);
+        if (0..window_minutes as i64).contains(&age) {
+            per_minute[window_minutes as usize - 1 - age as usize] += tokens;
+        }
+    }
+    let recent = RATE_MINUTES.min(window_minutes) as usize;
+    let recent_tokens: u64 = per_minute.iter().rev().take(recent).sum();
+    Ok(TokenVelocity {
+        window_minutes,
+        tokens_per_minute: recent_tokens as f64 / recent as f64,
+        window_tokens: per_minute.iter().sum(),
+        per_minute,
+    })
+}
+
+/// Per-minute token rates over the last `window_minutes` (default 30, at most 60), for the
+/// burn-rate gauge. Kept current by tailing session logs as the watcher reports writes;
+/// refetch on `sessions-changed`.
+#[tauri::command]
+pub async fn get_token_velocity(window_minutes: Option<u32>) -> Result<TokenVelocity, SprtError> {
+    let window = window_minutes
+        .unwrap_or(DEFAULT_WINDOW_MINUTES)
+        .clamp(1, MAX_WINDOW_MINUTES);
+    tauri::async_runtime::spawn_blocking(move || velocity(window))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
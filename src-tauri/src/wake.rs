@@ -0,0 +1,99 @@
+use crate::cache;
+use crate::claude;
+use crate::monitor;
+use crate::settings;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+/// Emitted once the caches are cleared and rate limits refetched after a wake
+pub const SYSTEM_WOKE: &str = "system-woke";
+
+const TICK_SECS: u64 = 5;
+/// Wall-clock time passing this much faster than the tick means the machine slept
+const WAKE_GAP_SECS: u64 = 60;
+
+/// Wakes since launch; the file watcher re-arms when it changes
+static WAKES: AtomicU64 = AtomicU64::new(0);
+/// When the last wake was handled. The OS notification and the clock gap both see the same
+/// wake, in either order; the second is dropped.
+static LAST_HANDLED: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub fn count() -> u64 {
+    WAKES.load(Ordering::Relaxed)
+}
+
+/// `slept` when known from the clock gap; None from the OS notification
+fn on_wake(app: &AppHandle, slept: Option<Duration>) {
+    {
+        let mut last = LAST_HANDLED.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|t| t.elapsed() < Duration::from_secs(WAKE_GAP_SECS)) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    match slept {
+        Some(slept) => log::info!("System woke after ~{} min", slept.as_secs() / 60),
+        None => log::info!("System woke"),
+    }
+    WAKES.fetch_add(1, Ordering::Relaxed);
+    cache::invalidate_all();
+    if !monitor::is_paused() {
+        let force = settings::load().rate_limit_polling.force_on_wake;
+        if let Err(e) = tauri::async_runtime::block_on(claude::get_rate_limits(Some(force))) {
+            log::warn!("Cannot refresh rate limits after wake: {e}");
+        }
+    }
+    let _ = app.emit(SYSTEM_WOKE, ());
+}
+
+/// macOS: NSWorkspace's did-wake notification, posted on the main thread
+#[cfg(target_os = "macos")]
+fn observe_did_wake(app: AppHandle) {
+    use block2::RcBlock;
+    use objc2_app_kit::{NSWorkspace, NSWorkspaceDidWakeNotification};
+    use objc2_foundation::NSNotification;
+    use std::ptr::NonNull;
+
+    let block = RcBlock::new(move |_: NonNull<NSNotification>| {
+        // Refetching rate limits blocks; keep it off the main thread
+        let app = app.clone();
+        std::thread::spawn(move || on_wake(&app, None));
+    });
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    // SAFETY: the name is AppKit's constant, and a nil queue delivers on the posting thread
+    let observer = unsafe {
+        center.addObserverForName_object_queue_usingBlock(
+            Some(NSWorkspaceDidWakeNotification),
+            None,
+            None,
+            &block,
+        )
+    };
+    // Observed for the life of the app
+    std::mem::forget(observer);
+}
+
+/// Sleep detection. On macOS the did-wake notification comes first; everywhere, as a fallback,
+/// the thread sleeps in short ticks, and a tick that took far longer by the wall clock spanned
+/// a system sleep. (`Instant` stands still during sleep on macOS, so only `SystemTime` shows
+/// the gap.)
+pub fn start(app: AppHandle) {
+    #[cfg(target_os = "macos")]
+    observe_did_wake(app.clone());
+    std::thread::spawn(move || {
+        let tick = Duration::from_secs(TICK_SECS);
+        let mut last = SystemTime::now();
+        loop {
+            std::thread::sleep(tick);
+            let now = SystemTime::now();
+            let elapsed = now.duration_since(last).unwrap_or_default();
+            last = now;
+            if elapsed > tick + Duration::from_secs(WAKE_GAP_SECS) {
+                on_wake(&app, Some(elapsed));
+            }
+        }
+    });
+}
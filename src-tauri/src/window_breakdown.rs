@@ -0,0 +1,185 @@
+use crate::claude;
+use crate::error::SprtError;
+use crate::session_index;
+use crate::storage;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+const WINDOW_SECS: i64 = 5 * 3600;
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionShare {
+    pub session_id: String,
+    pub title: Option<String>,
+    pub tokens: u64,
+    pub cost_usd: f64,
+    /// Fraction of the window's usage, 0–1
+    pub share: f64,
+    /// Estimated points of 5-hour utilization (share × utilization), 0–1
+    pub utilization: Option<f64>,
+    pub messages: u64,
+    /// RFC 3339, within the window
+    pub first_at: Option<String>,
+    pub last_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectShare {
+    /// Encoded projects dir name
+    pub project: String,
+    pub name: String,
+    pub tokens: u64,
+    pub cost_usd: f64,
+    pub share: f64,
+    pub utilization: Option<f64>,
+    /// Biggest first
+    pub sessions: Vec<SessionShare>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBreakdown {
+    /// RFC 3339
+    pub window_start: String,
+    pub window_end: String,
+    /// False when no reset time is known and the last 5 hours stand in for the window
+    pub aligned: bool,
+    /// Reported 5-hour utilization, 0–1, from the last rate-limit fetch
+    pub utilization: Option<f64>,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+    /// Biggest first
+    pub projects: Vec<ProjectShare>,
+}
+
+/// Current 5-hour window: ends at the reported reset, else the last 5 hours
+pub(crate) fn current_window(now: i64) -> (i64, i64, bool) {
+    let reset = claude::get_cached_rate_limits()
+        .and_then(|i| i.five_hour)
+        .and_then(|c| c.reset)
+        .map(|r| r as i64)
+        .filter(|r| *r > now && *r - WINDOW_SECS <= now);
+    match reset {
+        Some(end) => (end - WINDOW_SECS, end, true),
+        None => (now - WINDOW_SECS, now, false),
+    }
+}
+
+fn rfc3339(t: i64) -> String {
+    chrono::DateTime::from_timestamp(t, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Attribute the current window's usage to projects and sessions. Shares are by estimated
+/// cost rather than raw tokens, since cache reads are plentiful but count for little
+/// against the limit; tokens are the fallback when no model has a price.
+pub fn window_breakdown() -> Result<WindowBreakdown, SprtError> {
+    let now = chrono::Utc::now().timestamp();
+    let (start, end, aligned) = current_window(now);
+    let since = chrono::DateTime::from_timestamp(start, 0).unwrap_or_default();
+
+    // (project, session) → usage
+    let mut sessions: HashMap<(String, String), (SessionShare, i64, i64)> = HashMap::new();
+    claude::scan_usage(since, |e| {
+        let t = e.timestamp.timestamp();
+        let (s, first, last) = sessions
+            .entry((e.project.to_string(), e.session_id.to_string()))
+            .or_insert_with(|| (SessionShare::default(), t, t));
+        s.tokens += e.total_tokens();
+        s.cost_usd += e.cost_usd();
+        s.messages += 1;
+        *first = (*first).min(t);
+        *last = (*last).max(t);
+    })?;
+
+    let total_tokens: u64 = sessions.values().map(|(s, _, _)| s.tokens).sum();
+    let total_cost_usd: f64 = sessions.values().map(|(s, _, _)| s.cost_usd).sum();
+    let weight = |tokens: u64, cost: f64| {
+        if total_cost_usd > 0.0 {
+            cost / total_cost_usd
+        } else if total_tokens > 0 {
+            tokens as f64 / total_tokens as f64
+        } else {
+            0.0
+        }
+    };
+    let utilization = claude::get_cached_utilization();
+
+    let mut titles: HashMap<String, Option<String>> = HashMap::new();
+    let mut project_paths: HashMap<String, String> = HashMap::new();
+    if session_index::refresh().is_ok() {
+        let mut projects: Vec<&String> = sessions.keys().map(|(p, _)| p).collect();
+        projects.sort();
+        projects.dedup();
+        for project in projects {
+            for s in storage::project_sessions(project).unwrap_or_default() {
+                project_paths.insert(s.project.clone(), s.project_path.clone());
+                titles.insert(s.session_id, s.title);
+            }
+        }
+    }
+
+    let mut by_project: HashMap<String, ProjectShare> = HashMap::new();
+    for ((project, session_id), (mut s, first, last)) in sessions {
+        s.share = weight(s.tokens, s.cost_usd);
+        s.utilization = utilization.map(|u| u * s.share);
+        s.title = titles.get(&session_id).cloned().flatten();
+        s.session_id = session_id;
+        s.first_at = Some(rfc3339(first));
+        s.last_at = Some(rfc3339(last));
+
+        let p = by_project.entry(project.clone()).or_insert_with(|| {
+            let path = project_paths
+                .get(&project)
+                .cloned()
+                .unwrap_or_else(|| crate::git::decode_project_path(&project));
+            ProjectShare {
+                name: Path::new(&path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| project.clone()),
+                project,
+                tokens: 0,
+                cost_usd: 0.0,
+                share: 0.0,
+                utilization: None,
+                sessions: vec![],
+            }
+        });
+        p.tokens += s.tokens;
+        p.cost_usd += s.cost_usd;
+        p.sessions.push(s);
+    }
+
+    let mut projects: Vec<ProjectShare> = by_project.into_values().collect();
+    for p in &mut projects {
+        p.share = weight(p.tokens, p.cost_usd);
+        p.utilization = utilization.map(|u| u * p.share);
+        p.sessions.sort_by(|a, b| b.share.total_cmp(&a.share));
+    }
+    projects.sort_by(|a, b| b.share.total_cmp(&a.share));
+
+    Ok(WindowBreakdown {
+        window_start: rfc3339(start),
+        window_end: rfc3339(end),
+        aligned,
+        utilization,
+        total_tokens,
+        total_cost_usd,
+        projects,
+    })
+}
+
+/// What used up the current 5-hour window, per project and session, estimated from
+/// local session logs
+#[tauri::command]
+pub async fn get_window_breakdown() -> Result<WindowBreakdown, SprtError> {
+    tauri::async_runtime::spawn_blocking(window_breakdown)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
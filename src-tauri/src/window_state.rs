@@ -0,0 +1,72 @@
+use crate::dock;
+use crate::settings::{self, WindowBounds};
+
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow};
+
+/// Smallest saved size worth restoring; anything below is treated as garbage
+const MIN_SIDE: u32 = 100;
+
+/// Saved bounds are only used while their top-left corner is still on a connected monitor
+fn on_screen<R: Runtime>(w: &WebviewWindow<R>, b: &WindowBounds) -> bool {
+    let monitors = w.available_monitors().unwrap_or_default();
+    monitors.iter().any(|m| {
+        let p = m.position();
+        let s = m.size();
+        b.x >= p.x && b.y >= p.y && b.x < p.x + s.width as i32 && b.y < p.y + s.height as i32
+    })
+}
+
+/// Hide the popover and show the dashboard where it was last left, centered the first time
+pub fn show_dashboard<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(p) = app.get_webview_window("popover") {
+        let _ = p.hide();
+    }
+    let Some(w) = app.get_webview_window("main") else {
+        return;
+    };
+    match settings::load().window.bounds {
+        Some(b) if b.width >= MIN_SIDE && b.height >= MIN_SIDE && on_screen(&w, &b) => {
+            let _ = w.set_size(PhysicalSize::new(b.width, b.height));
+            let _ = w.set_position(PhysicalPosition::new(b.x, b.y));
+        }
+        _ => {
+            let _ = w.center();
+        }
+    }
+    let _ = w.show();
+    let _ = w.set_focus();
+    dock::mark_devlogs_seen();
+}
+
+/// Save the dashboard's bounds, and whether it should reopen at the next launch
+pub fn remember<R: Runtime>(w: &WebviewWindow<R>, open: bool) {
+    let mut s = settings::load();
+    if let (Ok(pos), Ok(size)) = (w.outer_position(), w.inner_size()) {
+        s.window.bounds = Some(WindowBounds {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+        });
+    }
+    s.window.dashboard_open = open;
+    if let Err(e) = settings::save(&s) {
+        log::warn!("Failed to save window state: {e}");
+    }
+}
+
+/// Before quitting: record whether the dashboard is up so launch can bring it back
+pub fn remember_on_quit<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(w) = app.get_webview_window("main") {
+        let open = w.is_visible().unwrap_or(false);
+        remember(&w, open);
+    }
+}
+
+/// At launch: reopen the dashboard if it was open at last quit, unless start_hidden is set
+pub fn restore_on_launch<R: Runtime>(app: &AppHandle<R>) {
+    let window = settings::load().window;
+    if window.dashboard_open && !window.start_hidden {
+        show_dashboard(app);
+    }
+}
@@ -47,15 +47,43 @@ interface RateLimitInfo {
   fiveHour: UsageClaim | null;
   sevenDay: UsageClaim | null;
   sevenDaySonnet: UsageClaim | null;
+  sevenDayOpus: UsageClaim | null;
   overageStatus: string | null;
   overageDisabledReason: string | null;
   overageReset: number | null;
   fallbackPercentage: number | null;
   checkedAt: string;
+  claims: Record<string, UsageClaim>;  // every reported window, by header name
+  extra: Record<string, string>;       // raw anthropic-ratelimit-unified-* headers
+  modelWeeklyClaim: string | null;     // claims key of the weekly cap for the model in use
+}
+/** Rejection value of every command (SprtError on the Rust side) */
+interface CommandError {
+  code: "claude_not_found" | "not_authenticated" | "token_expired" | "network" | "api" | "parse"
+    | "storage" | "paused" | "no_data" | "in_progress" | "invalid_input" | "internal";
+  message: string;
+}
+
+/** Model-specific weekly cap to show: the one for the model in use, else Sonnet, else Opus */
+function modelWeekly(rl: RateLimitInfo): { name: string; claim: UsageClaim } | null {
+  const key = rl.modelWeeklyClaim;
+  if (key && rl.claims[key]) {
+    const family = key.replace(/^(7d|seven_day)[_-]?/, "").replace(/[_-]/g, " ");
+    return { name: family.charAt(0).toUpperCase() + family.slice(1), claim: rl.claims[key] };
+  }
+  if (rl.sevenDaySonnet) return { name: "Sonnet", claim: rl.sevenDaySonnet };
+  if (rl.sevenDayOpus) return { name: "Opus", claim: rl.sevenDayOpus };
+  return null;
 }
 
 /* ── Helpers ── */
 const WINDOW_MS = 5 * 36e5;
+
+/** Normalize a rejected invoke; plugin commands and JS errors don't carry a code */
+function commandError(e: unknown): CommandError {
+  if (e && typeof e === "object" && "code" in e && "message" in e) return e as CommandError;
+  return { code: "internal", message: e instanceof Error ? e.message : String(e) };
+}
 const f = (n: number) => n >= 1e9 ? (n/1e9).toFixed(1)+"B" : n >= 1e6 ? (n/1e6).toFixed(1)+"M" : n >= 1e3 ? (n/1e3).toFixed(1)+"K" : n.toLocaleString();
 const localDate = (d: Date) => `${d.getFullYear()}-${String(d.getMonth()+1).padStart(2,'0')}-${String(d.getDate()).padStart(2,'0')}`;
 
@@ -143,7 +171,7 @@ function Popover() {
     try {
       const data = await invoke<RateLimitInfo>("get_rate_limits", { force: false });
       setRl(data);
-    } catch (e) { console.error("Popover load error:", e); }
+    } catch (e) { console.error("Popover load error:", commandError(e).message); }
     finally { loadingRef.current = false; }
   }, []);
 
@@ -152,11 +180,12 @@ function Popover() {
     const a = setInterval(load, 10000);
     const c = setInterval(() => setClock(new Date()), 1000);
     // Proper cleanup: await the promise, then call unlisten in cleanup
-    const unlistenPromise = listen("claude-data-changed", () => load());
+    const unlistenPromises = ["sessions-changed", "stats-cache-changed", "account-changed", "system-woke"]
+      .map(event => listen(event, () => load()));
     return () => {
       clearInterval(a);
       clearInterval(c);
-      unlistenPromise.then(fn => fn());
+      unlistenPromises.forEach(p => p.then(fn => fn()));
     };
   }, [load]);
 
@@ -195,8 +224,8 @@ function Dashboard() {
   const [stats, setStats] = useState<Stats | null>(null);
   const [rt, setRt] = useState<RealtimeStats | null>(null);
   const [rl, setRl] = useState<RateLimitInfo | null>(null);
-  const [statsErr, setStatsErr] = useState<string | null>(null);
-  const [rlErr, setRlErr] = useState(false);
+  const [statsErr, setStatsErr] = useState<CommandError | null>(null);
+  const [rlErr, setRlErr] = useState<CommandError | null>(null);
   const [loading, setLoading] = useState(true);
   const [tickCount, tick] = useState(0);
   const [clock, setClock] = useState(() => new Date());
@@ -222,7 +251,7 @@ function Dashboard() {
       ]);
       setStats(a); setRt(b); setStatsErr(null);
       setLastUpdated(new Date());
-    } catch (e) { setStatsErr(String(e)); }
+    } catch (e) { setStatsErr(commandError(e)); }
     finally { setLoading(false); statsLoadingRef.current = false; }
   }, []);
 
@@ -232,8 +261,12 @@ function Dashboard() {
     try {
       const data = await invoke<RateLimitInfo>("get_rate_limits", { force });
       setRl(data);
-      setRlErr(false);
-    } catch (e) { console.error("Rate limit load error:", e); setRlErr(true); }
+      setRlErr(null);
+    } catch (e) {
+      const err = commandError(e);
+      console.error("Rate limit load error:", err.message);
+      setRlErr(err);
+    }
     finally { rlLoadingRef.current = false; }
   }, []);
 
@@ -248,11 +281,21 @@ function Dashboard() {
     const rlInterval = setInterval(() => loadRateLimits(), 60000);
     const b = setInterval(() => tick(t => t + 1), 5000);
     const c = setInterval(() => setClock(new Date()), 1000);
+    // sprt:// links: the backend already brought the dashboard up; confirming the link
+    // clears it so it isn't replayed on the next load
+    const confirmNavigation = () => { invoke("take_pending_navigation").catch(() => {}); };
+    confirmNavigation();
     // Proper cleanup: await the promise, then call unlisten in cleanup
-    const unlistenPromise = listen("claude-data-changed", () => { loadStats(); loadRateLimits(); });
+    const unlistenPromises = [
+      listen("sessions-changed", () => { loadStats(); loadRateLimits(); }),
+      listen("stats-cache-changed", () => loadStats()),
+      listen("account-changed", () => { loadStats(); loadRateLimits(); }),
+      listen("system-woke", () => { loadStats(); loadRateLimits(); }),
+      listen("navigate", confirmNavigation),
+    ];
     return () => {
       clearInterval(a); clearInterval(rlInterval); clearInterval(b); clearInterval(c);
-      unlistenPromise.then(fn => fn());
+      unlistenPromises.forEach(p => p.then(fn => fn()));
     };
   }, [loadStats, loadRateLimits]);
 
@@ -297,7 +340,8 @@ function Dashboard() {
   );
 
   // Only block the entire UI if credentials are missing (can't do anything)
-  const isNoCreds = statsErr?.includes("credentials") || statsErr?.includes("home directory");
+  const notInstalled = statsErr?.code === "claude_not_found";
+  const isNoCreds = notInstalled || statsErr?.code === "not_authenticated";
   if (isNoCreds) {
     return (
       <div className="app">
@@ -306,9 +350,11 @@ function Dashboard() {
         <div className="scroll">
           <div className="onboard">
             <div className="onboard-icon">🔑</div>
-            <div className="onboard-title">Login Required</div>
+            <div className="onboard-title">{notInstalled ? "Claude Code Not Found" : "Login Required"}</div>
             <div className="onboard-desc">
-              Claude Code CLI is not logged in. Please authenticate first.
+              {notInstalled
+                ? "No ~/.claude directory yet. Install Claude Code and run it once."
+                : "Claude Code CLI is not logged in. Please authenticate first."}
             </div>
             <div className="onboard-steps">
               <div className="onboard-step">1. Open Terminal</div>
@@ -359,9 +405,15 @@ function Dashboard() {
           <div className="glass-section">
             <div className="chart-empty">
               <div className="chart-empty-text">
-                {rlErr
-                  ? "Session token expired. Re-run claude to refresh."
-                  : "Connecting to Anthropic API..."}
+                {!rlErr
+                  ? "Connecting to Anthropic API..."
+                  : rlErr.code === "token_expired" || rlErr.code === "not_authenticated"
+                    ? "Session token expired. Re-run claude to refresh."
+                    : rlErr.code === "network"
+                      ? "Can't reach the Anthropic API. Check your connection."
+                      : rlErr.code === "paused"
+                        ? "Monitoring is paused."
+                        : rlErr.message}
               </div>
             </div>
           </div>
@@ -392,7 +444,7 @@ function Dashboard() {
         })()}
 
         {/* ── Weekly Limits ── */}
-        {rl && (rl.sevenDay || rl.sevenDaySonnet || rl.overageStatus) && (
+        {rl && (rl.sevenDay || modelWeekly(rl) || rl.overageStatus) && (
           <div className="glass-section">
             <div className="section-title">Weekly Limits</div>
             {rl.sevenDay && (() => {
@@ -412,14 +464,16 @@ function Dashboard() {
                 </div>
               );
             })()}
-            {rl.sevenDaySonnet && (() => {
-              const pct = claimPct(rl.sevenDaySonnet);
+            {(() => {
+              const mw = modelWeekly(rl);
+              if (!mw) return null;
+              const pct = claimPct(mw.claim);
               return (
                 <div className="limit-card">
                   <div className="limit-header">
                     <div>
-                      <div className="limit-name">Sonnet</div>
-                      <div className="limit-sub">resets in {fmtResetCountdown(rl.sevenDaySonnet!.reset, clock)}</div>
+                      <div className="limit-name">{mw.name}</div>
+                      <div className="limit-sub">resets in {fmtResetCountdown(mw.claim.reset, clock)}</div>
                     </div>
                     <div className={`limit-pct ${pct >= 100 ? "done" : pct >= 80 ? "warn" : ""}`}>{pct}%</div>
                   </div>